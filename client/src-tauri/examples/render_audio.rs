@@ -13,7 +13,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         eprintln!("Arguments:");
         eprintln!("  session_path  - Path to the recording session directory");
         eprintln!("  player_name   - Name of the player to render");
-        eprintln!("  output_file   - Output file path (.wav)");
+        eprintln!("  output_file   - Output file path (.wav, .m4a, .opus or .flac)");
         eprintln!();
         eprintln!("Example:");
         eprintln!("  {} test-data/019aae95-82e2-7676-9466-ec8d5399e798 Alaydriem output.wav", args[0]);
@@ -26,6 +26,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let format = match output_path.extension().and_then(|e| e.to_str()) {
         Some("m4a") => AudioFormat::Mp4Opus,
+        Some("opus") => AudioFormat::OggOpus,
+        Some("flac") => AudioFormat::Flac,
         _ => AudioFormat::Bwav,
     };
 
@@ -0,0 +1,156 @@
+use crate::structs::app_state::AppState;
+use common::structs::channel::{ChannelEvent, ChannelEvents};
+use tauri::async_runtime::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// Channel membership changes shared by keybinds and the WebSocket API
+pub(crate) struct ChannelActionsManager {
+    app_handle: AppHandle,
+}
+
+impl ChannelActionsManager {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+
+    /// Id of the channel the current player is in on the current server
+    pub async fn current_channel(&self) -> Result<Option<String>, anyhow::Error> {
+        let Some(player) = self.current_player() else {
+            return Ok(None);
+        };
+
+        Ok(self
+            .channel_ids_with_player(&player)
+            .await?
+            .into_iter()
+            .find_map(|(id, has_player)| has_player.then_some(id)))
+    }
+
+    /// Join `channel`, leaving the current one first. Does nothing when already a member.
+    pub async fn join(&self, channel: String) -> Result<(), anyhow::Error> {
+        let current = self.current_channel().await?;
+        if current.as_deref() == Some(channel.as_str()) {
+            return Ok(());
+        }
+
+        if let Some(current) = current {
+            self.channel_event(current, ChannelEvents::Leave).await?;
+        }
+        self.channel_event(channel, ChannelEvents::Join).await
+    }
+
+    /// Leave the current channel, returning its id
+    pub async fn leave(&self) -> Result<String, anyhow::Error> {
+        let channel = self
+            .current_channel()
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Not in a channel"))?;
+        self.channel_event(channel.clone(), ChannelEvents::Leave)
+            .await?;
+        Ok(channel)
+    }
+
+    /// Join the channel after the current one in `candidates`, or every channel on the server
+    /// when `candidates` is empty. Candidates that no longer exist are skipped. Returns the
+    /// joined channel, if any.
+    pub async fn cycle(&self, candidates: &[String]) -> Result<Option<String>, anyhow::Error> {
+        let player = self.current_player();
+        let channels = self
+            .channel_ids_with_player(player.as_deref().unwrap_or_default())
+            .await?;
+
+        let available: Vec<String> = if candidates.is_empty() {
+            channels.iter().map(|(id, _)| id.clone()).collect()
+        } else {
+            candidates
+                .iter()
+                .filter(|id| channels.iter().any(|(channel, _)| channel == *id))
+                .cloned()
+                .collect()
+        };
+        let current = channels
+            .into_iter()
+            .find_map(|(id, has_player)| has_player.then_some(id));
+
+        let Some(next) = next_channel(&available, current.as_deref()) else {
+            return Ok(None);
+        };
+        if current.as_deref() == Some(next.as_str()) {
+            return Ok(Some(next));
+        }
+
+        if let Some(current) = current {
+            self.channel_event(current, ChannelEvents::Leave).await?;
+        }
+        self.channel_event(next.clone(), ChannelEvents::Join)
+            .await?;
+        Ok(Some(next))
+    }
+
+    fn current_player(&self) -> Option<String> {
+        self.app_handle
+            .store("store.json")
+            .ok()
+            .and_then(|store| store.get("current_player"))
+            .and_then(|v| v.as_str().map(str::to_string))
+    }
+
+    /// Every channel id on the server in listing order, paired with whether `player` is in it
+    async fn channel_ids_with_player(
+        &self,
+        player: &str,
+    ) -> Result<Vec<(String, bool)>, anyhow::Error> {
+        let app_state = self.app_handle.state::<Mutex<AppState>>();
+        let channels = crate::api::commands::api_list_channels(app_state, None)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        Ok(channels
+            .into_iter()
+            .map(|channel| {
+                let has_player = channel.players.iter().any(|p| p.name == player);
+                (channel.id(), has_player)
+            })
+            .collect())
+    }
+
+    async fn channel_event(
+        &self,
+        channel: String,
+        event: ChannelEvents,
+    ) -> Result<(), anyhow::Error> {
+        let app_state = self.app_handle.state::<Mutex<AppState>>();
+        crate::api::commands::api_channel_event(app_state, channel, ChannelEvent::new(event), None)
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?;
+        Ok(())
+    }
+}
+
+/// The entry after `current` in `channels`, wrapping around. Starts at the first entry when
+/// `current` is not one of them.
+fn next_channel(channels: &[String], current: Option<&str>) -> Option<String> {
+    let position = current.and_then(|current| channels.iter().position(|id| id == current));
+    let index = match position {
+        Some(position) => (position + 1) % channels.len(),
+        None => 0,
+    };
+    channels.get(index).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_channel() {
+        let channels = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+
+        assert_eq!(next_channel(&channels, None), Some("a".to_string()));
+        assert_eq!(next_channel(&channels, Some("a")), Some("b".to_string()));
+        assert_eq!(next_channel(&channels, Some("c")), Some("a".to_string()));
+        assert_eq!(next_channel(&channels, Some("x")), Some("a".to_string()));
+        assert_eq!(next_channel(&[], Some("a")), None);
+    }
+}
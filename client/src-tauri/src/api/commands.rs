@@ -1,5 +1,5 @@
 use crate::structs::app_state::AppState;
-use common::consts::version::PROTOCOL_VERSION;
+use common::consts::version::{self, VersionCompatibility, PROTOCOL_VERSION};
 use common::response::GamerpicResponse;
 use common::structs::channel::{Channel, ChannelEvent};
 use common::structs::config::ApiConfig;
@@ -70,26 +70,10 @@ pub(crate) async fn api_get_config(
 
     let config = api.get_config().await?;
     let client_version = PROTOCOL_VERSION.to_string();
-    let server_version = &config.protocol_version;
-
-    // Parse versions for comparison (major.minor.patch)
-    let server_parts: Vec<u32> = server_version
-        .split('.')
-        .filter_map(|s| s.parse().ok())
-        .collect();
-    let client_parts: Vec<u32> = client_version
-        .split('.')
-        .filter_map(|s| s.parse().ok())
-        .collect();
-
-    // Compare major and minor versions - both must match (patch can differ)
-    let server_major = server_parts.first().copied().unwrap_or(0);
-    let server_minor = server_parts.get(1).copied().unwrap_or(0);
-    let client_major = client_parts.first().copied().unwrap_or(0);
-    let client_minor = client_parts.get(1).copied().unwrap_or(0);
-
-    let compatible = server_major == client_major && server_minor == client_minor;
-    let client_too_old = (client_major, client_minor) < (server_major, server_minor);
+
+    let compatibility = version::compatibility(&config.protocol_version);
+    let compatible = compatibility == VersionCompatibility::Compatible;
+    let client_too_old = compatibility == VersionCompatibility::ClientTooOld;
 
     Ok(ConfigResponse {
         config,
@@ -3,10 +3,13 @@ use common::structs::config::ApiConfig;
 use common::request::LinkJavaIdentityRequest;
 use common::response::LinkJavaIdentityResponse;
 use log::error;
+mod actions;
 mod channel;
 mod client;
 mod gamerpic;
 
+pub(crate) use actions::ChannelActionsManager;
+
 use common::reqwest::{
     header::{HeaderMap, HeaderValue},
     Client as ReqwestClient, StatusCode,
@@ -140,6 +143,8 @@ impl Api {
                             protocol_version: String::new(),
                             quic_port: 0,
                             spatial_audio: Default::default(),
+                            players: None,
+                            server_id: None,
                         });
                     }
 
@@ -154,3 +159,26 @@ impl Api {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_override_reaches_the_client() {
+        let body = r#"{
+            "status": "Ok",
+            "client_id": "client",
+            "protocol_version": "1.0.0",
+            "quic_port": 3001,
+            "spatial_audio": { "broadcast_range": 96.0, "falloff_distance": 80.0 },
+            "players": 2,
+            "server_id": "lobby"
+        }"#;
+
+        let config: ApiConfig = serde_json::from_str(body).unwrap();
+        assert_eq!(config.server_id.as_deref(), Some("lobby"));
+        assert_eq!(config.spatial_audio.broadcast_range, 96.0);
+        assert_eq!(config.spatial_audio.falloff_distance, 80.0);
+    }
+}
@@ -1,12 +1,25 @@
 use crate::audio::types::AudioDeviceType;
 use crate::audio::{AudioStreamManager, RecordingManager};
-use common::structs::audio::{MuteEvent, StreamEvent};
+use common::structs::audio::{MuteEvent, PlayerGainSettings, PlayerGainStore, StreamEvent};
+use common::structs::recording::RecordingMarker;
+use common::structs::SpatialAudioConfig;
 use log::info;
+use serde::Serialize;
 use std::sync::Arc;
 use tauri::async_runtime::Mutex;
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_store::StoreExt;
 
+/// Highest per-player gain the dashboard slider allows
+const MAX_PLAYER_GAIN: f32 = 1.5;
+
+/// Payload of the `player_gain` event, sent when a player's volume or mute changes outside the UI
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PlayerGainEvent {
+    pub player: String,
+    pub settings: PlayerGainSettings,
+}
+
 pub(crate) struct AudioActionsManager {
     app_handle: AppHandle,
 }
@@ -36,6 +49,129 @@ impl AudioActionsManager {
         status
     }
 
+    /// Set output mute to `deafened`, or toggle it when `None`. Returns the new status.
+    pub async fn set_deafened(&self, deafened: Option<bool>) -> bool {
+        let current = self.is_muted(AudioDeviceType::OutputDevice).await;
+        match deafened {
+            Some(deafened) if deafened == current => current,
+            _ => self.toggle_mute(AudioDeviceType::OutputDevice).await,
+        }
+    }
+
+    /// Update a player's entry in the persisted `PlayerGainStore` and apply it to the output
+    /// stream. Omitted values are left unchanged. Emits `player_gain` so the dashboard follows.
+    pub async fn set_player_gain(
+        &self,
+        player: String,
+        gain: Option<f32>,
+        muted: Option<bool>,
+    ) -> Result<PlayerGainSettings, anyhow::Error> {
+        let store = self.app_handle.store("store.json")?;
+        let mut gain_store: PlayerGainStore = store
+            .get("player_gain_store")
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default();
+
+        let settings = gain_store
+            .0
+            .entry(player.clone())
+            .or_insert(PlayerGainSettings { gain: 1.0, muted: false });
+        if let Some(gain) = gain {
+            settings.gain = gain.clamp(0.0, MAX_PLAYER_GAIN);
+        }
+        if let Some(muted) = muted {
+            settings.muted = muted;
+        }
+        let settings = settings.clone();
+
+        store.set("player_gain_store", serde_json::to_value(&gain_store)?);
+        store.save()?;
+
+        let asm = self
+            .app_handle
+            .state::<Mutex<AudioStreamManager>>();
+        let mut asm = asm.lock().await;
+        asm.metadata(
+            String::from("player_gain_store"),
+            serde_json::to_string(&gain_store)?,
+            &AudioDeviceType::OutputDevice,
+        )
+        .await?;
+        drop(asm);
+
+        self.app_handle
+            .emit(
+                "player_gain",
+                PlayerGainEvent {
+                    player,
+                    settings: settings.clone(),
+                },
+            )
+            .ok();
+
+        Ok(settings)
+    }
+
+    /// Change the persisted master output volume by `delta`, clamped to 0..=1, and apply it to
+    /// the output stream. Emits `output_volume` with the new value.
+    pub async fn adjust_output_volume(&self, delta: f32) -> Result<f32, anyhow::Error> {
+        let store = self.app_handle.store("store.json")?;
+        let current = store
+            .get("output_volume")
+            .and_then(|v| v.as_f64())
+            .unwrap_or(1.0) as f32;
+        // Round to avoid drifting away from the slider's steps
+        let volume = ((current + delta).clamp(0.0, 1.0) * 100.0).round() / 100.0;
+
+        store.set("output_volume", volume);
+        store.save()?;
+
+        let asm = self
+            .app_handle
+            .state::<Mutex<AudioStreamManager>>();
+        let mut asm = asm.lock().await;
+        asm.metadata(
+            String::from("output_volume"),
+            volume.to_string(),
+            &AudioDeviceType::OutputDevice,
+        )
+        .await?;
+        drop(asm);
+
+        self.app_handle.emit("output_volume", volume).ok();
+        info!("Output volume set to {:.0}%", volume * 100.0);
+
+        Ok(volume)
+    }
+
+    /// Send input frames to channel members only, without spatialization, while `active`
+    pub async fn set_whisper(&self, active: bool) -> Result<(), anyhow::Error> {
+        let asm = self
+            .app_handle
+            .state::<Mutex<AudioStreamManager>>();
+        let mut asm = asm.lock().await;
+        asm.metadata(
+            String::from("whisper"),
+            active.to_string(),
+            &AudioDeviceType::InputDevice,
+        )
+        .await
+    }
+
+    /// Let the speech detector open and close the microphone instead of the noise gate
+    pub async fn set_voice_activation(&self, enabled: bool) -> Result<(), anyhow::Error> {
+        let asm = self
+            .app_handle
+            .state::<Mutex<AudioStreamManager>>();
+        let mut asm = asm.lock().await;
+        asm.metadata(
+            String::from("use_voice_activation"),
+            enabled.to_string(),
+            &AudioDeviceType::InputDevice,
+        )
+        .await
+    }
+
     /// Query mute status without toggling.
     pub async fn is_muted(&self, device: AudioDeviceType) -> bool {
         let asm = self
@@ -45,8 +181,19 @@ impl AudioActionsManager {
         asm.mute_status(&device).await.unwrap_or(false)
     }
 
+    /// Spatial settings currently applied to the output stream
+    pub async fn spatial_config(&self) -> Option<SpatialAudioConfig> {
+        let asm = self
+            .app_handle
+            .state::<Mutex<AudioStreamManager>>();
+        let asm = asm.lock().await;
+        asm.spatial_config().await
+    }
+
     /// Toggle recording on/off. Returns new recording state.
     pub async fn toggle_recording(&self) -> Result<bool, anyhow::Error> {
+        // Read before locking the recording manager; the stream manager locks it on reset
+        let spatial_config = self.spatial_config().await;
         let recording_manager = self
             .app_handle
             .state::<Arc<Mutex<RecordingManager>>>();
@@ -64,12 +211,21 @@ impl AudioActionsManager {
                 .and_then(|v| v.as_str().map(String::from))
                 .ok_or_else(|| anyhow::anyhow!("No current player"))?;
 
-            manager.start_recording(current_player).await?;
+            manager.start_recording(current_player, spatial_config).await?;
             Ok(true)
         }
     }
 
-    /// Query current muted/deafened/recording state as a DTO.
+    /// Place a marker in the active recording. Fails when nothing is being recorded.
+    pub async fn mark_recording(&self, label: Option<String>) -> Result<RecordingMarker, anyhow::Error> {
+        let recording_manager = self
+            .app_handle
+            .state::<Arc<Mutex<RecordingManager>>>();
+        let manager = recording_manager.lock().await;
+        manager.add_marker(label)
+    }
+
+    /// Query current muted/deafened/recording state and the local player as a DTO.
     pub async fn query_state(&self) -> crate::websocket::StateData {
         let asm = self
             .app_handle
@@ -92,10 +248,18 @@ impl AudioActionsManager {
         let recording = manager.is_recording();
         drop(manager);
 
+        let player = self
+            .app_handle
+            .store("store.json")
+            .ok()
+            .and_then(|store| store.get("current_player"))
+            .and_then(|v| v.as_str().map(String::from));
+
         crate::websocket::StateData {
             muted,
             deafened,
             recording,
+            player,
         }
     }
 
@@ -9,11 +9,15 @@ mod stream;
 
 pub(crate) use actions::AudioActionsManager;
 pub(crate) use recording::RecordingManager;
-pub(crate) use stream::AudioStreamManager;
+pub(crate) use stream::{AudioStreamManager, SpeakingMonitor};
 
 #[derive(Debug, Clone)]
 pub(crate) struct AudioPacket {
     pub data: QuicNetworkPacket,
+    /// Url of the server the packet was received from
+    pub server: String,
+    /// Whether that is the current server, whose positions drive spatial audio
+    pub primary: bool,
 }
 
 #[cfg(test)]
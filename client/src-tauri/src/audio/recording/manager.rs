@@ -1,19 +1,31 @@
-use super::{Recorder, RawRecordingData, RecordingProducer, RecordingConsumer};
+use super::{retention, session, Recorder, RawRecordingData, RecordingProducer, RecordingConsumer};
+use common::structs::recording::{RecordingMarker, RecordingRetentionPolicy};
+use common::structs::SpatialAudioConfig;
 use common::traits::StreamTrait;
-use log::info;
+use log::{error, info, warn};
 use std::{
+    path::PathBuf,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+/// How often the retention policy is enforced in the background
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Delay before the first pass, leaving startup recovery time to finish
+const RETENTION_STARTUP_DELAY: Duration = Duration::from_secs(60);
 
 /// Central recording manager following NetworkStreamManager patterns
 pub struct RecordingManager {
     recorder: Option<Recorder>,
     recording_state: Arc<AtomicBool>,
     app_handle: tauri::AppHandle,
+    // Serializes background and on-demand retention passes
+    retention_lock: Arc<tokio::sync::Mutex<()>>,
 
     // Recording channels (owned by manager)
     recording_producer: Arc<RecordingProducer>,
@@ -30,6 +42,7 @@ impl RecordingManager {
             recorder: None,
             recording_state: Arc::new(AtomicBool::new(false)),
             app_handle,
+            retention_lock: Arc::new(tokio::sync::Mutex::new(())),
             recording_producer: Arc::new(recording_producer),
             recording_consumer: Arc::new(recording_consumer),
         }
@@ -46,8 +59,13 @@ impl RecordingManager {
         Arc::clone(&self.recording_state)
     }
 
-    /// Start a new recording session
-    pub async fn start_recording(&mut self, current_player: String) -> Result<(), anyhow::Error> {
+    /// Start a new recording session. The spatial settings are stored with the session so it
+    /// can later be re-rendered from any participant's perspective.
+    pub async fn start_recording(
+        &mut self,
+        current_player: String,
+        spatial_config: Option<SpatialAudioConfig>,
+    ) -> Result<(), anyhow::Error> {
         if self.recording_state.load(Ordering::SeqCst) {
             return Err(anyhow::anyhow!("Recording already in progress"));
         }
@@ -55,6 +73,7 @@ impl RecordingManager {
         // Create new recorder instance with the consumer from app state
         let mut recorder = Recorder::new(
             current_player,
+            spatial_config,
             self.app_handle.clone(),
             self.recording_consumer.clone(),
         ).await?;
@@ -101,8 +120,139 @@ impl RecordingManager {
         self.recording_state.load(Ordering::SeqCst)
     }
 
+    /// Place a marker in the current session, e.g. to flag a clip worth exporting later
+    pub fn add_marker(&self, label: Option<String>) -> Result<RecordingMarker, anyhow::Error> {
+        let recorder = match &self.recorder {
+            Some(recorder) if self.is_recording() => recorder,
+            _ => return Err(anyhow::anyhow!("No recording in progress")),
+        };
+
+        let marker = recorder.add_marker(label)?;
+        self.app_handle.emit("recording:marker", &marker).ok();
+
+        info!(
+            "Marker placed at {}ms in recording session {}",
+            marker.relative_timestamp_ms,
+            recorder.session_id()
+        );
+        Ok(marker)
+    }
+
     /// Get current session ID if recording
     pub fn current_session_id(&self) -> Option<String> {
         self.recorder.as_ref().map(|r| r.session_id().to_string())
     }
-}
\ No newline at end of file
+
+    /// Repair sessions left unfinished by a crash or forced quit.
+    /// Orphans are identified synchronously, before any new recording can start, and repaired in the background.
+    pub fn recover_orphaned_sessions(&self) {
+        if self.is_recording() {
+            return;
+        }
+
+        let recordings_dir = match self.app_handle.path().app_local_data_dir() {
+            Ok(dir) => dir.join("recordings"),
+            Err(e) => {
+                error!("Failed to resolve recordings directory for recovery: {}", e);
+                return;
+            }
+        };
+
+        let orphaned = match session::find_orphaned_sessions(&recordings_dir) {
+            Ok(orphaned) if !orphaned.is_empty() => orphaned,
+            Ok(_) => return,
+            Err(e) => {
+                error!("Failed to scan {:?} for orphaned sessions: {}", recordings_dir, e);
+                return;
+            }
+        };
+
+        let app_handle = self.app_handle.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut recovered = Vec::new();
+            for session_path in orphaned {
+                match session::repair_session(&session_path) {
+                    Ok(report) => {
+                        info!(
+                            "Recovered recording session {:?} ({} segments truncated)",
+                            session_path,
+                            report.truncated.len()
+                        );
+                        if let Some(manifest) = report.manifest {
+                            recovered.push(manifest.session_id);
+                        }
+                    }
+                    Err(e) => warn!("Failed to recover recording session {:?}: {}", session_path, e),
+                }
+            }
+
+            if !recovered.is_empty() {
+                app_handle.emit("recording:recovered", &recovered).ok();
+            }
+        });
+    }
+
+    /// Enforce the stored retention policy every `RETENTION_INTERVAL`
+    pub fn start_retention_task(&self) {
+        let app_handle = self.app_handle.clone();
+        let retention_lock = self.retention_lock.clone();
+
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(RETENTION_STARTUP_DELAY).await;
+            loop {
+                if let Err(e) = Self::enforce_retention(&app_handle, &retention_lock).await {
+                    error!("Failed to enforce recording retention policy: {}", e);
+                }
+                tokio::time::sleep(RETENTION_INTERVAL).await;
+            }
+        });
+    }
+
+    /// Handle used by the `apply_recording_retention` command to run a pass immediately
+    pub fn retention_lock(&self) -> Arc<tokio::sync::Mutex<()>> {
+        self.retention_lock.clone()
+    }
+
+    /// Read the retention policy from the store
+    pub fn retention_policy(app_handle: &tauri::AppHandle) -> RecordingRetentionPolicy {
+        app_handle
+            .store("store.json")
+            .ok()
+            .and_then(|store| store.get(retention::RETENTION_STORE_KEY))
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Run a single retention pass with the stored policy
+    pub async fn enforce_retention(
+        app_handle: &tauri::AppHandle,
+        retention_lock: &tokio::sync::Mutex<()>,
+    ) -> Result<retention::RetentionReport, anyhow::Error> {
+        let policy = Self::retention_policy(app_handle);
+        if !policy.enabled {
+            return Ok(retention::RetentionReport::default());
+        }
+
+        let _guard = retention_lock.lock().await;
+        let data_dir = app_handle.path().app_local_data_dir()?;
+        let export_dir = policy
+            .export_directory
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| data_dir.join("exports"));
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+
+        let report = retention::enforce(&data_dir.join("recordings"), &export_dir, &policy, now_ms).await?;
+        if !report.deleted.is_empty() || !report.compressed.is_empty() {
+            info!(
+                "Retention pass deleted {} and compressed {} sessions, freeing {} bytes",
+                report.deleted.len(),
+                report.compressed.len(),
+                report.freed_bytes
+            );
+            app_handle.emit("recording:retention", &report).ok();
+        }
+
+        Ok(report)
+    }
+}
@@ -1,7 +1,10 @@
 mod manager;
 pub mod renderer;
+pub mod retention;
+pub mod session;
 
-use common::structs::recording::{RecordingPlayerData, SessionManifest, RecordingHeader, InputRecordingHeader, OutputRecordingHeader};
+use common::structs::recording::{RecordingPlayerData, RecordingMarker, SessionManifest, RecordingHeader, InputRecordingHeader, OutputRecordingHeader};
+use common::structs::SpatialAudioConfig;
 
 use log::{error, info};
 use serde::{Deserialize, Serialize};
@@ -54,6 +57,9 @@ pub struct Recorder {
     manifest: SessionManifest,
     recording_path: PathBuf,
     recording_consumer: Arc<RecordingConsumer>,
+    // Markers are handed to the recording loop, which owns the manifest
+    marker_producer: flume::Sender<RecordingMarker>,
+    marker_consumer: flume::Receiver<RecordingMarker>,
     session_start_timestamp: u64,
     #[allow(unused)]
     app_handle: tauri::AppHandle,
@@ -62,6 +68,7 @@ pub struct Recorder {
 impl Recorder {
     pub async fn new(
         current_player: String,
+        spatial_config: Option<SpatialAudioConfig>,
         app_handle: tauri::AppHandle,
         recording_consumer: Arc<RecordingConsumer>,
     ) -> Result<Self, anyhow::Error> {
@@ -97,8 +104,14 @@ impl Recorder {
                 start_timestamp
                     .as_secs()
             ),
+            recovered: false,
+            pinned: false,
+            markers: Vec::new(),
+            spatial_config,
         };
 
+        let (marker_producer, marker_consumer) = flume::unbounded();
+
         Ok(Self {
             jobs: Vec::new(),
             shutdown: Arc::new(AtomicBool::new(false)),
@@ -107,6 +120,8 @@ impl Recorder {
             manifest,
             recording_path,
             recording_consumer,
+            marker_producer,
+            marker_consumer,
             session_start_timestamp: start_timestamp.as_millis() as u64,
             app_handle,
         })
@@ -117,6 +132,7 @@ impl Recorder {
         const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
 
         let recording_consumer = self.recording_consumer.clone();
+        let marker_consumer = self.marker_consumer.clone();
         let shutdown = self.shutdown.clone();
         let recording_path = self.recording_path.clone();
         let mut manifest = self.manifest.clone();
@@ -172,6 +188,7 @@ impl Recorder {
                         };
                         batch_buffer.push((player_key, raw_data));
                     }
+                    manifest.markers.extend(marker_consumer.try_iter());
                     // Final flush
                     if !batch_buffer.is_empty() {
                         let _ = Self::flush(&mut wal, &mut batch_buffer).await;
@@ -214,6 +231,17 @@ impl Recorder {
                         }
                     }
 
+                    Ok(marker) = marker_consumer.recv_async() => {
+                        // Markers are rare and user-placed, so persist them right away
+                        manifest.markers.push(marker);
+                        manifest.participants = participants.iter().cloned().collect();
+                        if let Err(e) = Self::write_manifest(&recording_path, &manifest).await {
+                            error!("Failed to write manifest with marker: {:?}", e);
+                        } else {
+                            manifest_dirty = false;
+                        }
+                    }
+
                     _ = tokio::time::sleep(FLUSH_INTERVAL) => {
                         if !batch_buffer.is_empty() {
                             if let Err(e) = Self::flush(&mut wal, &mut batch_buffer).await {
@@ -274,16 +302,15 @@ impl Recorder {
         Ok((handle.abort_handle(), completion_rx))
     }
 
-    /// Write session manifest to disk
+    /// Write session manifest to disk, replacing the previous one atomically
     async fn write_manifest(
         recording_path: &PathBuf,
         manifest: &SessionManifest,
     ) -> Result<(), anyhow::Error> {
         let manifest_json = serde_json::to_string_pretty(manifest)?;
-        tokio::fs::write(
-            recording_path.join("session.json"),
-            manifest_json
-        ).await?;
+        let temp_path = recording_path.join("session.json.tmp");
+        tokio::fs::write(&temp_path, manifest_json).await?;
+        tokio::fs::rename(&temp_path, recording_path.join("session.json")).await?;
         Ok(())
     }
 
@@ -332,6 +359,18 @@ impl Recorder {
     pub fn session_id(&self) -> &str {
         &self.session_id
     }
+
+    /// Place a marker at the current position of the session
+    pub fn add_marker(&self, label: Option<String>) -> Result<RecordingMarker, anyhow::Error> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis() as u64;
+        let marker = RecordingMarker {
+            relative_timestamp_ms: now.saturating_sub(self.session_start_timestamp),
+            label: label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()),
+        };
+
+        self.marker_producer.send(marker.clone())?;
+        Ok(marker)
+    }
 }
 
 impl common::traits::StreamTrait for Recorder {
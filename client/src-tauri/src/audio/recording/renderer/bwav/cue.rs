@@ -0,0 +1,192 @@
+//! Cue points for BWav files
+//!
+//! `bwavfile` has no API for cue chunks, so they are appended after the audio data once the
+//! file is closed: a `cue ` chunk with one point per utterance, and a `LIST adtl` chunk that
+//! labels each point with the speaker and gives its length as a region (`ltxt`). User-placed
+//! markers follow as labelled points without a length.
+
+use crate::audio::recording::renderer::timeline::{Marker, Utterance};
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Append a cue point and its `labl` entry
+fn write_point(points: &mut Vec<u8>, adtl: &mut Vec<u8>, id: u32, position: u32, title: &str) {
+    // dwName, dwPosition, fccChunk, dwChunkStart, dwBlockStart, dwSampleOffset
+    points.extend_from_slice(&id.to_le_bytes());
+    points.extend_from_slice(&position.to_le_bytes());
+    points.extend_from_slice(b"data");
+    points.extend_from_slice(&0u32.to_le_bytes());
+    points.extend_from_slice(&0u32.to_le_bytes());
+    points.extend_from_slice(&position.to_le_bytes());
+
+    let mut label = id.to_le_bytes().to_vec();
+    label.extend_from_slice(title.as_bytes());
+    label.push(0);
+    write_chunk(adtl, b"labl", &label);
+}
+
+/// Build the `cue ` and `LIST adtl` chunks for the given utterances and markers
+fn build_chunks<'a>(
+    utterances: impl Iterator<Item = &'a Utterance>,
+    markers: &[Marker],
+    sample_rate: u32,
+) -> Vec<u8> {
+    let to_samples = |ms: u64| (ms * sample_rate as u64 / 1000).min(u32::MAX as u64) as u32;
+
+    let mut points = Vec::new();
+    let mut adtl = b"adtl".to_vec();
+    let mut id = 0u32;
+    for utterance in utterances {
+        id += 1;
+        let start = to_samples(utterance.start_ms);
+        write_point(&mut points, &mut adtl, id, start, &utterance.player);
+
+        // dwName, dwSampleLength, dwPurposeID, wCountry, wLanguage, wDialect, wCodePage
+        let mut region = id.to_le_bytes().to_vec();
+        region.extend_from_slice(&to_samples(utterance.end_ms).saturating_sub(start).to_le_bytes());
+        region.extend_from_slice(b"rgn ");
+        region.extend_from_slice(&[0u8; 8]);
+        write_chunk(&mut adtl, b"ltxt", &region);
+    }
+
+    for (index, marker) in markers.iter().enumerate() {
+        id += 1;
+        write_point(&mut points, &mut adtl, id, to_samples(marker.start_ms), &marker.title(index));
+    }
+
+    let count = (points.len() / 24) as u32;
+    let mut cue = count.to_le_bytes().to_vec();
+    cue.extend_from_slice(&points);
+
+    let mut chunks = Vec::new();
+    write_chunk(&mut chunks, b"cue ", &cue);
+    write_chunk(&mut chunks, b"LIST", &adtl);
+    chunks
+}
+
+/// Append a RIFF chunk, padded to an even length
+fn write_chunk(out: &mut Vec<u8>, id: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(id);
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        out.push(0);
+    }
+}
+
+/// Append cue points for `utterances` and `markers` to a finished WAV file and update its RIFF size
+pub fn append_cue_points<'a>(
+    path: &Path,
+    utterances: impl Iterator<Item = &'a Utterance>,
+    markers: &[Marker],
+    sample_rate: u32,
+) -> Result<(), anyhow::Error> {
+    let mut utterances = utterances.peekable();
+    if utterances.peek().is_none() && markers.is_empty() {
+        return Ok(());
+    }
+    let chunks = build_chunks(utterances, markers, sample_rate);
+
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    let mut header = [0u8; 24];
+    file.read_exact(&mut header)?;
+
+    let end = file.seek(SeekFrom::End(0))?;
+    // Chunks start on even offsets
+    if end % 2 == 1 {
+        file.write_all(&[0])?;
+    }
+    file.write_all(&chunks)?;
+    let riff_size = file.stream_position()? - 8;
+
+    match &header[0..4] {
+        b"RIFF" => {
+            file.seek(SeekFrom::Start(4))?;
+            file.write_all(&u32::try_from(riff_size)?.to_le_bytes())?;
+        }
+        // RF64 keeps the real RIFF size in the ds64 chunk that follows the WAVE id
+        b"RF64" if &header[12..16] == b"ds64" => {
+            file.seek(SeekFrom::Start(20))?;
+            file.write_all(&riff_size.to_le_bytes())?;
+        }
+        _ => return Err(anyhow::anyhow!("{:?} is not a RIFF WAVE file", path)),
+    }
+
+    file.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utterances() -> Vec<Utterance> {
+        vec![
+            Utterance { player: "Alex".to_string(), start_ms: 0, end_ms: 1000 },
+            Utterance { player: "Steve".to_string(), start_ms: 2000, end_ms: 2500 },
+        ]
+    }
+
+    #[test]
+    fn test_cue_chunk_positions_are_in_samples() {
+        let chunks = build_chunks(utterances().iter(), &[], 48000);
+        assert_eq!(&chunks[0..4], b"cue ");
+        assert_eq!(u32::from_le_bytes(chunks[4..8].try_into().unwrap()), 4 + 2 * 24);
+        assert_eq!(u32::from_le_bytes(chunks[8..12].try_into().unwrap()), 2);
+
+        // Second point: id 2 at 2s
+        let second = &chunks[12 + 24..12 + 48];
+        assert_eq!(u32::from_le_bytes(second[0..4].try_into().unwrap()), 2);
+        assert_eq!(u32::from_le_bytes(second[4..8].try_into().unwrap()), 96000);
+        assert_eq!(&second[8..12], b"data");
+
+        let list = &chunks[12 + 48..];
+        assert_eq!(&list[0..4], b"LIST");
+        assert_eq!(&list[8..12], b"adtl");
+        assert!(list.windows(5).any(|w| w == b"Steve"));
+        assert!(list.windows(4).any(|w| w == b"rgn "));
+    }
+
+    #[test]
+    fn test_markers_follow_utterances_without_regions() {
+        let markers = vec![Marker { start_ms: 500, label: None }];
+        let chunks = build_chunks(std::iter::empty(), &markers, 48000);
+        assert_eq!(u32::from_le_bytes(chunks[8..12].try_into().unwrap()), 1);
+        assert_eq!(u32::from_le_bytes(chunks[16..20].try_into().unwrap()), 24000);
+
+        let list = &chunks[12 + 24..];
+        assert!(list.windows(8).any(|w| w == b"Marker 1"));
+        assert!(!list.windows(4).any(|w| w == b"ltxt"));
+
+        let chunks = build_chunks(utterances().iter(), &markers, 48000);
+        assert_eq!(u32::from_le_bytes(chunks[8..12].try_into().unwrap()), 3);
+        let third = &chunks[12 + 48..12 + 72];
+        assert_eq!(u32::from_le_bytes(third[0..4].try_into().unwrap()), 3);
+    }
+
+    #[test]
+    fn test_append_updates_riff_size() {
+        let path = std::env::temp_dir().join(format!("bvc-cue-{}.wav", uuid::Uuid::new_v4()));
+
+        // Minimal mono 16-bit WAV with one sample frame
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&38u32.to_le_bytes());
+        wav.extend_from_slice(b"WAVE");
+        write_chunk(&mut wav, b"fmt ", &[1, 0, 1, 0, 0x80, 0xBB, 0, 0, 0, 0x77, 1, 0, 2, 0, 16, 0]);
+        write_chunk(&mut wav, b"data", &[0, 0]);
+        std::fs::write(&path, &wav).unwrap();
+
+        append_cue_points(&path, utterances().iter(), &[], 48000).unwrap();
+
+        let written = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            u32::from_le_bytes(written[4..8].try_into().unwrap()) as usize,
+            written.len() - 8
+        );
+        assert_eq!(&written[wav.len()..wav.len() + 4], b"cue ");
+    }
+}
@@ -1,12 +1,23 @@
+mod cue;
+
+use crate::audio::recording::renderer::mixdown::{
+    SessionMix, SessionStems, MIX_CHANNELS, MIX_SAMPLE_RATE,
+};
+use crate::audio::recording::renderer::timeline::SpeechTimeline;
 use crate::audio::recording::renderer::{AudioRenderer, PcmChunk, PcmStream, SessionInfo};
 use async_trait::async_trait;
 use bwavfile::{Bext, WaveFmt, WaveWriter};
 use chrono::{DateTime, Datelike, Local, TimeZone};
 use std::path::Path;
 
+/// Speaker positions defined for WAVE_FORMAT_EXTENSIBLE channel masks
+const MAX_POLYPHONIC_CHANNELS: u16 = 18;
+
 /// BWav audio renderer that outputs PCM BWav files
 pub struct BwavRenderer {
     bits_per_sample: u16,
+    /// Speech activity written as cue points
+    timeline: Option<SpeechTimeline>,
 }
 
 impl BwavRenderer {
@@ -15,15 +26,23 @@ impl BwavRenderer {
         Self {
             // f32 samples
             bits_per_sample: 32,
+            timeline: None,
         }
     }
 
+    /// Mark each utterance and marker in the timeline with a cue point
+    pub fn with_timeline(mut self, timeline: SpeechTimeline) -> Self {
+        self.timeline = Some(timeline);
+        self
+    }
+
     /// Create Bext metadata from session info
     fn create_bext(
         &self,
         session_info: &SessionInfo,
         player_name: &str,
         sample_rate: u32,
+        channels: u16,
         first_frame_relative_timestamp_ms: u64,
     ) -> Bext {
         // Convert Unix timestamp (ms) to DateTime, using the actual first packet time
@@ -60,9 +79,151 @@ impl BwavRenderer {
                 "A=PCM,F={},W={},M={},T=BVC\r\n",
                 sample_rate,
                 self.bits_per_sample,
-                "mono" // should always be mono?
+                match channels {
+                    1 => "mono",
+                    2 => "stereo",
+                    _ => "multitrack",
+                }
+            ),
+        }
+    }
+
+    /// Render a session mixdown to a stereo BWav file
+    pub fn render_mix(&self, mix: SessionMix, output_path: &Path) -> Result<(), anyhow::Error> {
+        let format = WaveFmt::new_pcm_stereo(MIX_SAMPLE_RATE, self.bits_per_sample);
+        let mut writer = WaveWriter::create(output_path, format)?;
+
+        let bext = self.create_bext(
+            mix.session_info(),
+            "Mixdown",
+            MIX_SAMPLE_RATE,
+            MIX_CHANNELS,
+            mix.origin_ms(),
+        );
+        writer.write_broadcast_metadata(&bext)?;
+
+        let mut frame_writer = writer.audio_frame_writer()?;
+        let frames = mix.run(|samples| {
+            frame_writer.write_frames(samples)?;
+            Ok(())
+        })?;
+
+        log::info!("Mixdown complete: {} sample frames written", frames);
+
+        let _writer = frame_writer.end()?;
+        if let Some(timeline) = &self.timeline {
+            cue::append_cue_points(output_path, timeline.utterances.iter(), &timeline.markers, MIX_SAMPLE_RATE)?;
+        }
+        Ok(())
+    }
+
+    /// Render every participant to its own channel of one polyphonic BWav file
+    pub fn render_polyphonic(
+        &self,
+        stems: SessionStems,
+        output_path: &Path,
+    ) -> Result<(), anyhow::Error> {
+        let channels = stems.channels();
+        let format = match channels {
+            1 => WaveFmt::new_pcm_mono(MIX_SAMPLE_RATE, self.bits_per_sample),
+            2..=MAX_POLYPHONIC_CHANNELS => WaveFmt::new_pcm_multichannel(
+                MIX_SAMPLE_RATE,
+                self.bits_per_sample,
+                (1u32 << channels) - 1,
             ),
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "{} participants exceed the {} channels a polyphonic WAV can hold; export a stem folder instead",
+                    channels,
+                    MAX_POLYPHONIC_CHANNELS
+                ))
+            }
+        };
+
+        let mut writer = WaveWriter::create(output_path, format)?;
+        let bext = self.create_bext(
+            stems.session_info(),
+            &stems.player_names().join(", "),
+            MIX_SAMPLE_RATE,
+            channels,
+            stems.origin_ms(),
+        );
+        writer.write_broadcast_metadata(&bext)?;
+
+        let mut frame_writer = writer.audio_frame_writer()?;
+        let frames = stems.run(|samples| {
+            frame_writer.write_frames(samples)?;
+            Ok(())
+        })?;
+
+        log::info!("Polyphonic render complete: {} channels, {} sample frames", channels, frames);
+
+        let _writer = frame_writer.end()?;
+        if let Some(timeline) = &self.timeline {
+            cue::append_cue_points(output_path, timeline.utterances.iter(), &timeline.markers, MIX_SAMPLE_RATE)?;
+        }
+        Ok(())
+    }
+
+    /// Render every participant to its own mono BWav in `output_dir`. All files share the
+    /// same length and BEXT `TimeReference`, so they line up when imported together.
+    pub fn render_stem_folder(
+        &self,
+        stems: SessionStems,
+        output_dir: &Path,
+    ) -> Result<(), anyhow::Error> {
+        std::fs::create_dir_all(output_dir)?;
+
+        let player_names = stems.player_names();
+        let stem_paths: Vec<_> = stems
+            .file_stems()
+            .iter()
+            .map(|stem| output_dir.join(format!("{}.{}", stem, self.file_extension())))
+            .collect();
+        let mut frame_writers = Vec::new();
+        for (player_name, stem_path) in player_names.iter().zip(&stem_paths) {
+            let format = WaveFmt::new_pcm_mono(MIX_SAMPLE_RATE, self.bits_per_sample);
+            let mut writer = WaveWriter::create(stem_path, format)?;
+            let bext = self.create_bext(
+                stems.session_info(),
+                player_name,
+                MIX_SAMPLE_RATE,
+                1,
+                stems.origin_ms(),
+            );
+            writer.write_broadcast_metadata(&bext)?;
+            frame_writers.push(writer.audio_frame_writer()?);
+        }
+
+        let channels = frame_writers.len();
+        let mut mono = Vec::new();
+        let frames = stems.run(|samples| {
+            for (channel, frame_writer) in frame_writers.iter_mut().enumerate() {
+                mono.clear();
+                mono.extend(samples.iter().skip(channel).step_by(channels));
+                frame_writer.write_frames(&mono)?;
+            }
+            Ok(())
+        })?;
+
+        log::info!("Stem folder render complete: {} files, {} sample frames", channels, frames);
+
+        for frame_writer in frame_writers {
+            frame_writer.end()?;
         }
+
+        // Each stem is marked with its own speaker's utterances and every session marker
+        if let Some(timeline) = &self.timeline {
+            for (player_name, stem_path) in player_names.iter().zip(&stem_paths) {
+                cue::append_cue_points(
+                    stem_path,
+                    timeline.utterances_of(player_name),
+                    &timeline.markers,
+                    MIX_SAMPLE_RATE,
+                )?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -98,6 +259,7 @@ impl AudioRenderer for BwavRenderer {
             &info.session_info,
             player_name,
             info.sample_rate,
+            info.channels,
             info.first_frame_timestamp_ms,
         );
         writer.write_broadcast_metadata(&bext)?;
@@ -136,6 +298,14 @@ impl AudioRenderer for BwavRenderer {
         );
 
         let _writer = frame_writer.end()?;
+        if let Some(timeline) = &self.timeline {
+            cue::append_cue_points(
+                output_path,
+                timeline.utterances_of(player_name),
+                &timeline.markers,
+                info.sample_rate,
+            )?;
+        }
         Ok(())
     }
 
@@ -0,0 +1,88 @@
+//! File names for per-player exports
+//!
+//! Player names come from the game server and may contain characters that are not valid in
+//! file names on every platform, or path separators that would escape the export folder.
+
+use std::collections::HashSet;
+
+/// Names Windows reserves regardless of extension
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Turn a player name into a file stem that is safe on every platform.
+/// Separators, control characters and characters Windows rejects become `_`.
+pub fn safe_file_stem(player_name: &str) -> String {
+    let mut stem: String = player_name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    // Windows strips trailing dots and spaces, and leading dots hide files elsewhere
+    stem = stem
+        .trim_end_matches(['.', ' '])
+        .trim_start_matches('.')
+        .to_string();
+
+    if stem.is_empty() {
+        return "player".to_string();
+    }
+
+    if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(&stem)) {
+        stem.insert(0, '_');
+    }
+
+    stem
+}
+
+/// Safe, unique file stems for `players`, in the same order.
+/// Names that collide after sanitizing (ignoring case) get a numeric suffix.
+pub fn player_file_stems(players: &[String]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    players
+        .iter()
+        .map(|player| {
+            let base = safe_file_stem(player);
+            let mut stem = base.clone();
+            let mut n = 2;
+            while !taken.insert(stem.to_lowercase()) {
+                stem = format!("{}-{}", base, n);
+                n += 1;
+            }
+            stem
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_safe_file_stem() {
+        assert_eq!(safe_file_stem("Steve"), "Steve");
+        assert_eq!(safe_file_stem("Alex The Great"), "Alex The Great");
+        assert_eq!(safe_file_stem("../../etc/passwd"), "_.._etc_passwd");
+        assert_eq!(safe_file_stem("a\\b:c*d?"), "a_b_c_d_");
+        assert_eq!(safe_file_stem("con"), "_con");
+        assert_eq!(safe_file_stem("..."), "player");
+        assert_eq!(safe_file_stem(""), "player");
+    }
+
+    #[test]
+    fn test_player_file_stems_are_unique() {
+        let players: Vec<String> = ["a/b", "a:b", "A_B", "Steve"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            player_file_stems(&players),
+            vec!["a_b", "a_b-2", "A_B-3", "Steve"]
+        );
+    }
+}
@@ -0,0 +1,409 @@
+//! Minimal FLAC encoder
+//!
+//! Writes 16-bit FLAC with fixed-size blocks, choosing per channel between CONSTANT,
+//! FIXED (orders 0-4, single Rice partition) and VERBATIM subframes.
+//! https://www.rfc-editor.org/rfc/rfc9639
+
+use crate::audio::recording::renderer::vorbis_comment::VorbisComment;
+use std::io::{Seek, SeekFrom, Write};
+
+/// Samples per channel in every frame but the last
+pub const BLOCK_SIZE: usize = 4096;
+pub const BITS_PER_SAMPLE: u32 = 16;
+
+pub const METADATA_STREAMINFO: u8 = 0;
+pub const METADATA_VORBIS_COMMENT: u8 = 4;
+
+const STREAMINFO_LEN: usize = 34;
+const MAX_FIXED_ORDER: usize = 4;
+/// Largest parameter a 4-bit Rice coding method can carry (15 is the escape code)
+const MAX_RICE_PARAMETER: u32 = 14;
+
+/// 14-bit sync code followed by the reserved bit and fixed blocking strategy bit
+pub const FRAME_SYNC: u64 = 0xFFF8;
+/// Subframe headers: zero pad bit, 6-bit type, wasted bits flag
+pub const SUBFRAME_CONSTANT: u64 = 0x00;
+pub const SUBFRAME_VERBATIM: u64 = 0x02;
+/// FIXED subframe, with the predictor order in bits 1-3
+pub const SUBFRAME_FIXED: u64 = 0x10;
+
+/// CRC-8 of frame headers: polynomial 0x07, zero initial value
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0u8;
+    for byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 { (crc << 1) ^ 0x07 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// CRC-16 of whole frames: polynomial 0x8005, zero initial value
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x8005 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// Big-endian bit packer
+#[derive(Debug, Default)]
+pub struct BitWriter {
+    bytes: Vec<u8>,
+    acc: u64,
+    bits: u32,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write the low `bits` bits of `value` (at most 32 at a time)
+    pub fn write(&mut self, value: u64, bits: u32) {
+        debug_assert!(bits <= 32);
+        if bits == 0 {
+            return;
+        }
+        self.acc = (self.acc << bits) | (value & ((1u64 << bits) - 1));
+        self.bits += bits;
+        while self.bits >= 8 {
+            self.bits -= 8;
+            self.bytes.push((self.acc >> self.bits) as u8);
+        }
+        self.acc &= (1u64 << self.bits) - 1;
+    }
+
+    /// Write a two's complement value in `bits` bits
+    pub fn write_signed(&mut self, value: i64, bits: u32) {
+        self.write(value as u64, bits);
+    }
+
+    /// Write `zeros` zero bits followed by a one
+    pub fn write_unary(&mut self, zeros: u64) {
+        let mut remaining = zeros;
+        while remaining >= 32 {
+            self.write(0, 32);
+            remaining -= 32;
+        }
+        self.write(1, remaining as u32 + 1);
+    }
+
+    /// Pad with zero bits to the next byte boundary
+    pub fn align(&mut self) {
+        if self.bits > 0 {
+            self.write(0, 8 - self.bits);
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        self.align();
+        self.bytes
+    }
+}
+
+/// Map negative residuals onto odd numbers so they can be Rice coded
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+/// Residual of the fixed predictor of `order` for every sample after the warm-up
+fn fixed_residual(samples: &[i32], order: usize) -> Vec<i64> {
+    (order..samples.len())
+        .map(|i| {
+            let s = |k: usize| samples[i - k] as i64;
+            let prediction = match order {
+                0 => 0,
+                1 => s(1),
+                2 => 2 * s(1) - s(2),
+                3 => 3 * s(1) - 3 * s(2) + s(3),
+                _ => 4 * s(1) - 6 * s(2) + 4 * s(3) - s(4),
+            };
+            samples[i] as i64 - prediction
+        })
+        .collect()
+}
+
+/// Best Rice parameter for a partition, with the number of bits it needs
+fn rice_parameter(residual: &[i64]) -> (u32, u64) {
+    let encoded: Vec<u64> = residual.iter().map(|r| zigzag(*r)).collect();
+    (0..=MAX_RICE_PARAMETER)
+        .map(|k| {
+            let bits = encoded.iter().map(|u| (u >> k) + 1 + k as u64).sum();
+            (k, bits)
+        })
+        .min_by_key(|(_, bits)| *bits)
+        .unwrap_or((0, 0))
+}
+
+/// Encode one channel's subframe into `out`
+fn write_subframe(out: &mut BitWriter, samples: &[i32]) {
+    // Subframe header: zero pad bit, 6-bit type, wasted bits flag
+    if samples.iter().all(|s| *s == samples[0]) {
+        out.write(SUBFRAME_CONSTANT, 8);
+        out.write_signed(samples[0] as i64, BITS_PER_SAMPLE);
+        return;
+    }
+
+    let verbatim_bits = samples.len() as u64 * BITS_PER_SAMPLE as u64;
+    let best_fixed = (0..=MAX_FIXED_ORDER.min(samples.len() - 1))
+        .map(|order| {
+            let residual = fixed_residual(samples, order);
+            let (parameter, bits) = rice_parameter(&residual);
+            let total = order as u64 * BITS_PER_SAMPLE as u64 + 10 + bits;
+            (order, residual, parameter, total)
+        })
+        .min_by_key(|(_, _, _, total)| *total);
+
+    match best_fixed {
+        Some((order, residual, parameter, total)) if total < verbatim_bits => {
+            out.write(SUBFRAME_FIXED | ((order as u64) << 1), 8);
+            for warm_up in &samples[..order] {
+                out.write_signed(*warm_up as i64, BITS_PER_SAMPLE);
+            }
+            // Rice coding with 4-bit parameters, partition order 0
+            out.write(0b00, 2);
+            out.write(0, 4);
+            out.write(parameter as u64, 4);
+            for r in residual {
+                let u = zigzag(r);
+                out.write_unary(u >> parameter);
+                out.write(u & ((1u64 << parameter) - 1), parameter);
+            }
+        }
+        _ => {
+            out.write(SUBFRAME_VERBATIM, 8);
+            for sample in samples {
+                out.write_signed(*sample as i64, BITS_PER_SAMPLE);
+            }
+        }
+    }
+}
+
+/// Frame header sample rate code, falling back to "read from STREAMINFO"
+fn sample_rate_code(sample_rate: u32) -> u64 {
+    match sample_rate {
+        8000 => 0b0100,
+        16000 => 0b0101,
+        22050 => 0b0110,
+        24000 => 0b0111,
+        32000 => 0b1000,
+        44100 => 0b1001,
+        48000 => 0b1010,
+        96000 => 0b1011,
+        _ => 0b0000,
+    }
+}
+
+/// UTF-8 style variable length coding used for frame numbers
+pub fn write_coded_number(out: &mut BitWriter, value: u64) {
+    if value < 0x80 {
+        out.write(value, 8);
+        return;
+    }
+
+    let mut bytes = 2;
+    while value >= 1u64 << (5 * bytes + 1) {
+        bytes += 1;
+    }
+
+    let lead_bits = 7 - bytes;
+    let lead = (0xFFu64 << (8 - bytes)) & 0xFF;
+    out.write(lead | (value >> (6 * (bytes - 1))), 8);
+    debug_assert!(value >> (6 * (bytes - 1)) < 1 << lead_bits);
+    for i in (0..bytes - 1).rev() {
+        out.write(0x80 | ((value >> (6 * i)) & 0x3F), 8);
+    }
+}
+
+/// Streaming FLAC writer. STREAMINFO is rewritten with the final totals on `finish`.
+pub struct FlacEncoder<W: Write + Seek> {
+    writer: W,
+    sample_rate: u32,
+    channels: u16,
+    // Interleaved samples not yet written to a frame
+    pending: Vec<i32>,
+    frame_number: u64,
+    total_samples: u64,
+    min_frame_size: u32,
+    max_frame_size: u32,
+    streaminfo_offset: u64,
+}
+
+impl<W: Write + Seek> FlacEncoder<W> {
+    /// Write the stream marker and metadata blocks
+    pub fn new(
+        mut writer: W,
+        sample_rate: u32,
+        channels: u16,
+        comment: &VorbisComment,
+    ) -> Result<Self, anyhow::Error> {
+        if channels == 0 || channels > 8 {
+            return Err(anyhow::anyhow!("FLAC supports 1-8 channels, got {}", channels));
+        }
+
+        writer.write_all(b"fLaC")?;
+        let streaminfo_offset = writer.stream_position()?;
+
+        let mut encoder = Self {
+            writer,
+            sample_rate,
+            channels,
+            pending: Vec::new(),
+            frame_number: 0,
+            total_samples: 0,
+            min_frame_size: 0,
+            max_frame_size: 0,
+            streaminfo_offset,
+        };
+
+        encoder.write_streaminfo()?;
+
+        let comment = comment.to_bytes();
+        encoder.write_metadata_header(METADATA_VORBIS_COMMENT, true, comment.len())?;
+        encoder.writer.write_all(&comment)?;
+
+        Ok(encoder)
+    }
+
+    fn write_metadata_header(
+        &mut self,
+        block_type: u8,
+        last: bool,
+        len: usize,
+    ) -> Result<(), anyhow::Error> {
+        let mut header = BitWriter::new();
+        header.write(last as u64, 1);
+        header.write(block_type as u64, 7);
+        header.write(len as u64, 24);
+        self.writer.write_all(&header.finish())?;
+        Ok(())
+    }
+
+    fn write_streaminfo(&mut self) -> Result<(), anyhow::Error> {
+        self.write_metadata_header(METADATA_STREAMINFO, false, STREAMINFO_LEN)?;
+
+        let mut info = BitWriter::new();
+        info.write(BLOCK_SIZE as u64, 16);
+        info.write(BLOCK_SIZE as u64, 16);
+        info.write(self.min_frame_size as u64, 24);
+        info.write(self.max_frame_size as u64, 24);
+        info.write(self.sample_rate as u64, 20);
+        info.write(self.channels as u64 - 1, 3);
+        info.write(BITS_PER_SAMPLE as u64 - 1, 5);
+        info.write(self.total_samples >> 32, 4);
+        info.write(self.total_samples & 0xFFFF_FFFF, 32);
+        // MD5 of the unencoded audio; zero means unknown
+        let mut info = info.finish();
+        info.extend_from_slice(&[0; 16]);
+
+        self.writer.write_all(&info)?;
+        Ok(())
+    }
+
+    /// Queue interleaved samples in the -1.0..=1.0 range
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<(), anyhow::Error> {
+        let max = ((1i32 << (BITS_PER_SAMPLE - 1)) - 1) as f32;
+        self.pending
+            .extend(samples.iter().map(|s| (s.clamp(-1.0, 1.0) * max).round() as i32));
+        self.write_full_blocks()
+    }
+
+    /// Queue `samples` interleaved samples of silence
+    pub fn write_silence(&mut self, samples: usize) -> Result<(), anyhow::Error> {
+        let mut remaining = samples;
+        while remaining > 0 {
+            let chunk = remaining.min(BLOCK_SIZE * self.channels as usize);
+            self.pending.extend(std::iter::repeat_n(0, chunk));
+            remaining -= chunk;
+            self.write_full_blocks()?;
+        }
+        Ok(())
+    }
+
+    fn write_full_blocks(&mut self) -> Result<(), anyhow::Error> {
+        let block_len = BLOCK_SIZE * self.channels as usize;
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= block_len {
+            let frame = self.encode_frame(&self.pending[consumed..consumed + block_len]);
+            self.write_frame(frame, BLOCK_SIZE)?;
+            consumed += block_len;
+        }
+        self.pending.drain(..consumed);
+        Ok(())
+    }
+
+    fn write_frame(&mut self, frame: Vec<u8>, block_size: usize) -> Result<(), anyhow::Error> {
+        let size = frame.len() as u32;
+        self.min_frame_size = if self.frame_number == 0 { size } else { self.min_frame_size.min(size) };
+        self.max_frame_size = self.max_frame_size.max(size);
+        self.writer.write_all(&frame)?;
+        self.frame_number += 1;
+        self.total_samples += block_size as u64;
+        Ok(())
+    }
+
+    /// Encode one block of interleaved samples as a complete frame
+    fn encode_frame(&self, interleaved: &[i32]) -> Vec<u8> {
+        let channels = self.channels as usize;
+        let block_size = interleaved.len() / channels;
+
+        let mut out = BitWriter::new();
+        // Sync code, reserved bit, fixed blocking strategy
+        out.write(FRAME_SYNC, 16);
+        // Block size: 16-bit (size - 1) at the end of the header
+        out.write(0b0111, 4);
+        out.write(sample_rate_code(self.sample_rate), 4);
+        // Independent channels, 16 bits per sample, reserved bit
+        out.write(channels as u64 - 1, 4);
+        out.write(0b100, 3);
+        out.write(0, 1);
+        write_coded_number(&mut out, self.frame_number);
+        out.write(block_size as u64 - 1, 16);
+        let header_crc = crc8(out.bytes());
+        out.write(header_crc as u64, 8);
+
+        for channel in 0..channels {
+            let samples: Vec<i32> = interleaved
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .copied()
+                .collect();
+            write_subframe(&mut out, &samples);
+        }
+
+        let mut frame = out.finish();
+        let crc = crc16(&frame);
+        frame.extend_from_slice(&crc.to_be_bytes());
+        frame
+    }
+
+    /// Write the final partial block and patch STREAMINFO with the totals
+    pub fn finish(mut self) -> Result<W, anyhow::Error> {
+        let channels = self.channels as usize;
+        let remainder = self.pending.len() / channels * channels;
+        if remainder > 0 {
+            let frame = self.encode_frame(&self.pending[..remainder]);
+            self.write_frame(frame, remainder / channels)?;
+        }
+        self.pending.clear();
+
+        let end = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(self.streaminfo_offset))?;
+        self.write_streaminfo()?;
+        self.writer.seek(SeekFrom::Start(end))?;
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
@@ -0,0 +1,74 @@
+//! FLAC renderer
+//!
+//! Decodes the WAL through `PcmStream` and writes 16-bit FLAC, with session and player
+//! metadata in a `VORBIS_COMMENT` block.
+
+mod encoder;
+
+#[cfg(test)]
+mod tests;
+
+use encoder::FlacEncoder;
+
+use super::vorbis_comment::VorbisComment;
+use super::{AudioRenderer, PcmChunk, PcmStream};
+use async_trait::async_trait;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// FLAC renderer that outputs losslessly compressed PCM
+pub struct FlacRenderer;
+
+impl FlacRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for FlacRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AudioRenderer for FlacRenderer {
+    async fn render(
+        &mut self,
+        session_path: &Path,
+        player_name: &str,
+        output_path: &Path,
+    ) -> Result<(), anyhow::Error> {
+        let stream = PcmStream::new(session_path, player_name)?;
+        let info = stream
+            .info()
+            .ok_or_else(|| anyhow::anyhow!("No audio data found for player: {}", player_name))?
+            .clone();
+
+        let comment =
+            VorbisComment::from_session(&info.session_info, player_name, info.first_frame_timestamp_ms);
+        let mut encoder = FlacEncoder::new(
+            BufWriter::new(File::create(output_path)?),
+            info.sample_rate,
+            info.channels,
+            &comment,
+        )?;
+
+        for chunk in stream {
+            match chunk? {
+                PcmChunk::Audio(samples) => encoder.write_samples(&samples)?,
+                PcmChunk::Silence(samples) => encoder.write_silence(samples)?,
+            }
+        }
+
+        encoder.finish()?;
+
+        log::info!("Rendering {} complete", player_name);
+        Ok(())
+    }
+
+    fn file_extension(&self) -> &str {
+        "flac"
+    }
+}
@@ -0,0 +1,333 @@
+//! Round-trip tests for FLAC output
+//!
+//! A small decoder for the subset the encoder produces reads files back so samples,
+//! STREAMINFO totals, checksums and Vorbis comments can be compared with the input.
+
+use super::encoder::*;
+use super::*;
+use crate::audio::recording::renderer::SessionInfo;
+use std::io::Cursor;
+
+/// Big-endian bit reader
+struct BitReader<'a> {
+    data: &'a [u8],
+    bit: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data, bit: 0 }
+    }
+
+    fn read(&mut self, bits: u32) -> u64 {
+        let mut value = 0u64;
+        for _ in 0..bits {
+            let byte = self.data[self.bit / 8];
+            let b = (byte >> (7 - self.bit % 8)) & 1;
+            value = (value << 1) | b as u64;
+            self.bit += 1;
+        }
+        value
+    }
+
+    fn read_signed(&mut self, bits: u32) -> i64 {
+        let value = self.read(bits) as i64;
+        (value << (64 - bits)) >> (64 - bits)
+    }
+
+    fn read_unary(&mut self) -> u64 {
+        let mut zeros = 0;
+        while self.read(1) == 0 {
+            zeros += 1;
+        }
+        zeros
+    }
+
+    fn align(&mut self) {
+        self.bit = self.bit.div_ceil(8) * 8;
+    }
+
+    fn byte_pos(&self) -> usize {
+        self.bit / 8
+    }
+}
+
+#[derive(Debug)]
+struct Decoded {
+    sample_rate: u32,
+    channels: usize,
+    total_samples: u64,
+    min_frame_size: u64,
+    max_frame_size: u64,
+    comment: VorbisComment,
+    samples: Vec<i32>,
+}
+
+fn decode(data: &[u8]) -> Decoded {
+    assert_eq!(&data[..4], b"fLaC");
+    let mut pos = 4;
+
+    let mut streaminfo = None;
+    let mut comment = None;
+    loop {
+        let last = data[pos] & 0x80 != 0;
+        let block_type = data[pos] & 0x7F;
+        let len = u32::from_be_bytes([0, data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+        let body = &data[pos + 4..pos + 4 + len];
+        match block_type {
+            METADATA_STREAMINFO => streaminfo = Some(body.to_vec()),
+            METADATA_VORBIS_COMMENT => comment = Some(VorbisComment::from_bytes(body).unwrap()),
+            other => panic!("unexpected metadata block {}", other),
+        }
+        pos += 4 + len;
+        if last {
+            break;
+        }
+    }
+
+    let streaminfo = streaminfo.expect("STREAMINFO missing");
+    assert_eq!(streaminfo.len(), 34);
+    let mut info = BitReader::new(&streaminfo);
+    assert_eq!(info.read(16), BLOCK_SIZE as u64);
+    assert_eq!(info.read(16), BLOCK_SIZE as u64);
+    let min_frame_size = info.read(24);
+    let max_frame_size = info.read(24);
+    let sample_rate = info.read(20) as u32;
+    let channels = info.read(3) as usize + 1;
+    assert_eq!(info.read(5) as u32 + 1, BITS_PER_SAMPLE);
+    let total_samples = info.read(36);
+
+    let mut samples = Vec::new();
+    let mut frame_number = 0;
+    while pos < data.len() {
+        let frame = &data[pos..];
+        let mut r = BitReader::new(frame);
+        assert_eq!(r.read(16), FRAME_SYNC);
+        assert_eq!(r.read(4), 0b0111, "expected 16-bit block size");
+        r.read(4);
+        assert_eq!(r.read(4) as usize + 1, channels);
+        assert_eq!(r.read(3), 0b100);
+        assert_eq!(r.read(1), 0);
+
+        // Coded frame number: leading ones give the byte count, as in UTF-8
+        let first = r.read(8);
+        let len = (first as u8).leading_ones();
+        let mut number = if len == 0 { first } else { first & (0xFF >> (len + 1)) };
+        for _ in 1..len {
+            number = (number << 6) | (r.read(8) & 0x3F);
+        }
+        assert_eq!(number, frame_number);
+
+        let block_size = r.read(16) as usize + 1;
+        let header_crc = crc8(&frame[..r.byte_pos()]);
+        assert_eq!(r.read(8) as u8, header_crc, "header CRC mismatch in frame {}", frame_number);
+
+        let mut planar = Vec::with_capacity(channels);
+        for _ in 0..channels {
+            assert_eq!(r.read(1), 0);
+            let kind = r.read(6);
+            assert_eq!(r.read(1), 0, "wasted bits are never written");
+
+            let channel: Vec<i64> = match kind {
+                0 => vec![r.read_signed(BITS_PER_SAMPLE); block_size],
+                1 => (0..block_size).map(|_| r.read_signed(BITS_PER_SAMPLE)).collect(),
+                8..=12 => {
+                    let order = (kind - 8) as usize;
+                    let mut s: Vec<i64> = (0..order).map(|_| r.read_signed(BITS_PER_SAMPLE)).collect();
+                    assert_eq!(r.read(2), 0, "4-bit Rice parameters");
+                    assert_eq!(r.read(4), 0, "single partition");
+                    let parameter = r.read(4) as u32;
+                    for i in order..block_size {
+                        let u = (r.read_unary() << parameter) | r.read(parameter);
+                        let residual = (u >> 1) as i64 ^ -((u & 1) as i64);
+                        let prediction = match order {
+                            0 => 0,
+                            1 => s[i - 1],
+                            2 => 2 * s[i - 1] - s[i - 2],
+                            3 => 3 * s[i - 1] - 3 * s[i - 2] + s[i - 3],
+                            _ => 4 * s[i - 1] - 6 * s[i - 2] + 4 * s[i - 3] - s[i - 4],
+                        };
+                        s.push(prediction + residual);
+                    }
+                    s
+                }
+                other => panic!("unexpected subframe type {}", other),
+            };
+            planar.push(channel);
+        }
+
+        r.align();
+        let frame_crc = crc16(&frame[..r.byte_pos()]);
+        assert_eq!(r.read(16) as u16, frame_crc, "frame CRC mismatch in frame {}", frame_number);
+
+        let frame_len = r.byte_pos() as u64;
+        assert!(frame_len >= min_frame_size && frame_len <= max_frame_size);
+
+        for i in 0..block_size {
+            for channel in &planar {
+                samples.push(channel[i] as i32);
+            }
+        }
+
+        pos += r.byte_pos();
+        frame_number += 1;
+    }
+
+    Decoded {
+        sample_rate,
+        channels,
+        total_samples,
+        min_frame_size,
+        max_frame_size,
+        comment: comment.expect("VORBIS_COMMENT missing"),
+        samples,
+    }
+}
+
+fn quantize(samples: &[f32]) -> Vec<i32> {
+    samples
+        .iter()
+        .map(|s| (s.clamp(-1.0, 1.0) * 32767.0).round() as i32)
+        .collect()
+}
+
+fn comment() -> VorbisComment {
+    let info = SessionInfo {
+        session_id: "session".to_string(),
+        start_timestamp: 1705329045000,
+        player_name: "Steve".to_string(),
+        duration_ms: None,
+    };
+    VorbisComment::from_session(&info, "Alex", 40)
+}
+
+fn encode(sample_rate: u32, channels: u16, chunks: &[Option<Vec<f32>>]) -> Vec<u8> {
+    let mut encoder =
+        FlacEncoder::new(Cursor::new(Vec::new()), sample_rate, channels, &comment()).unwrap();
+    for chunk in chunks {
+        match chunk {
+            Some(samples) => encoder.write_samples(samples).unwrap(),
+            None => encoder.write_silence(960 * channels as usize).unwrap(),
+        }
+    }
+    encoder.finish().unwrap().into_inner()
+}
+
+#[test]
+fn test_crc_check_values() {
+    assert_eq!(crc8(b"123456789"), 0xF4);
+    assert_eq!(crc16(b"123456789"), 0xFEE8);
+}
+
+#[test]
+fn test_coded_numbers_match_utf8() {
+    for (value, expected) in [
+        (0x24u64, vec![0x24]),
+        (0x80, vec![0xC2, 0x80]),
+        (0x20AC, vec![0xE2, 0x82, 0xAC]),
+        (0x10348, vec![0xF0, 0x90, 0x8D, 0x88]),
+    ] {
+        let mut out = BitWriter::new();
+        write_coded_number(&mut out, value);
+        assert_eq!(out.finish(), expected, "coded number for {:#x}", value);
+    }
+}
+
+#[test]
+fn test_mono_round_trip() {
+    // Speech-like tone, a constant run, noise and silence, in 20ms chunks
+    let tone: Vec<f32> = (0..960 * 10)
+        .map(|i| (i as f32 * 0.05).sin() * 0.5)
+        .collect();
+    let mut seed = 0x1234_5678u32;
+    let noise: Vec<f32> = (0..960 * 3)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 8) as f32 / (1u32 << 24) as f32 * 2.0 - 1.0
+        })
+        .collect();
+
+    let mut chunks: Vec<Option<Vec<f32>>> = tone.chunks(960).map(|c| Some(c.to_vec())).collect();
+    chunks.push(Some(vec![0.25; 960]));
+    chunks.push(None);
+    chunks.extend(noise.chunks(960).map(|c| Some(c.to_vec())));
+
+    let mut expected = Vec::new();
+    for chunk in &chunks {
+        match chunk {
+            Some(samples) => expected.extend(quantize(samples)),
+            None => expected.extend(std::iter::repeat_n(0, 960)),
+        }
+    }
+
+    let decoded = decode(&encode(48000, 1, &chunks));
+    assert_eq!(decoded.sample_rate, 48000);
+    assert_eq!(decoded.channels, 1);
+    assert_eq!(decoded.total_samples, expected.len() as u64);
+    assert!(decoded.min_frame_size > 0 && decoded.min_frame_size <= decoded.max_frame_size);
+    assert_eq!(decoded.samples, expected);
+    assert_eq!(decoded.comment, comment());
+}
+
+#[test]
+fn test_stereo_round_trip() {
+    let samples: Vec<f32> = (0..BLOCK_SIZE * 2 + 100)
+        .flat_map(|i| {
+            let t = i as f32 * 0.01;
+            [t.sin() * 0.8, (t * 3.0).cos() * -0.3]
+        })
+        .collect();
+    let chunks = vec![Some(samples.clone())];
+
+    let decoded = decode(&encode(44100, 2, &chunks));
+    assert_eq!(decoded.sample_rate, 44100);
+    assert_eq!(decoded.channels, 2);
+    assert_eq!(decoded.total_samples, (BLOCK_SIZE * 2 + 100) as u64);
+    assert_eq!(decoded.samples, quantize(&samples));
+}
+
+#[test]
+fn test_clipping_is_clamped() {
+    let chunks = vec![Some(vec![2.0, -2.0, 1.0, -1.0])];
+    let decoded = decode(&encode(16000, 1, &chunks));
+    assert_eq!(decoded.samples, vec![32767, -32767, 32767, -32767]);
+}
+
+/// Render a real session and decode it back (environment-based)
+///
+/// Set TEST_SESSION_PATH and TEST_PLAYER_NAME to run.
+#[tokio::test]
+async fn test_render_round_trip() {
+    let session_path = match std::env::var("TEST_SESSION_PATH") {
+        Ok(p) => p,
+        Err(_) => {
+            println!("Skipping: TEST_SESSION_PATH not set");
+            return;
+        }
+    };
+    let player = std::env::var("TEST_PLAYER_NAME").unwrap_or_else(|_| "Alaydriem".to_string());
+    let session = Path::new(&session_path);
+
+    let output_path = std::env::temp_dir().join(format!("{}_test_render.flac", player));
+    FlacRenderer::new()
+        .render(session, &player, &output_path)
+        .await
+        .expect("Render failed");
+
+    let stream = PcmStream::new(session, &player).unwrap();
+    let channels = stream.info().unwrap().channels as usize;
+    let mut expected = Vec::new();
+    for chunk in stream {
+        match chunk.unwrap() {
+            PcmChunk::Audio(samples) => expected.extend(quantize(&samples)),
+            PcmChunk::Silence(samples) => expected.extend(std::iter::repeat_n(0, samples)),
+        }
+    }
+    expected.truncate(expected.len() / channels * channels);
+
+    let decoded = decode(&std::fs::read(&output_path).unwrap());
+    assert_eq!(decoded.channels, channels);
+    assert_eq!(decoded.total_samples, (expected.len() / channels) as u64);
+    assert_eq!(decoded.samples, expected);
+    assert_eq!(decoded.comment.get("BVC_PLAYER"), Some(player.as_str()));
+}
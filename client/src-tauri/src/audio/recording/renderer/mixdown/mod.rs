@@ -0,0 +1,503 @@
+//! Session mixdown
+//!
+//! Mixes every participant's WAL into a single stereo timeline. Frames are aligned on
+//! `relative_timestamp_ms`, scaled by the gain settings recorded with each frame and,
+//! optionally, re-panned from the emitter/listener positions stored in `OutputRecordingHeader`
+//! as heard from a chosen `Perspective`.
+
+mod perspective;
+mod stems;
+
+pub use perspective::{Perspective, SpatialScene};
+pub use stems::SessionStems;
+
+use crate::audio::recording::renderer::{SessionInfo, WalAudioReader};
+use common::structs::recording::{RecordingHeader, SessionManifest};
+use common::structs::SpatialAudioConfig;
+use std::path::Path;
+
+/// Sample rate of the mixed timeline
+pub const MIX_SAMPLE_RATE: u32 = 48000;
+/// The mix is always interleaved stereo
+pub const MIX_CHANNELS: u16 = 2;
+
+const OPUS_FRAME_MS: u64 = 20;
+// Same tolerance WalAudioReader uses to treat packets as consecutive
+const NETWORK_JITTER_TOLERANCE_MS: u64 = 39;
+// Matches the volume non-spatial sinks play back at
+const NON_SPATIAL_VOLUME: f32 = 1.3;
+// Upper bound on sample frames handed to the sink at once when draining long gaps
+const MAX_DRAIN_FRAMES: usize = MIX_SAMPLE_RATE as usize;
+
+/// Options for a session mixdown
+#[derive(Debug, Clone, Default)]
+pub struct MixdownOptions {
+    /// Participants to include. All participants are mixed when empty.
+    pub players: Vec<String>,
+    /// Re-apply recorded spatial panning and distance attenuation
+    pub spatial: bool,
+    /// Whose ears the spatial mix is rendered through when `spatial` is enabled
+    pub perspective: Perspective,
+    /// Overrides the spatial settings recorded with the session
+    pub spatial_config: Option<SpatialAudioConfig>,
+}
+
+/// Every player with audio in a session: the recording player first, then remote participants
+pub fn session_participants(manifest: &SessionManifest) -> Vec<String> {
+    let mut players = vec![manifest.emitter_player.clone()];
+    for participant in &manifest.participants {
+        if !players.contains(participant) {
+            players.push(participant.clone());
+        }
+    }
+    players
+}
+
+fn ms_to_samples(ms: u64) -> u64 {
+    ms * MIX_SAMPLE_RATE as u64 / 1000
+}
+
+/// A decoded frame positioned on the mix timeline
+#[derive(Debug)]
+struct PlacedFrame {
+    /// Position on the timeline, in sample frames from session start
+    start: u64,
+    /// Interleaved stereo samples with gain and panning applied
+    samples: Vec<f32>,
+}
+
+/// Left/right amplitude for a frame of `emitter`, or `None` if the emitter was muted
+fn frame_gains(
+    emitter: &str,
+    header: &RecordingHeader,
+    timestamp_ms: u64,
+    scene: Option<&SpatialScene>,
+) -> Option<(f32, f32)> {
+    let metadata = match header {
+        RecordingHeader::Input(h) => &h.emitter_metadata,
+        RecordingHeader::Output(h) => &h.emitter_metadata,
+    };
+
+    let gain = match &metadata.gain_settings {
+        Some(settings) if settings.muted => return None,
+        Some(settings) => settings.amplitude(),
+        None => 1.0,
+    };
+
+    match scene.and_then(|scene| scene.spatialize(emitter, header, timestamp_ms)) {
+        Some(data) => {
+            let pan = data.pan.clamp(-1.0, 1.0);
+            let left = ((1.0 + pan) / 2.0).sqrt();
+            let right = ((1.0 - pan) / 2.0).sqrt();
+            Some((left * data.volume * gain, right * data.volume * gain))
+        }
+        None => Some((gain * NON_SPATIAL_VOLUME, gain * NON_SPATIAL_VOLUME)),
+    }
+}
+
+/// Resample a decoded frame to the mix rate and apply per-channel gain
+fn to_mix_frame(pcm: &[f32], sample_rate: u32, channels: u16, gains: (f32, f32)) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let frames_in = pcm.len() / channels;
+    let frames_out = frames_in as u64 * MIX_SAMPLE_RATE as u64 / sample_rate.max(1) as u64;
+
+    let mut out = Vec::with_capacity(frames_out as usize * MIX_CHANNELS as usize);
+    for i in 0..frames_out {
+        let src = ((i * sample_rate as u64 / MIX_SAMPLE_RATE as u64) as usize).min(frames_in - 1);
+        let (left, right) = if channels == 1 {
+            (pcm[src], pcm[src])
+        } else {
+            (pcm[src * channels], pcm[src * channels + 1])
+        };
+        out.push(left * gains.0);
+        out.push(right * gains.1);
+    }
+    out
+}
+
+/// One participant's frames in timeline order
+struct ParticipantTrack {
+    player_name: String,
+    reader: WalAudioReader,
+    // Timeline position right after the previously placed frame
+    cursor: Option<u64>,
+    last_timestamp_ms: Option<u64>,
+}
+
+impl ParticipantTrack {
+    fn first_timestamp_ms(&self) -> Option<u64> {
+        self.reader.peek_raw_entry().map(|e| e.relative_timestamp_ms)
+    }
+
+    /// Timeline position for a frame: consecutive packets are butted up against the previous
+    /// frame to avoid jitter clicks, anything after a real gap goes back to its timestamp.
+    fn place(&mut self, timestamp_ms: u64, len: u64) -> u64 {
+        let at_timestamp = ms_to_samples(timestamp_ms);
+        let start = match (self.cursor, self.last_timestamp_ms) {
+            (Some(cursor), Some(last)) => {
+                let gap_ms = timestamp_ms.saturating_sub(last).saturating_sub(OPUS_FRAME_MS);
+                if gap_ms <= NETWORK_JITTER_TOLERANCE_MS {
+                    cursor
+                } else {
+                    at_timestamp.max(cursor)
+                }
+            }
+            _ => at_timestamp,
+        };
+
+        self.cursor = Some(start + len);
+        self.last_timestamp_ms = Some(timestamp_ms);
+        start
+    }
+
+    /// Decode and place the next audible frame. `gains` returns left/right amplitude for a
+    /// frame of this player given its header and timestamp, or `None` to drop it.
+    fn next_frame<G>(&mut self, gains: &G) -> Result<Option<PlacedFrame>, anyhow::Error>
+    where
+        G: Fn(&str, &RecordingHeader, u64) -> Option<(f32, f32)>,
+    {
+        loop {
+            let (header, timestamp_ms) = match self.reader.peek_raw_entry() {
+                Some(entry) => (entry.header.clone(), entry.relative_timestamp_ms),
+                None => return Ok(None),
+            };
+
+            let frame = match self.reader.next_frame()? {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+
+            let gains = gains(&self.player_name, &header, timestamp_ms);
+            let samples = to_mix_frame(
+                &frame.pcm_data,
+                frame.sample_rate,
+                frame.channels,
+                gains.unwrap_or((0.0, 0.0)),
+            );
+            let start = self.place(
+                frame.relative_timestamp_ms,
+                (samples.len() / MIX_CHANNELS as usize) as u64,
+            );
+
+            // Muted frames still advance the cursor so the next audible frame lands correctly
+            if gains.is_some() && !samples.is_empty() {
+                return Ok(Some(PlacedFrame { start, samples }));
+            }
+        }
+    }
+}
+
+/// Accumulates overlapping frames and releases samples once no later frame can touch them
+struct MixBus {
+    // Timeline position of buffer[0]
+    start: u64,
+    channels: usize,
+    buffer: Vec<f32>,
+}
+
+impl MixBus {
+    fn new(start: u64, channels: usize) -> Self {
+        Self {
+            start,
+            channels,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Make room for `frames` sample frames from timeline position `start`, returning the
+    /// buffer offset of that position
+    fn reserve(&mut self, start: u64, frames: usize) -> usize {
+        let offset = start.saturating_sub(self.start) as usize * self.channels;
+        let end = offset + frames * self.channels;
+        if self.buffer.len() < end {
+            self.buffer.resize(end, 0.0);
+        }
+        offset
+    }
+
+    /// Sum a stereo frame into a stereo bus
+    fn add(&mut self, frame: &PlacedFrame) {
+        let offset = self.reserve(frame.start, frame.samples.len() / MIX_CHANNELS as usize);
+        let end = offset + frame.samples.len();
+        for (mixed, sample) in self.buffer[offset..end].iter_mut().zip(&frame.samples) {
+            *mixed += sample;
+        }
+    }
+
+    /// Sum a stereo frame, folded down to mono, into one channel of the bus
+    fn add_to_channel(&mut self, frame: &PlacedFrame, channel: usize) {
+        let frames = frame.samples.len() / MIX_CHANNELS as usize;
+        let offset = self.reserve(frame.start, frames);
+        for (i, pair) in frame.samples.chunks_exact(MIX_CHANNELS as usize).enumerate() {
+            self.buffer[offset + i * self.channels + channel] += (pair[0] + pair[1]) / 2.0;
+        }
+    }
+
+    /// Hand every sample before `until` to `sink`, clamped to full scale
+    fn drain_until<F>(&mut self, until: u64, sink: &mut F) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(&[f32]) -> Result<(), anyhow::Error>,
+    {
+        if until <= self.start {
+            return Ok(());
+        }
+
+        let mut remaining = (until - self.start) as usize * self.channels;
+        self.start = until;
+
+        let buffered = remaining.min(self.buffer.len());
+        if buffered > 0 {
+            let mut samples: Vec<f32> = self.buffer.drain(..buffered).collect();
+            for sample in samples.iter_mut() {
+                *sample = sample.clamp(-1.0, 1.0);
+            }
+            sink(&samples)?;
+            remaining -= buffered;
+        }
+
+        // Silence between frames
+        let max_chunk = MAX_DRAIN_FRAMES * self.channels;
+        while remaining > 0 {
+            let chunk = remaining.min(max_chunk);
+            sink(&vec![0.0; chunk])?;
+            remaining -= chunk;
+        }
+
+        Ok(())
+    }
+
+    fn finish<F>(&mut self, sink: &mut F) -> Result<(), anyhow::Error>
+    where
+        F: FnMut(&[f32]) -> Result<(), anyhow::Error>,
+    {
+        let end = self.start + (self.buffer.len() / self.channels) as u64;
+        self.drain_until(end, sink)
+    }
+}
+
+/// Open a reader for every player with audio, returning them with the earliest timestamp
+fn open_tracks(
+    session_path: &Path,
+    players: &[String],
+) -> Result<(Vec<ParticipantTrack>, u64), anyhow::Error> {
+    let mut tracks = Vec::new();
+    for player_name in players {
+        let reader = WalAudioReader::new(session_path, player_name)?;
+        if reader.peek_raw_entry().is_none() {
+            log::debug!("No audio for {} in {:?}", player_name, session_path);
+            continue;
+        }
+        tracks.push(ParticipantTrack {
+            player_name: player_name.clone(),
+            reader,
+            cursor: None,
+            last_timestamp_ms: None,
+        });
+    }
+
+    let origin_ms = tracks
+        .iter()
+        .filter_map(|t| t.first_timestamp_ms())
+        .min()
+        .ok_or_else(|| anyhow::anyhow!("No audio data found in {:?}", session_path))?;
+
+    Ok((tracks, origin_ms))
+}
+
+/// Pull frames from every track in timeline order, handing each to `place` and draining the
+/// bus up to it. Returns the number of sample frames written.
+fn merge_tracks<G, P, F>(
+    tracks: &mut [ParticipantTrack],
+    bus: &mut MixBus,
+    gains: &G,
+    mut place: P,
+    sink: &mut F,
+) -> Result<u64, anyhow::Error>
+where
+    G: Fn(&str, &RecordingHeader, u64) -> Option<(f32, f32)>,
+    P: FnMut(&mut MixBus, usize, &PlacedFrame),
+    F: FnMut(&[f32]) -> Result<(), anyhow::Error>,
+{
+    let origin = bus.start;
+
+    let mut pending: Vec<Option<PlacedFrame>> = Vec::with_capacity(tracks.len());
+    for track in tracks.iter_mut() {
+        pending.push(track.next_frame(gains)?);
+    }
+
+    // Frames are pulled in start order across all tracks, so once a frame is added
+    // nothing earlier than its start can change.
+    while let Some(index) = pending
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| f.as_ref().map(|f| (i, f.start)))
+        .min_by_key(|(_, start)| *start)
+        .map(|(i, _)| i)
+    {
+        let frame = pending[index].take().expect("selected frame is pending");
+        bus.drain_until(frame.start, sink)?;
+        place(bus, index, &frame);
+        pending[index] = tracks[index].next_frame(gains)?;
+    }
+
+    bus.finish(sink)?;
+
+    for track in tracks.iter() {
+        log::info!(
+            "Mixed {} ({} entries)",
+            track.player_name,
+            track.reader.entries_read()
+        );
+    }
+
+    Ok(bus.start - origin)
+}
+
+/// An opened session, ready to be mixed
+pub struct SessionMix {
+    session_info: SessionInfo,
+    tracks: Vec<ParticipantTrack>,
+    origin_ms: u64,
+    scene: Option<SpatialScene>,
+}
+
+impl SessionMix {
+    pub fn open(session_path: &Path, options: MixdownOptions) -> Result<Self, anyhow::Error> {
+        let manifest: SessionManifest = serde_json::from_str(&std::fs::read_to_string(
+            session_path.join("session.json"),
+        )?)?;
+
+        let players: Vec<String> = session_participants(&manifest)
+            .into_iter()
+            .filter(|p| options.players.is_empty() || options.players.contains(p))
+            .collect();
+
+        let (tracks, origin_ms) = open_tracks(session_path, &players)?;
+
+        let scene = if options.spatial {
+            let config = options
+                .spatial_config
+                .or_else(|| manifest.spatial_config.clone())
+                .unwrap_or_default();
+            Some(SpatialScene::load(session_path, &manifest, options.perspective, config)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            session_info: SessionInfo::load(session_path)?,
+            tracks,
+            origin_ms,
+            scene,
+        })
+    }
+
+    pub fn session_info(&self) -> &SessionInfo {
+        &self.session_info
+    }
+
+    /// Relative timestamp of the first audible frame, where the mix begins
+    pub fn origin_ms(&self) -> u64 {
+        self.origin_ms
+    }
+
+    /// Players with audio in the mix
+    pub fn player_names(&self) -> Vec<String> {
+        self.tracks.iter().map(|t| t.player_name.clone()).collect()
+    }
+
+    /// Mix all tracks, handing interleaved stereo samples to `sink` in timeline order.
+    /// Returns the number of sample frames written.
+    pub fn run<F>(mut self, mut sink: F) -> Result<u64, anyhow::Error>
+    where
+        F: FnMut(&[f32]) -> Result<(), anyhow::Error>,
+    {
+        let mut bus = MixBus::new(ms_to_samples(self.origin_ms), MIX_CHANNELS as usize);
+        let scene = self.scene.as_ref();
+        merge_tracks(
+            &mut self.tracks,
+            &mut bus,
+            &|emitter: &str, header: &RecordingHeader, timestamp_ms: u64| {
+                frame_gains(emitter, header, timestamp_ms, scene)
+            },
+            |bus, _, frame| bus.add(frame),
+            &mut sink,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(bus: &mut MixBus, until: u64) -> Vec<f32> {
+        let mut out = Vec::new();
+        bus.drain_until(until, &mut |s: &[f32]| {
+            out.extend_from_slice(s);
+            Ok(())
+        })
+        .unwrap();
+        out
+    }
+
+    #[test]
+    fn overlapping_frames_are_summed_and_clamped() {
+        let mut bus = MixBus::new(10, 2);
+        bus.add(&PlacedFrame { start: 10, samples: vec![0.25; 8] });
+        bus.add(&PlacedFrame { start: 12, samples: vec![0.9; 8] });
+
+        let out = collect(&mut bus, 16);
+        assert_eq!(out.len(), 12);
+        assert_eq!(&out[..4], &[0.25; 4]);
+        assert_eq!(&out[4..8], &[1.0; 4]);
+        assert_eq!(&out[8..], &[0.9; 4]);
+    }
+
+    #[test]
+    fn gaps_between_frames_are_silent() {
+        let mut bus = MixBus::new(0, 2);
+        bus.add(&PlacedFrame { start: 0, samples: vec![0.5; 2] });
+
+        let out = collect(&mut bus, 3);
+        assert_eq!(out, vec![0.5, 0.5, 0.0, 0.0, 0.0, 0.0]);
+        assert_eq!(bus.start, 3);
+    }
+
+    #[test]
+    fn stem_channels_are_folded_to_mono() {
+        let mut bus = MixBus::new(0, 3);
+        bus.add_to_channel(&PlacedFrame { start: 1, samples: vec![0.2, 0.4, 0.6, 0.8] }, 1);
+        bus.add_to_channel(&PlacedFrame { start: 0, samples: vec![0.5, 0.5] }, 2);
+
+        let out = collect(&mut bus, 3);
+        let expected = [0.0, 0.0, 0.5, 0.0, 0.3, 0.0, 0.0, 0.7, 0.0];
+        assert_eq!(out.len(), expected.len());
+        for (a, b) in out.iter().zip(expected) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn mono_frames_are_upmixed_and_resampled() {
+        let out = to_mix_frame(&[0.5, -0.5], 24000, 1, (1.0, 0.5));
+        assert_eq!(out, vec![0.5, 0.25, 0.5, 0.25, -0.5, -0.25, -0.5, -0.25]);
+    }
+
+    #[test]
+    fn session_participants_lists_recorder_first_without_duplicates() {
+        let manifest = SessionManifest {
+            session_id: "s".to_string(),
+            start_timestamp: 0,
+            end_timestamp: None,
+            duration_ms: None,
+            emitter_player: "Alice".to_string(),
+            participants: vec!["Bob".to_string(), "Alice".to_string()],
+            created_at: "0".to_string(),
+            recovered: false,
+            pinned: false,
+            markers: Vec::new(),
+            spatial_config: None,
+        };
+        assert_eq!(session_participants(&manifest), vec!["Alice", "Bob"]);
+    }
+}
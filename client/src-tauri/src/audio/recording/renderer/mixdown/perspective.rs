@@ -0,0 +1,319 @@
+//! Listener perspectives
+//!
+//! Re-runs the client's spatial pipeline over the positions stored in `OutputRecordingHeader`
+//! from a chosen point of view: the recording player, another participant, or a fixed camera.
+//! A participant's position is only known while they are heard (or, for the recording player,
+//! while anyone is heard), so between frames their last known position is held.
+
+use crate::audio::recording::renderer::segment::{segment_files, WalSegment};
+use crate::audio::stream::jitter_buffer::{JitterBuffer, SpatialAudioData};
+use common::structs::recording::{PlayerMetadata, RecordingHeader, SessionManifest};
+use common::structs::SpatialAudioConfig;
+use common::traits::player_data::PlayerData;
+use common::{Coordinate, Game, Orientation};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Whose ears a spatial mixdown is rendered through
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Perspective {
+    /// What the recording player heard
+    #[default]
+    Recorder,
+    /// What another participant would have heard from their own recorded positions
+    Participant { name: String },
+    /// A free camera fixed at one position
+    Camera {
+        position: Coordinate,
+        orientation: Orientation,
+        game: Game,
+    },
+}
+
+/// Where a player was and which way they faced
+#[derive(Debug, Clone, PartialEq)]
+struct Pose {
+    position: Coordinate,
+    orientation: Orientation,
+    game: Game,
+    deafened: bool,
+}
+
+impl Pose {
+    fn from_metadata(metadata: &PlayerMetadata) -> Option<Self> {
+        metadata.player_data.as_ref().map(|player| Self {
+            position: player.get_position().clone(),
+            orientation: player.get_orientation().clone(),
+            game: player.get_game(),
+            deafened: player.is_deafened(),
+        })
+    }
+}
+
+/// A player's recorded poses, ordered by timestamp
+#[derive(Debug, Default)]
+struct PoseTrack {
+    poses: Vec<(u64, Pose)>,
+}
+
+impl PoseTrack {
+    fn push(&mut self, timestamp_ms: u64, pose: Pose) {
+        self.poses.push((timestamp_ms, pose));
+    }
+
+    fn sort(&mut self) {
+        self.poses.sort_by_key(|(timestamp, _)| *timestamp);
+    }
+
+    /// The latest pose at or before `timestamp_ms`, falling back to the first one recorded
+    fn at(&self, timestamp_ms: u64) -> Option<&Pose> {
+        let index = self.poses.partition_point(|(timestamp, _)| *timestamp <= timestamp_ms);
+        self.poses
+            .get(index.saturating_sub(1))
+            .map(|(_, pose)| pose)
+    }
+}
+
+/// Recorded positions of every participant, for rendering a session from a perspective
+pub struct SpatialScene {
+    perspective: Perspective,
+    config: SpatialAudioConfig,
+    recorder: String,
+    tracks: HashMap<String, PoseTrack>,
+}
+
+impl SpatialScene {
+    /// Collect every recorded position in a session. Fails when a participant perspective
+    /// names a player whose position was never recorded.
+    pub fn load(
+        session_path: &Path,
+        manifest: &SessionManifest,
+        perspective: Perspective,
+        config: SpatialAudioConfig,
+    ) -> Result<Self, anyhow::Error> {
+        let mut scene = Self {
+            perspective,
+            config,
+            recorder: manifest.emitter_player.clone(),
+            tracks: HashMap::new(),
+        };
+
+        if scene.perspective != Perspective::Recorder {
+            for file_path in segment_files(&session_path.join("wal"), None)? {
+                let file_name = file_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let Some(player) = WalSegment::player_name(&file_name).map(str::to_string) else {
+                    continue;
+                };
+
+                for entry in WalSegment::read(&file_path)?.entries {
+                    scene.add(&player, &entry.header, entry.relative_timestamp_ms);
+                }
+            }
+            scene.tracks.values_mut().for_each(PoseTrack::sort);
+        }
+
+        if let Perspective::Participant { name } = &scene.perspective
+            && !scene.tracks.contains_key(name)
+        {
+            return Err(anyhow::anyhow!("No positions were recorded for {}", name));
+        }
+
+        Ok(scene)
+    }
+
+    /// Record the poses carried by one WAL entry of `emitter`
+    fn add(&mut self, emitter: &str, header: &RecordingHeader, timestamp_ms: u64) {
+        // The recording player is the listener of every output frame
+        if let RecordingHeader::Output(h) = header {
+            if let Some(pose) = Pose::from_metadata(&h.emitter_metadata) {
+                self.tracks.entry(emitter.to_string()).or_default().push(timestamp_ms, pose);
+            }
+            if let Some(pose) = Pose::from_metadata(&h.listener_metadata) {
+                self.tracks.entry(self.recorder.clone()).or_default().push(timestamp_ms, pose);
+            }
+        }
+    }
+
+    /// Pan and volume for a frame of `emitter`, or `None` when it should play unspatialized
+    pub fn spatialize(
+        &self,
+        emitter: &str,
+        header: &RecordingHeader,
+        timestamp_ms: u64,
+    ) -> Option<SpatialAudioData> {
+        let (emitter_pose, recorded_listener) = match header {
+            RecordingHeader::Output(h) if !h.is_spatial => return None,
+            RecordingHeader::Output(h) => (
+                Pose::from_metadata(&h.emitter_metadata),
+                Pose::from_metadata(&h.listener_metadata),
+            ),
+            // Input frames carry no position; the recording player is where the track says
+            RecordingHeader::Input(_) => (self.pose_of(emitter, timestamp_ms), None),
+        };
+
+        let listener = match &self.perspective {
+            Perspective::Recorder => recorded_listener?,
+            Perspective::Participant { name } if name == emitter => {
+                return Some(SpatialAudioData { pan: 0.0, volume: 1.0 });
+            }
+            Perspective::Participant { name } => self.pose_of(name, timestamp_ms)?,
+            Perspective::Camera { position, orientation, game } => Pose {
+                position: position.clone(),
+                orientation: orientation.clone(),
+                game: game.clone(),
+                deafened: false,
+            },
+        };
+        let emitter_pose = emitter_pose?;
+
+        Some(JitterBuffer::calculate_spatial_audio_data(
+            &emitter_pose.position,
+            emitter_pose.deafened,
+            &listener.position,
+            &listener.orientation,
+            listener.game,
+            &self.config,
+        ))
+    }
+
+    fn pose_of(&self, player: &str, timestamp_ms: u64) -> Option<Pose> {
+        self.tracks.get(player)?.at(timestamp_ms).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::recording::renderer::segment::input_header;
+
+    fn pose(x: f32) -> Pose {
+        Pose {
+            position: Coordinate { x, y: 0.0, z: 0.0 },
+            orientation: Orientation { x: 0.0, y: 0.0 },
+            game: Game::Minecraft,
+            deafened: false,
+        }
+    }
+
+    fn scene(perspective: Perspective, tracks: Vec<(&str, Vec<(u64, Pose)>)>) -> SpatialScene {
+        SpatialScene {
+            perspective,
+            config: SpatialAudioConfig::default(),
+            recorder: "Recorder".to_string(),
+            tracks: tracks
+                .into_iter()
+                .map(|(name, poses)| (name.to_string(), PoseTrack { poses }))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_pose_track_holds_last_known_position() {
+        let track = PoseTrack { poses: vec![(100, pose(1.0)), (200, pose(2.0))] };
+        assert_eq!(track.at(0), Some(&pose(1.0)));
+        assert_eq!(track.at(100), Some(&pose(1.0)));
+        assert_eq!(track.at(199), Some(&pose(1.0)));
+        assert_eq!(track.at(5000), Some(&pose(2.0)));
+        assert_eq!(PoseTrack::default().at(0), None);
+    }
+
+    #[test]
+    fn test_participant_hears_recorder_input_from_recorded_position() {
+        let scene = scene(
+            Perspective::Participant { name: "Alex".to_string() },
+            vec![
+                ("Recorder", vec![(0, pose(20.0))]),
+                ("Alex", vec![(0, pose(0.0))]),
+            ],
+        );
+        let header = input_header(50);
+
+        // Minecraft facing south: an emitter to the east is on the left
+        let data = scene.spatialize("Recorder", &header, 50).unwrap();
+        assert!(data.pan > 0.5, "Expected left pan, got {}", data.pan);
+        assert!(data.volume < 1.0);
+
+        // A participant hears themselves centred
+        let own = scene.spatialize("Alex", &header, 50).unwrap();
+        assert_eq!((own.pan, own.volume), (0.0, 1.0));
+    }
+
+    #[test]
+    fn test_camera_and_recorder_perspectives() {
+        let camera = scene(
+            Perspective::Camera {
+                position: Coordinate { x: 100.0, y: 0.0, z: 0.0 },
+                orientation: Orientation { x: 0.0, y: 0.0 },
+                game: Game::Minecraft,
+            },
+            vec![("Recorder", vec![(0, pose(0.0))])],
+        );
+        let header = input_header(0);
+
+        // Beyond the falloff distance the camera hears nothing
+        assert_eq!(camera.spatialize("Recorder", &header, 0).unwrap().volume, 0.0);
+
+        // Input frames have no recorded listener, so the recorder's own voice stays unspatialized
+        let recorder = scene(Perspective::Recorder, Vec::new());
+        assert!(recorder.spatialize("Recorder", &header, 0).is_none());
+    }
+
+    /// Output frame as the server recorder writes it: the speaker's position and no listener
+    fn server_header(x: f32, spatial: Option<bool>) -> RecordingHeader {
+        use common::structs::recording::OutputRecordingHeader;
+        use common::{GenericPlayer, PlayerEnum};
+
+        RecordingHeader::Output(OutputRecordingHeader {
+            sample_rate: 48000,
+            channels: 1,
+            relative_timestamp_ms: 0,
+            emitter_metadata: PlayerMetadata {
+                player_data: Some(PlayerEnum::Generic(GenericPlayer {
+                    name: "Alex".to_string(),
+                    coordinates: Coordinate { x, y: 0.0, z: 0.0 },
+                    orientation: Orientation { x: 0.0, y: 0.0 },
+                    game: Game::Minecraft,
+                })),
+                spatial,
+                gain_settings: None,
+            },
+            listener_metadata: PlayerMetadata {
+                player_data: None,
+                spatial: None,
+                gain_settings: None,
+            },
+            is_spatial: spatial.unwrap_or(true),
+        })
+    }
+
+    #[test]
+    fn test_server_recording_from_camera() {
+        let mut camera = scene(
+            Perspective::Camera {
+                position: Coordinate { x: 0.0, y: 0.0, z: 0.0 },
+                orientation: Orientation { x: 0.0, y: 0.0 },
+                game: Game::Minecraft,
+            },
+            Vec::new(),
+        );
+        let header = server_header(20.0, None);
+        camera.add("Alex", &header, 0);
+
+        // Minecraft facing south: an emitter to the east is on the left
+        let data = camera.spatialize("Alex", &header, 0).unwrap();
+        assert!(data.pan > 0.5, "Expected left pan, got {}", data.pan);
+        assert!(data.volume > 0.0 && data.volume < 1.0);
+
+        // Frames sent non-spatial stay that way
+        assert!(camera.spatialize("Alex", &server_header(20.0, Some(false)), 0).is_none());
+
+        // There is no recorded listener to hear server recordings through
+        let recorder = scene(Perspective::Recorder, Vec::new());
+        assert!(recorder.spatialize("Alex", &header, 0).is_none());
+    }
+}
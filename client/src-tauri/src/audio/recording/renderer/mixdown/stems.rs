@@ -0,0 +1,77 @@
+//! Time-aligned stems
+//!
+//! Renders each participant onto its own channel of a shared timeline, without gain or
+//! panning, so editors get isolated speakers that line up sample for sample.
+
+use super::{merge_tracks, ms_to_samples, open_tracks, session_participants, MixBus, ParticipantTrack};
+use crate::audio::recording::renderer::{file_name::player_file_stems, SessionInfo};
+use common::structs::recording::{RecordingHeader, SessionManifest};
+use std::path::Path;
+
+/// An opened session with one channel per participant
+pub struct SessionStems {
+    session_info: SessionInfo,
+    tracks: Vec<ParticipantTrack>,
+    origin_ms: u64,
+}
+
+impl SessionStems {
+    /// Open the given players, or every participant when `players` is empty
+    pub fn open(session_path: &Path, players: &[String]) -> Result<Self, anyhow::Error> {
+        let manifest: SessionManifest = serde_json::from_str(&std::fs::read_to_string(
+            session_path.join("session.json"),
+        )?)?;
+
+        let players: Vec<String> = session_participants(&manifest)
+            .into_iter()
+            .filter(|p| players.is_empty() || players.contains(p))
+            .collect();
+
+        let (tracks, origin_ms) = open_tracks(session_path, &players)?;
+
+        Ok(Self {
+            session_info: SessionInfo::load(session_path)?,
+            tracks,
+            origin_ms,
+        })
+    }
+
+    pub fn session_info(&self) -> &SessionInfo {
+        &self.session_info
+    }
+
+    /// Relative timestamp of the earliest frame, where every stem begins
+    pub fn origin_ms(&self) -> u64 {
+        self.origin_ms
+    }
+
+    /// Player on each channel, in channel order
+    pub fn player_names(&self) -> Vec<String> {
+        self.tracks.iter().map(|t| t.player_name.clone()).collect()
+    }
+
+    /// Unique, filesystem-safe file stem for each channel, in channel order
+    pub fn file_stems(&self) -> Vec<String> {
+        player_file_stems(&self.player_names())
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.tracks.len() as u16
+    }
+
+    /// Render all stems, handing interleaved samples (one channel per player) to `sink`.
+    /// Returns the number of sample frames written.
+    pub fn run<F>(mut self, mut sink: F) -> Result<u64, anyhow::Error>
+    where
+        F: FnMut(&[f32]) -> Result<(), anyhow::Error>,
+    {
+        let mut bus = MixBus::new(ms_to_samples(self.origin_ms), self.tracks.len());
+        merge_tracks(
+            &mut self.tracks,
+            &mut bus,
+            &|_: &str, _: &RecordingHeader, _: u64| Some((1.0, 1.0)),
+            |bus, channel, frame| bus.add_to_channel(frame, channel),
+            &mut sink,
+        )
+    }
+}
@@ -1,24 +1,45 @@
 mod stream;
 mod bwav;
+pub mod file_name;
+mod flac;
+pub mod mixdown;
 pub mod mp4;
+mod ogg;
+pub mod segment;
+pub mod timeline;
+mod vorbis_comment;
 
 use async_trait::async_trait;
 use common::structs::recording::{RecordingHeader, SessionManifest};
 use common::structs::AudioFormat;
+use std::collections::VecDeque;
 use std::fs;
-use std::path::Path;
-use log::debug;
+use std::path::{Path, PathBuf};
 
 use crate::audio::recording::renderer::{
     bwav::BwavRenderer,
+    flac::FlacRenderer,
+    mixdown::{session_participants, MixdownOptions, SessionMix, SessionStems},
     mp4::Mp4Renderer,
+    ogg::OggOpusRenderer,
+    segment::{segment_files, WalSegment},
+    timeline::SpeechTimeline,
     stream::{
         opus::{OpusChunk, OpusPacketStream, OpusStreamInfo},
         pcm::{PcmChunk, PcmStream}
     }
 };
 
-/// Extension trait for AudioFormat that provides rendering capabilities
+/// Write the speech timeline beside an export. The audio is already rendered, so a failure
+/// here is logged rather than failing the export.
+fn write_timeline(timeline: &SpeechTimeline, output_path: &Path) {
+    if let Err(e) = timeline.write_sidecar(output_path) {
+        log::warn!("Failed to write speech timeline for {:?}: {}", output_path, e);
+    }
+}
+
+/// Extension trait for AudioFormat that provides rendering capabilities.
+/// Every render also writes a `.timeline.json` sidecar with who spoke when.
 #[async_trait]
 pub trait AudioFormatRenderer {
     /// Render audio from a session to the specified output path
@@ -28,6 +49,23 @@ pub trait AudioFormatRenderer {
         player_name: &str,
         output_path: &Path,
     ) -> Result<(), anyhow::Error>;
+
+    /// Render every selected participant of a session into a single stereo mix
+    async fn render_mixdown(
+        &self,
+        session_path: &Path,
+        output_path: &Path,
+        options: MixdownOptions,
+    ) -> Result<(), anyhow::Error>;
+
+    /// Render the selected players (every participant when empty) as time-aligned stems.
+    /// For `BwavStems`, `output_path` is a directory that receives one file per player.
+    async fn render_stems(
+        &self,
+        session_path: &Path,
+        output_path: &Path,
+        players: &[String],
+    ) -> Result<(), anyhow::Error>;
 }
 
 #[async_trait]
@@ -39,9 +77,103 @@ impl AudioFormatRenderer for AudioFormat {
         player_name: &str,
         output_path: &Path,
     ) -> Result<(), anyhow::Error> {
+        let timeline = SpeechTimeline::load(session_path, &[player_name.to_string()])?;
         match self {
-            AudioFormat::Bwav => BwavRenderer::new().render(session_path, player_name, output_path).await,
-            AudioFormat::Mp4Opus => Mp4Renderer::new().render(session_path, player_name, output_path).await,
+            AudioFormat::Bwav | AudioFormat::BwavPolyphonic | AudioFormat::BwavStems => {
+                BwavRenderer::new()
+                    .with_timeline(timeline.clone())
+                    .render(session_path, player_name, output_path)
+                    .await?
+            }
+            AudioFormat::Mp4Opus | AudioFormat::Mp4OpusStems => {
+                Mp4Renderer::new()
+                    .with_chapters(timeline.chapters())
+                    .render(session_path, player_name, output_path)
+                    .await?
+            }
+            AudioFormat::OggOpus => OggOpusRenderer::new().render(session_path, player_name, output_path).await?,
+            AudioFormat::Flac => FlacRenderer::new().render(session_path, player_name, output_path).await?,
+        }
+
+        write_timeline(&timeline, output_path);
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(session_path, output_path, options), fields(format = ?self))]
+    async fn render_mixdown(
+        &self,
+        session_path: &Path,
+        output_path: &Path,
+        options: MixdownOptions,
+    ) -> Result<(), anyhow::Error> {
+        let mix = SessionMix::open(session_path, options)?;
+        let timeline = SpeechTimeline::load(session_path, &mix.player_names())?;
+        match self {
+            AudioFormat::Bwav | AudioFormat::BwavPolyphonic | AudioFormat::BwavStems => {
+                BwavRenderer::new()
+                    .with_timeline(timeline.clone())
+                    .render_mix(mix, output_path)?
+            }
+            AudioFormat::Mp4Opus | AudioFormat::Mp4OpusStems => {
+                Mp4Renderer::new()
+                    .with_chapters(timeline.chapters())
+                    .render_mix(mix, output_path)?
+            }
+            AudioFormat::OggOpus | AudioFormat::Flac => {
+                return Err(anyhow::anyhow!(
+                    "Mixdowns are not available as {:?}; use Bwav or Mp4Opus",
+                    self
+                ))
+            }
+        }
+
+        write_timeline(&timeline, output_path);
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(session_path, output_path, players), fields(format = ?self, player_count = players.len()))]
+    async fn render_stems(
+        &self,
+        session_path: &Path,
+        output_path: &Path,
+        players: &[String],
+    ) -> Result<(), anyhow::Error> {
+        match self {
+            AudioFormat::Mp4OpusStems => {
+                let players = if players.is_empty() {
+                    let manifest: SessionManifest = serde_json::from_str(&fs::read_to_string(
+                        session_path.join("session.json"),
+                    )?)?;
+                    session_participants(&manifest)
+                } else {
+                    players.to_vec()
+                };
+                let timeline = SpeechTimeline::load(session_path, &players)?;
+                Mp4Renderer::new()
+                    .with_chapters(timeline.chapters())
+                    .render_stems(session_path, &players, output_path)?;
+                write_timeline(&timeline, output_path);
+                Ok(())
+            }
+            AudioFormat::BwavPolyphonic | AudioFormat::BwavStems => {
+                let stems = SessionStems::open(session_path, players)?;
+                let timeline = SpeechTimeline::load(session_path, &stems.player_names())?;
+                let renderer = BwavRenderer::new().with_timeline(timeline.clone());
+                if *self == AudioFormat::BwavPolyphonic {
+                    renderer.render_polyphonic(stems, output_path)?;
+                } else {
+                    renderer.render_stem_folder(stems, output_path)?;
+                }
+                write_timeline(&timeline, output_path);
+                Ok(())
+            }
+            AudioFormat::Bwav
+            | AudioFormat::Mp4Opus
+            | AudioFormat::OggOpus
+            | AudioFormat::Flac => Err(anyhow::anyhow!(
+                "{:?} renders a single player per file; use a stem format",
+                self
+            )),
         }
     }
 }
@@ -98,10 +230,20 @@ pub struct WalEntry {
     pub relative_timestamp_ms: u64,
 }
 
-/// WAL audio reader that decodes Opus frames and handles silence gaps
+/// Entries kept buffered ahead of the read position, about ten seconds of 20ms frames.
+/// Entries are ordered by timestamp within this window, which covers packets written slightly
+/// out of order across a segment boundary.
+const READ_AHEAD_ENTRIES: usize = 500;
+
+/// WAL audio reader that decodes Opus frames and handles silence gaps.
+/// Segments are read one at a time as playback reaches them, so memory stays bounded
+/// regardless of session length.
 pub struct WalAudioReader {
-    entries: Vec<WalEntry>,
-    current_index: usize,
+    segment_files: Vec<PathBuf>,
+    next_segment: usize,
+    buffered: VecDeque<WalEntry>,
+    previous: Option<WalEntry>,
+    entries_read: usize,
     decoder: Option<opus2::Decoder>,
     decoder_config: Option<(u32, u16)>,
     session_info: SessionInfo,
@@ -112,49 +254,77 @@ impl WalAudioReader {
         let session_info = SessionInfo::load(session_path)?;
 
         let wal_path = session_path.join("wal");
-        let mut entries = Self::read_entries_with_headers(&wal_path, player_name)?;
-
-        entries.sort_by_key(|e| e.relative_timestamp_ms);
-        Ok(Self {
-            entries,
-            current_index: 0,
+        let mut reader = Self {
+            segment_files: segment_files(&wal_path, Some(player_name))?,
+            next_segment: 0,
+            buffered: VecDeque::new(),
+            previous: None,
+            entries_read: 0,
             decoder: None,
             decoder_config: None,
             session_info,
-        })
+        };
+        reader.fill()?;
+        Ok(reader)
     }
 
-    /// Get the next raw WAL entry without decoding
-    pub fn next_raw_entry(&mut self) -> Option<&WalEntry> {
-        if self.current_index >= self.entries.len() {
-            return None;
+    /// Read segments until at least `READ_AHEAD_ENTRIES` entries are buffered or none remain
+    fn fill(&mut self) -> Result<(), anyhow::Error> {
+        while self.buffered.len() < READ_AHEAD_ENTRIES && self.next_segment < self.segment_files.len() {
+            let path = &self.segment_files[self.next_segment];
+            self.next_segment += 1;
+
+            let entries = WalSegment::read(path)?.entries;
+            if entries.is_empty() {
+                continue;
+            }
+            self.buffered.extend(entries);
+            self.buffered
+                .make_contiguous()
+                .sort_by_key(|e| e.relative_timestamp_ms);
         }
+        Ok(())
+    }
 
-        let entry = &self.entries[self.current_index];
-        self.current_index += 1;
+    /// Consume the next buffered entry, reading ahead as needed
+    fn advance(&mut self) -> Option<WalEntry> {
+        let entry = self.buffered.pop_front()?;
+        self.entries_read += 1;
+        if let Err(e) = self.fill() {
+            log::error!("Failed to read WAL segment for {}: {}", self.session_info.session_id, e);
+        }
         Some(entry)
     }
 
+    /// Get the next raw WAL entry without decoding
+    pub fn next_raw_entry(&mut self) -> Option<&WalEntry> {
+        self.previous = self.advance();
+        self.previous.as_ref()
+    }
+
     /// Peek at the next raw WAL entry without advancing
     pub fn peek_raw_entry(&self) -> Option<&WalEntry> {
-        self.entries.get(self.current_index)
+        self.buffered.front()
     }
 
     /// Reset the reader to the beginning
     pub fn reset(&mut self) {
-        self.current_index = 0;
+        self.next_segment = 0;
+        self.buffered.clear();
+        self.previous = None;
+        self.entries_read = 0;
         self.decoder = None;
         self.decoder_config = None;
+        if let Err(e) = self.fill() {
+            log::error!("Failed to read WAL segment for {}: {}", self.session_info.session_id, e);
+        }
     }
 
     /// Get the next decoded audio frame, inserting silence if needed
     pub fn next_frame(&mut self) -> Result<Option<DecodedAudioFrame>, anyhow::Error> {
-        if self.current_index >= self.entries.len() {
+        let Some(entry) = self.advance() else {
             return Ok(None);
-        }
-
-        let entry = &self.entries[self.current_index];
-        self.current_index += 1;
+        };
 
         let sample_rate = entry.header.sample_rate();
         let channels = entry.header.channels();
@@ -187,11 +357,14 @@ impl WalAudioReader {
 
         pcm_data.truncate(decoded_samples * channels as usize);
 
+        let relative_timestamp_ms = entry.relative_timestamp_ms;
+        self.previous = Some(entry);
+
         Ok(Some(DecodedAudioFrame {
             pcm_data,
             sample_rate,
             channels,
-            relative_timestamp_ms: entry.relative_timestamp_ms,
+            relative_timestamp_ms,
         }))
     }
 
@@ -199,12 +372,8 @@ impl WalAudioReader {
         const OPUS_FRAME_MS: u64 = 20;
         const NETWORK_JITTER_TOLERANCE_MS: u64 = 39; // 0-39ms = consecutive packets
 
-        if self.current_index == 0 || self.current_index >= self.entries.len() {
-            return None;
-        }
-
-        let prev_entry = &self.entries[self.current_index - 1];
-        let next_entry = &self.entries[self.current_index];
+        let prev_entry = self.previous.as_ref()?;
+        let next_entry = self.buffered.front()?;
 
         let time_gap_ms = next_entry.relative_timestamp_ms
             .saturating_sub(prev_entry.relative_timestamp_ms)
@@ -235,134 +404,76 @@ impl WalAudioReader {
         &self.session_info
     }
 
-    /// Get total number of entries
-    pub fn entry_count(&self) -> usize {
-        self.entries.len()
+    /// Number of entries consumed so far
+    pub fn entries_read(&self) -> usize {
+        self.entries_read
     }
+}
 
-    /// Read WAL entries with headers by parsing segment files directly
-    fn read_entries_with_headers(wal_path: &Path, player_name: &str) -> Result<Vec<WalEntry>, anyhow::Error> {
-        const NANO_REC_SIGNATURE: [u8; 6] = *b"NANORC";
-        const MAX_HEADER_SIZE: usize = 1024;
-        const MAX_CONTENT_SIZE: usize = 50 * 1024;
-        let mut entries = Vec::new();
-
-        // Find all segment files for this player (files are named: PlayerName-hash-sequence.log)
-        debug!("Reading directory: {:?}", wal_path);
-        let dir_entries = fs::read_dir(wal_path)?;
-        let mut segment_files = Vec::new();
-
-        for entry in dir_entries.flatten() {
-            if let Some(filename) = entry.file_name().to_str() {
-                if filename.starts_with(player_name) && filename.ends_with(".log") {
-                    segment_files.push(entry.path());
-                }
-            }
-        }
-
-        segment_files.sort();
-
-        for file_path in segment_files {
-            log::info!("Parsing WAL file: {:?}", file_path);
-
-            let file_bytes = std::fs::read(&file_path)?;
-            // Each WAL record is at minimum 16 bytes (6 signature + 2 header_len + 8 content_len),
-            // so this limit scales with file size and can never be exceeded by valid data
-            let max_records = file_bytes.len() / 16;
-            let mut pos = 0;
-
-            const MAX_HEADER_SEARCH_BYTES: usize = 4096;
-            let search_limit = pos + MAX_HEADER_SEARCH_BYTES.min(file_bytes.len().saturating_sub(6));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::recording::renderer::segment::{encode_record, input_header};
+    use uuid::Uuid;
 
-            while pos + 6 <= search_limit {
-                if &file_bytes[pos..pos + 6] == NANO_REC_SIGNATURE {
-                    break;
-                }
-                pos += 1;
-            }
+    const PLAYER: &str = "Steve";
 
-            // If signature not found in first 4KB, skip this file
-            if pos + 6 > search_limit {
-                log::warn!("Could not find NANO_REC_SIGNATURE in {:?}, skipping file", file_path);
-                continue;
+    fn write_session(segments: &[&[u64]]) -> std::path::PathBuf {
+        let session_path = std::env::temp_dir().join(format!("bvc-reader-{}", Uuid::new_v4()));
+        let wal_path = session_path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+
+        let manifest = serde_json::json!({
+            "session_id": "reader",
+            "start_timestamp": 0,
+            "end_timestamp": null,
+            "duration_ms": null,
+            "emitter_player": PLAYER,
+            "participants": [PLAYER],
+            "created_at": "2026-01-01T00:00:00Z",
+        });
+        fs::write(session_path.join("session.json"), manifest.to_string()).unwrap();
+
+        for (index, timestamps) in segments.iter().enumerate() {
+            let mut segment = Vec::new();
+            for timestamp in timestamps.iter() {
+                segment.extend(encode_record(&input_header(*timestamp), &[0xF8]));
             }
+            fs::write(wal_path.join(format!("{}-abc-{:04}.log", PLAYER, index + 1)), segment).unwrap();
+        }
 
-            let mut records_parsed = 0;
-            while pos + 6 <= file_bytes.len() && records_parsed < max_records {
-                if &file_bytes[pos..pos + 6] != NANO_REC_SIGNATURE {
-                    break;
-                }
-                pos += 6;
-
-                // Read header length (2 bytes)
-                if pos + 2 > file_bytes.len() {
-                    break;
-                }
-                let header_len = u16::from_le_bytes([file_bytes[pos], file_bytes[pos + 1]]) as usize;
-                pos += 2;
-
-                // Validate header length
-                if header_len > MAX_HEADER_SIZE {
-                    log::warn!("Invalid header_len {} in {:?}, stopping parse", header_len, file_path);
-                    break;
-                }
+        session_path
+    }
 
-                if pos + header_len > file_bytes.len() {
-                    break;
-                }
-                let header_bytes = &file_bytes[pos..pos + header_len];
-                pos += header_len;
+    #[test]
+    fn test_reader_orders_entries_across_segments() {
+        let session_path = write_session(&[&[0, 20, 60], &[40, 80]]);
+        let mut reader = WalAudioReader::new(&session_path, PLAYER).unwrap();
 
-                if pos + 8 > file_bytes.len() {
-                    break;
-                }
-                let content_len = u64::from_le_bytes([
-                    file_bytes[pos], file_bytes[pos + 1], file_bytes[pos + 2], file_bytes[pos + 3],
-                    file_bytes[pos + 4], file_bytes[pos + 5], file_bytes[pos + 6], file_bytes[pos + 7],
-                ]) as usize;
-                pos += 8;
-
-                if content_len > MAX_CONTENT_SIZE {
-                    log::warn!("Invalid content_len {} in {:?}, stopping parse", content_len, file_path);
-                    break;
-                }
+        let mut timestamps = Vec::new();
+        while let Some(entry) = reader.next_raw_entry() {
+            timestamps.push(entry.relative_timestamp_ms);
+        }
+        assert_eq!(timestamps, vec![0, 20, 40, 60, 80]);
+        assert_eq!(reader.entries_read(), 5);
 
-                if pos + content_len > file_bytes.len() {
-                    break;
-                }
-                let content = file_bytes[pos..pos + content_len].to_vec();
-                pos += content_len;
-
-                if !header_bytes.is_empty() {
-                    match RecordingHeader::from_bytes(header_bytes) {
-                        Ok(header) => {
-                            let relative_timestamp_ms = match &header {
-                                RecordingHeader::Input(h) => h.relative_timestamp_ms.unwrap_or(0),
-                                RecordingHeader::Output(h) => h.relative_timestamp_ms,
-                            };
-
-                            entries.push(WalEntry {
-                                header,
-                                opus_data: content,
-                                relative_timestamp_ms,
-                            });
-                        }
-                        Err(e) => {
-                            eprintln!("DEBUG: Failed to parse header (len={}): {:?}", header_bytes.len(), e);
-                        }
-                    }
-                }
+        reader.reset();
+        assert_eq!(reader.peek_raw_entry().map(|e| e.relative_timestamp_ms), Some(0));
 
-                records_parsed += 1;
-            }
+        let _ = fs::remove_dir_all(&session_path);
+    }
 
-            if records_parsed >= max_records {
-                log::warn!("Hit max_records limit in {:?}, stopping parse", file_path);
-            }
+    #[test]
+    fn test_reader_silence_follows_previous_entry() {
+        let session_path = write_session(&[&[0], &[200]]);
+        let mut reader = WalAudioReader::new(&session_path, PLAYER).unwrap();
 
-            log::info!("  Parsed {} total records from {:?}", records_parsed, file_path);
-        }
+        assert_eq!(reader.calculate_silence_before_next(), None);
+        reader.next_raw_entry();
+        assert_eq!(reader.calculate_silence_before_next(), Some(180 * 48));
+        reader.next_raw_entry();
+        assert_eq!(reader.calculate_silence_before_next(), None);
 
-        Ok(entries)
+        let _ = fs::remove_dir_all(&session_path);
     }
 }
@@ -0,0 +1,392 @@
+//! QuickTime chapter track (trak box) construction
+//!
+//! Chapters are stored as a disabled `text` track with one sample per chapter title. Audio
+//! tracks point at it through a `chap` track reference, which QuickTime, ffmpeg and most
+//! players read as the file's chapter list.
+//!
+//! # Box Structure
+//!
+//! ```text
+//! trak
+//! ├── tkhd (in movie, not enabled)
+//! └── mdia
+//!     ├── mdhd (timescale: milliseconds)
+//!     ├── hdlr (handler: text)
+//!     └── minf
+//!         ├── gmhd
+//!         │   ├── gmin
+//!         │   └── text
+//!         ├── dinf
+//!         │   └── dref
+//!         └── stbl
+//!             ├── stsd (text sample entry)
+//!             ├── stts
+//!             ├── stsc
+//!             ├── stsz
+//!             └── stco
+//! ```
+
+use crate::audio::recording::renderer::mp4::boxes::BoxWriter;
+use crate::audio::recording::renderer::mp4::constants::{
+    DREF_SELF_CONTAINED, IDENTITY_MATRIX, LANGUAGE_UNDETERMINED, TEXT_ENCODING_UTF8,
+    TKHD_FLAGS_IN_MOVIE,
+};
+use crate::audio::recording::renderer::timeline::Chapter;
+
+/// Chapter sample times are stored in milliseconds
+const CHAPTER_TIMESCALE: u32 = 1000;
+
+/// A chapter text track whose samples are stored as a single contiguous chunk
+#[derive(Debug, Clone)]
+pub struct ChapterTrack {
+    /// Track ID for this chapter track
+    track_id: u32,
+    chapters: Vec<Chapter>,
+    /// Length of the audio the chapters cover, in milliseconds
+    duration_ms: u64,
+    /// Length of the audio in movie timescale, for the track header
+    movie_duration: u64,
+    /// File offset where the chapter samples will be stored
+    data_offset: u64,
+}
+
+impl ChapterTrack {
+    /// Create a chapter track. The first chapter is extended back to the start of the file so
+    /// the samples cover the whole timeline, chapters sharing a start time are merged into
+    /// one whose title lists each of them, and chapters starting after the audio are dropped.
+    pub fn new(
+        track_id: u32,
+        chapters: &[Chapter],
+        duration_ms: u64,
+        movie_duration: u64,
+        data_offset: u64,
+    ) -> Self {
+        Self {
+            track_id,
+            chapters: Self::merge_shared_starts(chapters, duration_ms),
+            duration_ms,
+            movie_duration,
+            data_offset,
+        }
+    }
+
+    /// Build the `chap` track reference that audio tracks add to their `tref`
+    pub fn tref_entry(track_id: u32) -> Vec<u8> {
+        BoxWriter::new()
+            .write_box(b"chap", &BoxWriter::new().u32(track_id).finish())
+            .finish()
+    }
+
+    /// The chapter samples, to be written contiguously at `data_offset`
+    pub fn sample_data(&self) -> Vec<u8> {
+        self.chapters
+            .iter()
+            .flat_map(|chapter| Self::build_sample(&chapter.title))
+            .collect()
+    }
+
+    /// Serialize the chapter track to a complete trak box
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let trak = BoxWriter::new()
+            .write_box(b"tkhd", &self.build_tkhd())
+            .write_box(b"mdia", &self.build_mdia());
+
+        BoxWriter::new().write_box(b"trak", trak.as_bytes()).finish()
+    }
+
+    /// A text sample: length-prefixed title followed by an encoding atom
+    fn build_sample(title: &str) -> Vec<u8> {
+        let title = &title.as_bytes()[..title.len().min(u16::MAX as usize)];
+        BoxWriter::new()
+            .u16(title.len() as u16)
+            .bytes(title)
+            .write_box(b"encd", &BoxWriter::new().u32(TEXT_ENCODING_UTF8).finish())
+            .finish()
+    }
+
+    /// Sort chapters by start and combine those that start together, so every sample has a
+    /// distinct start time. Chapters from `duration_ms` on would be empty, so only the first
+    /// chapter is kept past the end of the audio.
+    fn merge_shared_starts(chapters: &[Chapter], duration_ms: u64) -> Vec<Chapter> {
+        let mut sorted = chapters.to_vec();
+        sorted.sort_by_key(|c| c.start_ms);
+
+        let mut merged: Vec<Chapter> = Vec::with_capacity(sorted.len());
+        for chapter in sorted {
+            match merged.last_mut() {
+                Some(last) if last.start_ms == chapter.start_ms => {
+                    if !last.title.split(", ").any(|t| t == chapter.title) {
+                        last.title = format!("{}, {}", last.title, chapter.title);
+                    }
+                }
+                Some(_) if chapter.start_ms >= duration_ms => break,
+                _ => merged.push(chapter),
+            }
+        }
+        merged
+    }
+
+    /// Duration of each chapter: until the next one starts, the last until the end of the audio
+    fn sample_durations(&self) -> Vec<u32> {
+        let mut durations = Vec::with_capacity(self.chapters.len());
+        for (index, chapter) in self.chapters.iter().enumerate() {
+            let start = if index == 0 { 0 } else { chapter.start_ms };
+            let end = self
+                .chapters
+                .get(index + 1)
+                .map(|next| next.start_ms)
+                .unwrap_or(self.duration_ms);
+            durations.push(end.saturating_sub(start) as u32);
+        }
+        durations
+    }
+
+    /// Build the track header box (tkhd)
+    fn build_tkhd(&self) -> Vec<u8> {
+        let mut tkhd = BoxWriter::new()
+            // Version 0, flags (in_movie only; chapter tracks are never played)
+            .u32(TKHD_FLAGS_IN_MOVIE)
+            // Creation/modification time
+            .u32(0)
+            .u32(0)
+            .u32(self.track_id)
+            // Reserved
+            .u32(0)
+            .u32(self.movie_duration as u32)
+            // Reserved (8 bytes)
+            .u64(0)
+            // Layer, alternate group
+            .u16(0)
+            .u16(0)
+            // Volume (0 for text)
+            .u16(0)
+            // Reserved
+            .u16(0);
+
+        for value in IDENTITY_MATRIX {
+            tkhd = tkhd.u32(value);
+        }
+
+        // Width, height
+        tkhd.u32(0).u32(0).finish()
+    }
+
+    /// Build the media box (mdia)
+    fn build_mdia(&self) -> Vec<u8> {
+        let mdhd = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Creation/modification time
+            .u32(0)
+            .u32(0)
+            .u32(CHAPTER_TIMESCALE)
+            .u32(self.duration_ms as u32)
+            .u16(LANGUAGE_UNDETERMINED)
+            // Quality
+            .u16(0)
+            .finish();
+
+        let hdlr = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Pre-defined
+            .u32(0)
+            .fourcc(b"text")
+            // Reserved (3 x u32)
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .bytes(b"ChapterHandler\0")
+            .finish();
+
+        BoxWriter::new()
+            .write_box(b"mdhd", &mdhd)
+            .write_box(b"hdlr", &hdlr)
+            .write_box(b"minf", &self.build_minf())
+            .finish()
+    }
+
+    /// Build the media information box (minf)
+    fn build_minf(&self) -> Vec<u8> {
+        let gmin = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Graphics mode (dither copy), opcolor
+            .u16(0x0040)
+            .u16(0x8000)
+            .u16(0x8000)
+            .u16(0x8000)
+            // Balance, reserved
+            .u16(0)
+            .u16(0)
+            .finish();
+
+        // QuickTime text media information: an identity matrix stored as 16.16 / 2.30 values
+        let text = BoxWriter::new()
+            .u16(0x0001)
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .u32(0x0001)
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .u32(0x0000_4000)
+            .u16(0)
+            .finish();
+
+        let gmhd = BoxWriter::new()
+            .write_box(b"gmin", &gmin)
+            .write_box(b"text", &text)
+            .finish();
+
+        let url_entry = BoxWriter::new().u32(DREF_SELF_CONTAINED).finish();
+        let dref = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            .write_box(b"url ", &url_entry)
+            .finish();
+        let dinf = BoxWriter::new().write_box(b"dref", &dref).finish();
+
+        BoxWriter::new()
+            .write_box(b"gmhd", &gmhd)
+            .write_box(b"dinf", &dinf)
+            .write_box(b"stbl", &self.build_stbl())
+            .finish()
+    }
+
+    /// Build the sample table box (stbl)
+    fn build_stbl(&self) -> Vec<u8> {
+        // Text sample entry: display flags 0, justification 1, colors, box, font all zero
+        let text_entry = BoxWriter::new()
+            // Reserved (6 bytes)
+            .zeros(6)
+            // Data reference index
+            .u16(1)
+            .u32(0)
+            .u32(1)
+            .zeros(35)
+            .finish();
+        let stsd = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            .write_box(b"text", &text_entry)
+            .finish();
+
+        let durations = self.sample_durations();
+        let stts = durations
+            .iter()
+            .fold(
+                BoxWriter::new()
+                    // Version 0, flags 0
+                    .u32(0)
+                    .u32(durations.len() as u32),
+                |writer, duration| writer.u32(1).u32(*duration),
+            )
+            .finish();
+
+        let stsc = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            // First chunk, samples per chunk, sample description index
+            .u32(1)
+            .u32(self.chapters.len() as u32)
+            .u32(1)
+            .finish();
+
+        let stsz = self
+            .chapters
+            .iter()
+            .fold(
+                BoxWriter::new()
+                    // Version 0, flags 0
+                    .u32(0)
+                    // Sample size 0: sizes follow per sample
+                    .u32(0)
+                    .u32(self.chapters.len() as u32),
+                |writer, chapter| writer.u32(Self::build_sample(&chapter.title).len() as u32),
+            )
+            .finish();
+
+        let stco = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            .u32(self.data_offset as u32)
+            .finish();
+
+        BoxWriter::new()
+            .write_box(b"stsd", &stsd)
+            .write_box(b"stts", &stts)
+            .write_box(b"stsc", &stsc)
+            .write_box(b"stsz", &stsz)
+            .write_box(b"stco", &stco)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> ChapterTrack {
+        let chapters = vec![
+            Chapter { start_ms: 0, title: "Alex".to_string() },
+            Chapter { start_ms: 2000, title: "Steve".to_string() },
+        ];
+        ChapterTrack::new(3, &chapters, 5000, 240000, 1024)
+    }
+
+    #[test]
+    fn test_samples_are_length_prefixed_titles() {
+        let data = track().sample_data();
+        // Alex: 2 + 4 + encd (12), Steve: 2 + 5 + encd (12)
+        assert_eq!(data.len(), 18 + 19);
+        assert_eq!(&data[..6], &[0, 4, b'A', b'l', b'e', b'x']);
+        assert_eq!(&data[10..14], b"encd");
+    }
+
+    #[test]
+    fn test_last_chapter_runs_to_end_of_audio() {
+        assert_eq!(track().sample_durations(), vec![2000, 3000]);
+    }
+
+    #[test]
+    fn test_chapters_sharing_a_start_are_merged_and_late_ones_dropped() {
+        let chapters = vec![
+            Chapter { start_ms: 0, title: "Alex".to_string() },
+            Chapter { start_ms: 2000, title: "Steve".to_string() },
+            Chapter { start_ms: 2000, title: "Marker 1".to_string() },
+            Chapter { start_ms: 2000, title: "Steve".to_string() },
+            Chapter { start_ms: 7000, title: "Marker 2".to_string() },
+        ];
+        let track = ChapterTrack::new(3, &chapters, 5000, 240000, 1024);
+
+        let titles: Vec<&str> = track.chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Alex", "Steve, Marker 1"]);
+
+        let durations = track.sample_durations();
+        assert_eq!(durations, vec![2000, 3000]);
+        assert_eq!(durations.iter().map(|d| *d as u64).sum::<u64>(), 5000);
+    }
+
+    #[test]
+    fn test_to_bytes_produces_text_trak() {
+        let bytes = track().to_bytes();
+        assert_eq!(&bytes[4..8], b"trak");
+        assert_eq!(
+            u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+            bytes.len()
+        );
+        assert!(bytes.windows(4).any(|w| w == b"gmhd"));
+        assert!(bytes.windows(4).any(|w| w == b"stco"));
+        assert_eq!(ChapterTrack::tref_entry(3), vec![0, 0, 0, 12, b'c', b'h', b'a', b'p', 0, 0, 0, 3]);
+    }
+}
@@ -8,6 +8,9 @@ pub const AUDIO_FRAMES_PER_SECOND: u8 = 50;
 /// Default track header flags: enabled (0x1) + in_movie (0x2) + in_preview (0x4)
 pub const TKHD_FLAGS_DEFAULT: u32 = 0x0000_0007;
 
+/// Track header flags for tracks that are referenced but never played: in_movie (0x2) only
+pub const TKHD_FLAGS_IN_MOVIE: u32 = 0x0000_0002;
+
 /// Timecode flag: 24-hour wrap (0x02)
 /// Per Apple TN2174, this indicates timecode wraps at 24 hours
 pub const TMCD_FLAG_24_HOUR_WRAP: u32 = 0x0000_0002;
@@ -36,8 +39,14 @@ pub const DREF_SELF_CONTAINED: u32 = 0x0000_0001;
 /// Timecode sample size in bytes (32-bit frame number)
 pub const TIMECODE_SAMPLE_SIZE: u32 = 4;
 
+/// Text sample `encd` atom value for UTF-8 chapter titles
+pub const TEXT_ENCODING_UTF8: u32 = 0x0000_0100;
+
 /// Metadata data type: UTF-8 string
 pub const METADATA_TYPE_UTF8: u32 = 1;
 
 /// Metadata data type: unsigned integer
 pub const METADATA_TYPE_UINT: u32 = 0x15;
+
+/// Opus encoder delay in samples at 48kHz (6.5ms), written to dOps
+pub const OPUS_PRE_SKIP: u16 = 312;
@@ -8,14 +8,23 @@
 //! - Lossless Opus passthrough (no decode/re-encode)
 //! - Professional timecode track (tmcd) for NLE compatibility
 //! - User data box (udta) with session metadata
+//! - Multi-track stem export, one Opus track per participant
+//! - QuickTime chapter track marking each utterance
 
 mod boxes;
+mod chapters;
 pub mod constants;
+mod multitrack;
 mod timecode;
 
 use boxes::BoxWriter;
+use chapters::ChapterTrack;
+use constants::{OPUS_PRE_SKIP, TIMECODE_SAMPLE_SIZE};
+use multitrack::Stem;
 use timecode::{TimecodeSample, TimecodeTrack, UserDataBox};
 
+use super::mixdown::{SessionMix, MIX_CHANNELS, MIX_SAMPLE_RATE};
+use super::timeline::Chapter;
 use super::{AudioRenderer, OpusChunk, OpusPacketStream, OpusStreamInfo};
 use async_trait::async_trait;
 use shiguredo_mp4::boxes::{AudioSampleEntryFields, DopsBox, OpusBox, SampleEntry};
@@ -26,13 +35,14 @@ use std::io::{Seek, Write};
 use std::num::{NonZeroU16, NonZeroU32};
 use std::path::Path;
 
-/// Create a tref box that references the timecode track
-fn create_tref_to_timecode(timecode_track_id: u32) -> Vec<u8> {
+/// Create a tref box that references the timecode track, and the chapter track if present
+fn create_tref_to_timecode(timecode_track_id: u32, chapter_track_id: Option<u32>) -> Vec<u8> {
     // Build tref box: tref contains a tmcd reference with the track ID
-    // tref: size (4) + 'tref' (4) + tmcd reference
+    // tref: size (4) + 'tref' (4) + tmcd reference [+ chap reference]
     // tmcd reference: size (4) + 'tmcd' (4) + track_id (4)
     let tmcd_size: u32 = 12; // 4 + 4 + 4
-    let tref_size: u32 = 8 + tmcd_size;
+    let chap = chapter_track_id.map(ChapterTrack::tref_entry).unwrap_or_default();
+    let tref_size: u32 = 8 + tmcd_size + chap.len() as u32;
 
     BoxWriter::new()
         .u32(tref_size)
@@ -40,6 +50,7 @@ fn create_tref_to_timecode(timecode_track_id: u32) -> Vec<u8> {
         .u32(tmcd_size)
         .fourcc(b"tmcd")
         .u32(timecode_track_id)
+        .bytes(&chap)
         .finish()
 }
 
@@ -131,12 +142,23 @@ fn inject_tref_after_tkhd(trak_content: &[u8], tref: &[u8]) -> Vec<u8> {
 /// - Raw Opus audio track (lossless passthrough)
 /// - Timecode track for NLE synchronization
 /// - User data with session metadata
-pub struct Mp4Renderer;
+/// - Chapter track, when chapters are set
+pub struct Mp4Renderer {
+    chapters: Vec<Chapter>,
+}
 
 impl Mp4Renderer {
     /// Create a new MP4 renderer
     pub fn new() -> Self {
-        Self
+        Self {
+            chapters: Vec::new(),
+        }
+    }
+
+    /// Mark these chapters in rendered files
+    pub fn with_chapters(mut self, chapters: Vec<Chapter>) -> Self {
+        self.chapters = chapters;
+        self
     }
 
     /// Build OpusBox for sample entry
@@ -150,7 +172,7 @@ impl Mp4Renderer {
             },
             dops_box: DopsBox {
                 output_channel_count: info.channels as u8,
-                pre_skip: 312, // Standard Opus encoder delay (6.5ms @ 48kHz)
+                pre_skip: OPUS_PRE_SKIP,
                 input_sample_rate: info.sample_rate,
                 output_gain: 0, // No gain adjustment
             },
@@ -161,40 +183,49 @@ impl Mp4Renderer {
     /// Write an Opus packet to file and register with muxer
     fn write_sample(
         &self,
-        file: &mut File,
-        muxer: &mut Mp4FileMuxer,
+        output: &mut OpusTrackOutput,
         data: &[u8],
         duration_samples: u32,
-        data_offset: &mut u64,
-        opus_box: &OpusBox,
-        first_sample: &mut bool,
-        sample_rate: u32,
     ) -> Result<(), anyhow::Error> {
         // Write Opus data to file
-        file.write_all(data)?;
+        output.file.write_all(data)?;
 
         // Register with muxer
-        muxer.append_sample(&Sample {
+        output.muxer.append_sample(&Sample {
             track_kind: TrackKind::Audio,
-            sample_entry: if *first_sample {
-                *first_sample = false;
-                Some(SampleEntry::Opus(opus_box.clone()))
+            sample_entry: if output.first_sample {
+                output.first_sample = false;
+                Some(SampleEntry::Opus(output.opus_box.clone()))
             } else {
                 None
             },
             keyframe: false,
-            timescale: NonZeroU32::new(sample_rate).unwrap(),
+            timescale: NonZeroU32::new(output.sample_rate).unwrap(),
             duration: duration_samples,
-            data_offset: *data_offset,
+            data_offset: output.data_offset,
             data_size: data.len(),
             composition_time_offset: Some(0),
         })?;
 
-        *data_offset += data.len() as u64;
+        output.data_offset += data.len() as u64;
+        output.total_samples += duration_samples as u64;
         Ok(())
     }
 }
 
+/// An MP4 file whose Opus samples are being written, before the moov box is added
+struct OpusTrackOutput {
+    muxer: Mp4FileMuxer,
+    file: File,
+    /// Current file offset for sample data
+    data_offset: u64,
+    /// Sample entry, only written with the first sample
+    opus_box: OpusBox,
+    first_sample: bool,
+    total_samples: u64,
+    sample_rate: u32,
+}
+
 impl Default for Mp4Renderer {
     fn default() -> Self {
         Self::new()
@@ -216,8 +247,33 @@ impl AudioRenderer for Mp4Renderer {
             .ok_or_else(|| anyhow::anyhow!("No audio data for player: {}", player_name))?
             .clone();
 
+        self.mux(&info, stream, output_path)
+    }
+
+    fn file_extension(&self) -> &str {
+        "m4a"
+    }
+}
+
+impl Mp4Renderer {
+    /// Mux a sequence of Opus packets into an MP4 file with timecode and session metadata
+    fn mux(
+        &self,
+        info: &OpusStreamInfo,
+        chunks: impl IntoIterator<Item = Result<OpusChunk, anyhow::Error>>,
+        output_path: &Path,
+    ) -> Result<(), anyhow::Error> {
+        let mut output = self.begin_mux(info, output_path)?;
+        for chunk in chunks {
+            self.write_chunk(&mut output, chunk?)?;
+        }
+        self.finish_mux(info, output)
+    }
+
+    /// Create the output file and write the initial boxes ahead of the sample data
+    fn begin_mux(&self, info: &OpusStreamInfo, output_path: &Path) -> Result<OpusTrackOutput, anyhow::Error> {
         // Create muxer
-        let mut muxer = Mp4FileMuxer::new()?;
+        let muxer = Mp4FileMuxer::new()?;
 
         // Create output file
         let mut file = File::create(output_path)?;
@@ -226,51 +282,39 @@ impl AudioRenderer for Mp4Renderer {
         let initial_bytes = muxer.initial_boxes_bytes();
         file.write_all(initial_bytes)?;
 
-        // Track current file offset for sample data
-        let mut data_offset = initial_bytes.len() as u64;
-
-        // Prepare sample entry (only needed for first sample)
-        let opus_box = self.create_opus_box(&info);
-        let mut first_sample = true;
-        let mut total_samples: u64 = 0;
-
-        // Process all packets
-        for chunk in stream {
-            match chunk? {
-                OpusChunk::Packet {
-                    data,
-                    duration_samples,
-                } => {
-                    self.write_sample(
-                        &mut file,
-                        &mut muxer,
-                        &data,
-                        duration_samples,
-                        &mut data_offset,
-                        &opus_box,
-                        &mut first_sample,
-                        info.sample_rate,
-                    )?;
-                    total_samples += duration_samples as u64;
-                }
-                OpusChunk::Silence {
-                    data,
-                    duration_samples,
-                } => {
-                    self.write_sample(
-                        &mut file,
-                        &mut muxer,
-                        &data,
-                        duration_samples,
-                        &mut data_offset,
-                        &opus_box,
-                        &mut first_sample,
-                        info.sample_rate,
-                    )?;
-                    total_samples += duration_samples as u64;
-                }
+        Ok(OpusTrackOutput {
+            data_offset: initial_bytes.len() as u64,
+            muxer,
+            file,
+            opus_box: self.create_opus_box(info),
+            first_sample: true,
+            total_samples: 0,
+            sample_rate: info.sample_rate,
+        })
+    }
+
+    /// Append one packet, or encoded silence, to the audio track
+    fn write_chunk(&self, output: &mut OpusTrackOutput, chunk: OpusChunk) -> Result<(), anyhow::Error> {
+        match chunk {
+            OpusChunk::Packet {
+                data,
+                duration_samples,
             }
+            | OpusChunk::Silence {
+                data,
+                duration_samples,
+            } => self.write_sample(output, &data, duration_samples),
         }
+    }
+
+    /// Write the moov box with timecode, chapter and user data, then the timecode and chapter samples
+    fn finish_mux(&self, info: &OpusStreamInfo, output: OpusTrackOutput) -> Result<(), anyhow::Error> {
+        let OpusTrackOutput {
+            mut muxer,
+            mut file,
+            total_samples,
+            ..
+        } = output;
 
         // Calculate duration for metadata
         let duration_ms = (total_samples * 1000) / info.sample_rate as u64;
@@ -283,9 +327,11 @@ impl AudioRenderer for Mp4Renderer {
             .ok_or_else(|| anyhow::anyhow!("Muxer not finalized"))?;
 
         // Create timecode sample using new struct-based API
-        let timecode_sample = TimecodeSample::from_stream_info(&info);
+        let timecode_sample = TimecodeSample::from_stream_info(info);
 
         let mut timecode_data_offset = 0u64;
+        let mut chapter_track = None;
+        let chapter_track_id = (!self.chapters.is_empty()).then_some(3);
 
         // Write all boxes from muxer, modifying moov as needed
         for (offset, bytes) in finalized.offset_and_bytes_pairs() {
@@ -293,11 +339,11 @@ impl AudioRenderer for Mp4Renderer {
             if bytes.len() >= 8 && &bytes[4..8] == b"moov" {
                 // First pass: calculate where timecode data will end up
                 let original_content = &bytes[8..]; // Skip size and 'moov' fourcc
-                let audio_tref = create_tref_to_timecode(2);
+                let audio_tref = create_tref_to_timecode(2, chapter_track_id);
                 let modified_content = inject_tref_into_audio_trak(original_content, &audio_tref);
 
                 // Create user data box using new struct-based API
-                let udta = UserDataBox::from_stream_info(&info, Some(duration_ms));
+                let udta = UserDataBox::from_stream_info(info, Some(duration_ms));
                 let udta_bytes = udta.to_bytes();
 
                 // Timecode data goes AFTER the moov box
@@ -307,7 +353,7 @@ impl AudioRenderer for Mp4Renderer {
 
                 // Create a dummy timecode track to get its size
                 let dummy_timecode_track = TimecodeTrack::builder()
-                    .from_stream_info(&info)
+                    .from_stream_info(info)
                     .track_id(2)
                     .audio_track_id(1)
                     .duration_samples(total_samples)
@@ -317,15 +363,40 @@ impl AudioRenderer for Mp4Renderer {
                     .to_bytes()
                     .map_err(|e| anyhow::anyhow!("Failed to serialize timecode track: {}", e))?;
 
-                let new_moov_size =
-                    8 + modified_content.len() + dummy_timecode_track.len() + udta_bytes.len();
+                // Chapter samples follow the timecode sample; the track's size doesn't depend on
+                // the offset, so it can be measured the same way
+                let chapter_bytes_len = chapter_track_id
+                    .map(|id| {
+                        ChapterTrack::new(id, &self.chapters, duration_ms, total_samples, 0)
+                            .to_bytes()
+                            .len()
+                    })
+                    .unwrap_or(0);
+
+                let new_moov_size = 8
+                    + modified_content.len()
+                    + dummy_timecode_track.len()
+                    + chapter_bytes_len
+                    + udta_bytes.len();
 
                 // Timecode sample data will be written right after moov
                 timecode_data_offset = offset + new_moov_size as u64;
+                let chapter_bytes = chapter_track_id.map(|id| {
+                    let track = ChapterTrack::new(
+                        id,
+                        &self.chapters,
+                        duration_ms,
+                        total_samples,
+                        timecode_data_offset + TIMECODE_SAMPLE_SIZE as u64,
+                    );
+                    let bytes = track.to_bytes();
+                    chapter_track = Some(track);
+                    bytes
+                });
 
                 // Now create the real timecode track with correct offset
                 let timecode_track = TimecodeTrack::builder()
-                    .from_stream_info(&info)
+                    .from_stream_info(info)
                     .track_id(2)
                     .audio_track_id(1)
                     .duration_samples(total_samples)
@@ -335,15 +406,21 @@ impl AudioRenderer for Mp4Renderer {
                     .to_bytes()
                     .map_err(|e| anyhow::anyhow!("Failed to serialize timecode track: {}", e))?;
 
-                // Build new moov: modified content (with tref in audio trak) + timecode trak + udta
-                let new_size =
-                    8 + modified_content.len() + timecode_track.len() + udta_bytes.len();
+                // Build new moov: modified content (with tref in audio trak) + timecode trak
+                // + chapter trak + udta
+                let chapter_bytes = chapter_bytes.unwrap_or_default();
+                let new_size = 8
+                    + modified_content.len()
+                    + timecode_track.len()
+                    + chapter_bytes.len()
+                    + udta_bytes.len();
 
                 let mut new_moov = Vec::with_capacity(new_size);
                 new_moov.extend_from_slice(&(new_size as u32).to_be_bytes());
                 new_moov.extend_from_slice(b"moov");
                 new_moov.extend_from_slice(&modified_content);
                 new_moov.extend_from_slice(&timecode_track);
+                new_moov.extend_from_slice(&chapter_bytes);
                 new_moov.extend_from_slice(&udta_bytes);
 
                 file.seek(std::io::SeekFrom::Start(offset))?;
@@ -354,15 +431,131 @@ impl AudioRenderer for Mp4Renderer {
             }
         }
 
-        // Now write timecode sample data AFTER moov
+        // Now write timecode sample data AFTER moov, followed by the chapter titles
         file.seek(std::io::SeekFrom::Start(timecode_data_offset))?;
         file.write_all(&timecode_sample.to_vec())?;
+        if let Some(chapter_track) = chapter_track {
+            file.write_all(&chapter_track.sample_data())?;
+        }
 
         Ok(())
     }
 
-    fn file_extension(&self) -> &str {
-        "m4a"
+    /// Render each player as its own time-aligned track of a single MP4 file
+    pub fn render_stems(
+        &self,
+        session_path: &Path,
+        players: &[String],
+        output_path: &Path,
+    ) -> Result<(), anyhow::Error> {
+        let mut stems = Vec::with_capacity(players.len());
+        for player in players {
+            let stream = OpusPacketStream::new(session_path, player)?;
+            if stream.info().is_none() {
+                log::warn!("Skipping stem for {}: no audio data", player);
+                continue;
+            }
+            stems.push(Stem {
+                name: player.clone(),
+                stream,
+            });
+        }
+
+        multitrack::mux_stems(stems, &self.chapters, output_path)
+    }
+
+    /// Render a session mixdown, re-encoded as stereo Opus
+    pub fn render_mix(&self, mix: SessionMix, output_path: &Path) -> Result<(), anyhow::Error> {
+        let info = OpusStreamInfo {
+            sample_rate: MIX_SAMPLE_RATE,
+            channels: MIX_CHANNELS,
+            first_packet_timestamp_ms: mix.origin_ms(),
+            session_info: mix.session_info().clone(),
+        };
+
+        // Packets go to the file as they are encoded, so memory doesn't grow with session length
+        let mut output = self.begin_mux(&info, output_path)?;
+        let mut encoder = MixEncoder::new()?;
+        let mut packets = 0usize;
+        let mut write = |chunk: OpusChunk| {
+            packets += 1;
+            self.write_chunk(&mut output, chunk)
+        };
+        let frames = mix.run(|samples| encoder.push(samples, &mut write))?;
+        encoder.finish(&mut write)?;
+
+        log::info!(
+            "Mixdown complete: {} sample frames encoded into {} packets",
+            frames,
+            packets
+        );
+
+        self.finish_mux(&info, output)
+    }
+}
+
+/// Encodes the mixed stereo timeline into 20ms Opus packets
+struct MixEncoder {
+    encoder: opus2::Encoder,
+    pending: Vec<f32>,
+    out: Vec<u8>,
+}
+
+impl MixEncoder {
+    const FRAME_SAMPLES: usize = (MIX_SAMPLE_RATE as usize * 20) / 1000;
+
+    fn new() -> Result<Self, anyhow::Error> {
+        let mut encoder = opus2::Encoder::new(
+            MIX_SAMPLE_RATE,
+            opus2::Channels::Stereo,
+            opus2::Application::Audio,
+        )?;
+        encoder.set_bitrate(opus2::Bitrate::Bits(128000))?;
+
+        Ok(Self {
+            encoder,
+            pending: Vec::new(),
+            out: vec![0u8; 4000],
+        })
+    }
+
+    fn frame_len() -> usize {
+        Self::FRAME_SAMPLES * MIX_CHANNELS as usize
+    }
+
+    /// Buffer samples and hand each complete packet to `emit`
+    fn push<E>(&mut self, samples: &[f32], emit: &mut E) -> Result<(), anyhow::Error>
+    where
+        E: FnMut(OpusChunk) -> Result<(), anyhow::Error>,
+    {
+        self.pending.extend_from_slice(samples);
+
+        let frame_len = Self::frame_len();
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= frame_len {
+            let encoded_len = self
+                .encoder
+                .encode_float(&self.pending[consumed..consumed + frame_len], &mut self.out)?;
+            emit(OpusChunk::Packet {
+                data: self.out[..encoded_len].to_vec(),
+                duration_samples: Self::FRAME_SAMPLES as u32,
+            })?;
+            consumed += frame_len;
+        }
+        self.pending.drain(..consumed);
+        Ok(())
+    }
+
+    /// Pad the final partial frame with silence and emit it
+    fn finish<E>(mut self, emit: &mut E) -> Result<(), anyhow::Error>
+    where
+        E: FnMut(OpusChunk) -> Result<(), anyhow::Error>,
+    {
+        if !self.pending.is_empty() {
+            let padding = Self::frame_len() - self.pending.len();
+            self.push(&vec![0.0; padding], emit)?;
+        }
+        Ok(())
     }
 }
 
@@ -0,0 +1,221 @@
+//! Multi-track MP4 muxing for stem export
+//!
+//! Writes one Opus `trak` per participant into a single file. Every track starts at the
+//! session's earliest packet (leading gaps are filled with encoded silence) so the stems
+//! line up when dropped into an NLE, and all of them reference one shared tmcd track and,
+//! when the session has speech markers, one shared chapter track.
+//!
+//! # File Layout
+//!
+//! ```text
+//! ftyp
+//! mdat (64-bit size)
+//! ├── timecode sample (4 bytes)
+//! ├── chapter titles (optional)
+//! ├── track 1 packets
+//! └── track N packets
+//! moov
+//! ├── mvhd
+//! ├── trak (Opus) × N
+//! ├── trak (tmcd)
+//! ├── trak (text chapters, optional)
+//! └── udta
+//! ```
+
+mod track;
+
+use track::OpusTrack;
+
+use super::boxes::BoxWriter;
+use super::chapters::ChapterTrack;
+use super::constants::IDENTITY_MATRIX;
+use super::timecode::{TimecodeSample, TimecodeTrack, UserDataBox};
+use crate::audio::recording::renderer::timeline::Chapter;
+use crate::audio::recording::renderer::stream::opus::{
+    OpusChunk, OpusPacketStream, OpusStreamInfo, SilenceEncoder,
+};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+
+/// Timescale used for the movie header and the timecode track
+const MOVIE_TIMESCALE: u32 = 48000;
+
+/// A participant's packet stream, labelled with the name its track should carry
+pub struct Stem {
+    pub name: String,
+    pub stream: OpusPacketStream,
+}
+
+/// Build the file type box
+fn build_ftyp() -> Vec<u8> {
+    let ftyp = BoxWriter::new()
+        .fourcc(b"M4A ")
+        // Minor version
+        .u32(0)
+        .fourcc(b"M4A ")
+        .fourcc(b"isom")
+        .fourcc(b"iso2")
+        .fourcc(b"mp41")
+        .finish();
+    BoxWriter::new().write_box(b"ftyp", &ftyp).finish()
+}
+
+/// Build the movie header box (mvhd)
+fn build_mvhd(duration: u64, next_track_id: u32) -> Vec<u8> {
+    let mut mvhd = BoxWriter::new()
+        // Version 0, flags 0
+        .u32(0)
+        // Creation/modification time
+        .u32(0)
+        .u32(0)
+        .u32(MOVIE_TIMESCALE)
+        .u32(duration as u32)
+        // Rate (1.0), volume (1.0)
+        .u32(0x0001_0000)
+        .u16(0x0100)
+        // Reserved
+        .u16(0)
+        .u64(0);
+
+    for value in IDENTITY_MATRIX {
+        mvhd = mvhd.u32(value);
+    }
+
+    // Pre-defined (6 x u32)
+    mvhd.zeros(24).u32(next_track_id).finish()
+}
+
+/// Mux every stem into `output_path`, time-aligned to the earliest first packet
+pub fn mux_stems(stems: Vec<Stem>, chapters: &[Chapter], output_path: &Path) -> Result<(), anyhow::Error> {
+    let infos: Vec<OpusStreamInfo> = stems
+        .iter()
+        .map(|stem| {
+            stem.stream
+                .info()
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("No audio data for player: {}", stem.name))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let first = infos
+        .iter()
+        .min_by_key(|info| info.first_packet_timestamp_ms)
+        .ok_or_else(|| anyhow::anyhow!("No stems to render"))?;
+
+    // Shared timeline: everything is measured from the earliest packet of any stem
+    let timeline = OpusStreamInfo {
+        sample_rate: MOVIE_TIMESCALE,
+        channels: 1,
+        first_packet_timestamp_ms: first.first_packet_timestamp_ms,
+        session_info: first.session_info.clone(),
+    };
+
+    let timecode_track_id = stems.len() as u32 + 1;
+    let chapter_track_id = (!chapters.is_empty()).then_some(timecode_track_id + 1);
+    let mut file = BufWriter::new(File::create(output_path)?);
+
+    let ftyp = build_ftyp();
+    file.write_all(&ftyp)?;
+
+    // mdat with a 64-bit size, patched once all samples are written
+    let mdat_offset = ftyp.len() as u64;
+    file.write_all(&BoxWriter::new().u32(1).fourcc(b"mdat").u64(0).finish())?;
+    let mut offset = mdat_offset + 16;
+
+    let timecode_offset = offset;
+    let timecode_sample = TimecodeSample::from_stream_info(&timeline);
+    file.write_all(&timecode_sample.to_vec())?;
+    offset += 4;
+
+    let mut tracks = Vec::with_capacity(stems.len());
+    for (index, (stem, info)) in stems.into_iter().zip(&infos).enumerate() {
+        let mut track = OpusTrack::new(
+            index as u32 + 1,
+            timecode_track_id,
+            &stem.name,
+            info.sample_rate,
+            info.channels,
+            MOVIE_TIMESCALE,
+            offset,
+        );
+        track.chapter_track_id = chapter_track_id;
+
+        // Pad the start so this stem lines up with the earliest one
+        let lead_ms = info.first_packet_timestamp_ms - timeline.first_packet_timestamp_ms;
+        if lead_ms > 0 {
+            let lead_samples = (lead_ms * info.sample_rate as u64 / 1000) as u32;
+            let mut encoder = SilenceEncoder::new(info.sample_rate, info.channels)?;
+            for (data, duration_samples) in encoder.encode_silence(lead_samples)? {
+                file.write_all(&data)?;
+                track.push_sample(data.len(), duration_samples);
+                offset += data.len() as u64;
+            }
+        }
+
+        for chunk in stem.stream {
+            let (data, duration_samples) = match chunk? {
+                OpusChunk::Packet { data, duration_samples }
+                | OpusChunk::Silence { data, duration_samples } => (data, duration_samples),
+            };
+            file.write_all(&data)?;
+            track.push_sample(data.len(), duration_samples);
+            offset += data.len() as u64;
+        }
+
+        log::info!(
+            "Muxed stem {} ({} packets) as track {}",
+            track.name,
+            track.sample_sizes.len(),
+            track.track_id
+        );
+        tracks.push(track);
+    }
+
+    let duration = tracks.iter().map(|t| t.movie_duration()).max().unwrap_or(0);
+    let duration_ms = duration * 1000 / MOVIE_TIMESCALE as u64;
+
+    // Chapters past the end of the audio are dropped, so the titles follow the audio samples
+    let chapter_track = chapter_track_id
+        .map(|id| ChapterTrack::new(id, chapters, duration_ms, duration, offset));
+    if let Some(chapter_track) = &chapter_track {
+        let sample_data = chapter_track.sample_data();
+        file.write_all(&sample_data)?;
+        offset += sample_data.len() as u64;
+    }
+
+    let mdat_size = offset - mdat_offset;
+
+    let track_ids: Vec<u32> = tracks.iter().map(|t| t.track_id).collect();
+    let timecode_track = TimecodeTrack::builder()
+        .from_stream_info(&timeline)
+        .track_id(timecode_track_id)
+        .audio_track_ids(&track_ids)
+        .duration_samples(duration)
+        .data_offset(timecode_offset)
+        .build()
+        .map_err(|e| anyhow::anyhow!("Failed to build timecode track: {}", e))?
+        .to_bytes()
+        .map_err(|e| anyhow::anyhow!("Failed to serialize timecode track: {}", e))?;
+
+    let next_track_id = chapter_track_id.unwrap_or(timecode_track_id) + 1;
+    let mut moov = BoxWriter::new().bytes(&build_mvhd(duration, next_track_id));
+    for track in &tracks {
+        moov = moov.bytes(&track.to_bytes());
+    }
+    moov = moov.bytes(&timecode_track);
+    if let Some(chapter_track) = &chapter_track {
+        moov = moov.bytes(&chapter_track.to_bytes());
+    }
+    moov = moov
+        .bytes(&UserDataBox::from_stream_info(&timeline, Some(duration_ms)).to_bytes());
+
+    file.write_all(&BoxWriter::new().write_box(b"moov", moov.as_bytes()).finish())?;
+
+    // Patch the mdat size now that all samples are in place
+    file.seek(SeekFrom::Start(mdat_offset + 8))?;
+    file.write_all(&mdat_size.to_be_bytes())?;
+    file.flush()?;
+
+    Ok(())
+}
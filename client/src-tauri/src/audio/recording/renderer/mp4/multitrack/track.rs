@@ -0,0 +1,339 @@
+//! Opus audio track (trak box) construction
+//!
+//! Builds a complete audio trak for one participant of a multi-track file.
+
+use crate::audio::recording::renderer::mp4::boxes::BoxWriter;
+use crate::audio::recording::renderer::mp4::chapters::ChapterTrack;
+use crate::audio::recording::renderer::mp4::constants::{
+    DREF_SELF_CONTAINED, IDENTITY_MATRIX, LANGUAGE_UNDETERMINED, OPUS_PRE_SKIP,
+    TKHD_FLAGS_DEFAULT,
+};
+
+/// One Opus audio track whose samples are stored as a single contiguous chunk
+#[derive(Debug, Clone)]
+pub struct OpusTrack {
+    /// Track ID for this audio track
+    pub track_id: u32,
+    /// Track ID of the timecode track this track references
+    pub timecode_track_id: u32,
+    /// Track ID of the chapter track this track references, if any
+    pub chapter_track_id: Option<u32>,
+    /// Track name, shown by NLEs as the clip/track label
+    pub name: String,
+    /// Sample rate (media timescale)
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Movie timescale, used for the track header duration
+    pub movie_timescale: u32,
+    /// File offset of the first sample
+    pub chunk_offset: u64,
+    /// Size in bytes of each Opus packet
+    pub sample_sizes: Vec<u32>,
+    /// Duration in samples of each Opus packet
+    pub sample_durations: Vec<u32>,
+}
+
+impl OpusTrack {
+    /// Create an empty track; samples are appended as they are written to mdat
+    pub fn new(
+        track_id: u32,
+        timecode_track_id: u32,
+        name: &str,
+        sample_rate: u32,
+        channels: u16,
+        movie_timescale: u32,
+        chunk_offset: u64,
+    ) -> Self {
+        Self {
+            track_id,
+            timecode_track_id,
+            chapter_track_id: None,
+            name: name.to_string(),
+            sample_rate,
+            channels,
+            movie_timescale,
+            chunk_offset,
+            sample_sizes: Vec::new(),
+            sample_durations: Vec::new(),
+        }
+    }
+
+    /// Register a sample written to mdat
+    pub fn push_sample(&mut self, size: usize, duration_samples: u32) {
+        self.sample_sizes.push(size as u32);
+        self.sample_durations.push(duration_samples);
+    }
+
+    /// Total duration in media timescale
+    pub fn duration_samples(&self) -> u64 {
+        self.sample_durations.iter().map(|d| *d as u64).sum()
+    }
+
+    /// Total duration in movie timescale
+    pub fn movie_duration(&self) -> u64 {
+        self.duration_samples() * self.movie_timescale as u64 / self.sample_rate.max(1) as u64
+    }
+
+    /// Serialize the audio track to a complete trak box
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let chap = self.chapter_track_id.map(ChapterTrack::tref_entry).unwrap_or_default();
+        let tref = BoxWriter::new()
+            .write_box(b"tmcd", &BoxWriter::new().u32(self.timecode_track_id).finish())
+            .bytes(&chap)
+            .finish();
+        let name = BoxWriter::new()
+            .write_box(b"name", self.name.as_bytes())
+            .finish();
+
+        let trak = BoxWriter::new()
+            .write_box(b"tkhd", &self.build_tkhd())
+            .write_box(b"tref", &tref)
+            .write_box(b"mdia", &self.build_mdia())
+            .write_box(b"udta", &name);
+
+        BoxWriter::new().write_box(b"trak", trak.as_bytes()).finish()
+    }
+
+    /// Build the track header box (tkhd)
+    fn build_tkhd(&self) -> Vec<u8> {
+        let mut tkhd = BoxWriter::new()
+            // Version 0, flags (enabled, in_movie, in_preview)
+            .u32(TKHD_FLAGS_DEFAULT)
+            // Creation/modification time
+            .u32(0)
+            .u32(0)
+            .u32(self.track_id)
+            // Reserved
+            .u32(0)
+            .u32(self.movie_duration() as u32)
+            // Reserved (8 bytes)
+            .u64(0)
+            // Layer, alternate group
+            .u16(0)
+            .u16(0)
+            // Volume (1.0 in 8.8 fixed point)
+            .u16(0x0100)
+            // Reserved
+            .u16(0);
+
+        for value in IDENTITY_MATRIX {
+            tkhd = tkhd.u32(value);
+        }
+
+        // Width, height (0 for audio)
+        tkhd.u32(0).u32(0).finish()
+    }
+
+    /// Build the media box (mdia)
+    fn build_mdia(&self) -> Vec<u8> {
+        let mdhd = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Creation/modification time
+            .u32(0)
+            .u32(0)
+            .u32(self.sample_rate)
+            .u32(self.duration_samples() as u32)
+            .u16(LANGUAGE_UNDETERMINED)
+            // Quality
+            .u16(0)
+            .finish();
+
+        let hdlr = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Pre-defined
+            .u32(0)
+            .fourcc(b"soun")
+            // Reserved (3 x u32)
+            .u32(0)
+            .u32(0)
+            .u32(0)
+            .bytes(b"SoundHandler\0")
+            .finish();
+
+        BoxWriter::new()
+            .write_box(b"mdhd", &mdhd)
+            .write_box(b"hdlr", &hdlr)
+            .write_box(b"minf", &self.build_minf())
+            .finish()
+    }
+
+    /// Build the media information box (minf)
+    fn build_minf(&self) -> Vec<u8> {
+        // Sound media header: version/flags, balance, reserved
+        let smhd = BoxWriter::new().u32(0).i16(0).u16(0).finish();
+
+        let url_entry = BoxWriter::new().u32(DREF_SELF_CONTAINED).finish();
+        let dref = BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            .write_box(b"url ", &url_entry)
+            .finish();
+        let dinf = BoxWriter::new().write_box(b"dref", &dref).finish();
+
+        BoxWriter::new()
+            .write_box(b"smhd", &smhd)
+            .write_box(b"dinf", &dinf)
+            .write_box(b"stbl", &self.build_stbl())
+            .finish()
+    }
+
+    /// Build the sample table box (stbl)
+    fn build_stbl(&self) -> Vec<u8> {
+        let stbl = BoxWriter::new()
+            .write_box(b"stsd", &self.build_stsd())
+            .write_box(b"stts", &self.build_stts())
+            .write_box(b"stsc", &self.build_stsc())
+            .write_box(b"stsz", &self.build_stsz());
+
+        if self.chunk_offset > u32::MAX as u64 {
+            let co64 = BoxWriter::new().u32(0).u32(1).u64(self.chunk_offset).finish();
+            stbl.write_box(b"co64", &co64).finish()
+        } else {
+            let stco = BoxWriter::new()
+                .u32(0)
+                .u32(1)
+                .u32(self.chunk_offset as u32)
+                .finish();
+            stbl.write_box(b"stco", &stco).finish()
+        }
+    }
+
+    /// Build the sample description box (stsd) with an Opus sample entry
+    fn build_stsd(&self) -> Vec<u8> {
+        // OpusSpecificBox, per "Encapsulation of Opus in ISO Base Media File Format"
+        let dops = BoxWriter::new()
+            // Version
+            .u8(0)
+            .u8(self.channels as u8)
+            .u16(OPUS_PRE_SKIP)
+            .u32(self.sample_rate)
+            // Output gain
+            .i16(0)
+            // Channel mapping family 0 (mono/stereo)
+            .u8(0)
+            .finish();
+
+        let opus_entry = BoxWriter::new()
+            // Reserved (6 bytes)
+            .zeros(6)
+            // Data reference index
+            .u16(1)
+            // Reserved (2 x u32)
+            .u32(0)
+            .u32(0)
+            .u16(self.channels)
+            // Sample size
+            .u16(16)
+            // Pre-defined, reserved
+            .u16(0)
+            .u16(0)
+            // Sample rate (16.16 fixed point)
+            .u32(self.sample_rate << 16)
+            .write_box(b"dOps", &dops)
+            .finish();
+
+        BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            .write_box(b"Opus", &opus_entry)
+            .finish()
+    }
+
+    /// Build the time to sample box (stts), collapsing runs of equal durations
+    fn build_stts(&self) -> Vec<u8> {
+        let mut runs: Vec<(u32, u32)> = Vec::new();
+        for duration in &self.sample_durations {
+            match runs.last_mut() {
+                Some((count, last)) if last == duration => *count += 1,
+                _ => runs.push((1, *duration)),
+            }
+        }
+
+        runs.iter()
+            .fold(
+                BoxWriter::new().u32(0).u32(runs.len() as u32),
+                |writer, (count, duration)| writer.u32(*count).u32(*duration),
+            )
+            .finish()
+    }
+
+    /// Build the sample to chunk box (stsc): every sample lives in one chunk
+    fn build_stsc(&self) -> Vec<u8> {
+        BoxWriter::new()
+            // Version 0, flags 0
+            .u32(0)
+            // Entry count
+            .u32(1)
+            // First chunk, samples per chunk, sample description index
+            .u32(1)
+            .u32(self.sample_sizes.len() as u32)
+            .u32(1)
+            .finish()
+    }
+
+    /// Build the sample size box (stsz)
+    fn build_stsz(&self) -> Vec<u8> {
+        self.sample_sizes
+            .iter()
+            .fold(
+                BoxWriter::new()
+                    // Version 0, flags 0
+                    .u32(0)
+                    // Sample size 0: sizes follow per sample
+                    .u32(0)
+                    .u32(self.sample_sizes.len() as u32),
+                |writer, size| writer.u32(*size),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track() -> OpusTrack {
+        let mut track = OpusTrack::new(1, 3, "Steve", 48000, 1, 48000, 1024);
+        track.push_sample(40, 960);
+        track.push_sample(42, 960);
+        track.push_sample(38, 480);
+        track
+    }
+
+    #[test]
+    fn test_stts_collapses_equal_durations() {
+        let stts = track().build_stts();
+        assert_eq!(
+            stts,
+            vec![0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 3, 0xC0, 0, 0, 0, 1, 0, 0, 1, 0xE0]
+        );
+    }
+
+    #[test]
+    fn test_to_bytes_produces_named_trak() {
+        let bytes = track().to_bytes();
+        assert_eq!(&bytes[4..8], b"trak");
+        assert_eq!(
+            u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize,
+            bytes.len()
+        );
+        assert!(bytes.windows(4).any(|w| w == b"dOps"));
+        assert!(bytes.windows(5).any(|w| w == b"Steve"));
+        assert!(bytes.windows(4).any(|w| w == b"stco"));
+    }
+
+    #[test]
+    fn test_large_offsets_use_co64() {
+        let mut track = track();
+        track.chunk_offset = u32::MAX as u64 + 1;
+        let bytes = track.to_bytes();
+        assert!(bytes.windows(4).any(|w| w == b"co64"));
+        assert!(!bytes.windows(4).any(|w| w == b"stco"));
+    }
+}
@@ -485,7 +485,7 @@ mod tests {
         use crate::audio::recording::renderer::WalAudioReader;
         let reader = WalAudioReader::new(session, &player);
         match &reader {
-            Ok(r) => println!("WalAudioReader created, entries: {}", r.entry_count()),
+            Ok(r) => println!("WalAudioReader created, first entry: {:?}", r.peek_raw_entry().map(|e| e.relative_timestamp_ms)),
             Err(e) => println!("WalAudioReader error: {}", e),
         }
 
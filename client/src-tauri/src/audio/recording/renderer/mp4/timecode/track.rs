@@ -37,8 +37,8 @@ impl std::error::Error for TimecodeError {}
 pub struct TimecodeTrack {
     /// Track ID for this timecode track
     track_id: u32,
-    /// Track ID of the audio track this timecode references
-    audio_track_id: u32,
+    /// Track IDs of the audio tracks this timecode references
+    audio_track_ids: Vec<u32>,
     /// Sample rate (timescale)
     sample_rate: u32,
     /// Duration in samples
@@ -132,8 +132,12 @@ impl TimecodeTrack {
 
     /// Build the track reference box (tref)
     fn build_tref(&self) -> Vec<u8> {
-        // tmcd reference containing the audio track ID
-        let tmcd_ref = BoxWriter::new().u32(self.audio_track_id).finish();
+        // tmcd reference containing the audio track IDs
+        let tmcd_ref = self
+            .audio_track_ids
+            .iter()
+            .fold(BoxWriter::new(), |writer, id| writer.u32(*id))
+            .finish();
 
         BoxWriter::new().write_box(b"tmcd", &tmcd_ref).finish()
     }
@@ -347,7 +351,7 @@ impl TimecodeTrack {
 #[derive(Debug, Default)]
 pub struct TimecodeTrackBuilder {
     track_id: Option<u32>,
-    audio_track_id: Option<u32>,
+    audio_track_ids: Vec<u32>,
     sample_rate: Option<u32>,
     duration_samples: Option<u64>,
     data_offset: Option<u64>,
@@ -377,7 +381,13 @@ impl TimecodeTrackBuilder {
 
     /// Set the audio track ID that this timecode references
     pub fn audio_track_id(mut self, id: u32) -> Self {
-        self.audio_track_id = Some(id);
+        self.audio_track_ids = vec![id];
+        self
+    }
+
+    /// Set every audio track ID that this timecode references, for multi-track files
+    pub fn audio_track_ids(mut self, ids: &[u32]) -> Self {
+        self.audio_track_ids = ids.to_vec();
         self
     }
 
@@ -411,9 +421,11 @@ impl TimecodeTrackBuilder {
             track_id: self
                 .track_id
                 .ok_or(TimecodeError::MissingField("track_id"))?,
-            audio_track_id: self
-                .audio_track_id
-                .ok_or(TimecodeError::MissingField("audio_track_id"))?,
+            audio_track_ids: if self.audio_track_ids.is_empty() {
+                return Err(TimecodeError::MissingField("audio_track_id"));
+            } else {
+                self.audio_track_ids
+            },
             sample_rate: self
                 .sample_rate
                 .ok_or(TimecodeError::MissingField("sample_rate"))?,
@@ -475,4 +487,23 @@ mod tests {
         // Check fourcc is 'trak'
         assert_eq!(&bytes[4..8], b"trak");
     }
+
+    #[test]
+    fn test_tref_references_every_audio_track() {
+        let track = TimecodeTrackBuilder::new()
+            .track_id(4)
+            .audio_track_ids(&[1, 2, 3])
+            .sample_rate(48000)
+            .duration_samples(480000)
+            .data_offset(1000)
+            .timecode(Timecode::new(0, 48000))
+            .build()
+            .unwrap();
+
+        let tref = track.build_tref();
+        // tmcd box header (8) + 3 track IDs
+        assert_eq!(tref.len(), 8 + 12);
+        assert_eq!(&tref[4..8], b"tmcd");
+        assert_eq!(&tref[8..], &[0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3]);
+    }
 }
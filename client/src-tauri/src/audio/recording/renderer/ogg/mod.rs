@@ -0,0 +1,113 @@
+//! Ogg Opus renderer
+//!
+//! Encapsulates the raw WAL Opus packets in an Ogg stream without re-encoding,
+//! per RFC 7845. Session and player metadata are carried in the `OpusTags` header.
+
+mod page;
+
+#[cfg(test)]
+mod tests;
+
+use page::OggPageWriter;
+
+use super::mp4::constants::OPUS_PRE_SKIP;
+use super::vorbis_comment::VorbisComment;
+use super::{AudioRenderer, OpusChunk, OpusPacketStream, OpusStreamInfo};
+use async_trait::async_trait;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// Ogg Opus granule positions always count 48kHz samples, whatever the input rate
+const GRANULE_RATE: u64 = 48000;
+
+/// Build the identification header packet
+fn opus_head(info: &OpusStreamInfo) -> Vec<u8> {
+    let mut head = Vec::with_capacity(19);
+    head.extend_from_slice(b"OpusHead");
+    // Version
+    head.push(1);
+    head.push(info.channels as u8);
+    head.extend_from_slice(&OPUS_PRE_SKIP.to_le_bytes());
+    head.extend_from_slice(&info.sample_rate.to_le_bytes());
+    // Output gain
+    head.extend_from_slice(&0i16.to_le_bytes());
+    // Channel mapping family 0 (mono/stereo)
+    head.push(0);
+    head
+}
+
+/// Build the comment header packet
+fn opus_tags(comment: &VorbisComment) -> Vec<u8> {
+    let mut tags = b"OpusTags".to_vec();
+    tags.extend_from_slice(&comment.to_bytes());
+    tags
+}
+
+/// Ogg Opus renderer with lossless Opus passthrough
+pub struct OggOpusRenderer;
+
+impl OggOpusRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for OggOpusRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl AudioRenderer for OggOpusRenderer {
+    async fn render(
+        &mut self,
+        session_path: &Path,
+        player_name: &str,
+        output_path: &Path,
+    ) -> Result<(), anyhow::Error> {
+        let stream = OpusPacketStream::new(session_path, player_name)?;
+        let info = stream
+            .info()
+            .ok_or_else(|| anyhow::anyhow!("No audio data for player: {}", player_name))?
+            .clone();
+
+        let serial = page::crc32(format!("{}/{}", info.session_info.session_id, player_name).as_bytes());
+        let mut writer = OggPageWriter::new(BufWriter::new(File::create(output_path)?), serial);
+
+        // Each header packet sits alone on its page(s), granule 0
+        writer.write_packet(&opus_head(&info), 0)?;
+        writer.flush()?;
+        let comment =
+            VorbisComment::from_session(&info.session_info, player_name, info.first_packet_timestamp_ms);
+        writer.write_packet(&opus_tags(&comment), 0)?;
+        writer.flush()?;
+
+        let mut granule = OPUS_PRE_SKIP as u64;
+        let mut packets = 0;
+        for chunk in stream {
+            let (data, duration_samples) = match chunk? {
+                OpusChunk::Packet { data, duration_samples }
+                | OpusChunk::Silence { data, duration_samples } => (data, duration_samples),
+            };
+            granule += duration_samples as u64 * GRANULE_RATE / info.sample_rate as u64;
+            writer.write_packet(&data, granule as i64)?;
+            packets += 1;
+        }
+
+        writer.finish()?;
+
+        log::info!(
+            "Rendering {} complete: {} Opus packets, final granule {}",
+            player_name,
+            packets,
+            granule
+        );
+        Ok(())
+    }
+
+    fn file_extension(&self) -> &str {
+        "opus"
+    }
+}
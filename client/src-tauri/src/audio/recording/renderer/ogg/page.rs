@@ -0,0 +1,148 @@
+//! Ogg page framing
+//!
+//! Packs packets into Ogg pages with lacing, continuation and granule positions.
+//! https://www.rfc-editor.org/rfc/rfc3533
+
+use std::io::Write;
+
+/// Page header flag: first packet on this page continues one from the previous page
+pub const FLAG_CONTINUED: u8 = 0x01;
+/// Page header flag: first page of the logical stream
+pub const FLAG_BOS: u8 = 0x02;
+/// Page header flag: last page of the logical stream
+pub const FLAG_EOS: u8 = 0x04;
+
+/// Maximum lacing values (and so segments) on a single page
+const MAX_SEGMENTS: usize = 255;
+/// Start a new page once the body reaches this size, as libogg does
+const TARGET_PAGE_BYTES: usize = 4096;
+/// Granule position of a page on which no packet completes
+const NO_GRANULE: i64 = -1;
+
+/// CRC-32 used by Ogg: polynomial 0x04C11DB7, no reflection, zero initial value
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0u32;
+    for byte in data {
+        crc ^= (*byte as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04C1_1DB7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Writes one logical Ogg stream
+pub struct OggPageWriter<W: Write> {
+    writer: W,
+    serial: u32,
+    sequence: u32,
+    segments: Vec<u8>,
+    body: Vec<u8>,
+    // Granule position of the last packet completed on the pending page
+    granule: i64,
+    // The pending page starts with the tail of a packet from the previous page
+    continued: bool,
+}
+
+impl<W: Write> OggPageWriter<W> {
+    pub fn new(writer: W, serial: u32) -> Self {
+        Self {
+            writer,
+            serial,
+            sequence: 0,
+            segments: Vec::new(),
+            body: Vec::new(),
+            granule: NO_GRANULE,
+            continued: false,
+        }
+    }
+
+    /// Queue a packet. `granule` is the position after this packet completes.
+    /// Packets larger than a page are split across pages.
+    pub fn write_packet(&mut self, packet: &[u8], granule: i64) -> Result<(), std::io::Error> {
+        if self.body.len() >= TARGET_PAGE_BYTES {
+            self.write_page(0)?;
+        }
+
+        // Lacing: runs of 255 followed by a terminating value below 255 (possibly 0)
+        let mut chunks = packet.chunks(255).peekable();
+        let mut lacing: Vec<(u8, &[u8])> = Vec::new();
+        while let Some(chunk) = chunks.next() {
+            lacing.push((chunk.len() as u8, chunk));
+            if chunks.peek().is_none() && chunk.len() == 255 {
+                lacing.push((0, &[]));
+            }
+        }
+        if packet.is_empty() {
+            lacing.push((0, &[]));
+        }
+
+        for (index, (value, chunk)) in lacing.into_iter().enumerate() {
+            if self.segments.len() == MAX_SEGMENTS {
+                self.write_page(0)?;
+                self.continued = index > 0;
+            }
+            self.segments.push(value);
+            self.body.extend_from_slice(chunk);
+        }
+
+        self.granule = granule;
+        Ok(())
+    }
+
+    /// Write out the pending page even if it has room left, e.g. to end the header pages
+    pub fn flush(&mut self) -> Result<(), std::io::Error> {
+        if !self.segments.is_empty() {
+            self.write_page(0)?;
+        }
+        Ok(())
+    }
+
+    /// Write the final page with the end-of-stream flag and return the inner writer
+    pub fn finish(mut self) -> Result<W, std::io::Error> {
+        self.write_page(FLAG_EOS)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_page(&mut self, flags: u8) -> Result<(), std::io::Error> {
+        let mut header_type = flags;
+        if self.sequence == 0 {
+            header_type |= FLAG_BOS;
+        }
+        if self.continued {
+            header_type |= FLAG_CONTINUED;
+        }
+
+        let mut page = Vec::with_capacity(27 + self.segments.len() + self.body.len());
+        page.extend_from_slice(b"OggS");
+        // Stream structure version
+        page.push(0);
+        page.push(header_type);
+        page.extend_from_slice(&self.granule.to_le_bytes());
+        page.extend_from_slice(&self.serial.to_le_bytes());
+        page.extend_from_slice(&self.sequence.to_le_bytes());
+        // CRC placeholder
+        page.extend_from_slice(&[0; 4]);
+        page.push(self.segments.len() as u8);
+        page.extend_from_slice(&self.segments);
+        page.extend_from_slice(&self.body);
+
+        let crc = crc32(&page);
+        page[22..26].copy_from_slice(&crc.to_le_bytes());
+
+        self.writer.write_all(&page)?;
+
+        self.sequence += 1;
+        self.segments.clear();
+        self.body.clear();
+        // A page that only carries part of a packet has no granule position
+        self.granule = NO_GRANULE;
+        self.continued = false;
+        Ok(())
+    }
+}
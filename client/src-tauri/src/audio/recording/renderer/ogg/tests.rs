@@ -0,0 +1,229 @@
+//! Round-trip tests for Ogg Opus output
+//!
+//! Pages are parsed back, CRC-checked and reassembled into packets so the lacing,
+//! continuation flags and granule positions can be compared with what was written.
+
+use super::page::{crc32, OggPageWriter, FLAG_BOS, FLAG_CONTINUED, FLAG_EOS};
+use super::*;
+use crate::audio::recording::renderer::SessionInfo;
+
+/// A parsed Ogg page
+#[derive(Debug)]
+struct Page {
+    header_type: u8,
+    granule: i64,
+    serial: u32,
+    sequence: u32,
+    segments: Vec<u8>,
+    body: Vec<u8>,
+}
+
+/// Parse every page in `data`, verifying capture patterns and checksums
+fn parse_pages(data: &[u8]) -> Vec<Page> {
+    let mut pages = Vec::new();
+    let mut pos = 0;
+
+    while pos < data.len() {
+        assert_eq!(&data[pos..pos + 4], b"OggS", "bad capture pattern at {}", pos);
+        assert_eq!(data[pos + 4], 0, "unsupported stream structure version");
+
+        let segment_count = data[pos + 26] as usize;
+        let segments = data[pos + 27..pos + 27 + segment_count].to_vec();
+        let body_len: usize = segments.iter().map(|s| *s as usize).sum();
+        let page_len = 27 + segment_count + body_len;
+
+        let mut page = data[pos..pos + page_len].to_vec();
+        let stored_crc = u32::from_le_bytes(page[22..26].try_into().unwrap());
+        page[22..26].copy_from_slice(&[0; 4]);
+        assert_eq!(crc32(&page), stored_crc, "CRC mismatch on page at {}", pos);
+
+        pages.push(Page {
+            header_type: data[pos + 5],
+            granule: i64::from_le_bytes(data[pos + 6..pos + 14].try_into().unwrap()),
+            serial: u32::from_le_bytes(data[pos + 14..pos + 18].try_into().unwrap()),
+            sequence: u32::from_le_bytes(data[pos + 18..pos + 22].try_into().unwrap()),
+            body: data[pos + 27 + segment_count..pos + page_len].to_vec(),
+            segments,
+        });
+        pos += page_len;
+    }
+
+    pages
+}
+
+/// Reassemble packets from lacing values, checking continuation flags along the way
+fn packets(pages: &[Page]) -> Vec<Vec<u8>> {
+    let mut packets = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+
+    for page in pages {
+        assert_eq!(
+            page.header_type & FLAG_CONTINUED != 0,
+            current.is_some(),
+            "continuation flag mismatch on page {}",
+            page.sequence
+        );
+
+        let mut offset = 0;
+        for segment in &page.segments {
+            let len = *segment as usize;
+            current
+                .get_or_insert_with(Vec::new)
+                .extend_from_slice(&page.body[offset..offset + len]);
+            offset += len;
+            if len < 255 {
+                packets.push(current.take().unwrap());
+            }
+        }
+    }
+
+    assert!(current.is_none(), "stream ended mid-packet");
+    packets
+}
+
+fn write_stream(input: &[(Vec<u8>, i64)]) -> Vec<u8> {
+    let mut writer = OggPageWriter::new(Vec::new(), 0xB0C0);
+    for (packet, granule) in input {
+        writer.write_packet(packet, *granule).unwrap();
+    }
+    writer.finish().unwrap()
+}
+
+#[test]
+fn test_packets_round_trip() {
+    let input: Vec<(Vec<u8>, i64)> = vec![
+        (vec![1; 10], 960),
+        (vec![2; 600], 1920),
+        // Exact multiple of 255 needs a terminating zero lacing value
+        (vec![3; 510], 2880),
+        (Vec::new(), 3840),
+        (vec![4; 80], 4800),
+    ];
+
+    let data = write_stream(&input);
+    let pages = parse_pages(&data);
+
+    let expected: Vec<Vec<u8>> = input.iter().map(|(p, _)| p.clone()).collect();
+    assert_eq!(packets(&pages), expected);
+    assert_eq!(pages.len(), 1);
+    assert_eq!(pages[0].header_type, FLAG_BOS | FLAG_EOS);
+    assert_eq!(pages[0].granule, 4800);
+    assert_eq!(pages[0].serial, 0xB0C0);
+}
+
+#[test]
+fn test_large_packet_spans_pages() {
+    let large: Vec<u8> = (0..70_000u32).map(|i| i as u8).collect();
+    let input = vec![(vec![9; 20], 960), (large, 1920), (vec![7; 20], 2880)];
+
+    let data = write_stream(&input);
+    let pages = parse_pages(&data);
+
+    let expected: Vec<Vec<u8>> = input.iter().map(|(p, _)| p.clone()).collect();
+    assert_eq!(packets(&pages), expected);
+    assert_eq!(pages.len(), 3);
+
+    // The first page completes only the first packet before the large one starts
+    assert_eq!(pages[0].header_type, FLAG_BOS);
+    assert_eq!(pages[0].granule, 960);
+    // The tail of the large packet fills the second page past the target size
+    assert_eq!(pages[1].header_type, FLAG_CONTINUED);
+    assert_eq!(pages[1].granule, 1920);
+    assert_eq!(pages[2].header_type, FLAG_EOS);
+    assert_eq!(pages[2].granule, 2880);
+
+    for (index, page) in pages.iter().enumerate() {
+        assert_eq!(page.sequence, index as u32);
+    }
+}
+
+#[test]
+fn test_page_with_only_partial_packet_has_no_granule() {
+    let huge = vec![5u8; 255 * 255 * 2];
+    let data = write_stream(&[(huge.clone(), 960)]);
+    let pages = parse_pages(&data);
+
+    assert_eq!(packets(&pages), vec![huge]);
+    assert_eq!(pages[0].granule, -1);
+    assert_eq!(pages.last().unwrap().granule, 960);
+}
+
+#[test]
+fn test_headers() {
+    let info = OpusStreamInfo {
+        sample_rate: 48000,
+        channels: 1,
+        first_packet_timestamp_ms: 250,
+        session_info: SessionInfo {
+            session_id: "session".to_string(),
+            start_timestamp: 1705329045000,
+            player_name: "Steve".to_string(),
+            duration_ms: None,
+        },
+    };
+
+    let head = opus_head(&info);
+    assert_eq!(head.len(), 19);
+    assert_eq!(&head[..8], b"OpusHead");
+    assert_eq!(head[8], 1);
+    assert_eq!(head[9], 1);
+    assert_eq!(u16::from_le_bytes([head[10], head[11]]), OPUS_PRE_SKIP);
+    assert_eq!(u32::from_le_bytes(head[12..16].try_into().unwrap()), 48000);
+
+    let comment = VorbisComment::from_session(&info.session_info, "Alex", 250);
+    let tags = opus_tags(&comment);
+    assert_eq!(&tags[..8], b"OpusTags");
+    let parsed = VorbisComment::from_bytes(&tags[8..]).unwrap();
+    assert_eq!(parsed.get("BVC_PLAYER"), Some("Alex"));
+    assert_eq!(parsed.get("BVC_START_TIMESTAMP"), Some("1705329045250"));
+}
+
+/// Render a real session and read it back (environment-based)
+///
+/// Set TEST_SESSION_PATH and TEST_PLAYER_NAME to run.
+#[tokio::test]
+async fn test_render_round_trip() {
+    let session_path = match std::env::var("TEST_SESSION_PATH") {
+        Ok(p) => p,
+        Err(_) => {
+            println!("Skipping: TEST_SESSION_PATH not set");
+            return;
+        }
+    };
+    let player = std::env::var("TEST_PLAYER_NAME").unwrap_or_else(|_| "Alaydriem".to_string());
+    let session = Path::new(&session_path);
+
+    let output_path = std::env::temp_dir().join(format!("{}_test_render.opus", player));
+    OggOpusRenderer::new()
+        .render(session, &player, &output_path)
+        .await
+        .expect("Render failed");
+
+    let data = std::fs::read(&output_path).unwrap();
+    let pages = parse_pages(&data);
+    let packets = packets(&pages);
+
+    // Headers, then every packet the stream yields, unchanged
+    let stream = OpusPacketStream::new(session, &player).unwrap();
+    let sample_rate = stream.info().unwrap().sample_rate as i64;
+    let mut expected_granule = OPUS_PRE_SKIP as i64;
+    let mut expected = Vec::new();
+    for chunk in stream {
+        let (data, duration_samples) = match chunk.unwrap() {
+            OpusChunk::Packet { data, duration_samples }
+            | OpusChunk::Silence { data, duration_samples } => (data, duration_samples),
+        };
+        expected_granule += duration_samples as i64 * 48000 / sample_rate;
+        expected.push(data);
+    }
+
+    assert_eq!(&packets[0][..8], b"OpusHead");
+    assert_eq!(&packets[1][..8], b"OpusTags");
+    assert_eq!(
+        VorbisComment::from_bytes(&packets[1][8..]).unwrap().get("BVC_PLAYER"),
+        Some(player.as_str())
+    );
+    assert_eq!(&packets[2..], expected.as_slice());
+    assert_eq!(pages.last().unwrap().granule, expected_granule);
+    assert_ne!(pages.last().unwrap().header_type & FLAG_EOS, 0);
+}
@@ -0,0 +1,290 @@
+use common::structs::recording::RecordingHeader;
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::audio::recording::renderer::WalEntry;
+
+const NANO_REC_SIGNATURE: [u8; 6] = *b"NANORC";
+const MAX_HEADER_SIZE: usize = 1024;
+const MAX_CONTENT_SIZE: usize = 50 * 1024;
+const MAX_HEADER_SEARCH_BYTES: usize = 4096;
+
+/// A single parsed nano-wal segment file (`PlayerName-hash-sequence.log`)
+#[derive(Debug)]
+pub struct WalSegment {
+    pub path: PathBuf,
+    pub entries: Vec<WalEntry>,
+    /// Records that were framed correctly but whose header failed to decode
+    pub undecodable: usize,
+    /// Offset of the first record, `None` when no record signature was found
+    pub first_record: Option<u64>,
+    /// Offset just past the last complete record
+    pub valid_len: u64,
+    pub file_len: u64,
+    /// Why parsing stopped before the end of the file
+    pub stop_reason: Option<String>,
+}
+
+impl WalSegment {
+    /// Parse every complete record in a segment file
+    pub fn read(path: &Path) -> Result<Self, anyhow::Error> {
+        log::info!("Parsing WAL file: {:?}", path);
+
+        let file_bytes = fs::read(path)?;
+        let mut segment = Self {
+            path: path.to_path_buf(),
+            entries: Vec::new(),
+            undecodable: 0,
+            first_record: None,
+            valid_len: file_bytes.len() as u64,
+            file_len: file_bytes.len() as u64,
+            stop_reason: None,
+        };
+
+        // Each WAL record is at minimum 16 bytes (6 signature + 2 header_len + 8 content_len),
+        // so this limit scales with file size and can never be exceeded by valid data
+        let max_records = file_bytes.len() / 16;
+        let mut pos = 0;
+
+        let search_limit = MAX_HEADER_SEARCH_BYTES.min(file_bytes.len().saturating_sub(6));
+        while pos + 6 <= search_limit {
+            if file_bytes[pos..pos + 6] == NANO_REC_SIGNATURE {
+                break;
+            }
+            pos += 1;
+        }
+
+        // If signature not found in first 4KB, skip this file
+        if pos + 6 > search_limit {
+            log::warn!("Could not find NANO_REC_SIGNATURE in {:?}, skipping file", path);
+            segment.stop_reason = Some("no record signature in the first 4 KiB".to_string());
+            return Ok(segment);
+        }
+        segment.first_record = Some(pos as u64);
+
+        let mut records_parsed = 0;
+        loop {
+            let record_start = pos;
+            if pos == file_bytes.len() {
+                break;
+            }
+            if records_parsed >= max_records {
+                log::warn!("Hit max_records limit in {:?}, stopping parse", path);
+                segment.stop_reason = Some(format!("record limit reached at offset {}", pos));
+                break;
+            }
+
+            match Self::read_record(&file_bytes, &mut pos) {
+                Ok((header_bytes, content)) => {
+                    if !header_bytes.is_empty() {
+                        match RecordingHeader::from_bytes(header_bytes) {
+                            Ok(header) => {
+                                let relative_timestamp_ms = match &header {
+                                    RecordingHeader::Input(h) => h.relative_timestamp_ms.unwrap_or(0),
+                                    RecordingHeader::Output(h) => h.relative_timestamp_ms,
+                                };
+
+                                segment.entries.push(WalEntry {
+                                    header,
+                                    opus_data: content.to_vec(),
+                                    relative_timestamp_ms,
+                                });
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to parse header (len={}) in {:?}: {:?}", header_bytes.len(), path, e);
+                                segment.undecodable += 1;
+                            }
+                        }
+                    }
+                    records_parsed += 1;
+                }
+                Err(reason) => {
+                    log::warn!("Stopped parsing {:?} at offset {}: {}", path, record_start, reason);
+                    segment.stop_reason = Some(format!("{} at offset {}", reason, record_start));
+                    pos = record_start;
+                    break;
+                }
+            }
+        }
+
+        segment.valid_len = pos as u64;
+        log::info!("  Parsed {} total records from {:?}", records_parsed, path);
+
+        Ok(segment)
+    }
+
+    /// Read one framed record starting at `pos`, advancing past it on success
+    fn read_record<'a>(bytes: &'a [u8], pos: &mut usize) -> Result<(&'a [u8], &'a [u8]), &'static str> {
+        let mut cursor = *pos;
+
+        if cursor + 6 > bytes.len() {
+            return Err("truncated record signature");
+        }
+        if bytes[cursor..cursor + 6] != NANO_REC_SIGNATURE {
+            return Err("missing record signature");
+        }
+        cursor += 6;
+
+        if cursor + 2 > bytes.len() {
+            return Err("truncated header length");
+        }
+        let header_len = u16::from_le_bytes([bytes[cursor], bytes[cursor + 1]]) as usize;
+        cursor += 2;
+
+        if header_len > MAX_HEADER_SIZE {
+            return Err("header length out of range");
+        }
+        if cursor + header_len > bytes.len() {
+            return Err("truncated header");
+        }
+        let header = &bytes[cursor..cursor + header_len];
+        cursor += header_len;
+
+        if cursor + 8 > bytes.len() {
+            return Err("truncated content length");
+        }
+        let mut content_len_bytes = [0u8; 8];
+        content_len_bytes.copy_from_slice(&bytes[cursor..cursor + 8]);
+        let content_len = u64::from_le_bytes(content_len_bytes) as usize;
+        cursor += 8;
+
+        if content_len > MAX_CONTENT_SIZE {
+            return Err("content length out of range");
+        }
+        if cursor + content_len > bytes.len() {
+            return Err("truncated content");
+        }
+        let content = &bytes[cursor..cursor + content_len];
+        cursor += content_len;
+
+        *pos = cursor;
+        Ok((header, content))
+    }
+
+    /// Whether every byte after the first record belongs to a complete record
+    pub fn is_intact(&self) -> bool {
+        self.stop_reason.is_none() && self.undecodable == 0
+    }
+
+    /// Bytes that follow the last complete record
+    pub fn trailing_bytes(&self) -> u64 {
+        self.file_len.saturating_sub(self.valid_len)
+    }
+
+    /// Extract the player name from a segment file name (`PlayerName-hash-sequence.log`)
+    pub fn player_name(file_name: &str) -> Option<&str> {
+        let stem = file_name.strip_suffix(".log")?;
+        let mut parts = stem.rsplitn(3, '-');
+        let _sequence = parts.next()?;
+        let _hash = parts.next()?;
+        parts.next().filter(|name| !name.is_empty())
+    }
+}
+
+/// List the segment files in a WAL directory, optionally limited to one player, in sequence order
+pub fn segment_files(wal_path: &Path, player_name: Option<&str>) -> Result<Vec<PathBuf>, anyhow::Error> {
+    debug!("Reading directory: {:?}", wal_path);
+    let mut segment_files = Vec::new();
+
+    for entry in fs::read_dir(wal_path)?.flatten() {
+        if let Some(filename) = entry.file_name().to_str() {
+            let matches = match player_name {
+                Some(player_name) => filename.starts_with(player_name) && filename.ends_with(".log"),
+                None => filename.ends_with(".log"),
+            };
+            if matches {
+                segment_files.push(entry.path());
+            }
+        }
+    }
+
+    segment_files.sort();
+    Ok(segment_files)
+}
+
+/// Frame a record the way nano-wal writes it to a segment file
+#[cfg(test)]
+pub(crate) fn encode_record(header: &RecordingHeader, content: &[u8]) -> Vec<u8> {
+    let header = header.to_bytes().unwrap();
+
+    let mut bytes = NANO_REC_SIGNATURE.to_vec();
+    bytes.extend_from_slice(&(header.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(&header);
+    bytes.extend_from_slice(&(content.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(content);
+    bytes
+}
+
+/// A minimal input header at the given timestamp
+#[cfg(test)]
+pub(crate) fn input_header(timestamp: u64) -> RecordingHeader {
+    use common::structs::recording::{InputRecordingHeader, PlayerMetadata};
+
+    RecordingHeader::Input(InputRecordingHeader {
+        sample_rate: 48000,
+        channels: 1,
+        relative_timestamp_ms: Some(timestamp),
+        emitter_metadata: PlayerMetadata {
+            player_data: None,
+            spatial: None,
+            gain_settings: None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_segment(name: &str, bytes: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bvc-segment-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_player_name_from_file_name() {
+        assert_eq!(WalSegment::player_name("Alaydriem-1a2b3c-0001.log"), Some("Alaydriem"));
+        assert_eq!(WalSegment::player_name("Some-Player-1a2b3c-0001.log"), Some("Some-Player"));
+        assert_eq!(WalSegment::player_name("Alaydriem.log"), None);
+        assert_eq!(WalSegment::player_name("session.json"), None);
+    }
+
+    #[test]
+    fn test_reads_complete_segment() {
+        let mut bytes = b"NANOWAL-HEADER".to_vec();
+        bytes.extend(encode_record(&input_header(0), &[1, 2, 3]));
+        bytes.extend(encode_record(&input_header(20), &[4, 5]));
+        let path = write_segment("Player-abc-0001.log", &bytes);
+
+        let segment = WalSegment::read(&path).unwrap();
+        assert_eq!(segment.entries.len(), 2);
+        assert_eq!(segment.first_record, Some(14));
+        assert_eq!(segment.valid_len, bytes.len() as u64);
+        assert!(segment.is_intact());
+        assert_eq!(segment.entries[1].relative_timestamp_ms, 20);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_stops_at_torn_record() {
+        let mut bytes = encode_record(&input_header(0), &[1, 2, 3]);
+        let intact = bytes.len() as u64;
+        let torn = encode_record(&input_header(20), &[4, 5, 6, 7]);
+        bytes.extend_from_slice(&torn[..torn.len() - 2]);
+        let path = write_segment("Player-abc-0001.log", &bytes);
+
+        let segment = WalSegment::read(&path).unwrap();
+        assert_eq!(segment.entries.len(), 1);
+        assert_eq!(segment.valid_len, intact);
+        assert_eq!(segment.trailing_bytes(), torn.len() as u64 - 2);
+        assert!(!segment.is_intact());
+        assert!(segment.stop_reason.as_deref().unwrap().starts_with("truncated content"));
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
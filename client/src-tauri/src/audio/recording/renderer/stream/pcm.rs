@@ -32,6 +32,8 @@ pub struct PcmStream {
     reader: WalAudioReader,
     info: Option<PcmStreamInfo>,
     pending_silence: Option<usize>,
+    // Set once the gap before the upcoming frame has been emitted
+    silence_emitted: bool,
     finished: bool,
 }
 
@@ -59,6 +61,7 @@ impl PcmStream {
             reader,
             info,
             pending_silence: None,
+            silence_emitted: false,
             finished: false,
         })
     }
@@ -86,14 +89,16 @@ impl Iterator for PcmStream {
 
         // Check for silence gap before the next frame
         // This looks at the gap between the previously-read frame and the upcoming one
-        if let Some(silence_samples) = self.reader.calculate_silence_before_next() {
-            // Store the silence and emit it now
-            // The next call will then read the actual audio frame
-            self.pending_silence = None; // Will read frame on next call
-            return Some(Ok(PcmChunk::Silence(silence_samples)));
+        if !self.silence_emitted {
+            if let Some(silence_samples) = self.reader.calculate_silence_before_next() {
+                // Emit the silence now; the next call will then read the actual audio frame
+                self.silence_emitted = true;
+                return Some(Ok(PcmChunk::Silence(silence_samples)));
+            }
         }
 
         // Get next decoded frame
+        self.silence_emitted = false;
         match self.reader.next_frame() {
             Ok(Some(frame)) => Some(Ok(PcmChunk::Audio(frame.pcm_data))),
             Ok(None) => {
@@ -107,3 +112,63 @@ impl Iterator for PcmStream {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::recording::renderer::segment::{encode_record, input_header};
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    const PLAYER: &str = "Steve";
+
+    /// A session with one frame of tone at each timestamp
+    fn session_with_frames(timestamps: &[u64]) -> PathBuf {
+        let session_path = std::env::temp_dir()
+            .join(format!("bvc-pcm-{}", Uuid::new_v4()))
+            .join(Uuid::now_v7().to_string());
+        let wal_path = session_path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+
+        let manifest = serde_json::json!({
+            "session_id": "pcm",
+            "start_timestamp": 0,
+            "end_timestamp": null,
+            "duration_ms": null,
+            "emitter_player": PLAYER,
+            "participants": [PLAYER],
+            "created_at": "2026-01-01T00:00:00Z",
+        });
+        fs::write(session_path.join("session.json"), manifest.to_string()).unwrap();
+
+        let mut encoder =
+            opus2::Encoder::new(48000, opus2::Channels::Mono, opus2::Application::Voip).unwrap();
+        let mut opus_out = vec![0u8; 4000];
+        let pcm: Vec<f32> = (0..960).map(|i| (i as f32 * 0.05).sin() * 0.3).collect();
+        let mut segment = Vec::new();
+        for timestamp in timestamps {
+            let len = encoder.encode_float(&pcm, &mut opus_out).unwrap();
+            segment.extend(encode_record(&input_header(*timestamp), &opus_out[..len]));
+        }
+        fs::write(wal_path.join(format!("{}-abc-0001.log", PLAYER)), segment).unwrap();
+
+        session_path
+    }
+
+    #[test]
+    fn test_gap_then_audio_ends() {
+        // 180ms of silence between the frames
+        let session_path = session_with_frames(&[0, 200]);
+        let stream = PcmStream::new(&session_path, PLAYER).unwrap();
+
+        // Bounded so a stream that keeps re-emitting the gap fails instead of hanging
+        let chunks: Vec<PcmChunk> = stream.take(10).map(Result::unwrap).collect();
+        let _ = fs::remove_dir_all(session_path.parent().unwrap());
+
+        assert_eq!(chunks.len(), 3, "{:?}", chunks);
+        assert!(matches!(chunks[0], PcmChunk::Audio(_)));
+        assert!(matches!(chunks[1], PcmChunk::Silence(samples) if samples == 180 * 48));
+        assert!(matches!(chunks[2], PcmChunk::Audio(_)));
+    }
+}
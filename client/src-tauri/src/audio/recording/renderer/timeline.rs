@@ -0,0 +1,287 @@
+//! Speech-activity timeline
+//!
+//! Derives when each participant spoke from WAL entry timestamps. Consecutive entries belong
+//! to one utterance; a silence gap (as `WalAudioReader::calculate_silence_before_next` measures
+//! it) longer than `UTTERANCE_GAP_MS` starts the next one. Renderers embed the timeline as MP4
+//! chapters and BWAV cue points, and write it beside each export as JSON. Markers the user
+//! placed while recording are carried along on the same clock.
+
+use crate::audio::recording::renderer::segment::{segment_files, WalSegment};
+use crate::audio::recording::renderer::SessionInfo;
+use crate::audio::recording::session::read_manifest;
+use common::structs::recording::RecordingMarker;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const OPUS_FRAME_MS: u64 = 20;
+// Pauses up to this long are treated as breaths within a single utterance
+const UTTERANCE_GAP_MS: u64 = 500;
+
+/// A span of continuous speech, in milliseconds from the start of the rendered file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Utterance {
+    pub player: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// A user-placed marker, in milliseconds from the start of the rendered file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Marker {
+    pub start_ms: u64,
+    pub label: Option<String>,
+}
+
+impl Marker {
+    /// The label, or `Marker n` for unlabelled markers where `n` counts from 1
+    pub fn title(&self, index: usize) -> String {
+        self.label.clone().unwrap_or_else(|| format!("Marker {}", index + 1))
+    }
+}
+
+/// A chapter marker, in milliseconds from the start of the rendered file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub start_ms: u64,
+    pub title: String,
+}
+
+/// Who spoke when, measured from the first audible frame of a render
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeechTimeline {
+    pub session_id: String,
+    pub session_start_timestamp: u64,
+    /// Relative session timestamp the rendered file begins at
+    pub origin_ms: u64,
+    /// Every utterance, ordered by start
+    pub utterances: Vec<Utterance>,
+    /// User-placed markers, ordered by start
+    pub markers: Vec<Marker>,
+}
+
+impl SpeechTimeline {
+    /// Build the timeline for the given players. The origin is the earliest entry of any of
+    /// them, which is where single-player renders, mixdowns and stems all begin.
+    pub fn load(session_path: &Path, players: &[String]) -> Result<Self, anyhow::Error> {
+        let session_info = SessionInfo::load(session_path)?;
+        let wal_path = session_path.join("wal");
+
+        let mut timestamps = Vec::with_capacity(players.len());
+        for player in players {
+            let mut player_timestamps = Vec::new();
+            for file_path in segment_files(&wal_path, Some(player.as_str()))? {
+                player_timestamps.extend(
+                    WalSegment::read(&file_path)?
+                        .entries
+                        .iter()
+                        .map(|e| e.relative_timestamp_ms),
+                );
+            }
+            timestamps.push((player.clone(), player_timestamps));
+        }
+
+        let manifest = read_manifest(session_path)?;
+        Ok(Self::from_timestamps(&session_info, timestamps).with_markers(&manifest.markers))
+    }
+
+    /// Build the timeline from each player's entry timestamps
+    pub fn from_timestamps(session_info: &SessionInfo, timestamps: Vec<(String, Vec<u64>)>) -> Self {
+        let origin_ms = timestamps
+            .iter()
+            .filter_map(|(_, ts)| ts.iter().min())
+            .min()
+            .copied()
+            .unwrap_or(0);
+
+        let mut utterances = Vec::new();
+        for (player, mut player_timestamps) in timestamps {
+            player_timestamps.sort_unstable();
+            utterances.extend(speech_spans(&player_timestamps).into_iter().map(|(start, end)| {
+                Utterance {
+                    player: player.clone(),
+                    start_ms: start - origin_ms,
+                    end_ms: end - origin_ms,
+                }
+            }));
+        }
+        utterances.sort_by(|a, b| a.start_ms.cmp(&b.start_ms).then_with(|| a.player.cmp(&b.player)));
+
+        Self {
+            session_id: session_info.session_id.clone(),
+            session_start_timestamp: session_info.start_timestamp,
+            origin_ms,
+            utterances,
+            markers: Vec::new(),
+        }
+    }
+
+    /// Position the session's markers on the timeline. Markers placed before the first audible
+    /// frame are moved to the start of the file.
+    pub fn with_markers(mut self, markers: &[RecordingMarker]) -> Self {
+        self.markers = markers
+            .iter()
+            .map(|m| Marker {
+                start_ms: m.relative_timestamp_ms.saturating_sub(self.origin_ms),
+                label: m.label.clone(),
+            })
+            .collect();
+        self.markers.sort_by_key(|m| m.start_ms);
+        self
+    }
+
+    /// Utterances of a single player
+    pub fn utterances_of<'a>(&'a self, player: &'a str) -> impl Iterator<Item = &'a Utterance> {
+        self.utterances.iter().filter(move |u| u.player == player)
+    }
+
+    /// One chapter per utterance, titled with the speaker's name, and one per marker
+    pub fn chapters(&self) -> Vec<Chapter> {
+        let mut chapters: Vec<Chapter> = self
+            .utterances
+            .iter()
+            .map(|u| Chapter {
+                start_ms: u.start_ms,
+                title: u.player.clone(),
+            })
+            .chain(self.markers.iter().enumerate().map(|(index, m)| Chapter {
+                start_ms: m.start_ms,
+                title: m.title(index),
+            }))
+            .collect();
+        chapters.sort_by_key(|c| c.start_ms);
+        chapters
+    }
+
+    /// Sidecar path for an export: `Steve.m4a` becomes `Steve.timeline.json`
+    pub fn sidecar_path(output_path: &Path) -> PathBuf {
+        output_path.with_extension("timeline.json")
+    }
+
+    /// Write the timeline beside an export
+    pub fn write_sidecar(&self, output_path: &Path) -> Result<(), anyhow::Error> {
+        fs::write(Self::sidecar_path(output_path), serde_json::to_vec_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Group sorted entry timestamps into `(start, end)` spans of continuous speech
+fn speech_spans(timestamps: &[u64]) -> Vec<(u64, u64)> {
+    let mut spans = Vec::new();
+    let Some((&first, rest)) = timestamps.split_first() else {
+        return spans;
+    };
+
+    let (mut start, mut last) = (first, first);
+    for &timestamp in rest {
+        let silence_ms = timestamp.saturating_sub(last).saturating_sub(OPUS_FRAME_MS);
+        if silence_ms > UTTERANCE_GAP_MS {
+            spans.push((start, last + OPUS_FRAME_MS));
+            start = timestamp;
+        }
+        last = timestamp;
+    }
+    spans.push((start, last + OPUS_FRAME_MS));
+
+    spans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_info() -> SessionInfo {
+        SessionInfo {
+            session_id: "session".to_string(),
+            start_timestamp: 1_700_000_000_000,
+            player_name: "Steve".to_string(),
+            duration_ms: None,
+        }
+    }
+
+    /// Consecutive 20ms frames covering `start..end`
+    fn frames(start: u64, end: u64) -> Vec<u64> {
+        (start..end).step_by(OPUS_FRAME_MS as usize).collect()
+    }
+
+    #[test]
+    fn test_short_pauses_stay_in_one_utterance() {
+        let mut timestamps = frames(0, 1000);
+        // 400ms pause, then more speech
+        timestamps.extend(frames(1400, 2000));
+        // 2s pause starts a new utterance
+        timestamps.extend(frames(4000, 4500));
+
+        assert_eq!(speech_spans(&timestamps), vec![(0, 2000), (4000, 4500)]);
+        assert!(speech_spans(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_timeline_is_measured_from_earliest_entry() {
+        let timeline = SpeechTimeline::from_timestamps(
+            &session_info(),
+            vec![
+                ("Steve".to_string(), frames(3000, 4000)),
+                ("Alex".to_string(), frames(1000, 2000)),
+                ("Empty".to_string(), Vec::new()),
+            ],
+        );
+
+        assert_eq!(timeline.origin_ms, 1000);
+        assert_eq!(
+            timeline.utterances,
+            vec![
+                Utterance { player: "Alex".to_string(), start_ms: 0, end_ms: 1000 },
+                Utterance { player: "Steve".to_string(), start_ms: 2000, end_ms: 3000 },
+            ]
+        );
+        assert_eq!(timeline.utterances_of("Steve").count(), 1);
+        assert_eq!(
+            timeline.chapters(),
+            vec![
+                Chapter { start_ms: 0, title: "Alex".to_string() },
+                Chapter { start_ms: 2000, title: "Steve".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markers_are_measured_from_origin_and_become_chapters() {
+        let timeline = SpeechTimeline::from_timestamps(
+            &session_info(),
+            vec![("Steve".to_string(), frames(1000, 2000))],
+        )
+        .with_markers(&[
+            RecordingMarker { relative_timestamp_ms: 1500, label: Some("good clip here".to_string()) },
+            RecordingMarker { relative_timestamp_ms: 500, label: None },
+        ]);
+
+        assert_eq!(
+            timeline.markers,
+            vec![
+                Marker { start_ms: 0, label: None },
+                Marker { start_ms: 500, label: Some("good clip here".to_string()) },
+            ]
+        );
+        assert_eq!(
+            timeline.chapters(),
+            vec![
+                Chapter { start_ms: 0, title: "Steve".to_string() },
+                Chapter { start_ms: 0, title: "Marker 1".to_string() },
+                Chapter { start_ms: 500, title: "good clip here".to_string() },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sidecar_path() {
+        assert_eq!(
+            SpeechTimeline::sidecar_path(Path::new("renders/Steve.m4a")),
+            PathBuf::from("renders/Steve.timeline.json")
+        );
+        assert_eq!(
+            SpeechTimeline::sidecar_path(Path::new("renders/stems")),
+            PathBuf::from("renders/stems.timeline.json")
+        );
+    }
+}
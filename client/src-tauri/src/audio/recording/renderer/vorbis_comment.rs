@@ -0,0 +1,132 @@
+//! Vorbis comment metadata
+//!
+//! The same comment block is used by Ogg Opus (`OpusTags`) and FLAC (`VORBIS_COMMENT`).
+//! https://xiph.org/vorbis/doc/v-comment.html
+
+use crate::audio::recording::renderer::SessionInfo;
+use chrono::{DateTime, Local};
+
+const VENDOR: &str = "Bedrock Voice Chat";
+
+/// A list of `KEY=value` comments with a vendor string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VorbisComment {
+    pub vendor: String,
+    pub comments: Vec<(String, String)>,
+}
+
+impl VorbisComment {
+    /// Session and player metadata for a rendered recording
+    pub fn from_session(
+        session_info: &SessionInfo,
+        player_name: &str,
+        first_frame_relative_timestamp_ms: u64,
+    ) -> Self {
+        let start_timestamp = session_info.start_timestamp + first_frame_relative_timestamp_ms;
+        let date = DateTime::from_timestamp_millis(start_timestamp as i64)
+            .map(|dt| dt.with_timezone(&Local).to_rfc3339())
+            .unwrap_or_default();
+
+        let mut comments = vec![
+            ("TITLE".to_string(), format!("BVC Recording - {}", player_name)),
+            ("ARTIST".to_string(), player_name.to_string()),
+            ("DATE".to_string(), date),
+            ("BVC_SESSION_ID".to_string(), session_info.session_id.clone()),
+            ("BVC_PLAYER".to_string(), player_name.to_string()),
+            (
+                "BVC_SESSION_START".to_string(),
+                session_info.start_timestamp.to_string(),
+            ),
+            ("BVC_START_TIMESTAMP".to_string(), start_timestamp.to_string()),
+        ];
+
+        if let Some(duration_ms) = session_info.duration_ms {
+            comments.push(("BVC_DURATION_MS".to_string(), duration_ms.to_string()));
+        }
+
+        Self {
+            vendor: VENDOR.to_string(),
+            comments,
+        }
+    }
+
+    /// Look up the first value for a key, case-insensitively as the spec requires
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.comments
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Serialize without the Vorbis framing bit
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&(self.vendor.len() as u32).to_le_bytes());
+        out.extend_from_slice(self.vendor.as_bytes());
+        out.extend_from_slice(&(self.comments.len() as u32).to_le_bytes());
+        for (key, value) in &self.comments {
+            let comment = format!("{}={}", key, value);
+            out.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            out.extend_from_slice(comment.as_bytes());
+        }
+        out
+    }
+
+    /// Parse a comment block produced by `to_bytes`
+    pub fn from_bytes(data: &[u8]) -> Result<Self, anyhow::Error> {
+        let mut pos = 0;
+        let read_u32 = |pos: &mut usize| -> Result<usize, anyhow::Error> {
+            let bytes = data
+                .get(*pos..*pos + 4)
+                .ok_or_else(|| anyhow::anyhow!("Truncated vorbis comment"))?;
+            *pos += 4;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+        };
+        let read_str = |pos: &mut usize, len: usize| -> Result<String, anyhow::Error> {
+            let bytes = data
+                .get(*pos..*pos + len)
+                .ok_or_else(|| anyhow::anyhow!("Truncated vorbis comment"))?;
+            *pos += len;
+            Ok(String::from_utf8(bytes.to_vec())?)
+        };
+
+        let vendor_len = read_u32(&mut pos)?;
+        let vendor = read_str(&mut pos, vendor_len)?;
+        let count = read_u32(&mut pos)?;
+
+        let mut comments = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let len = read_u32(&mut pos)?;
+            let comment = read_str(&mut pos, len)?;
+            let (key, value) = comment
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Vorbis comment without '=': {}", comment))?;
+            comments.push((key.to_string(), value.to_string()));
+        }
+
+        Ok(Self { vendor, comments })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let info = SessionInfo {
+            session_id: "019aae95-82e2-7676-9466-ec8d5399e798".to_string(),
+            start_timestamp: 1705329045000,
+            player_name: "Steve".to_string(),
+            duration_ms: Some(60000),
+        };
+
+        let comment = VorbisComment::from_session(&info, "Alex", 500);
+        let parsed = VorbisComment::from_bytes(&comment.to_bytes()).unwrap();
+
+        assert_eq!(parsed, comment);
+        assert_eq!(parsed.get("bvc_player"), Some("Alex"));
+        assert_eq!(parsed.get("BVC_START_TIMESTAMP"), Some("1705329045500"));
+        assert_eq!(parsed.get("BVC_SESSION_ID"), Some(info.session_id.as_str()));
+    }
+}
@@ -0,0 +1,503 @@
+use common::structs::recording::{RecordingRetentionPolicy, SessionManifest};
+use common::structs::AudioFormat;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::audio::recording::renderer::{
+    file_name::player_file_stems, mixdown::session_participants, AudioFormatRenderer,
+    WalAudioReader,
+};
+use crate::audio::recording::session::{directory_size, list_sessions};
+
+/// Store key holding the `RecordingRetentionPolicy`
+pub const RETENTION_STORE_KEY: &str = "recording_retention";
+
+/// Compressed WAL directory of a session that aged past `compress_after_days`
+pub const WAL_ARCHIVE: &str = "wal.tar.zst";
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// A finalized session as seen by the retention policy
+#[derive(Debug, Clone)]
+pub struct RetainedSession {
+    pub path: PathBuf,
+    pub manifest: SessionManifest,
+    pub size_bytes: u64,
+    pub compressed: bool,
+}
+
+/// Sessions the policy wants deleted or compressed
+#[derive(Debug, Default, PartialEq)]
+pub struct RetentionPlan {
+    pub delete: Vec<PathBuf>,
+    pub compress: Vec<PathBuf>,
+}
+
+/// Outcome of a retention pass
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct RetentionReport {
+    pub deleted: Vec<String>,
+    pub exported: Vec<String>,
+    pub compressed: Vec<String>,
+    pub freed_bytes: u64,
+}
+
+/// Collect every finalized session. Sessions still recording (or awaiting recovery) have no
+/// `end_timestamp` and are never touched.
+pub fn retained_sessions(recordings_dir: &Path) -> Result<Vec<RetainedSession>, anyhow::Error> {
+    Ok(list_sessions(recordings_dir)?
+        .into_iter()
+        .filter(|s| s.manifest.end_timestamp.is_some())
+        .map(|s| RetainedSession {
+            size_bytes: directory_size(&s.path).unwrap_or(0),
+            compressed: s.path.join(WAL_ARCHIVE).is_file(),
+            path: s.path,
+            manifest: s.manifest,
+        })
+        .collect())
+}
+
+/// Decide which sessions to delete and compress. Pinned sessions are never selected.
+pub fn plan(policy: &RecordingRetentionPolicy, sessions: &[RetainedSession], now_ms: u64) -> RetentionPlan {
+    if !policy.enabled {
+        return RetentionPlan::default();
+    }
+
+    let mut newest_first: Vec<&RetainedSession> = sessions.iter().collect();
+    newest_first.sort_by_key(|s| std::cmp::Reverse(s.manifest.start_timestamp));
+
+    let is_older_than = |session: &RetainedSession, days: u32| {
+        session.manifest.start_timestamp + days as u64 * DAY_MS < now_ms
+    };
+
+    let mut delete = HashSet::new();
+    for (rank, session) in newest_first.iter().filter(|s| !s.manifest.pinned).enumerate() {
+        let over_count = policy.keep_latest.is_some_and(|n| rank >= n as usize);
+        let too_old = policy.max_age_days.is_some_and(|days| is_older_than(session, days));
+        if over_count || too_old {
+            delete.insert(session.path.clone());
+        }
+    }
+
+    if let Some(max_mb) = policy.max_total_size_mb {
+        let max_bytes = max_mb as u64 * 1024 * 1024;
+        let mut total: u64 = newest_first
+            .iter()
+            .filter(|s| !delete.contains(&s.path))
+            .map(|s| s.size_bytes)
+            .sum();
+
+        for session in newest_first.iter().rev().filter(|s| !s.manifest.pinned) {
+            if total <= max_bytes {
+                break;
+            }
+            if delete.insert(session.path.clone()) {
+                total = total.saturating_sub(session.size_bytes);
+            }
+        }
+    }
+
+    let compress = match policy.compress_after_days {
+        Some(days) => newest_first
+            .iter()
+            .filter(|s| !s.manifest.pinned && !s.compressed && !delete.contains(&s.path))
+            .filter(|s| is_older_than(s, days))
+            .map(|s| s.path.clone())
+            .collect(),
+        None => Vec::new(),
+    };
+
+    // Oldest first, so an interrupted pass removes the least valuable sessions
+    let delete = newest_first
+        .iter()
+        .rev()
+        .filter(|s| delete.contains(&s.path))
+        .map(|s| s.path.clone())
+        .collect();
+
+    RetentionPlan { delete, compress }
+}
+
+/// Apply the policy to a recordings directory. A session whose export fails is kept.
+pub async fn enforce(
+    recordings_dir: &Path,
+    export_dir: &Path,
+    policy: &RecordingRetentionPolicy,
+    now_ms: u64,
+) -> Result<RetentionReport, anyhow::Error> {
+    let sessions = retained_sessions(recordings_dir)?;
+    let plan = plan(policy, &sessions, now_ms);
+    let mut report = RetentionReport::default();
+
+    for path in &plan.delete {
+        let Some(session) = sessions.iter().find(|s| &s.path == path) else {
+            continue;
+        };
+        let session_id = session.manifest.session_id.clone();
+
+        if let Some(format) = policy.export_before_delete {
+            if let Err(e) = export_session(session, format, &export_dir.join(&session_id)).await {
+                log::warn!("Keeping session {}: export before delete failed: {}", session_id, e);
+                continue;
+            }
+            report.exported.push(session_id.clone());
+        }
+
+        let path = session.path.clone();
+        tokio::task::spawn_blocking(move || fs::remove_dir_all(path)).await??;
+        log::info!("Retention policy deleted session {}", session_id);
+        report.freed_bytes += session.size_bytes;
+        report.deleted.push(session_id);
+    }
+
+    for path in plan.compress {
+        let session_id = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let before = directory_size(&path).unwrap_or(0);
+
+        let compress_path = path.clone();
+        match tokio::task::spawn_blocking(move || compress_session(&compress_path)).await? {
+            Ok(()) => {
+                report.freed_bytes += before.saturating_sub(directory_size(&path).unwrap_or(0));
+                report.compressed.push(session_id);
+            }
+            Err(e) => log::warn!("Failed to compress session {}: {}", session_id, e),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Render every participant of a session into `output_dir`. Compressed sessions are rendered
+/// from a temporary extraction, so they stay compressed whether or not the export succeeds.
+/// Participants without any audio are skipped rather than failing the export, so a session
+/// whose only audible player is someone else can still be exported and deleted.
+async fn export_session(session: &RetainedSession, format: AudioFormat, output_dir: &Path) -> Result<(), anyhow::Error> {
+    let path = session.path.clone();
+    let extracted = tokio::task::spawn_blocking(move || extract_session(&path)).await??;
+    let session_path = extracted.path();
+    fs::create_dir_all(output_dir)?;
+
+    let mut players = Vec::new();
+    for player in session_participants(&session.manifest) {
+        if WalAudioReader::new(session_path, &player)?.peek_raw_entry().is_some() {
+            players.push(player);
+        } else {
+            log::warn!(
+                "Skipping {} in export of session {}: no audio",
+                player,
+                session.manifest.session_id
+            );
+        }
+    }
+    if players.is_empty() {
+        log::warn!("Session {} has no audio to export", session.manifest.session_id);
+        return Ok(());
+    }
+
+    if format.is_multitrack() {
+        let output_path = match format {
+            AudioFormat::BwavStems => output_dir.join("stems"),
+            _ => output_dir.join(format!("stems.{}", format.extension())),
+        };
+        return format.render_stems(session_path, &output_path, &players).await;
+    }
+
+    for (player, stem) in players.iter().zip(player_file_stems(&players)) {
+        let output_path = output_dir.join(format!("{}.{}", stem, format.extension()));
+        format.render(session_path, player, &output_path).await?;
+    }
+
+    Ok(())
+}
+
+/// Replace a session's `wal` directory with a zstd-compressed tarball
+pub fn compress_session(session_path: &Path) -> Result<(), anyhow::Error> {
+    let wal_path = session_path.join("wal");
+    if !wal_path.is_dir() {
+        return Ok(());
+    }
+
+    // Write to a temporary name first so a crash never leaves a truncated archive next to a deleted WAL
+    let temp_path = session_path.join(format!("{}.tmp", WAL_ARCHIVE));
+    let encoder = zstd::stream::write::Encoder::new(File::create(&temp_path)?, 3)?;
+    let mut tar_builder = tar::Builder::new(encoder);
+    tar_builder.append_dir_all("wal", &wal_path)?;
+    tar_builder.into_inner()?.finish()?.sync_all()?;
+
+    fs::rename(&temp_path, session_path.join(WAL_ARCHIVE))?;
+    fs::remove_dir_all(&wal_path)?;
+    Ok(())
+}
+
+/// Restore a compressed session's `wal` directory so it can be rendered
+pub fn decompress_session(session_path: &Path) -> Result<(), anyhow::Error> {
+    let archive_path = session_path.join(WAL_ARCHIVE);
+    if !archive_path.is_file() {
+        return Ok(());
+    }
+
+    let decoder = zstd::stream::read::Decoder::new(File::open(&archive_path)?)?;
+    tar::Archive::new(decoder).unpack(session_path)?;
+    fs::remove_file(&archive_path)?;
+    Ok(())
+}
+
+/// A session whose WAL can be read, extracted to a temporary directory when it is compressed.
+/// The extraction is removed on drop and the session itself stays compressed.
+pub struct ExtractedSession {
+    path: PathBuf,
+    temp_dir: Option<PathBuf>,
+}
+
+impl ExtractedSession {
+    /// Session directory to read from
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for ExtractedSession {
+    fn drop(&mut self) {
+        if let Some(temp_dir) = &self.temp_dir {
+            if let Err(e) = fs::remove_dir_all(temp_dir) {
+                log::warn!("Failed to remove extracted session {:?}: {}", temp_dir, e);
+            }
+        }
+    }
+}
+
+/// Make a session's WAL readable without decompressing it in place
+pub fn extract_session(session_path: &Path) -> Result<ExtractedSession, anyhow::Error> {
+    let archive_path = session_path.join(WAL_ARCHIVE);
+    if !archive_path.is_file() {
+        return Ok(ExtractedSession {
+            path: session_path.to_path_buf(),
+            temp_dir: None,
+        });
+    }
+
+    // Keeps the session's directory name, which readers may treat as its id
+    let temp_dir = std::env::temp_dir().join(format!("bvc-session-{}", uuid::Uuid::new_v4()));
+    let extracted = ExtractedSession {
+        path: temp_dir.join(session_path.file_name().unwrap_or_default()),
+        temp_dir: Some(temp_dir),
+    };
+    fs::create_dir_all(&extracted.path)?;
+
+    for entry in fs::read_dir(session_path)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && entry.file_name() != WAL_ARCHIVE {
+            fs::copy(entry.path(), extracted.path.join(entry.file_name()))?;
+        }
+    }
+
+    let decoder = zstd::stream::read::Decoder::new(File::open(&archive_path)?)?;
+    tar::Archive::new(decoder).unpack(&extracted.path)?;
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 100 * DAY_MS;
+
+    fn session(id: &str, age_days: u64, size_mb: u64, pinned: bool) -> RetainedSession {
+        RetainedSession {
+            path: PathBuf::from(id),
+            manifest: SessionManifest {
+                session_id: id.to_string(),
+                start_timestamp: NOW - age_days * DAY_MS,
+                end_timestamp: Some(NOW - age_days * DAY_MS + 1000),
+                duration_ms: Some(1000),
+                emitter_player: "Recorder".to_string(),
+                participants: Vec::new(),
+                created_at: "0".to_string(),
+                recovered: false,
+                pinned,
+                markers: Vec::new(),
+                spatial_config: None,
+            },
+            size_bytes: size_mb * 1024 * 1024,
+            compressed: false,
+        }
+    }
+
+    fn paths(ids: &[&str]) -> Vec<PathBuf> {
+        ids.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_disabled_policy_does_nothing() {
+        let policy = RecordingRetentionPolicy {
+            max_age_days: Some(1),
+            ..Default::default()
+        };
+        let sessions = vec![session("a", 10, 1, false)];
+        assert_eq!(plan(&policy, &sessions, NOW), RetentionPlan::default());
+    }
+
+    #[test]
+    fn test_keep_latest_skips_pinned_sessions() {
+        let policy = RecordingRetentionPolicy {
+            enabled: true,
+            keep_latest: Some(1),
+            ..Default::default()
+        };
+        let sessions = vec![
+            session("newest", 1, 1, false),
+            session("pinned", 2, 1, true),
+            session("middle", 3, 1, false),
+            session("oldest", 4, 1, false),
+        ];
+        assert_eq!(plan(&policy, &sessions, NOW).delete, paths(&["oldest", "middle"]));
+    }
+
+    #[test]
+    fn test_max_age_and_compression() {
+        let policy = RecordingRetentionPolicy {
+            enabled: true,
+            max_age_days: Some(30),
+            compress_after_days: Some(7),
+            ..Default::default()
+        };
+        let mut compressed = session("compressed", 10, 1, false);
+        compressed.compressed = true;
+        let sessions = vec![
+            session("fresh", 1, 1, false),
+            session("week_old", 8, 1, false),
+            compressed,
+            session("pinned_old", 60, 1, true),
+            session("expired", 31, 1, false),
+        ];
+
+        let plan = plan(&policy, &sessions, NOW);
+        assert_eq!(plan.delete, paths(&["expired"]));
+        assert_eq!(plan.compress, paths(&["week_old"]));
+    }
+
+    #[test]
+    fn test_max_total_size_deletes_oldest_first() {
+        let policy = RecordingRetentionPolicy {
+            enabled: true,
+            max_total_size_mb: Some(10),
+            ..Default::default()
+        };
+        let sessions = vec![
+            session("newest", 1, 4, false),
+            session("pinned", 2, 4, true),
+            session("middle", 3, 4, false),
+            session("oldest", 4, 4, false),
+        ];
+        // 16MB total; dropping the two oldest unpinned sessions brings it to 8MB
+        assert_eq!(plan(&policy, &sessions, NOW).delete, paths(&["oldest", "middle"]));
+    }
+
+    #[test]
+    fn test_compress_round_trip() {
+        let session_path = std::env::temp_dir().join(format!("bvc-retention-{}", uuid::Uuid::new_v4()));
+        let wal_path = session_path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+        fs::write(wal_path.join("Player-abc-0001.log"), vec![7u8; 64 * 1024]).unwrap();
+
+        compress_session(&session_path).unwrap();
+        assert!(!wal_path.exists());
+        assert!(session_path.join(WAL_ARCHIVE).is_file());
+
+        decompress_session(&session_path).unwrap();
+        assert!(!session_path.join(WAL_ARCHIVE).exists());
+        assert_eq!(fs::read(wal_path.join("Player-abc-0001.log")).unwrap(), vec![7u8; 64 * 1024]);
+
+        let _ = fs::remove_dir_all(&session_path);
+    }
+
+    #[tokio::test]
+    async fn test_failed_export_leaves_session_compressed() {
+        let root = std::env::temp_dir().join(format!("bvc-retention-{}", uuid::Uuid::new_v4()));
+        let mut retained = session("export", 10, 1, false);
+        retained.path = root.join("export");
+        let wal_path = retained.path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+        fs::write(wal_path.join("Recorder-abc-0001.log"), vec![7u8; 1024]).unwrap();
+        compress_session(&retained.path).unwrap();
+
+        // No manifest and no valid entries, so rendering fails
+        assert!(export_session(&retained, AudioFormat::Bwav, &root.join("exports")).await.is_err());
+        assert!(!wal_path.exists());
+        assert!(retained.path.join(WAL_ARCHIVE).is_file());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn test_export_skips_participants_without_audio() {
+        use crate::audio::recording::renderer::segment::{encode_record, input_header};
+
+        let root = std::env::temp_dir().join(format!("bvc-retention-{}", uuid::Uuid::new_v4()));
+        let mut retained = session("export", 10, 1, false);
+        retained.path = root.join("export");
+        retained.manifest.participants = vec!["Steve".to_string()];
+        let wal_path = retained.path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+        fs::write(
+            retained.path.join("session.json"),
+            serde_json::to_string(&retained.manifest).unwrap(),
+        )
+        .unwrap();
+
+        // Only Steve spoke; the recorder has no segments at all
+        let mut encoder =
+            opus2::Encoder::new(48000, opus2::Channels::Mono, opus2::Application::Voip).unwrap();
+        let mut opus_out = vec![0u8; 4000];
+        let len = encoder.encode_float(&[0.0; 960], &mut opus_out).unwrap();
+        fs::write(
+            wal_path.join("Steve-abc-0001.log"),
+            encode_record(&input_header(0), &opus_out[..len]),
+        )
+        .unwrap();
+
+        let output_dir = root.join("exports");
+        export_session(&retained, AudioFormat::Bwav, &output_dir).await.unwrap();
+        assert!(output_dir.join("Steve.wav").is_file());
+        assert!(!output_dir.join("Recorder.wav").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_extract_leaves_session_compressed() {
+        let session_path = std::env::temp_dir().join(format!("bvc-retention-{}", uuid::Uuid::new_v4()));
+        let wal_path = session_path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+        fs::write(session_path.join("session.json"), "{}").unwrap();
+        fs::write(wal_path.join("Player-abc-0001.log"), vec![7u8; 1024]).unwrap();
+        compress_session(&session_path).unwrap();
+
+        let extracted = extract_session(&session_path).unwrap();
+        let extracted_path = extracted.path().to_path_buf();
+        assert_ne!(extracted_path, session_path);
+        assert_eq!(extracted_path.file_name(), session_path.file_name());
+        assert!(extracted_path.join("session.json").is_file());
+        assert!(!extracted_path.join(WAL_ARCHIVE).exists());
+        assert_eq!(fs::read(extracted_path.join("wal").join("Player-abc-0001.log")).unwrap(), vec![7u8; 1024]);
+        assert!(!wal_path.exists());
+        assert!(session_path.join(WAL_ARCHIVE).is_file());
+
+        drop(extracted);
+        assert!(!extracted_path.exists());
+
+        // Uncompressed sessions are read where they are
+        decompress_session(&session_path).unwrap();
+        let extracted = extract_session(&session_path).unwrap();
+        assert_eq!(extracted.path(), session_path.as_path());
+        drop(extracted);
+        assert!(wal_path.is_dir());
+
+        let _ = fs::remove_dir_all(&session_path);
+    }
+}
@@ -0,0 +1,454 @@
+use common::structs::recording::{RecordingHeader, SessionManifest};
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::audio::recording::renderer::segment::{segment_files, WalSegment};
+
+/// Length of a single Opus frame, used to close out the final entry of a session
+const OPUS_FRAME_MS: u64 = 20;
+
+/// A session directory containing a readable `session.json`
+#[derive(Debug, Clone)]
+pub struct StoredSession {
+    pub path: PathBuf,
+    pub manifest: SessionManifest,
+}
+
+/// Health of a single WAL segment file
+#[derive(Debug, Clone, Serialize)]
+pub struct SegmentReport {
+    pub file_name: String,
+    pub player: Option<String>,
+    pub records: usize,
+    pub undecodable: usize,
+    pub trailing_bytes: u64,
+    pub stop_reason: Option<String>,
+    /// Whether this segment contains the local player's input headers
+    pub has_input: bool,
+    pub last_timestamp_ms: Option<u64>,
+}
+
+/// Result of validating (or repairing) a session directory
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReport {
+    pub path: PathBuf,
+    pub manifest: Option<SessionManifest>,
+    pub manifest_error: Option<String>,
+    pub segments: Vec<SegmentReport>,
+    /// Players that have WAL segments but are not listed in the manifest
+    pub unlisted_players: Vec<String>,
+    /// Segments that were truncated back to their last complete record
+    pub truncated: Vec<String>,
+    /// Whether `session.json` was rewritten
+    pub manifest_rebuilt: bool,
+}
+
+impl SessionReport {
+    /// Whether the session can be rendered without any repair
+    pub fn is_healthy(&self) -> bool {
+        self.manifest.is_some()
+            && self.manifest_error.is_none()
+            && self.unlisted_players.is_empty()
+            && self
+                .segments
+                .iter()
+                .all(|s| s.stop_reason.is_none() && s.undecodable == 0)
+            && self.manifest.as_ref().is_some_and(is_finalized)
+    }
+
+    /// The latest entry timestamp across every segment
+    pub fn last_timestamp_ms(&self) -> Option<u64> {
+        self.segments.iter().filter_map(|s| s.last_timestamp_ms).max()
+    }
+}
+
+/// List every session in a recordings directory, newest first.
+/// Directories without a readable `session.json` are skipped.
+pub fn list_sessions(recordings_dir: &Path) -> Result<Vec<StoredSession>, anyhow::Error> {
+    let mut sessions = Vec::new();
+    if !recordings_dir.exists() {
+        return Ok(sessions);
+    }
+
+    for entry in fs::read_dir(recordings_dir)? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        match read_manifest(&path) {
+            Ok(manifest) => sessions.push(StoredSession { path, manifest }),
+            Err(e) => log::warn!("Skipping {:?}: {}", path, e),
+        }
+    }
+
+    sessions.sort_by_key(|s| std::cmp::Reverse(s.manifest.start_timestamp));
+    Ok(sessions)
+}
+
+/// Whether a manifest was closed out by the recorder (or by a later recovery)
+fn is_finalized(manifest: &SessionManifest) -> bool {
+    manifest.end_timestamp.is_some() && manifest.duration_ms.is_some()
+}
+
+/// Total size of every file below a directory
+pub fn directory_size(path: &Path) -> Result<u64, std::io::Error> {
+    let mut total_size = 0u64;
+
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let path = entry.path();
+            if path.is_dir() {
+                total_size += directory_size(&path)?;
+            } else {
+                total_size += entry.metadata()?.len();
+            }
+        }
+    }
+
+    Ok(total_size)
+}
+
+/// Read and parse a session's `session.json`
+pub fn read_manifest(session_path: &Path) -> Result<SessionManifest, anyhow::Error> {
+    Ok(serde_json::from_str(&fs::read_to_string(
+        session_path.join("session.json"),
+    )?)?)
+}
+
+/// Write `session.json` through a temporary file so a crash never leaves it half-written
+pub fn write_manifest(session_path: &Path, manifest: &SessionManifest) -> Result<(), anyhow::Error> {
+    let temp_path = session_path.join("session.json.tmp");
+    fs::write(&temp_path, serde_json::to_string_pretty(manifest)?)?;
+    fs::rename(&temp_path, session_path.join("session.json"))?;
+    Ok(())
+}
+
+/// Whether a session directory was left behind by a recording that never finished
+pub fn is_orphaned(session_path: &Path) -> bool {
+    if !session_path.join("wal").is_dir() {
+        return false;
+    }
+
+    match read_manifest(session_path) {
+        Ok(manifest) => !is_finalized(&manifest),
+        Err(_) => true,
+    }
+}
+
+/// Find every session in a recordings directory that was never finalized.
+/// Only call this while no recording is in progress, since an active session is indistinguishable from an orphaned one.
+pub fn find_orphaned_sessions(recordings_dir: &Path) -> Result<Vec<PathBuf>, anyhow::Error> {
+    let mut orphaned = Vec::new();
+    if !recordings_dir.exists() {
+        return Ok(orphaned);
+    }
+
+    for entry in fs::read_dir(recordings_dir)? {
+        let path = entry?.path();
+        if path.is_dir() && is_orphaned(&path) {
+            orphaned.push(path);
+        }
+    }
+
+    orphaned.sort();
+    Ok(orphaned)
+}
+
+/// Check the manifest and every WAL segment of a session without modifying anything
+pub fn validate_session(session_path: &Path) -> Result<SessionReport, anyhow::Error> {
+    let wal_path = session_path.join("wal");
+    if !wal_path.is_dir() {
+        return Err(anyhow::anyhow!("{:?} has no wal directory", session_path));
+    }
+
+    let (manifest, manifest_error) = match read_manifest(session_path) {
+        Ok(manifest) => (Some(manifest), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+
+    let mut segments = Vec::new();
+    for file_path in segment_files(&wal_path, None)? {
+        let segment = WalSegment::read(&file_path)?;
+        let file_name = file_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        segments.push(SegmentReport {
+            player: WalSegment::player_name(&file_name).map(str::to_string),
+            records: segment.entries.len() + segment.undecodable,
+            undecodable: segment.undecodable,
+            trailing_bytes: segment.trailing_bytes(),
+            stop_reason: segment.stop_reason.clone(),
+            has_input: segment
+                .entries
+                .iter()
+                .any(|e| matches!(e.header, RecordingHeader::Input(_))),
+            last_timestamp_ms: segment.entries.iter().map(|e| e.relative_timestamp_ms).max(),
+            file_name,
+        });
+    }
+
+    let unlisted_players = match &manifest {
+        Some(manifest) => segment_players(&segments)
+            .into_iter()
+            .filter(|p| *p != manifest.emitter_player && !manifest.participants.contains(p))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    Ok(SessionReport {
+        path: session_path.to_path_buf(),
+        manifest,
+        manifest_error,
+        segments,
+        unlisted_players,
+        truncated: Vec::new(),
+        manifest_rebuilt: false,
+    })
+}
+
+/// Truncate torn WAL segments back to their last complete record and rebuild
+/// `session.json` from the WAL when it is missing, unreadable or incomplete.
+/// A session whose manifest was never finalized is marked as `recovered`.
+pub fn repair_session(session_path: &Path) -> Result<SessionReport, anyhow::Error> {
+    let report = validate_session(session_path)?;
+    let wal_path = session_path.join("wal");
+
+    let mut truncated = Vec::new();
+    for file_path in segment_files(&wal_path, None)? {
+        let segment = WalSegment::read(&file_path)?;
+        if segment.first_record.is_none() || segment.trailing_bytes() == 0 {
+            continue;
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .open(&file_path)?
+            .set_len(segment.valid_len)?;
+        log::info!(
+            "Truncated {:?} from {} to {} bytes",
+            file_path,
+            segment.file_len,
+            segment.valid_len
+        );
+        truncated.push(
+            file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        );
+    }
+
+    let manifest_rebuilt = if report.manifest.is_none()
+        || !report.unlisted_players.is_empty()
+        || report.manifest.as_ref().is_some_and(|m| !is_finalized(m))
+    {
+        write_manifest(session_path, &rebuild_manifest(session_path, &report)?)?;
+        true
+    } else {
+        false
+    };
+
+    let mut repaired = validate_session(session_path)?;
+    repaired.truncated = truncated;
+    repaired.manifest_rebuilt = manifest_rebuilt;
+    Ok(repaired)
+}
+
+/// Reconstruct a manifest from the WAL, keeping whatever the existing manifest already knows
+fn rebuild_manifest(session_path: &Path, report: &SessionReport) -> Result<SessionManifest, anyhow::Error> {
+    let session_id = session_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| anyhow::anyhow!("Invalid session path {:?}", session_path))?;
+
+    let mut manifest = match &report.manifest {
+        Some(manifest) => manifest.clone(),
+        None => {
+            // Session ids are v7 UUIDs, so the start time is recoverable from the id itself
+            let start_timestamp = Uuid::parse_str(&session_id)
+                .ok()
+                .and_then(|id| id.get_timestamp())
+                .map(|ts| {
+                    let (secs, nanos) = ts.to_unix();
+                    secs * 1000 + (nanos / 1_000_000) as u64
+                })
+                .ok_or_else(|| anyhow::anyhow!("Cannot derive a start time from session id {}", session_id))?;
+
+            let emitter_player = report
+                .segments
+                .iter()
+                .find(|s| s.has_input)
+                .and_then(|s| s.player.clone())
+                .ok_or_else(|| anyhow::anyhow!("No input segment identifies the recording player"))?;
+
+            SessionManifest {
+                session_id: session_id.clone(),
+                start_timestamp,
+                end_timestamp: None,
+                duration_ms: None,
+                emitter_player,
+                participants: Vec::new(),
+                created_at: format!("{}", start_timestamp / 1000),
+                recovered: true,
+                pinned: false,
+                markers: Vec::new(),
+                spatial_config: None,
+            }
+        }
+    };
+
+    let mut participants: BTreeSet<String> = manifest.participants.iter().cloned().collect();
+    participants.extend(
+        segment_players(&report.segments)
+            .into_iter()
+            .filter(|p| *p != manifest.emitter_player),
+    );
+    manifest.participants = participants.into_iter().collect();
+
+    if !is_finalized(&manifest) {
+        let duration_ms = report
+            .last_timestamp_ms()
+            .map(|ts| ts + OPUS_FRAME_MS)
+            .unwrap_or(0);
+        manifest.duration_ms = Some(duration_ms);
+        manifest.end_timestamp = Some(manifest.start_timestamp + duration_ms);
+        manifest.recovered = true;
+    }
+
+    Ok(manifest)
+}
+
+fn segment_players(segments: &[SegmentReport]) -> BTreeSet<String> {
+    segments
+        .iter()
+        .filter(|s| s.records > 0)
+        .filter_map(|s| s.player.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::recording::renderer::segment::{encode_record, input_header};
+    use common::structs::recording::{OutputRecordingHeader, PlayerMetadata};
+
+    fn output_header(timestamp: u64) -> RecordingHeader {
+        let metadata = PlayerMetadata {
+            player_data: None,
+            spatial: None,
+            gain_settings: None,
+        };
+        RecordingHeader::Output(OutputRecordingHeader {
+            sample_rate: 48000,
+            channels: 1,
+            relative_timestamp_ms: timestamp,
+            emitter_metadata: metadata.clone(),
+            listener_metadata: metadata,
+            is_spatial: false,
+        })
+    }
+
+    fn crashed_session() -> PathBuf {
+        let session_path = std::env::temp_dir()
+            .join(format!("bvc-recordings-{}", Uuid::new_v4()))
+            .join(Uuid::now_v7().to_string());
+        let wal_path = session_path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+
+        let mut input = encode_record(&input_header(0), &[1, 2, 3]);
+        input.extend(encode_record(&input_header(20), &[4, 5, 6]));
+        let torn = encode_record(&input_header(40), &[7, 8, 9]);
+        input.extend_from_slice(&torn[..torn.len() - 1]);
+        fs::write(wal_path.join("Recorder-abc-0001.log"), input).unwrap();
+
+        let mut output = encode_record(&output_header(100), &[1]);
+        output.extend(encode_record(&output_header(480), &[2]));
+        fs::write(wal_path.join("Other-Player-def-0001.log"), output).unwrap();
+
+        session_path
+    }
+
+    #[test]
+    fn test_validate_reports_torn_segment_and_missing_manifest() {
+        let session_path = crashed_session();
+
+        let report = validate_session(&session_path).unwrap();
+        assert!(!report.is_healthy());
+        assert!(report.manifest.is_none());
+        assert_eq!(report.segments.len(), 2);
+
+        let recorder = report.segments.iter().find(|s| s.has_input).unwrap();
+        assert_eq!(recorder.player.as_deref(), Some("Recorder"));
+        assert_eq!(recorder.records, 2);
+        assert!(recorder.stop_reason.is_some());
+        assert_eq!(report.last_timestamp_ms(), Some(480));
+
+        let _ = fs::remove_dir_all(session_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_repair_truncates_and_rebuilds_manifest() {
+        let session_path = crashed_session();
+
+        let report = repair_session(&session_path).unwrap();
+        assert!(report.is_healthy(), "{:?}", report);
+        assert_eq!(report.truncated, vec!["Recorder-abc-0001.log".to_string()]);
+        assert!(report.manifest_rebuilt);
+
+        let manifest = read_manifest(&session_path).unwrap();
+        assert_eq!(manifest.emitter_player, "Recorder");
+        assert_eq!(manifest.participants, vec!["Other-Player".to_string()]);
+        assert_eq!(manifest.duration_ms, Some(500));
+        assert_eq!(manifest.end_timestamp, Some(manifest.start_timestamp + 500));
+        assert!(manifest.recovered);
+
+        // A second pass has nothing left to do
+        let again = repair_session(&session_path).unwrap();
+        assert!(again.truncated.is_empty());
+        assert!(!again.manifest_rebuilt);
+
+        let _ = fs::remove_dir_all(session_path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_unfinished_manifest_is_orphaned_and_recovered() {
+        let session_path = crashed_session();
+        let recordings_dir = session_path.parent().unwrap().to_path_buf();
+        let manifest = SessionManifest {
+            session_id: session_path.file_name().unwrap().to_string_lossy().to_string(),
+            start_timestamp: 1_000,
+            end_timestamp: None,
+            duration_ms: None,
+            emitter_player: "Recorder".to_string(),
+            participants: Vec::new(),
+            created_at: "1".to_string(),
+            recovered: false,
+            pinned: false,
+            markers: Vec::new(),
+            spatial_config: None,
+        };
+        write_manifest(&session_path, &manifest).unwrap();
+
+        assert!(is_orphaned(&session_path));
+        assert!(find_orphaned_sessions(&recordings_dir).unwrap().contains(&session_path));
+
+        let report = repair_session(&session_path).unwrap();
+        assert!(report.is_healthy());
+        assert!(!is_orphaned(&session_path));
+
+        let manifest = read_manifest(&session_path).unwrap();
+        assert!(manifest.recovered);
+        assert_eq!(manifest.start_timestamp, 1_000);
+        assert_eq!(manifest.end_timestamp, Some(1_500));
+        assert_eq!(manifest.participants, vec!["Other-Player".to_string()]);
+
+        let _ = fs::remove_dir_all(session_path.parent().unwrap());
+    }
+}
@@ -25,8 +25,7 @@ impl AdaptationEngine {
         let (loss_rate, jitter, avg_depth) = metrics.network_summary();
 
         // Update network quality assessment
-        self.network_quality =
-            NetworkQuality::from_metrics(loss_rate, jitter, metrics.network_metrics.rtt_variance);
+        self.network_quality = NetworkQuality::from_metrics(loss_rate, jitter);
 
         // Update congestion level
         self.congestion_level = CongestionLevel::from_buffer_metrics(
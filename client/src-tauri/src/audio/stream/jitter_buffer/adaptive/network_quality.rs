@@ -1,3 +1,5 @@
+use common::structs::network::LinkQuality;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NetworkQuality {
     Excellent, // < 1% loss, < 20ms jitter, stable RTT
@@ -8,7 +10,7 @@ pub enum NetworkQuality {
 
 impl NetworkQuality {
     /// Assess network quality from metrics
-    pub fn from_metrics(loss_rate: f64, jitter_ms: f64, _rtt_variance: f64) -> Self {
+    pub fn from_metrics(loss_rate: f64, jitter_ms: f64) -> Self {
         match (loss_rate, jitter_ms) {
             (loss, jitter) if loss < 0.01 && jitter < 20.0 => NetworkQuality::Excellent,
             (loss, jitter) if loss < 0.03 && jitter < 50.0 => NetworkQuality::Good,
@@ -48,6 +50,17 @@ impl NetworkQuality {
     }
 }
 
+impl From<NetworkQuality> for LinkQuality {
+    fn from(quality: NetworkQuality) -> Self {
+        match quality {
+            NetworkQuality::Excellent => LinkQuality::Excellent,
+            NetworkQuality::Good => LinkQuality::Good,
+            NetworkQuality::Moderate => LinkQuality::Moderate,
+            NetworkQuality::Poor => LinkQuality::Poor,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CongestionLevel {
     None,     // Minimal buffering needed
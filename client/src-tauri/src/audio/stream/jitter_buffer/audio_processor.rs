@@ -107,9 +107,15 @@ impl AudioProcessor {
         // Copy the decoded samples to avoid borrowing conflicts
         let decoded_samples: Vec<f32> = self.decode_buffer[..samples_written].to_vec();
         let frames_written = self.write_samples_to_ring(&decoded_samples);
+        self.last_decoded_samples = decoded_samples;
         Ok(frames_written)
     }
 
+    /// Samples produced by the most recent successful decode
+    pub fn last_decoded_samples(&self) -> &[f32] {
+        &self.last_decoded_samples
+    }
+
     /// Write samples to ring buffer in frame-sized chunks
     fn write_samples_to_ring(&mut self, samples: &[f32]) -> usize {
         let mut frames_written = 0;
@@ -139,8 +145,9 @@ impl AudioProcessor {
         frames_written
     }
 
-    /// Generate PLC (Packet Loss Concealment) directly to ring buffer
-    pub fn generate_plc(&mut self) -> Result<(), AudioProcessorError> {
+    /// Generate PLC (Packet Loss Concealment) directly to ring buffer. Returns false once
+    /// the gap has gone on long enough to fall back to silence.
+    pub fn generate_plc(&mut self) -> Result<bool, AudioProcessorError> {
         self.plc_consecutive_count += 1;
 
         let plc_samples = if self.plc_consecutive_count <= 5 {
@@ -171,7 +178,7 @@ impl AudioProcessor {
         }
 
         self.queued_frames = self.queued_frames.saturating_add(1);
-        Ok(())
+        Ok(self.plc_consecutive_count <= 5)
     }
 
     /// Get next audio sample from ring buffer
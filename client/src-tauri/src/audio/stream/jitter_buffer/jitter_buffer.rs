@@ -8,6 +8,7 @@ use std::time::Duration;
 use super::jitter_buffer_source::{JitterBufferError, JitterBufferSource};
 use super::EncodedAudioFramePacket;
 use crate::audio::recording::RecordingProducer;
+use crate::network::diagnostics::DiagnosticsReporter;
 use common::structs::SpatialAudioConfig;
 use common::{Coordinate, Game, Orientation};
 
@@ -53,6 +54,7 @@ impl JitterBuffer {
         activity_tx: Option<flume::Sender<crate::audio::stream::ActivityUpdate>>,
         recording_producer: Option<RecordingProducer>,
         recording_active: Option<Arc<AtomicBool>>,
+        diagnostics: Option<DiagnosticsReporter>,
     ) -> Result<(Self, JitterBufferHandle), JitterBufferError> {
         let (tx, rx) = flume::unbounded::<Option<EncodedAudioFramePacket>>();
 
@@ -77,7 +79,8 @@ impl JitterBuffer {
             activity_tx,
             recording_producer,
             recording_active,
-        )?;
+        )?
+        .with_diagnostics(identifier, diagnostics);
 
         let jitter_buffer = Self {
             source: Arc::new(Mutex::new(source)),
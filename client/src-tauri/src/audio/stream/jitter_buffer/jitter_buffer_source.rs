@@ -4,17 +4,21 @@ use std::collections::VecDeque;
 use std::num::NonZero;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use super::adaptive::AdaptationEngine;
 use super::audio_processor::{AudioProcessor, AudioProcessorError};
 use super::metrics::MetricsCollector;
 use super::EncodedAudioFramePacket;
 use crate::audio::recording::{RawRecordingData, RecordingProducer};
-use crate::audio::stream::activity_detector::ActivityUpdate;
+use crate::audio::stream::activity_detector::{ActivityDetector, ActivityUpdate};
 use crate::audio::stream::stream_manager::AudioSinkType;
+use crate::network::diagnostics::DiagnosticsReporter;
 use common::RecordingPlayerData;
 
+/// How often statistics are sent to the diagnostics collector
+const DIAGNOSTICS_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug)]
 pub enum JitterBufferError {
     AudioProcessorError(AudioProcessorError),
@@ -61,13 +65,16 @@ pub struct JitterBufferSource {
     warmup_packets_received: usize,
     last_output_ts_ms: u64,
     last_accepted_timestamp: u64,
-    player_name: String,
-    activity_tx: Option<flume::Sender<ActivityUpdate>>,
-    last_activity_emission: std::time::Instant,
+    activity_detector: ActivityDetector,
     recording_producer: Option<RecordingProducer>,
     recording_active: Option<Arc<AtomicBool>>,
     pending_recordings: VecDeque<PendingRecording>,
     current_recording: Option<PendingRecording>,
+    identifier: String,
+    player_name: String,
+    spatial: bool,
+    diagnostics: Option<DiagnosticsReporter>,
+    last_diagnostics: Instant,
 }
 
 impl JitterBufferSource {
@@ -108,6 +115,7 @@ impl JitterBufferSource {
             });
         }
 
+        let spatial = initial_packet.route == AudioSinkType::Spatial;
         let mut source = Self {
             audio_processor,
             packet_receiver,
@@ -118,51 +126,41 @@ impl JitterBufferSource {
             warmup_packets_received: 1,
             last_output_ts_ms: initial_packet.timestamp.saturating_sub(20),
             last_accepted_timestamp: initial_packet.timestamp,
-            player_name,
-            activity_tx,
-            last_activity_emission: std::time::Instant::now(),
+            // Players without a name cannot be attributed, so their activity is not reported
+            activity_detector: ActivityDetector::new(
+                player_name.clone(),
+                sample_rate,
+                activity_tx.filter(|_| !player_name.is_empty()),
+            ),
             recording_producer,
             recording_active,
             pending_recordings,
             current_recording: None,
+            identifier: String::new(),
+            player_name,
+            spatial,
+            diagnostics: None,
+            last_diagnostics: Instant::now(),
         };
 
-        source
-            .metrics_collector
-            .record_packet_arrival(initial_packet.timestamp, source.packet_ring.len());
-
-        source.emit_activity_if_needed();
+        source.metrics_collector.record_packet_arrival(
+            initial_packet.timestamp,
+            initial_packet.received_at,
+            source.packet_ring.len(),
+        );
 
         Ok(source)
     }
 
-    /// Emit activity update if we have packets and enough time has passed
-    fn emit_activity_if_needed(&mut self) {
-        if self.packet_ring.is_empty() {
-            return;
-        }
-
-        // Rate limit emissions to every 50ms
-        let now = std::time::Instant::now();
-        if now.duration_since(self.last_activity_emission).as_millis() < 50 {
-            return;
-        }
-
-        if let Some(ref tx) = self.activity_tx {
-            if !self.player_name.is_empty() {
-                let update = ActivityUpdate {
-                    player_name: self.player_name.clone(),
-                    rms_level: 1.0,
-                    timestamp: std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .map(|d| d.as_millis() as u64)
-                        .unwrap_or(0),
-                };
-
-                let _ = tx.try_send(update);
-                self.last_activity_emission = now;
-            }
-        }
+    /// Name the buffer in logs and report its statistics to the diagnostics collector
+    pub fn with_diagnostics(
+        mut self,
+        identifier: String,
+        reporter: Option<DiagnosticsReporter>,
+    ) -> Self {
+        self.identifier = identifier;
+        self.diagnostics = reporter;
+        self
     }
 
     /// Drain incoming packets from channel
@@ -171,6 +169,7 @@ impl JitterBufferSource {
             match msg {
                 Some(packet) => {
                     let packet_timestamp = packet.timestamp;
+                    let received_at = packet.received_at;
 
                     // Check packet acceptance with adaptive logic
                     if !self.is_packet_acceptable(packet_timestamp) {
@@ -217,13 +216,14 @@ impl JitterBufferSource {
                         .min(self.adaptation_engine.warmup_packets_needed());
 
                     // Record metrics
-                    self.metrics_collector
-                        .record_packet_arrival(packet_timestamp, self.packet_ring.len());
+                    self.metrics_collector.record_packet_arrival(
+                        packet_timestamp,
+                        received_at,
+                        self.packet_ring.len(),
+                    );
                     self.metrics_collector
                         .update_ring_metrics(self.packet_ring.len());
 
-                    // Emit activity since we just received a packet
-                    self.emit_activity_if_needed();
                 }
                 None => {
                     self.stopped = true;
@@ -236,8 +236,11 @@ impl JitterBufferSource {
             .adaptation_engine
             .adjust_buffer_if_needed(&self.metrics_collector)
         {
-            self.metrics_collector
-                .record_adaptation(self.last_accepted_timestamp);
+            self.metrics_collector.record_adaptation(
+                self.last_accepted_timestamp,
+                new_capacity,
+                self.adaptation_engine.network_quality,
+            );
 
             // Resize packet ring if needed
             if new_capacity < self.packet_ring.len() {
@@ -252,6 +255,38 @@ impl JitterBufferSource {
                 }
             }
         }
+
+        self.report_diagnostics();
+    }
+
+    /// Periodically share statistics with the diagnostics collector and the log
+    fn report_diagnostics(&mut self) {
+        if self.metrics_collector.should_report() {
+            log::debug!(
+                "[{}] {}",
+                self.identifier,
+                self.metrics_collector.generate_report()
+            );
+        }
+
+        let Some(reporter) = &self.diagnostics else {
+            return;
+        };
+        if self.last_diagnostics.elapsed() < DIAGNOSTICS_INTERVAL {
+            return;
+        }
+        self.last_diagnostics = Instant::now();
+
+        reporter.report(
+            &self.identifier,
+            self.metrics_collector.diagnostics(
+                self.player_name.clone(),
+                self.spatial,
+                self.packet_ring.len(),
+                self.adaptation_engine.current_capacity(),
+                self.adaptation_engine.network_quality,
+            ),
+        );
     }
 
     /// Check if packet is acceptable with adaptive logic
@@ -269,6 +304,10 @@ impl JitterBufferSource {
                     self.audio_processor.reset_plc_counter();
                     self.metrics_collector.record_decode_success(frames_written);
 
+                    // Report the level of what is about to be played
+                    self.activity_detector
+                        .analyze_samples(self.audio_processor.last_decoded_samples());
+
                     // Assessment network conditions after successful decode
                     self.adaptation_engine
                         .assess_network_conditions(&self.metrics_collector);
@@ -288,8 +327,11 @@ impl JitterBufferSource {
     /// Generate PLC sample
     fn generate_plc_sample(&mut self) -> Option<f32> {
         match self.audio_processor.generate_plc() {
-            Ok(()) => {
-                self.metrics_collector.record_plc_generation();
+            Ok(concealed) => {
+                // Once concealment gives up the player has stopped talking, which is not loss
+                if concealed {
+                    self.metrics_collector.record_plc_generation();
+                }
                 self.audio_processor.next_sample()
             }
             Err(_) => {
@@ -346,6 +388,27 @@ impl JitterBufferSource {
     }
 }
 
+/// Access for the replay tests in `simulation`
+#[cfg(test)]
+impl JitterBufferSource {
+    pub(crate) fn metrics(&self) -> &MetricsCollector {
+        &self.metrics_collector
+    }
+
+    pub(crate) fn adaptation(&self) -> &AdaptationEngine {
+        &self.adaptation_engine
+    }
+
+    /// Let simulated time pass for the adaptation rate limits, which otherwise follow the
+    /// wall clock
+    pub(crate) fn advance_clock(&mut self, elapsed: Duration) {
+        let state = &mut self.adaptation_engine.state;
+        if let Some(earlier) = state.last_adjustment.checked_sub(elapsed) {
+            state.last_adjustment = earlier;
+        }
+    }
+}
+
 impl Iterator for JitterBufferSource {
     type Item = f32;
 
@@ -1,11 +1,18 @@
 use super::{DiagnosticMetrics, NetworkMetrics};
+use crate::audio::stream::jitter_buffer::adaptive::NetworkQuality;
+use common::structs::network::{BufferAdaptation, EmitterDiagnostics};
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 
+/// Buffer resizes kept for diagnostics
+const ADAPTATION_HISTORY: usize = 20;
+
 /// Centralized metrics collection and reporting
 #[derive(Debug)]
 pub struct MetricsCollector {
     pub network_metrics: NetworkMetrics,
     pub diagnostic_metrics: DiagnosticMetrics,
+    adaptations: VecDeque<BufferAdaptation>,
     report_interval: Duration,
     last_report: Instant,
 }
@@ -15,6 +22,7 @@ impl Default for MetricsCollector {
         Self {
             network_metrics: NetworkMetrics::default(),
             diagnostic_metrics: DiagnosticMetrics::default(),
+            adaptations: VecDeque::with_capacity(ADAPTATION_HISTORY),
             report_interval: Duration::from_secs(30), // Report every 30 seconds
             last_report: Instant::now(),
         }
@@ -26,15 +34,16 @@ impl MetricsCollector {
         Self {
             network_metrics: NetworkMetrics::default(),
             diagnostic_metrics: DiagnosticMetrics::default(),
+            adaptations: VecDeque::with_capacity(ADAPTATION_HISTORY),
             report_interval,
             last_report: Instant::now(),
         }
     }
 
     /// Record packet arrival and update network metrics
-    pub fn record_packet_arrival(&mut self, timestamp: u64, buffer_depth: usize) {
+    pub fn record_packet_arrival(&mut self, timestamp: u64, arrival: Instant, buffer_depth: usize) {
         self.network_metrics
-            .record_packet_arrival(timestamp, buffer_depth);
+            .record_packet_arrival(timestamp, arrival, buffer_depth);
     }
 
     /// Record successful decode
@@ -74,8 +83,17 @@ impl MetricsCollector {
     }
 
     /// Record adaptation event
-    pub fn record_adaptation(&mut self, timestamp: u64) {
+    pub fn record_adaptation(&mut self, timestamp: u64, capacity: usize, quality: NetworkQuality) {
         self.diagnostic_metrics.record_adaptation(timestamp);
+
+        if self.adaptations.len() == ADAPTATION_HISTORY {
+            self.adaptations.pop_front();
+        }
+        self.adaptations.push_back(BufferAdaptation {
+            timestamp,
+            capacity: capacity as u32,
+            quality: quality.into(),
+        });
     }
 
     /// Check if it's time to generate a report
@@ -116,4 +134,36 @@ impl MetricsCollector {
             self.network_metrics.avg_buffer_depth,
         )
     }
+
+    /// Snapshot for the diagnostics panel and exported reports
+    pub fn diagnostics(
+        &self,
+        player: String,
+        spatial: bool,
+        buffer_depth: usize,
+        buffer_capacity: usize,
+        quality: NetworkQuality,
+    ) -> EmitterDiagnostics {
+        let network = &self.network_metrics;
+        let diagnostic = &self.diagnostic_metrics;
+
+        EmitterDiagnostics {
+            player,
+            spatial,
+            jitter_ms: network.jitter(),
+            loss_rate: network.packet_loss_rate(),
+            packets_received: network.packets_received,
+            packets_lost: network.packet_loss_count,
+            frames_decoded: diagnostic.frames_decoded,
+            frames_concealed: diagnostic.frames_plc,
+            frames_dropped_overflow: diagnostic.frames_dropped_overflow,
+            frames_dropped_late: diagnostic.frames_dropped_ooo,
+            buffer_depth: buffer_depth as u32,
+            buffer_capacity: buffer_capacity as u32,
+            avg_buffer_depth: network.avg_buffer_depth,
+            quality: quality.into(),
+            quality_score: diagnostic.calculate_quality_score(),
+            adaptations: self.adaptations.iter().cloned().collect(),
+        }
+    }
 }
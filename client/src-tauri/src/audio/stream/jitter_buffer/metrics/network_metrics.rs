@@ -1,9 +1,17 @@
 use std::time::Instant;
 
+/// Audio frame length the sender packetizes at
+const FRAME_MS: u64 = 20;
+
+/// Gaps in the sender's timestamps up to this long are counted as lost packets. Longer gaps are
+/// the sender pausing between words.
+const MAX_LOSS_GAP_MS: u64 = 200;
+
 /// Network condition tracking and analysis
 #[derive(Debug, Clone)]
 pub struct NetworkMetrics {
-    pub rtt_variance: f64,
+    /// Smoothed interarrival jitter in milliseconds (RFC 3550)
+    pub jitter_ms: f64,
     pub packets_received: u64,
     pub packets_expected: u64,
     pub large_timestamp_jumps: u64,
@@ -13,13 +21,14 @@ pub struct NetworkMetrics {
     pub avg_buffer_depth: f64,
     pub target_buffer_depth: usize,
     pub last_packet_timestamp: u64,
+    pub last_arrival: Option<Instant>,
     pub last_update: Instant,
 }
 
 impl Default for NetworkMetrics {
     fn default() -> Self {
         Self {
-            rtt_variance: 0.0,
+            jitter_ms: 0.0,
             packets_received: 0,
             packets_expected: 0,
             large_timestamp_jumps: 0,
@@ -29,14 +38,15 @@ impl Default for NetworkMetrics {
             avg_buffer_depth: 0.0,
             target_buffer_depth: 10,
             last_packet_timestamp: 0,
+            last_arrival: None,
             last_update: Instant::now(),
         }
     }
 }
 
 impl NetworkMetrics {
-    /// Record packet arrival with timestamp and current buffer depth
-    pub fn record_packet_arrival(&mut self, timestamp: u64, buffer_depth: usize) {
+    /// Record packet arrival with its sender timestamp, local arrival time and current buffer depth
+    pub fn record_packet_arrival(&mut self, timestamp: u64, arrival: Instant, buffer_depth: usize) {
         self.packets_received += 1;
         self.packets_expected += 1;
 
@@ -49,16 +59,31 @@ impl NetworkMetrics {
             self.avg_buffer_depth = 0.9 * self.avg_buffer_depth + 0.1 * depth;
         }
 
-        // Check for large timestamp jumps
-        if self.last_packet_timestamp > 0 {
+        if let Some(last_arrival) = self.last_arrival {
             let time_diff = timestamp.saturating_sub(self.last_packet_timestamp);
             if time_diff > 1000 {
                 // > 1 second jump
                 self.large_timestamp_jumps += 1;
             }
+
+            if time_diff <= MAX_LOSS_GAP_MS {
+                // Sender clocks wander by a few ms, so round to whole frames
+                let frames = (time_diff + FRAME_MS / 2) / FRAME_MS;
+                let missing = frames.saturating_sub(1);
+                self.packets_expected += missing;
+                self.packet_loss_count += missing;
+
+                let arrival_diff = arrival
+                    .saturating_duration_since(last_arrival)
+                    .as_secs_f64()
+                    * 1000.0;
+                let transit_change = (arrival_diff - time_diff as f64).abs();
+                self.jitter_ms += (transit_change - self.jitter_ms) / 16.0;
+            }
         }
 
         self.last_packet_timestamp = timestamp;
+        self.last_arrival = Some(arrival);
         self.last_update = Instant::now();
     }
 
@@ -67,7 +92,7 @@ impl NetworkMetrics {
         self.buffer_underruns += 1;
     }
 
-    /// Record a buffer overflow event  
+    /// Record a buffer overflow event
     pub fn record_overflow(&mut self) {
         self.buffer_overflows += 1;
     }
@@ -82,8 +107,48 @@ impl NetworkMetrics {
         lost as f64 / self.packets_expected as f64
     }
 
-    /// Get current jitter (RTT variance)
+    /// Get current jitter in milliseconds
     pub fn jitter(&self) -> f64 {
-        self.rtt_variance.sqrt()
+        self.jitter_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_loss_from_timestamp_gaps() {
+        let mut metrics = NetworkMetrics::default();
+        let start = Instant::now();
+
+        metrics.record_packet_arrival(1000, start, 1);
+        metrics.record_packet_arrival(1021, start + Duration::from_millis(20), 1);
+        // Two frames missing
+        metrics.record_packet_arrival(1080, start + Duration::from_millis(80), 1);
+        // A pause between words is not loss
+        metrics.record_packet_arrival(3000, start + Duration::from_millis(2000), 1);
+
+        assert_eq!(metrics.packets_received, 4);
+        assert_eq!(metrics.packet_loss_count, 2);
+        assert!((metrics.packet_loss_rate() - 2.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_jitter_from_arrival_spread() {
+        let mut steady = NetworkMetrics::default();
+        let mut bursty = NetworkMetrics::default();
+        let start = Instant::now();
+
+        for i in 0..50u64 {
+            steady.record_packet_arrival(i * 20, start + Duration::from_millis(i * 20), 1);
+            // Packets arrive in pairs every 40ms
+            let arrival = start + Duration::from_millis((i / 2) * 40 + 40);
+            bursty.record_packet_arrival(i * 20, arrival, 1);
+        }
+
+        assert!(steady.jitter() < 0.5);
+        assert!(bursty.jitter() > 10.0);
     }
 }
@@ -1,6 +1,7 @@
 use crate::audio::stream::stream_manager::AudioSinkType;
 use base64::{engine::general_purpose, Engine as _};
 use common::RecordingPlayerData as PlayerData;
+use std::time::Instant;
 
 pub mod adaptive;
 pub mod audio_processor;
@@ -8,8 +9,10 @@ mod jitter_buffer;
 pub mod jitter_buffer_source;
 pub mod metrics;
 pub mod pan_state;
+#[cfg(test)]
+mod simulation;
 
-pub use jitter_buffer::{JitterBuffer, JitterBufferHandle};
+pub use jitter_buffer::{JitterBuffer, JitterBufferHandle, SpatialAudioData};
 pub use pan_state::PanState;
 
 #[allow(dead_code)]
@@ -70,6 +73,10 @@ pub(crate) struct EncodedAudioFramePacket {
     pub listener: PlayerData,
     pub buffer_size_ms: u32,
     pub time_between_reports_secs: u64,
+    /// When the packet came off the network, for measuring jitter
+    pub received_at: Instant,
+    /// Url of the server the packet came from
+    pub server: String,
 }
 
 impl EncodedAudioFramePacket {
@@ -0,0 +1,327 @@
+//! Headless replay of recorded audio through the jitter buffer
+//!
+//! Reads a player's packets from a recording session's WAL, passes them through the network
+//! impairment simulator and plays them out of a `JitterBufferSource` on simulated time, one
+//! frame per 20ms tick. No server or audio device is involved, so `AdaptationEngine` changes
+//! can be checked against the resulting `DiagnosticMetrics`.
+
+use super::EncodedAudioFramePacket;
+use super::adaptive::NetworkQuality;
+use super::jitter_buffer_source::JitterBufferSource;
+use super::metrics::{DiagnosticMetrics, NetworkMetrics};
+use crate::audio::recording::renderer::WalAudioReader;
+use crate::audio::stream::stream_manager::AudioSinkType;
+use crate::network::impairment::{Impairment, ImpairmentConfig};
+use common::RecordingPlayerData;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const FRAME_MS: u64 = 20;
+
+/// One way delay of the simulated network before any impairment
+const BASE_LATENCY_MS: u64 = 30;
+
+/// Playout continues this long after the last arrival so the buffer drains
+const DRAIN_MS: u64 = 2000;
+
+/// Buffer size the output stream asks jitter buffers for
+const BUFFER_SIZE_MS: u64 = 120;
+
+/// A packet as it comes off the simulated network
+struct Arrival {
+    arrival_ms: u64,
+    timestamp: u64,
+    sample_rate: u32,
+    data: Vec<u8>,
+}
+
+/// What the jitter buffer made of a replay
+pub(crate) struct Replay {
+    pub packets_sent: usize,
+    pub packets_delivered: usize,
+    pub diagnostics: DiagnosticMetrics,
+    pub network: NetworkMetrics,
+    pub quality: NetworkQuality,
+    pub capacity: usize,
+}
+
+/// Replay a player's recorded packets through the given impairment
+pub(crate) fn replay(
+    session_path: &Path,
+    player_name: &str,
+    config: ImpairmentConfig,
+) -> Result<Replay, anyhow::Error> {
+    let mut reader = WalAudioReader::new(session_path, player_name)?;
+    let mut impairment = Impairment::new(config);
+
+    let mut arrivals = Vec::new();
+    let mut packets_sent = 0;
+    while let Some(entry) = reader.next_raw_entry() {
+        packets_sent += 1;
+        for delay in impairment.apply() {
+            arrivals.push(Arrival {
+                arrival_ms: entry.relative_timestamp_ms
+                    + BASE_LATENCY_MS
+                    + delay.as_millis() as u64,
+                timestamp: entry.relative_timestamp_ms,
+                sample_rate: entry.header.sample_rate(),
+                data: entry.opus_data.clone(),
+            });
+        }
+    }
+    // Stable, so packets arriving together keep the order they were sent in
+    arrivals.sort_by_key(|arrival| arrival.arrival_ms);
+    let packets_delivered = arrivals.len();
+
+    let player = RecordingPlayerData {
+        name: player_name.to_string(),
+        client_id: None,
+        player_data: None,
+        spatial: Some(false),
+        gain_settings: None,
+    };
+    let start = Instant::now();
+    let packet = |arrival: &Arrival| EncodedAudioFramePacket {
+        timestamp: arrival.timestamp,
+        sample_rate: arrival.sample_rate,
+        data: arrival.data.clone(),
+        route: AudioSinkType::Normal,
+        emitter: player.clone(),
+        listener: player.clone(),
+        buffer_size_ms: BUFFER_SIZE_MS as u32,
+        time_between_reports_secs: 30,
+        received_at: start + Duration::from_millis(arrival.arrival_ms),
+        server: String::new(),
+    };
+
+    let end_ms = arrivals.last().map_or(0, |arrival| arrival.arrival_ms) + DRAIN_MS;
+    let mut arrivals = arrivals.into_iter().peekable();
+    let first = arrivals
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("No packets delivered for {}", player_name))?;
+
+    // The output stream creates the buffer from the first packet it receives for a player
+    let (tx, rx) = flume::unbounded();
+    let capacity = ((BUFFER_SIZE_MS / FRAME_MS) as usize).max(5);
+    let mut source = JitterBufferSource::new_with_activity(
+        rx,
+        packet(&first),
+        capacity,
+        player_name.to_string(),
+        None,
+        None,
+        None,
+    )
+    .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+    let samples_per_frame = (first.sample_rate as u64 * FRAME_MS / 1000) as usize;
+    let mut now_ms = first.arrival_ms;
+    while now_ms <= end_ms {
+        while let Some(arrival) = arrivals.next_if(|arrival| arrival.arrival_ms <= now_ms) {
+            _ = tx.send(Some(packet(&arrival)));
+        }
+
+        // The output device pulls one frame per tick
+        for _ in 0..samples_per_frame {
+            source.next();
+        }
+        source.advance_clock(Duration::from_millis(FRAME_MS));
+        now_ms += FRAME_MS;
+    }
+
+    Ok(Replay {
+        packets_sent,
+        packets_delivered,
+        diagnostics: source.metrics().diagnostic_metrics.clone(),
+        network: source.metrics().network_metrics.clone(),
+        quality: source.adaptation().network_quality,
+        capacity: source.adaptation().current_capacity(),
+    })
+}
+
+mod tests {
+    use super::*;
+    use crate::audio::recording::renderer::segment::{encode_record, input_header};
+    use std::fs;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    const PLAYER: &str = "Steve";
+    const TALK_SPURTS: u64 = 4;
+    const SPURT_FRAMES: u64 = 250;
+    /// Longer than a second, which the jitter buffer treats as a new stream
+    const PAUSE_MS: u64 = 1200;
+
+    /// A session with a few seconds of tone in each of several talk spurts
+    fn recorded_session() -> PathBuf {
+        let session_path = std::env::temp_dir()
+            .join(format!("bvc-replay-{}", Uuid::new_v4()))
+            .join(Uuid::now_v7().to_string());
+        let wal_path = session_path.join("wal");
+        fs::create_dir_all(&wal_path).unwrap();
+
+        let manifest = serde_json::json!({
+            "session_id": "replay",
+            "start_timestamp": 0,
+            "end_timestamp": null,
+            "duration_ms": null,
+            "emitter_player": PLAYER,
+            "participants": [PLAYER],
+            "created_at": "2026-01-01T00:00:00Z",
+        });
+        fs::write(session_path.join("session.json"), manifest.to_string()).unwrap();
+
+        let mut encoder =
+            opus2::Encoder::new(48000, opus2::Channels::Mono, opus2::Application::Voip).unwrap();
+        let mut opus_out = vec![0u8; 4000];
+        let mut phase = 0.0f32;
+        let mut segment = Vec::new();
+        for spurt in 0..TALK_SPURTS {
+            let spurt_start = spurt * (SPURT_FRAMES * FRAME_MS + PAUSE_MS);
+            for frame in 0..SPURT_FRAMES {
+                let pcm: Vec<f32> = (0..960)
+                    .map(|_| {
+                        phase = (phase + std::f32::consts::TAU * 440.0 / 48000.0)
+                            % std::f32::consts::TAU;
+                        phase.sin() * 0.3
+                    })
+                    .collect();
+                let len = encoder.encode_float(&pcm, &mut opus_out).unwrap();
+                segment.extend(encode_record(
+                    &input_header(spurt_start + frame * FRAME_MS),
+                    &opus_out[..len],
+                ));
+            }
+        }
+        fs::write(wal_path.join(format!("{}-abc-0001.log", PLAYER)), segment).unwrap();
+
+        session_path
+    }
+
+    fn run(config: ImpairmentConfig) -> Replay {
+        let session_path = recorded_session();
+        let replay = replay(&session_path, PLAYER, config).unwrap();
+        let _ = fs::remove_dir_all(session_path.parent().unwrap());
+        replay
+    }
+
+    /// Every delivered packet is either played or dropped
+    fn assert_accounted(replay: &Replay) {
+        let diagnostics = &replay.diagnostics;
+        assert_eq!(
+            diagnostics.frames_decoded
+                + diagnostics.frames_dropped_ooo
+                + diagnostics.frames_dropped_overflow,
+            replay.packets_delivered as u64,
+            "{}",
+            diagnostics.format_diagnostics()
+        );
+    }
+
+    #[test]
+    fn test_clean_network() {
+        let replay = run(ImpairmentConfig::default());
+
+        assert_eq!(replay.packets_sent as u64, TALK_SPURTS * SPURT_FRAMES);
+        assert_eq!(replay.packets_delivered, replay.packets_sent);
+        assert_eq!(
+            replay.diagnostics.frames_decoded,
+            replay.packets_sent as u64
+        );
+        assert_eq!(replay.diagnostics.frames_dropped_ooo, 0);
+        assert_eq!(replay.network.packet_loss_count, 0);
+        assert!(replay.network.jitter() < 1.0);
+        // Concealment only runs out the end of each talk spurt
+        assert!(replay.diagnostics.frames_plc <= TALK_SPURTS * 5);
+        assert_eq!(replay.quality, NetworkQuality::Excellent);
+    }
+
+    #[test]
+    fn test_random_loss() {
+        let replay = run(ImpairmentConfig {
+            loss: 0.05,
+            seed: Some(11),
+            ..Default::default()
+        });
+
+        let lost = replay.packets_sent - replay.packets_delivered;
+        let injected_rate = lost as f64 / replay.packets_sent as f64;
+        assert!(lost > 0);
+        assert!(replay.network.packet_loss_count <= lost as u64);
+        assert!((replay.network.packet_loss_rate() - injected_rate).abs() < 0.02);
+        assert!(replay.diagnostics.frames_plc > 0);
+        assert_ne!(replay.quality, NetworkQuality::Excellent);
+        assert_accounted(&replay);
+    }
+
+    #[test]
+    fn test_jitter_and_reordering() {
+        let replay = run(ImpairmentConfig {
+            reorder: 0.05,
+            duplicate: 0.02,
+            jitter_ms: 60,
+            seed: Some(21),
+            ..Default::default()
+        });
+
+        assert!(replay.packets_delivered > replay.packets_sent);
+        assert!(replay.network.jitter() > 10.0);
+        // Duplicates and packets overtaken by later ones arrive too late to play
+        assert!(replay.diagnostics.frames_dropped_ooo > 0);
+        assert_accounted(&replay);
+    }
+
+    #[test]
+    fn test_seeded_replay_repeats() {
+        let config = ImpairmentConfig {
+            loss: 0.02,
+            burst: Some((0.01, 0.3)),
+            reorder: 0.05,
+            jitter_ms: 40,
+            seed: Some(5),
+            ..Default::default()
+        };
+
+        let first = run(config.clone());
+        let second = run(config);
+        assert_eq!(
+            first.diagnostics.format_diagnostics(),
+            second.diagnostics.format_diagnostics()
+        );
+        assert_eq!(
+            first.network.packet_loss_count,
+            second.network.packet_loss_count
+        );
+        assert_eq!(first.capacity, second.capacity);
+    }
+
+    /// Replay a real session through an impairment (environment-based)
+    ///
+    /// Set TEST_SESSION_PATH and TEST_PLAYER_NAME to run, and BVC_NETWORK_IMPAIRMENT to
+    /// degrade the network.
+    #[test]
+    fn test_replay_recorded_session() {
+        let session_path = match std::env::var("TEST_SESSION_PATH") {
+            Ok(p) => p,
+            Err(_) => {
+                println!("Skipping: TEST_SESSION_PATH not set");
+                return;
+            }
+        };
+        let player = std::env::var("TEST_PLAYER_NAME").unwrap_or_else(|_| "Alaydriem".to_string());
+        let config = ImpairmentConfig::from_env().unwrap_or_default();
+
+        let replay = replay(Path::new(&session_path), &player, config).expect("Replay failed");
+        println!(
+            "sent={} delivered={} loss={:.1}% jitter={:.1}ms quality={:?} capacity={} | {}",
+            replay.packets_sent,
+            replay.packets_delivered,
+            replay.network.packet_loss_rate() * 100.0,
+            replay.network.jitter(),
+            replay.quality,
+            replay.capacity,
+            replay.diagnostics.format_diagnostics()
+        );
+        assert_accounted(&replay);
+    }
+}
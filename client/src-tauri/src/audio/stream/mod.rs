@@ -1,12 +1,15 @@
 mod activity_detector;
 pub mod jitter_buffer;
+mod speaking_monitor;
 mod stream_manager;
+mod voice_activity;
 
 use crate::audio::types::{AudioDevice, AudioDeviceType};
 use crate::audio::recording::RecordingManager;
 use crate::NetworkPacket;
 use anyhow::Error;
 use common::structs::audio::StreamEvent;
+use common::structs::SpatialAudioConfig;
 use log::warn;
 use std::sync::Arc;
 use tauri::async_runtime::Mutex as TauriMutex;
@@ -17,6 +20,7 @@ use super::AudioPacket;
 use stream_manager::{StreamTrait, StreamTraitType};
 
 pub(crate) use activity_detector::ActivityUpdate;
+pub(crate) use speaking_monitor::SpeakingMonitor;
 
 /// Event sent when a stream encounters an error requiring recovery
 #[derive(Debug, Clone)]
@@ -314,6 +318,12 @@ impl AudioStreamManager {
         Ok(())
     }
 
+    /// The server's spatial audio settings, as last pushed to the output stream
+    pub async fn spatial_config(&self) -> Option<SpatialAudioConfig> {
+        let json = self.output.get_metadata().get("spatial_audio_config").await?;
+        serde_json::from_str(&json).ok()
+    }
+
     /// Returns the currently tracked players with their game type
     pub fn get_current_players(&self) -> std::collections::HashMap<String, Option<String>> {
         match &self.output {
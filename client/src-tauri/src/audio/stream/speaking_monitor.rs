@@ -0,0 +1,161 @@
+use crate::audio::stream::ActivityUpdate;
+use crate::events::event::speaking::{SPEAKING, Speaking};
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+/// How long a player must be silent before they are considered to have stopped speaking
+const SPEAKING_HOLD_MS: u64 = 400;
+
+/// How often silent speakers are checked for
+const SPEAKING_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone)]
+struct Speaker {
+    last_active_ms: u64,
+    level: f32,
+}
+
+/// Debounces activity updates into per-player started/stopped transitions
+#[derive(Debug)]
+struct SpeakingDetector {
+    hold_ms: u64,
+    speakers: HashMap<String, Speaker>,
+}
+
+impl SpeakingDetector {
+    fn new(hold_ms: u64) -> Self {
+        Self {
+            hold_ms,
+            speakers: HashMap::new(),
+        }
+    }
+
+    /// Record activity for a player, returning a started event if they were silent
+    fn observe(&mut self, update: &ActivityUpdate) -> Option<Speaking> {
+        let speaker = Speaker {
+            last_active_ms: update.timestamp,
+            level: update.rms_level,
+        };
+
+        match self.speakers.insert(update.player_name.clone(), speaker) {
+            Some(_) => None,
+            None => Some(Speaking::new(
+                update.player_name.clone(),
+                true,
+                update.rms_level,
+            )),
+        }
+    }
+
+    /// Stopped events for every player silent for longer than the hold time
+    fn expire(&mut self, now_ms: u64) -> Vec<Speaking> {
+        let mut stopped = Vec::new();
+        self.speakers.retain(|player, speaker| {
+            if now_ms.saturating_sub(speaker.last_active_ms) > self.hold_ms {
+                stopped.push(Speaking::new(player.clone(), false, speaker.level));
+                false
+            } else {
+                true
+            }
+        });
+        stopped
+    }
+}
+
+/// Turns the activity of every heard player, and the local player's microphone, into
+/// `speaking` events for overlays. Managed as Tauri state so input and output streams
+/// can feed it regardless of restarts.
+pub(crate) struct SpeakingMonitor {
+    activity_tx: flume::Sender<ActivityUpdate>,
+}
+
+impl SpeakingMonitor {
+    pub fn new(app_handle: AppHandle) -> Self {
+        let (activity_tx, activity_rx) = flume::unbounded::<ActivityUpdate>();
+
+        tauri::async_runtime::spawn(async move {
+            let mut detector = SpeakingDetector::new(SPEAKING_HOLD_MS);
+            let mut check_timer = tokio::time::interval(SPEAKING_CHECK_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    update = activity_rx.recv_async() => {
+                        let Ok(update) = update else {
+                            break;
+                        };
+                        if let Some(event) = detector.observe(&update) {
+                            Self::emit(&app_handle, event);
+                        }
+                    }
+
+                    _ = check_timer.tick() => {
+                        let now_ms = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .map(|d| d.as_millis() as u64)
+                            .unwrap_or(0);
+                        for event in detector.expire(now_ms) {
+                            Self::emit(&app_handle, event);
+                        }
+                    }
+                }
+            }
+        });
+
+        Self { activity_tx }
+    }
+
+    /// Sender for activity updates; dropped updates only delay a started event
+    pub fn sender(&self) -> flume::Sender<ActivityUpdate> {
+        self.activity_tx.clone()
+    }
+
+    fn emit(app_handle: &AppHandle, event: Speaking) {
+        if let Err(e) = app_handle.emit(SPEAKING, &event) {
+            log::warn!("Failed to emit speaking event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(player: &str, level: f32, timestamp: u64) -> ActivityUpdate {
+        ActivityUpdate {
+            player_name: player.to_string(),
+            rms_level: level,
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn test_started_once_per_speaking_run() {
+        let mut detector = SpeakingDetector::new(400);
+
+        let started = detector.observe(&update("Steve", 0.2, 1000)).unwrap();
+        assert_eq!(started, Speaking::new("Steve".to_string(), true, 0.2));
+        assert!(detector.observe(&update("Steve", 0.3, 1050)).is_none());
+        assert!(detector.observe(&update("Alex", 0.1, 1050)).is_some());
+    }
+
+    #[test]
+    fn test_stopped_after_hold_time() {
+        let mut detector = SpeakingDetector::new(400);
+        detector.observe(&update("Steve", 0.2, 1000));
+        detector.observe(&update("Steve", 0.05, 1300));
+
+        // Brief pauses between words do not end the run
+        assert!(detector.expire(1600).is_empty());
+
+        let stopped = detector.expire(1701);
+        assert_eq!(
+            stopped,
+            vec![Speaking::new("Steve".to_string(), false, 0.05)]
+        );
+        assert!(detector.expire(5000).is_empty());
+
+        // Speaking again starts a new run
+        assert!(detector.observe(&update("Steve", 0.2, 6000)).is_some());
+    }
+}
@@ -3,12 +3,14 @@ use super::resampler::AudioResampler;
 use super::AudioFrame;
 use common::consts::OPUS_FRAME_DURATION_MS;
 use crate::audio::recording::{RawRecordingData, RecordingProducer};
-use crate::audio::stream::{RecoverySender, StreamRecoveryEvent};
+use crate::audio::stream::activity_detector::ActivityDetector;
+use crate::audio::stream::voice_activity::{VoiceActivity, VoiceActivityDetector};
+use crate::audio::stream::{RecoverySender, SpeakingMonitor, StreamRecoveryEvent};
 use crate::audio::types::{AudioDevice, AudioDeviceCpal, AudioDeviceType, BUFFER_SIZE};
 use crate::{audio::stream::stream_manager::AudioFrameData, NetworkPacket};
 use anyhow::anyhow;
 use audio_gate::NoiseGate;
-use common::structs::audio::{NoiseGateSettings, StreamEvent};
+use common::structs::audio::{NoiseGateSettings, StreamEvent, VoiceActivationSettings};
 use common::structs::packet::{AudioFramePacket, QuicNetworkPacket, QuicNetworkPacketData};
 use common::RecordingPlayerData;
 use log::{error, debug, warn};
@@ -23,11 +25,14 @@ use std::{
     },
     time::Duration,
 };
+use tauri::Manager;
 use tauri_plugin_store::StoreExt;
 use tokio::task::{AbortHandle, JoinHandle};
 
 /// Indicator for if the Input Stream should be muted
 static MUTE_INPUT_STREAM: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
+/// Indicator for if frames should only be heard by channel members, without spatialization
+static WHISPER_TO_CHANNEL: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
 static USE_NOISE_GATE: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
 static UPDATE_NOISE_GATE_SETTINGS: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
 static NOISE_GATE_SETTINGS: Lazy<Mutex<serde_json::Value>> = Lazy::new(|| {
@@ -36,6 +41,14 @@ static NOISE_GATE_SETTINGS: Lazy<Mutex<serde_json::Value>> = Lazy::new(|| {
             .expect("Failed to serialize NoiseGateSettings"),
     )
 });
+static USE_VOICE_ACTIVATION: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
+static UPDATE_VOICE_ACTIVATION_SETTINGS: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
+static VOICE_ACTIVATION_SETTINGS: Lazy<Mutex<serde_json::Value>> = Lazy::new(|| {
+    Mutex::new(
+        serde_json::to_value(VoiceActivationSettings::default())
+            .expect("Failed to serialize VoiceActivationSettings"),
+    )
+});
 
 pub(crate) struct InputStream {
     pub device: Option<AudioDevice>,
@@ -63,6 +76,13 @@ impl common::traits::StreamTrait for InputStream {
                 // Recording is now controlled by RecordingManager's shared flag
                 // No action needed here
             },
+            // Send to channel members only while held
+            "whisper" => {
+                match value.as_str() {
+                    "true" => WHISPER_TO_CHANNEL.store(true, Ordering::Relaxed),
+                    _ => WHISPER_TO_CHANNEL.store(false, Ordering::Relaxed),
+                };
+            }
             // Toggle Noise Gate
             "use_noise_gate" => {
                 match value.as_str() {
@@ -87,6 +107,30 @@ impl common::traits::StreamTrait for InputStream {
                     }
                 };
             }
+            // Toggle Voice Activation
+            "use_voice_activation" => {
+                match value.as_str() {
+                    "true" => USE_VOICE_ACTIVATION.store(true, Ordering::Relaxed),
+                    _ => USE_VOICE_ACTIVATION.store(false, Ordering::Relaxed),
+                };
+            }
+            "voice_activation_settings" => {
+                match serde_json::from_str::<VoiceActivationSettings>(&value) {
+                    Ok(settings) => {
+                        let mut lock_settings = VOICE_ACTIVATION_SETTINGS.lock().unwrap();
+                        *lock_settings = serde_json::to_value(settings)
+                            .expect("Failed to serialize VoiceActivationSettings");
+                        UPDATE_VOICE_ACTIVATION_SETTINGS.store(true, Ordering::Relaxed);
+                        drop(lock_settings);
+                    }
+                    Err(e) => {
+                        log::error!(
+                            "Failed to deserialize VoiceActivationSettings on metadata set: {}",
+                            e
+                        );
+                    }
+                };
+            }
             _ => {
                 let metadata = self.metadata.clone();
                 metadata.insert(key, value).await;
@@ -311,6 +355,20 @@ impl InputStream {
                                     None => None,
                                 };
 
+                                // Speech detection for the voice activation mode, run on the mono stream after resampling
+                                let voice_activation_settings = serde_json::from_value::<VoiceActivationSettings>(
+                                    VOICE_ACTIVATION_SETTINGS.lock().unwrap().clone(),
+                                )
+                                .unwrap_or_default();
+                                let mut voice_activity = VoiceActivityDetector::new(
+                                    voice_activation_settings,
+                                    if audio_resampler.is_some() {
+                                        crate::audio::types::OPUS_SAMPLE_RATE
+                                    } else {
+                                        device_config.sample_rate
+                                    },
+                                );
+
                                 let process_fn = move |data: &[f32]| {
                                     let len = data.len();
 
@@ -320,8 +378,10 @@ impl InputStream {
 
                                     pcm_buffer[..len].copy_from_slice(data);
 
-                                    // If the noise gate is enabled, process data through it
-                                    if USE_NOISE_GATE.load(Ordering::Relaxed) {
+                                    let use_voice_activation = USE_VOICE_ACTIVATION.load(Ordering::Relaxed);
+
+                                    // If the noise gate is enabled, process data through it. Voice activation replaces it.
+                                    if USE_NOISE_GATE.load(Ordering::Relaxed) && !use_voice_activation {
                                         // If there is a pending update, apply it, then disable the lock check
                                         if UPDATE_NOISE_GATE_SETTINGS.load(Ordering::Relaxed) {
                                             let current_settings = NOISE_GATE_SETTINGS.lock().unwrap();
@@ -370,14 +430,55 @@ impl InputStream {
                                     };
 
                                     // Resample 44.1 kHz → 48 kHz if needed
-                                    let mono_pcm = if let Some(ref mut rs) = audio_resampler {
+                                    let mut mono_pcm = if let Some(ref mut rs) = audio_resampler {
                                         rs.process(&mono_pcm)
                                     } else {
                                         mono_pcm
                                     };
 
-                                    let is_silent = mono_pcm.iter().all(|&e| f32::abs(e) == 0.0);
                                     let is_muted = MUTE_INPUT_STREAM.load(Ordering::Relaxed);
+                                    let mut pre_roll = Vec::new();
+
+                                    if use_voice_activation {
+                                        if UPDATE_VOICE_ACTIVATION_SETTINGS.load(Ordering::Relaxed) {
+                                            let current_settings = VOICE_ACTIVATION_SETTINGS.lock().unwrap();
+                                            match serde_json::from_value::<VoiceActivationSettings>(
+                                                current_settings.clone(),
+                                            ) {
+                                                Ok(settings) => {
+                                                    log::info!(
+                                                        "Updating voice activation settings: {:?}",
+                                                        settings
+                                                    );
+                                                    voice_activity.update(settings);
+                                                }
+                                                Err(e) => {
+                                                    warn!("Voice activation settings were asked to update, but failed to deserialize: {}", e);
+                                                }
+                                            };
+                                            drop(current_settings);
+
+                                            UPDATE_VOICE_ACTIVATION_SETTINGS.store(false, Ordering::Relaxed);
+                                        }
+
+                                        if is_muted {
+                                            voice_activity.reset();
+                                        } else {
+                                            let captured_at_ms = std::time::SystemTime::now()
+                                                .duration_since(std::time::UNIX_EPOCH)
+                                                .unwrap_or_default()
+                                                .as_millis() as u64;
+
+                                            match voice_activity.process(&mono_pcm, captured_at_ms) {
+                                                VoiceActivity::Opened(frames) => pre_roll = frames,
+                                                VoiceActivity::Active => {}
+                                                // Closed like a gate, so the trailing silence below ends the stream
+                                                VoiceActivity::Inactive => mono_pcm.fill(0.0),
+                                            }
+                                        }
+                                    }
+
+                                    let is_silent = mono_pcm.iter().all(|&e| f32::abs(e) == 0.0);
 
                                     if is_muted {
                                         // Hard mute: reset state, send nothing
@@ -386,6 +487,14 @@ impl InputStream {
                                         // Gate is open — real audio, reset counter, send frame
                                         consecutive_silent_frames = 0;
 
+                                        // Voice activation just opened: send the audio from before speech was detected first
+                                        for frame in pre_roll {
+                                            let _ = producer.try_send(AudioFrame::F32(AudioFrameData {
+                                                pcm: frame.pcm,
+                                                captured_at_ms: frame.captured_at_ms,
+                                            }));
+                                        }
+
                                         let captured_at_ms = std::time::SystemTime::now()
                                             .duration_since(std::time::UNIX_EPOCH)
                                             .unwrap_or_default()
@@ -653,6 +762,15 @@ impl InputStream {
                         let bus = self.bus.clone();
                         let recording_producer = self.recording_producer.clone();
 
+                        // Report the local player's own speaking activity
+                        let mut activity_detector = ActivityDetector::new(
+                            current_player_name.clone(),
+                            device_config.sample_rate,
+                            self.app_handle
+                                .try_state::<SpeakingMonitor>()
+                                .map(|monitor| monitor.sender()),
+                        );
+
                         let handle = tokio::spawn(async move {
                             #[cfg(target_os = "windows")]
                             {
@@ -689,6 +807,8 @@ impl InputStream {
                                         .drain(0..BUFFER_SIZE as usize)
                                         .collect();
 
+                                    activity_detector.analyze_samples(&sample_to_process);
+
                                     let encoded_data = match encoder.encode_vec_float(
                                         &sample_to_process,
                                         sample_to_process.len() * 4,
@@ -735,7 +855,9 @@ impl InputStream {
                                                 encoded_data.clone(),
                                                 device_config.sample_rate,
                                                 None,
-                                                None
+                                                WHISPER_TO_CHANNEL
+                                                    .load(Ordering::Relaxed)
+                                                    .then_some(false)
                                             ))
                                         }
                                     };
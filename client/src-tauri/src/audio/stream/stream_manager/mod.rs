@@ -2,6 +2,7 @@ mod audio_sink;
 mod input;
 mod mono_to_panned;
 mod output;
+mod presence;
 mod resampler;
 mod sink_manager;
 
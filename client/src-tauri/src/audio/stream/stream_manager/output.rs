@@ -1,3 +1,4 @@
+use super::presence::ServerPresence;
 use super::sink_manager::SinkManager;
 use crate::audio::stream::stream_manager::AudioSinkType;
 use crate::audio::stream::RecoverySender;
@@ -49,7 +50,7 @@ pub(crate) struct OutputStream {
     app_handle: tauri::AppHandle,
     sink_manager: Option<SinkManager>,
     playback_stream: Option<rodio::MixerDeviceSink>,
-    player_presence: Arc<moka::sync::Cache<String, Option<String>>>,
+    player_presence: ServerPresence,
     player_presence_debounce: Arc<moka::sync::Cache<String, ()>>,
     client_id_to_player: Arc<moka::sync::Cache<String, String>>,
     recording_producer: Option<Arc<RecordingProducer>>,
@@ -76,6 +77,14 @@ impl common::traits::StreamTrait for OutputStream {
                 }
                 let _ = self.metadata.insert(key.clone(), value.clone()).await;
             }
+            "output_volume" => {
+                if let Ok(volume) = value.parse::<f32>() {
+                    if let Some(sink_manager) = self.sink_manager.as_ref() {
+                        sink_manager.update_output_volume(volume);
+                    }
+                }
+                let _ = self.metadata.insert(key.clone(), value.clone()).await;
+            }
             "player_gain_store" => {
                 match serde_json::from_str::<PlayerGainStore>(&value) {
                     Ok(settings) => {
@@ -201,10 +210,6 @@ impl OutputStream {
             .time_to_idle(Duration::from_secs(15 * 60))
             .build();
 
-        let player_presence = moka::sync::Cache::builder()
-            .time_to_idle(Duration::from_secs(3 * 60))
-            .build();
-
         let player_presence_debounce = moka::sync::Cache::builder()
             .time_to_live(Duration::from_secs(3))
             .build();
@@ -227,7 +232,7 @@ impl OutputStream {
             app_handle: app_handle.clone(),
             sink_manager: None,
             playback_stream: None,
-            player_presence: Arc::new(player_presence),
+            player_presence: ServerPresence::new(),
             player_presence_debounce: Arc::new(player_presence_debounce),
             client_id_to_player: Arc::new(client_id_to_player),
             recording_producer,
@@ -272,7 +277,7 @@ impl OutputStream {
                                         PacketType::AudioFrame => {
                                             OutputStream::handle_audio_data(
                                                 producer.clone(),
-                                                &packet.data,
+                                                &packet,
                                                 metadata.clone(),
                                                 players.clone(),
                                                 player_gain_cache.clone(),
@@ -286,7 +291,7 @@ impl OutputStream {
                                         PacketType::PlayerData => {
                                             OutputStream::handle_player_data(
                                                 players.clone(),
-                                                &packet.data,
+                                                &packet,
                                             )
                                             .await
                                         }
@@ -299,7 +304,7 @@ impl OutputStream {
                                         }
                                         PacketType::PlayerPresence => {
                                             OutputStream::handle_player_presence(
-                                                &packet.data,
+                                                &packet,
                                                 metadata.clone(),
                                                 Some(&app_handle.clone()),
                                                 player_presence.clone(),
@@ -436,6 +441,14 @@ impl OutputStream {
                                 None => 0.8,
                             };
 
+                            let output_volume = match metadata
+                                .get("output_volume")
+                                .await
+                            {
+                                Some(val) => val.parse::<f32>().unwrap_or(1.0),
+                                None => 1.0,
+                            };
+
                             let sink_manager = SinkManager::new(
                                 consumer,
                                 (*players).clone(),
@@ -447,6 +460,7 @@ impl OutputStream {
                                 self.recording_active.clone(),
                                 spatial_config,
                                 panning_intensity,
+                                output_volume,
                             );
 
                             self.sink_manager = Some(sink_manager);
@@ -487,10 +501,10 @@ impl OutputStream {
 
     // Process the player presence event
     async fn handle_player_presence(
-        data: &QuicNetworkPacket,
+        packet: &AudioPacket,
         metadata: Arc<Cache<String, String>>,
         app_handle: Option<&tauri::AppHandle>,
-        player_presence: Arc<moka::sync::Cache<String, Option<String>>>,
+        player_presence: ServerPresence,
         player_presence_debounce: Arc<moka::sync::Cache<String, ()>>,
         player_data: Arc<moka::sync::Cache<String, PlayerEnum>>,
     ) {
@@ -500,7 +514,7 @@ impl OutputStream {
         };
 
         if let Some(app_handle) = app_handle {
-            let data: Result<PlayerPresenceEvent, ()> = data.data.to_owned().try_into();
+            let data: Result<PlayerPresenceEvent, ()> = packet.data.data.to_owned().try_into();
 
             match data {
                 Ok(data) => {
@@ -512,11 +526,11 @@ impl OutputStream {
                     let game = player_data
                         .get(&data.player_name)
                         .map(|p| p.get_game().as_str().to_string())
-                        .or_else(|| player_presence.get(&data.player_name).flatten());
+                        .or_else(|| player_presence.game(&data.player_name));
 
                     match data.event_type {
                         ConnectionEventType::Connected => {
-                            player_presence.insert(data.player_name.clone(), game.clone());
+                            player_presence.insert(&packet.server, &data.player_name, game.clone());
 
                             // Only emit if not recently debounced
                             if player_presence_debounce.get(&data.player_name).is_none() {
@@ -535,7 +549,10 @@ impl OutputStream {
                             }
                         }
                         ConnectionEventType::Disconnected => {
-                            player_presence.remove(&data.player_name);
+                            // Still connected through another server
+                            if !player_presence.remove(&packet.server, &data.player_name) {
+                                return;
+                            }
                             player_presence_debounce.remove(&data.player_name);
 
                             if let Err(e) = app_handle.emit(
@@ -608,11 +625,11 @@ impl OutputStream {
     /// Processes AudioFramePacket data
     async fn handle_audio_data(
         producer: flume::Sender<EncodedAudioFramePacket>,
-        data: &QuicNetworkPacket,
+        packet: &AudioPacket,
         metadata: Arc<Cache<String, String>>,
         players: Arc<moka::sync::Cache<String, PlayerEnum>>,
         player_gain_cache: Arc<moka::sync::Cache<String, PlayerGainSettings>>,
-        player_presence: Arc<moka::sync::Cache<String, Option<String>>>,
+        player_presence: ServerPresence,
         player_presence_debounce: Arc<moka::sync::Cache<String, ()>>,
         client_id_to_player: Arc<moka::sync::Cache<String, String>>,
         app_handle: Option<&tauri::AppHandle>,
@@ -622,6 +639,8 @@ impl OutputStream {
             None => return,
         };
 
+        let data = &packet.data;
+
         // Check if this is a new player we haven't seen before
         if let Some(owner) = &data.owner {
             let player_name = &owner.name;
@@ -638,10 +657,10 @@ impl OutputStream {
                 let game = players
                     .get(player_name)
                     .map(|p| p.get_game().as_str().to_string())
-                    .or_else(|| player_presence.get(player_name).flatten());
+                    .or_else(|| player_presence.game(player_name));
 
                 // Always update the presence cache (stores game type alongside presence)
-                player_presence.insert(player_name.clone(), game.clone());
+                player_presence.insert(&packet.server, player_name, game.clone());
 
                 // Only emit if not recently debounced
                 if player_presence_debounce.get(player_name).is_none() {
@@ -673,7 +692,7 @@ impl OutputStream {
         match data {
             Ok(data) => {
                 // Create emitter RecordingPlayerData from packet owner and audio data
-                let emitter = owner
+                let mut emitter = owner
                     .as_ref()
                     .map(|o| RecordingPlayerData::from_packet_owner(
                         o,
@@ -682,6 +701,13 @@ impl OutputStream {
                     ))
                     .unwrap_or_else(RecordingPlayerData::unknown);
 
+                // Positions from other servers are in another world than the listener's,
+                // so their players are heard without spatial audio
+                let spatial = packet.primary && data.spatial.unwrap_or(true);
+                if !spatial {
+                    emitter.spatial = Some(false);
+                }
+
                 // Create listener RecordingPlayerData from current player
                 let listener = players
                     .get(&current_player_name)
@@ -697,14 +723,13 @@ impl OutputStream {
                     timestamp: timestamp,
                     sample_rate: data.sample_rate,
                     data: data.data,
-                    route: AudioSinkType::from_spatial(match data.spatial {
-                        Some(s) => s,
-                        None => true,
-                    }),
+                    route: AudioSinkType::from_spatial(spatial),
                     emitter,
                     listener,
                     buffer_size_ms: 120,
                     time_between_reports_secs: 30,
+                    received_at: std::time::Instant::now(),
+                    server: packet.server.clone(),
                 };
 
                 // Send to playback - recording is now handled post-jitter-buffer in JitterBufferSource
@@ -724,11 +749,17 @@ impl OutputStream {
     // Sender<AudioFrame> is technically as alias of Sender<QuicNetworkPacket> with a nested data
     // The data we can receive can be _any_ valid QuicNetworkPacket, which is good because
     // We need the positional information that is pulsed by the server
+    // Only the current server's positions are kept, since coordinates from different game
+    // servers are not comparable
     async fn handle_player_data(
         player_data: Arc<moka::sync::Cache<String, PlayerEnum>>,
-        data: &QuicNetworkPacket,
+        packet: &AudioPacket,
     ) {
-        let data: Result<PlayerDataPacket, ()> = data.data.to_owned().try_into();
+        if !packet.primary {
+            return;
+        }
+
+        let data: Result<PlayerDataPacket, ()> = packet.data.data.to_owned().try_into();
         match data {
             Ok(data) => {
                 for player in data.players {
@@ -791,9 +822,50 @@ impl OutputStream {
 
     /// Returns the currently tracked players with their game type from the presence cache
     pub fn get_current_players(&self) -> std::collections::HashMap<String, Option<String>> {
-        self.player_presence
-            .iter()
-            .map(|(name, game)| ((*name).clone(), Option::clone(&game)))
-            .collect()
+        self.player_presence.players()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::structs::packet::QuicNetworkPacketData;
+
+    fn player_data(server: &str, primary: bool, x: f32) -> AudioPacket {
+        AudioPacket {
+            data: QuicNetworkPacket {
+                packet_type: PacketType::PlayerData,
+                owner: None,
+                data: QuicNetworkPacketData::PlayerData(PlayerDataPacket {
+                    players: vec![PlayerEnum::Generic(GenericPlayer {
+                        name: "Steve".to_string(),
+                        coordinates: Coordinate { x, y: 64.0, z: 0.0 },
+                        orientation: Orientation { x: 0.0, y: 0.0 },
+                        game: Game::Minecraft,
+                    })],
+                }),
+            },
+            server: server.to_string(),
+            primary,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_positions_only_from_current_server() {
+        let players = Arc::new(moka::sync::Cache::builder().build());
+
+        OutputStream::handle_player_data(
+            players.clone(),
+            &player_data("https://primary.example.com", true, 10.0),
+        )
+        .await;
+        OutputStream::handle_player_data(
+            players.clone(),
+            &player_data("https://secondary.example.com", false, 5000.0),
+        )
+        .await;
+
+        let steve = players.get("Steve").unwrap();
+        assert_eq!(steve.get_position().x, 10.0);
     }
 }
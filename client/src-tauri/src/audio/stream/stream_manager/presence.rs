@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Players heard on each connected server, with their game when known.
+/// A player on several servers stays present until every one of them reports them gone.
+#[derive(Clone)]
+pub(crate) struct ServerPresence {
+    players: Arc<moka::sync::Cache<(String, String), Option<String>>>,
+}
+
+impl ServerPresence {
+    pub fn new() -> Self {
+        Self {
+            players: Arc::new(
+                moka::sync::Cache::builder()
+                    .time_to_idle(Duration::from_secs(3 * 60))
+                    .build(),
+            ),
+        }
+    }
+
+    pub fn insert(&self, server: &str, player: &str, game: Option<String>) {
+        self.players
+            .insert((server.to_string(), player.to_string()), game);
+    }
+
+    /// Removes the player from one server. Returns true when no other server has them.
+    pub fn remove(&self, server: &str, player: &str) -> bool {
+        self.players
+            .invalidate(&(server.to_string(), player.to_string()));
+        !self.players.iter().any(|(key, _)| key.1 == player)
+    }
+
+    /// Game the player was last seen in on any server
+    pub fn game(&self, player: &str) -> Option<String> {
+        self.players
+            .iter()
+            .filter(|(key, _)| key.1 == player)
+            .find_map(|(_, game)| game)
+    }
+
+    /// Every present player once, with their game when any server knows it
+    pub fn players(&self) -> HashMap<String, Option<String>> {
+        let mut players: HashMap<String, Option<String>> = HashMap::new();
+        for (key, game) in self.players.iter() {
+            let entry = players.entry(key.1.clone()).or_default();
+            if entry.is_none() {
+                *entry = game;
+            }
+        }
+        players
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRIMARY: &str = "https://primary.example.com";
+    const SECONDARY: &str = "https://secondary.example.com";
+
+    #[test]
+    fn test_leaving_one_server_keeps_player_on_another() {
+        let presence = ServerPresence::new();
+        presence.insert(PRIMARY, "Steve", Some("minecraft".to_string()));
+        presence.insert(SECONDARY, "Steve", None);
+
+        assert!(!presence.remove(SECONDARY, "Steve"));
+        assert_eq!(
+            presence.players().get("Steve"),
+            Some(&Some("minecraft".to_string()))
+        );
+
+        assert!(presence.remove(PRIMARY, "Steve"));
+        assert!(presence.players().is_empty());
+    }
+
+    #[test]
+    fn test_game_from_any_server() {
+        let presence = ServerPresence::new();
+        presence.insert(PRIMARY, "Alex", None);
+        presence.insert(SECONDARY, "Alex", Some("hytale".to_string()));
+
+        assert_eq!(presence.game("Alex"), Some("hytale".to_string()));
+        assert_eq!(presence.players().len(), 1);
+        assert_eq!(presence.game("Steve"), None);
+    }
+}
@@ -7,7 +7,7 @@ use log::{info, warn};
 use moka::sync::Cache;
 use std::num::NonZero;
 use rodio::{mixer::Mixer, Player, Source};
-use tauri::Emitter;
+use tauri::{Emitter, Manager};
 use tokio::task::JoinHandle;
 
 use crate::audio::recording::RecordingProducer;
@@ -16,7 +16,8 @@ use crate::audio::stream::jitter_buffer::{
 };
 use crate::audio::stream::stream_manager::audio_sink::AudioSink;
 use crate::audio::stream::stream_manager::mono_to_panned::MonoToPanned;
-use crate::audio::stream::ActivityUpdate;
+use crate::audio::stream::{ActivityUpdate, SpeakingMonitor};
+use crate::network::diagnostics::{DiagnosticsCollector, DiagnosticsReporter};
 use common::structs::audio::{PlayerGainSettings, PlayerGainStore};
 use common::structs::SpatialAudioConfig;
 use common::PlayerEnum;
@@ -95,12 +96,6 @@ where
     }
 }
 
-/// Convert a linear slider position (0.0-1.5) to a perceptually-correct amplitude factor.
-/// Uses a power curve (x^2.5) so equal slider increments produce roughly equal loudness changes.
-fn perceptual_gain(linear_position: f32) -> f32 {
-    linear_position.powf(2.5)
-}
-
 #[derive(Clone, Default)]
 struct PlayerSinks {
     normal: Option<Arc<AudioSink>>,
@@ -115,10 +110,12 @@ pub struct SinkManager {
     shutdown: Arc<AtomicBool>,
     global_mute: Arc<AtomicBool>,
     panning_intensity: Arc<AtomicU32>,
+    output_volume: Arc<AtomicU32>,
     players: Cache<String, PlayerEnum>,
     current_player_name: String,
     player_gain_store: Arc<StdMutex<PlayerGainStore>>,
-    sinks: Cache<Vec<u8>, PlayerSinks>,
+    /// Keyed by server and client id, so a player heard through two servers gets two buffers
+    sinks: Cache<(String, Vec<u8>), PlayerSinks>,
     mixer: Arc<Mixer>,
     activity_tx: Option<flume::Sender<ActivityUpdate>>,
     #[allow(unused)]
@@ -126,6 +123,7 @@ pub struct SinkManager {
     recording_producer: Option<RecordingProducer>,
     recording_active: Option<Arc<AtomicBool>>,
     spatial_config: SpatialAudioConfig,
+    diagnostics: Option<DiagnosticsReporter>,
 }
 
 impl SinkManager {
@@ -140,10 +138,20 @@ impl SinkManager {
         recording_active: Option<Arc<AtomicBool>>,
         spatial_config: SpatialAudioConfig,
         panning_intensity: f32,
+        output_volume: f32,
     ) -> Self {
         // Create activity streaming channel
         let (activity_tx, activity_rx) = flume::unbounded::<ActivityUpdate>();
 
+        // Speaking detection sees every update, not just the batched levels
+        let speaking_tx = app_handle
+            .try_state::<SpeakingMonitor>()
+            .map(|monitor| monitor.sender());
+
+        let diagnostics = app_handle
+            .try_state::<DiagnosticsCollector>()
+            .map(|collector| collector.reporter());
+
         // Spawn activity streaming task
         let app_handle_clone = app_handle.clone();
         tokio::spawn(async move {
@@ -155,6 +163,9 @@ impl SinkManager {
                     // Collect activity updates
                     Ok(update) = activity_rx.recv_async() => {
                         current_activities.insert(update.player_name.clone(), update.rms_level);
+                        if let Some(tx) = &speaking_tx {
+                            let _ = tx.try_send(update);
+                        }
                     }
 
                     // Batch and stream every 100ms
@@ -175,6 +186,7 @@ impl SinkManager {
             shutdown: Arc::new(AtomicBool::new(false)),
             global_mute: Arc::new(AtomicBool::new(false)),
             panning_intensity: Arc::new(AtomicU32::new(panning_intensity.clamp(0.0, 1.0).to_bits())),
+            output_volume: Arc::new(AtomicU32::new(output_volume.clamp(0.0, 1.0).to_bits())),
             players,
             current_player_name,
             player_gain_store,
@@ -188,6 +200,7 @@ impl SinkManager {
             recording_producer,
             recording_active,
             spatial_config,
+            diagnostics,
         }
     }
 
@@ -206,6 +219,12 @@ impl SinkManager {
             .store(intensity.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
     }
 
+    /// Master volume applied on top of every player's gain
+    pub fn update_output_volume(&self, volume: f32) {
+        self.output_volume
+            .store(volume.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
     pub async fn listen(&mut self) -> Result<JoinHandle<()>, anyhow::Error> {
         _ = self.shutdown.store(false, Ordering::Relaxed);
 
@@ -221,10 +240,12 @@ impl SinkManager {
         let mixer = self.mixer.clone();
         let global_mute = self.global_mute.clone();
         let panning_intensity = self.panning_intensity.clone();
+        let output_volume = self.output_volume.clone();
         let activity_tx = self.activity_tx.clone();
         let recording_producer = self.recording_producer.clone();
         let recording_active = self.recording_active.clone();
         let spatial_config = self.spatial_config.clone();
+        let diagnostics = self.diagnostics.clone();
 
         // Spawn an async task; use async recv to avoid blocking
         let handle = tokio::spawn(async move {
@@ -234,7 +255,7 @@ impl SinkManager {
                 }
 
                 let author = packet.get_author();
-                let author_bytes = packet.get_client_id();
+                let sink_key = (packet.server.clone(), packet.get_client_id());
 
                 let display_name = match packet.emitter.name.clone() {
                     name if !name.is_empty() && name != "api" => name,
@@ -281,12 +302,12 @@ impl SinkManager {
                     continue;
                 }
 
-                let mut bundle = sinks.get(&author_bytes).unwrap_or_else(|| {
+                let mut bundle = sinks.get(&sink_key).unwrap_or_else(|| {
                     let b = PlayerSinks::default();
-                    if let Some(existing) = sinks.get(&author_bytes) {
+                    if let Some(existing) = sinks.get(&sink_key) {
                         existing
                     } else {
-                        sinks.insert(author_bytes.clone(), b.clone());
+                        sinks.insert(sink_key.clone(), b.clone());
                         b
                     }
                 });
@@ -325,7 +346,8 @@ impl SinkManager {
                             1.0
                         };
                         let volume = spatial_data.volume
-                            * perceptual_gain(gain_settings.gain)
+                            * gain_settings.amplitude()
+                            * f32::from_bits(output_volume.load(Ordering::Relaxed))
                             * mute_mult;
 
                         let intensity = f32::from_bits(panning_intensity.load(Ordering::Relaxed));
@@ -343,6 +365,7 @@ impl SinkManager {
                             activity_tx.clone(),
                             recording_producer.clone(),
                             recording_active.clone(),
+                            diagnostics.clone(),
                         ) {
                             Ok((jitter_buffer, handle)) => {
                                 if let (Some(spatial_sink), Some(pan_state)) =
@@ -379,7 +402,10 @@ impl SinkManager {
                         } else {
                             1.0
                         };
-                        let volume = 1.3 * perceptual_gain(gain_settings.gain) * mute_mult;
+                        let volume = 1.3
+                            * gain_settings.amplitude()
+                            * f32::from_bits(output_volume.load(Ordering::Relaxed))
+                            * mute_mult;
                         normal_sink.set_volume(volume);
                     }
 
@@ -391,6 +417,7 @@ impl SinkManager {
                             activity_tx.clone(),
                             recording_producer.clone(),
                             recording_active.clone(),
+                            diagnostics.clone(),
                         ) {
                             Ok((jitter_buffer, handle)) => {
                                 if let Some(normal_sink) = &bundle.normal {
@@ -412,7 +439,7 @@ impl SinkManager {
                     }
                 }
 
-                sinks.insert(author_bytes.clone(), bundle);
+                sinks.insert(sink_key, bundle);
             }
         });
 
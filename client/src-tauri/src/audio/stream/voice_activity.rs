@@ -0,0 +1,300 @@
+use common::structs::audio::VoiceActivationSettings;
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+/// Lowest noise floor tracked, so digital silence doesn't make every sound look like speech
+const MIN_NOISE_FLOOR_DB: f32 = -70.0;
+
+/// The noise floor is the quietest level over this many blocks. Pauses between words keep
+/// it at the background level while speaking, and a new steady noise becomes the floor once
+/// it has lasted the whole window.
+const NOISE_FLOOR_BLOCK_MS: f32 = 500.0;
+const NOISE_FLOOR_BLOCKS: usize = 8;
+
+/// Frequencies carrying most speech energy; breathing and rumble fall largely outside them
+const SPEECH_BAND_LOW_HZ: f32 = 300.0;
+const SPEECH_BAND_HIGH_HZ: f32 = 3400.0;
+
+/// Share of a frame's energy that must be in the speech band for it to count as speech
+const MIN_SPEECH_BAND_RATIO: f32 = 0.4;
+
+/// How long speech must be heard before the microphone opens. Pre-roll covers the delay.
+const ATTACK_MS: f32 = 30.0;
+
+/// Audio captured by the input stream, held back while the detector decides
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct VoiceFrame {
+    pub pcm: Vec<f32>,
+    pub captured_at_ms: u64,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum VoiceActivity {
+    /// Speech started. Holds the pre-roll, oldest first, to send before the current frame.
+    Opened(Vec<VoiceFrame>),
+    /// Speaking, or within the hang time after speech
+    Active,
+    /// No speech; the frame was kept as pre-roll
+    Inactive,
+}
+
+/// Single pole low-pass filter
+#[derive(Debug)]
+struct OnePole {
+    coefficient: f32,
+    state: f32,
+}
+
+impl OnePole {
+    fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        Self {
+            coefficient: 1.0 - (-2.0 * PI * cutoff_hz / sample_rate as f32).exp(),
+            state: 0.0,
+        }
+    }
+
+    fn process(&mut self, sample: f32) -> f32 {
+        self.state += self.coefficient * (sample - self.state);
+        self.state
+    }
+}
+
+/// Speech detector for the voice activation mode. Opens on speech band energy well above a
+/// tracked noise floor, stays open until the level drops below a lower threshold for the
+/// hang time, and buffers recent audio so the start of the first word is still sent.
+pub(crate) struct VoiceActivityDetector {
+    settings: VoiceActivationSettings,
+    sample_rate: u32,
+    floor_blocks: VecDeque<f32>,
+    block_min_db: f32,
+    block_ms: f32,
+    active: bool,
+    speech_ms: f32,
+    quiet_ms: f32,
+    band_high_pass: OnePole,
+    band_low_pass: OnePole,
+    pre_roll: VecDeque<VoiceFrame>,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(settings: VoiceActivationSettings, sample_rate: u32) -> Self {
+        Self {
+            settings,
+            sample_rate,
+            floor_blocks: VecDeque::with_capacity(NOISE_FLOOR_BLOCKS),
+            block_min_db: f32::INFINITY,
+            block_ms: 0.0,
+            active: false,
+            speech_ms: 0.0,
+            quiet_ms: 0.0,
+            band_high_pass: OnePole::new(SPEECH_BAND_LOW_HZ, sample_rate),
+            band_low_pass: OnePole::new(SPEECH_BAND_HIGH_HZ, sample_rate),
+            pre_roll: VecDeque::new(),
+        }
+    }
+
+    pub fn update(&mut self, settings: VoiceActivationSettings) {
+        self.settings = settings;
+        self.trim_pre_roll();
+    }
+
+    /// Forget any open state and buffered audio, e.g. after the microphone was muted
+    pub fn reset(&mut self) {
+        self.active = false;
+        self.speech_ms = 0.0;
+        self.quiet_ms = 0.0;
+        self.pre_roll.clear();
+    }
+
+    pub fn process(&mut self, pcm: &[f32], captured_at_ms: u64) -> VoiceActivity {
+        if pcm.is_empty() {
+            return if self.active {
+                VoiceActivity::Active
+            } else {
+                VoiceActivity::Inactive
+            };
+        }
+
+        let frame_ms = pcm.len() as f32 * 1000.0 / self.sample_rate as f32;
+        let (band_db, band_ratio) = self.measure(pcm);
+        self.track_noise_floor(band_db, frame_ms);
+        let above_floor = band_db - self.noise_floor_db();
+
+        if self.active {
+            if above_floor >= self.settings.close_threshold {
+                self.quiet_ms = 0.0;
+            } else {
+                self.quiet_ms += frame_ms;
+            }
+
+            if self.quiet_ms < self.settings.hang_time {
+                return VoiceActivity::Active;
+            }
+
+            self.reset();
+        } else if above_floor >= self.settings.open_threshold && band_ratio >= MIN_SPEECH_BAND_RATIO
+        {
+            self.speech_ms += frame_ms;
+            if self.speech_ms >= ATTACK_MS {
+                self.active = true;
+                self.quiet_ms = 0.0;
+                return VoiceActivity::Opened(self.pre_roll.drain(..).collect());
+            }
+        } else {
+            self.speech_ms = 0.0;
+        }
+
+        self.pre_roll.push_back(VoiceFrame {
+            pcm: pcm.to_vec(),
+            captured_at_ms,
+        });
+        self.trim_pre_roll();
+
+        VoiceActivity::Inactive
+    }
+
+    /// Speech band level in dBFS and the share of the frame's energy in the speech band
+    fn measure(&mut self, pcm: &[f32]) -> (f32, f32) {
+        let mut total_energy = 0.0f32;
+        let mut band_energy = 0.0f32;
+
+        for &sample in pcm {
+            let high_passed = sample - self.band_high_pass.process(sample);
+            let band = self.band_low_pass.process(high_passed);
+            total_energy += sample * sample;
+            band_energy += band * band;
+        }
+
+        let band_power = band_energy / pcm.len() as f32;
+        let band_db = 10.0 * (band_power + 1e-12).log10();
+        let band_ratio = band_energy / (total_energy + 1e-12);
+        (band_db, band_ratio)
+    }
+
+    fn track_noise_floor(&mut self, level_db: f32, frame_ms: f32) {
+        self.block_min_db = self.block_min_db.min(level_db);
+        self.block_ms += frame_ms;
+
+        if self.block_ms >= NOISE_FLOOR_BLOCK_MS {
+            if self.floor_blocks.len() == NOISE_FLOOR_BLOCKS {
+                self.floor_blocks.pop_front();
+            }
+            self.floor_blocks.push_back(self.block_min_db);
+            self.block_min_db = f32::INFINITY;
+            self.block_ms = 0.0;
+        }
+    }
+
+    fn noise_floor_db(&self) -> f32 {
+        self.floor_blocks
+            .iter()
+            .copied()
+            .fold(self.block_min_db, f32::min)
+            .max(MIN_NOISE_FLOOR_DB)
+    }
+
+    fn trim_pre_roll(&mut self) {
+        let max_samples =
+            (self.settings.pre_roll.max(0.0) * self.sample_rate as f32 / 1000.0) as usize;
+        let mut buffered: usize = self.pre_roll.iter().map(|frame| frame.pcm.len()).sum();
+        while buffered > max_samples {
+            match self.pre_roll.pop_front() {
+                Some(frame) => buffered -= frame.pcm.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+    const FRAME: usize = 480; // 10ms
+
+    fn tone(frequency: f32, amplitude: f32, offset: usize) -> Vec<f32> {
+        (0..FRAME)
+            .map(|i| {
+                let t = (offset + i) as f32 / SAMPLE_RATE as f32;
+                amplitude * (2.0 * PI * frequency * t).sin()
+            })
+            .collect()
+    }
+
+    fn quiet(offset: usize) -> Vec<f32> {
+        tone(1000.0, 0.0005, offset)
+    }
+
+    #[test]
+    fn test_opens_with_pre_roll_and_closes_after_hang_time() {
+        let mut vad = VoiceActivityDetector::new(VoiceActivationSettings::default(), SAMPLE_RATE);
+
+        // Settle on the background level
+        for n in 0..100 {
+            assert_eq!(
+                vad.process(&quiet(n * FRAME), n as u64),
+                VoiceActivity::Inactive
+            );
+        }
+
+        // Speech is confirmed after the attack time, and everything buffered so far is released
+        let mut opened = None;
+        for n in 100..110 {
+            match vad.process(&tone(1000.0, 0.2, n * FRAME), n as u64) {
+                VoiceActivity::Opened(pre_roll) => {
+                    opened = Some(pre_roll);
+                    break;
+                }
+                activity => assert_eq!(activity, VoiceActivity::Inactive),
+            }
+        }
+        let pre_roll = opened.expect("speech should open the detector");
+        assert_eq!(pre_roll.len(), 20); // 200ms of 10ms frames
+        assert_eq!(pre_roll.last().unwrap().captured_at_ms, 101);
+
+        // Short pauses stay open, the hang time closes
+        for n in 110..140 {
+            assert_eq!(
+                vad.process(&quiet(n * FRAME), n as u64),
+                VoiceActivity::Active
+            );
+        }
+        let mut closed = false;
+        for n in 140..160 {
+            if vad.process(&quiet(n * FRAME), n as u64) == VoiceActivity::Inactive {
+                closed = true;
+                break;
+            }
+        }
+        assert!(closed);
+    }
+
+    #[test]
+    fn test_rumble_does_not_open() {
+        let mut vad = VoiceActivityDetector::new(VoiceActivationSettings::default(), SAMPLE_RATE);
+
+        for n in 0..200 {
+            assert_eq!(
+                vad.process(&tone(40.0, 0.3, n * FRAME), n as u64),
+                VoiceActivity::Inactive
+            );
+        }
+    }
+
+    #[test]
+    fn test_steady_background_becomes_noise_floor() {
+        let mut vad = VoiceActivityDetector::new(VoiceActivationSettings::default(), SAMPLE_RATE);
+        for n in 0..100 {
+            vad.process(&quiet(n * FRAME), n as u64);
+        }
+
+        // A fan switching on opens the microphone, but not for longer than the floor window
+        let mut activity = Vec::new();
+        for n in 100..1100 {
+            activity.push(vad.process(&tone(1000.0, 0.02, n * FRAME), n as u64));
+        }
+        assert!(matches!(activity[2], VoiceActivity::Opened(_)));
+        assert_eq!(activity.last(), Some(&VoiceActivity::Inactive));
+    }
+}
@@ -0,0 +1,107 @@
+use bvc_client_lib::audio::recording::renderer::segment::{segment_files, WalSegment};
+use clap::Parser;
+use common::structs::recording::RecordingHeader;
+use std::process::exit;
+
+use super::Config as StateConfig;
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Inspect the WAL segments of a session", long_about = None)]
+pub struct Config {
+    /// Session id or path to a session directory
+    pub session: String,
+
+    /// Only inspect this player's segments
+    #[clap(short, long)]
+    pub player: Option<String>,
+
+    /// Print every entry, not just the segment summaries
+    #[clap(short, long)]
+    pub entries: bool,
+
+    /// Maximum number of entries to print per segment
+    #[clap(short, long)]
+    pub limit: Option<usize>,
+
+    /// Print entries as JSON lines, including the full RecordingHeader
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let session = cfg.extracted_session(&self.session);
+        let wal_path = session.path().join("wal");
+        let files = match segment_files(&wal_path, self.player.as_deref()) {
+            Ok(files) => files,
+            Err(e) => {
+                eprintln!("Failed to read {:?}: {}", wal_path, e);
+                exit(1);
+            }
+        };
+
+        for file_path in files {
+            let segment = match WalSegment::read(&file_path) {
+                Ok(segment) => segment,
+                Err(e) => {
+                    eprintln!("Failed to read {:?}: {}", file_path, e);
+                    continue;
+                }
+            };
+            let file_name = file_path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            if !self.json {
+                println!(
+                    "{}: {} entries, {} undecodable, {} of {} bytes valid{}",
+                    file_name,
+                    segment.entries.len(),
+                    segment.undecodable,
+                    segment.valid_len,
+                    segment.file_len,
+                    segment
+                        .stop_reason
+                        .as_ref()
+                        .map(|r| format!(" ({})", r))
+                        .unwrap_or_default()
+                );
+            }
+
+            if !self.entries && !self.json {
+                continue;
+            }
+
+            let limit = self.limit.unwrap_or(usize::MAX);
+            for (index, entry) in segment.entries.iter().take(limit).enumerate() {
+                if self.json {
+                    let line = serde_json::json!({
+                        "segment": file_name,
+                        "index": index,
+                        "relative_timestamp_ms": entry.relative_timestamp_ms,
+                        "opus_bytes": entry.opus_data.len(),
+                        "header": entry.header,
+                    });
+                    println!("{}", line);
+                    continue;
+                }
+
+                let kind = match &entry.header {
+                    RecordingHeader::Input(_) => "input",
+                    RecordingHeader::Output(_) => "output",
+                };
+                println!(
+                    "  #{:<6} {:>9}ms  {:<6} {}Hz/{}ch  spatial={}  {} bytes",
+                    index,
+                    entry.relative_timestamp_ms,
+                    kind,
+                    entry.header.sample_rate(),
+                    entry.header.channels(),
+                    entry.header.is_spatial(),
+                    entry.opus_data.len()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use bvc_client_lib::audio::recording::session::list_sessions;
+use chrono::{Local, TimeZone};
+use clap::Parser;
+use std::process::exit;
+
+use super::Config as StateConfig;
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "List recording sessions", long_about = None)]
+pub struct Config {
+    /// Print the manifests as JSON
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let recordings_dir = cfg.recordings_dir();
+        let sessions = match list_sessions(&recordings_dir) {
+            Ok(sessions) => sessions,
+            Err(e) => {
+                eprintln!("Failed to read {:?}: {}", recordings_dir, e);
+                exit(1);
+            }
+        };
+
+        if self.json {
+            let manifests: Vec<_> = sessions.iter().map(|s| &s.manifest).collect();
+            match serde_json::to_string_pretty(&manifests) {
+                Ok(json) => println!("{}", json),
+                Err(e) => {
+                    eprintln!("Failed to serialize sessions: {}", e);
+                    exit(1);
+                }
+            }
+            return;
+        }
+
+        if sessions.is_empty() {
+            println!("No recording sessions in {:?}", recordings_dir);
+            return;
+        }
+
+        for session in sessions {
+            let manifest = session.manifest;
+            let started = Local
+                .timestamp_millis_opt(manifest.start_timestamp as i64)
+                .single()
+                .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+                .unwrap_or_else(|| manifest.start_timestamp.to_string());
+            let duration = match manifest.duration_ms {
+                Some(ms) => format!("{}s", ms / 1000),
+                None => "incomplete".to_string(),
+            };
+
+            println!(
+                "{}  {}  {:>10}  {}  (+{} participants){}",
+                manifest.session_id,
+                started,
+                duration,
+                manifest.emitter_player,
+                manifest.participants.len(),
+                if manifest.recovered { "  recovered" } else { "" }
+            );
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use bvc_client_lib::audio::recording::session::read_manifest;
+use clap::Parser;
+use std::process::exit;
+
+use super::Config as StateConfig;
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Print a session manifest", long_about = None)]
+pub struct Config {
+    /// Session id or path to a session directory
+    pub session: String,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let session_path = cfg.session_path(&self.session);
+        let manifest = match read_manifest(&session_path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                eprintln!("Failed to read manifest for {:?}: {}", session_path, e);
+                exit(1);
+            }
+        };
+
+        match serde_json::to_string_pretty(&manifest) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Failed to serialize manifest: {}", e);
+                exit(1);
+            }
+        }
+    }
+}
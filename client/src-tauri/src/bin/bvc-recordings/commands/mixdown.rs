@@ -0,0 +1,119 @@
+use bvc_client_lib::audio::recording::renderer::{
+    mixdown::{MixdownOptions, Perspective},
+    AudioFormatRenderer,
+};
+use clap::Parser;
+use common::structs::AudioFormat;
+use common::{Coordinate, Game, Orientation};
+use std::path::PathBuf;
+use std::process::exit;
+
+use super::{parse_format, Config as StateConfig};
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Render a session into a single stereo mix", long_about = None)]
+pub struct Config {
+    /// Session id or path to a session directory
+    pub session: String,
+
+    /// Player to include; repeat for several. Every participant is mixed when omitted
+    #[clap(short, long)]
+    pub player: Vec<String>,
+
+    /// Position each participant relative to the recording player
+    #[clap(short, long)]
+    pub spatial: bool,
+
+    /// Render the spatial mix as this participant heard it. Implies --spatial
+    #[clap(short, long, conflicts_with = "camera")]
+    pub listener: Option<String>,
+
+    /// Render the spatial mix from a fixed camera at `x,y,z[,yaw[,pitch]]`. Implies --spatial
+    #[clap(long, value_parser = parse_camera, allow_hyphen_values = true)]
+    pub camera: Option<(Coordinate, Orientation)>,
+
+    /// Game whose axes the camera orientation follows: minecraft or hytale
+    #[clap(long, value_parser = parse_game, default_value = "minecraft")]
+    pub game: Game,
+
+    /// Output format: wav or m4a
+    #[clap(short, long, value_parser = parse_format, default_value = "wav")]
+    pub format: AudioFormat,
+
+    /// Output file. Defaults to `renders/mixdown.<ext>` inside the session
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let session = cfg.extracted_session(&self.session);
+        let output_path = match &self.output {
+            Some(path) => path.clone(),
+            None => cfg
+                .session_path(&self.session)
+                .join("renders")
+                .join(format!("mixdown.{}", self.format.extension())),
+        };
+
+        if let Some(parent) = output_path.parent()
+            && let Err(e) = std::fs::create_dir_all(parent)
+        {
+            eprintln!("Failed to create {:?}: {}", parent, e);
+            exit(1);
+        }
+
+        let perspective = match (&self.listener, &self.camera) {
+            (Some(name), _) => Perspective::Participant { name: name.clone() },
+            (None, Some((position, orientation))) => Perspective::Camera {
+                position: position.clone(),
+                orientation: orientation.clone(),
+                game: self.game.clone(),
+            },
+            (None, None) => Perspective::Recorder,
+        };
+
+        let options = MixdownOptions {
+            players: self.player.clone(),
+            spatial: self.spatial || perspective != Perspective::Recorder,
+            perspective,
+            ..Default::default()
+        };
+
+        match self.format.render_mixdown(session.path(), &output_path, options).await {
+            Ok(()) => println!("Rendered mixdown to {:?}", output_path),
+            Err(e) => {
+                eprintln!("Failed to render mixdown: {}", e);
+                exit(1);
+            }
+        }
+    }
+}
+
+/// Parses `x,y,z` with an optional yaw and pitch in degrees
+fn parse_camera(value: &str) -> Result<(Coordinate, Orientation), String> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<f32>())
+        .collect::<Result<Vec<f32>, _>>()
+        .map_err(|e| format!("invalid camera '{}': {}", value, e))?;
+
+    match parts.as_slice() {
+        [x, y, z, rest @ ..] if rest.len() <= 2 => Ok((
+            Coordinate { x: *x, y: *y, z: *z },
+            Orientation {
+                x: rest.get(1).copied().unwrap_or(0.0),
+                y: rest.first().copied().unwrap_or(0.0),
+            },
+        )),
+        _ => Err(format!("invalid camera '{}' (expected x,y,z[,yaw[,pitch]])", value)),
+    }
+}
+
+fn parse_game(value: &str) -> Result<Game, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "minecraft" => Ok(Game::Minecraft),
+        "hytale" => Ok(Game::Hytale),
+        _ => Err(format!("unknown game '{}' (expected minecraft or hytale)", value)),
+    }
+}
@@ -0,0 +1,154 @@
+use bvc_client_lib::audio::recording::retention::{
+    compress_session, decompress_session, extract_session, ExtractedSession, WAL_ARCHIVE,
+};
+use clap::Parser;
+use common::structs::AudioFormat;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+
+mod inspect;
+mod list;
+mod manifest;
+mod mixdown;
+mod render;
+mod repair;
+mod validate;
+
+/// Tauri bundle identifier, which names the client's local data directory
+const APP_IDENTIFIER: &str = "com.alaydriem.bvc.client";
+
+#[derive(clap::Subcommand, Debug, Clone)]
+pub enum SubCommand {
+    /// Lists the sessions in the recordings directory
+    List(list::Config),
+    /// Prints a session's manifest
+    Manifest(manifest::Config),
+    /// Prints the WAL segments and entries of a session
+    Inspect(inspect::Config),
+    /// Renders one, several or every player of a session
+    Render(render::Config),
+    /// Renders the selected players into a single stereo mix
+    Mixdown(mixdown::Config),
+    /// Checks a session's manifest and WAL segments
+    Validate(validate::Config),
+    /// Truncates torn WAL segments and rebuilds the manifest
+    Repair(repair::Config),
+}
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Bedrock Voice Chat recording tools", long_about = None)]
+pub struct Config {
+    /// Directory containing recording sessions. Defaults to the client's recordings directory
+    #[clap(global = true, short, long, value_parser, required = false)]
+    pub recordings_dir: Option<PathBuf>,
+
+    /// Command to execute
+    #[clap(subcommand)]
+    pub cmd: SubCommand,
+}
+
+pub async fn launch() {
+    let cfg = Config::parse();
+
+    match &cfg.cmd {
+        SubCommand::List(command) => command.run(&cfg).await,
+        SubCommand::Manifest(command) => command.run(&cfg).await,
+        SubCommand::Inspect(command) => command.run(&cfg).await,
+        SubCommand::Render(command) => command.run(&cfg).await,
+        SubCommand::Mixdown(command) => command.run(&cfg).await,
+        SubCommand::Validate(command) => command.run(&cfg).await,
+        SubCommand::Repair(command) => command.run(&cfg).await,
+    }
+}
+
+impl Config {
+    /// The recordings directory, falling back to the client's app local data directory
+    pub fn recordings_dir(&self) -> PathBuf {
+        if let Some(dir) = &self.recordings_dir {
+            return dir.clone();
+        }
+
+        match default_data_dir() {
+            Some(dir) => dir.join(APP_IDENTIFIER).join("recordings"),
+            None => {
+                eprintln!("Unable to determine the recordings directory; pass --recordings-dir");
+                exit(1);
+            }
+        }
+    }
+
+    /// Resolves a session argument, which is either a session directory or a session id
+    pub fn session_path(&self, session: &str) -> PathBuf {
+        let mut path = PathBuf::from(session);
+        if !(path.join("wal").is_dir() || path.join("session.json").is_file()) {
+            path = self.recordings_dir().join(session);
+            if !path.is_dir() {
+                eprintln!("Recording session '{}' not found", session);
+                exit(1);
+            }
+        }
+
+        path
+    }
+
+    /// Resolves a session whose WAL is read. Sessions compressed by the retention policy are
+    /// extracted to a temporary directory and stay compressed.
+    pub fn extracted_session(&self, session: &str) -> ExtractedSession {
+        let path = self.session_path(session);
+        match extract_session(&path) {
+            Ok(extracted) => extracted,
+            Err(e) => {
+                eprintln!("Failed to extract {:?}: {}", path, e);
+                exit(1);
+            }
+        }
+    }
+}
+
+/// Decompresses a session in place for commands that modify its WAL. Returns true when it was
+/// compressed, so it can be compressed again afterwards.
+fn decompress(session_path: &Path) -> bool {
+    let compressed = session_path.join(WAL_ARCHIVE).is_file();
+    if let Err(e) = decompress_session(session_path) {
+        eprintln!("Failed to decompress {:?}: {}", session_path, e);
+        exit(1);
+    }
+    compressed
+}
+
+fn compress(session_path: &Path) {
+    if let Err(e) = compress_session(session_path) {
+        eprintln!("Failed to compress {:?}: {}", session_path, e);
+        exit(1);
+    }
+}
+
+/// Mirrors the platform directory Tauri resolves for `app_local_data_dir`
+fn default_data_dir() -> Option<PathBuf> {
+    if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    }
+}
+
+/// Parses an output format from its name or file extension
+pub fn parse_format(value: &str) -> Result<AudioFormat, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "bwav" | "wav" => Ok(AudioFormat::Bwav),
+        "mp4" | "m4a" | "mp4opus" => Ok(AudioFormat::Mp4Opus),
+        "opus" | "ogg" | "oggopus" => Ok(AudioFormat::OggOpus),
+        "flac" => Ok(AudioFormat::Flac),
+        "mp4-stems" | "mp4opusstems" => Ok(AudioFormat::Mp4OpusStems),
+        "bwav-polyphonic" | "bwavpolyphonic" => Ok(AudioFormat::BwavPolyphonic),
+        "bwav-stems" | "bwavstems" => Ok(AudioFormat::BwavStems),
+        _ => Err(format!(
+            "unknown format '{}' (expected wav, m4a, opus, flac, mp4-stems, bwav-polyphonic or bwav-stems)",
+            value
+        )),
+    }
+}
@@ -0,0 +1,91 @@
+use bvc_client_lib::audio::recording::{
+    renderer::{file_name::player_file_stems, mixdown::session_participants, AudioFormatRenderer},
+    session::read_manifest,
+};
+use clap::Parser;
+use common::structs::AudioFormat;
+use std::fs;
+use std::path::PathBuf;
+use std::process::exit;
+
+use super::{parse_format, Config as StateConfig};
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Render players of a session", long_about = None)]
+pub struct Config {
+    /// Session id or path to a session directory
+    pub session: String,
+
+    /// Player to render; repeat for several. Every participant is rendered when omitted
+    #[clap(short, long)]
+    pub player: Vec<String>,
+
+    /// Output format: wav, m4a, opus, flac, mp4-stems, bwav-polyphonic or bwav-stems
+    #[clap(short, long, value_parser = parse_format, default_value = "wav")]
+    pub format: AudioFormat,
+
+    /// Output directory. Defaults to the session's renders directory
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let session = cfg.extracted_session(&self.session);
+        let session_path = session.path();
+        let render_path = self
+            .output
+            .clone()
+            .unwrap_or_else(|| cfg.session_path(&self.session).join("renders"));
+
+        if let Err(e) = fs::create_dir_all(&render_path) {
+            eprintln!("Failed to create {:?}: {}", render_path, e);
+            exit(1);
+        }
+
+        if self.format.is_multitrack() {
+            let output_path = match self.format {
+                AudioFormat::BwavStems => render_path.join("stems"),
+                _ => render_path.join(format!("stems.{}", self.format.extension())),
+            };
+
+            match self.format.render_stems(session_path, &output_path, &self.player).await {
+                Ok(()) => println!("Rendered stems to {:?}", output_path),
+                Err(e) => {
+                    eprintln!("Failed to render stems: {}", e);
+                    exit(1);
+                }
+            }
+            return;
+        }
+
+        let players = if self.player.is_empty() {
+            match read_manifest(session_path) {
+                Ok(manifest) => session_participants(&manifest),
+                Err(e) => {
+                    eprintln!("Failed to read manifest for {:?}: {}", session_path, e);
+                    exit(1);
+                }
+            }
+        } else {
+            self.player.clone()
+        };
+
+        let mut failed = 0;
+        for (player, stem) in players.iter().zip(player_file_stems(&players)) {
+            let output_path = render_path.join(format!("{}.{}", stem, self.format.extension()));
+            match self.format.render(session_path, player, &output_path).await {
+                Ok(()) => println!("Rendered {} to {:?}", player, output_path),
+                Err(e) => {
+                    eprintln!("Failed to render {}: {}", player, e);
+                    failed += 1;
+                }
+            }
+        }
+
+        if failed > 0 {
+            eprintln!("{} of {} renders failed", failed, players.len());
+            exit(1);
+        }
+    }
+}
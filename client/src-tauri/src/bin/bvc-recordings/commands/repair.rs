@@ -0,0 +1,40 @@
+use bvc_client_lib::audio::recording::session::repair_session;
+use clap::Parser;
+use std::process::exit;
+
+use super::{compress, decompress, validate::print_report, Config as StateConfig};
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Repair a session directory", long_about = None)]
+pub struct Config {
+    /// Session id or path to a session directory
+    pub session: String,
+
+    /// Print the report as JSON
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let session_path = cfg.session_path(&self.session);
+        let compressed = decompress(&session_path);
+        let report = repair_session(&session_path);
+        if compressed {
+            compress(&session_path);
+        }
+
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("Failed to repair {:?}: {}", session_path, e);
+                exit(1);
+            }
+        };
+
+        print_report(&report, self.json);
+        if !report.is_healthy() {
+            exit(2);
+        }
+    }
+}
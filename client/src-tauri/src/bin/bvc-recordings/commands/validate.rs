@@ -0,0 +1,84 @@
+use bvc_client_lib::audio::recording::session::{validate_session, SessionReport};
+use clap::Parser;
+use std::process::exit;
+
+use super::Config as StateConfig;
+
+#[derive(Debug, Parser, Clone)]
+#[clap(author, version, about = "Validate a session directory", long_about = None)]
+pub struct Config {
+    /// Session id or path to a session directory
+    pub session: String,
+
+    /// Print the report as JSON
+    #[clap(long)]
+    pub json: bool,
+}
+
+impl Config {
+    pub async fn run(&self, cfg: &StateConfig) {
+        let session = cfg.extracted_session(&self.session);
+        let session_path = session.path();
+        let report = match validate_session(session_path) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("Failed to validate {:?}: {}", session_path, e);
+                exit(1);
+            }
+        };
+
+        print_report(&report, self.json);
+        if !report.is_healthy() {
+            exit(2);
+        }
+    }
+}
+
+/// Prints a validation or repair report
+pub fn print_report(report: &SessionReport, json: bool) {
+    if json {
+        match serde_json::to_string_pretty(report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => eprintln!("Failed to serialize report: {}", e),
+        }
+        return;
+    }
+
+    println!("Session {:?}", report.path);
+    match (&report.manifest, &report.manifest_error) {
+        (Some(manifest), _) => println!(
+            "  manifest: {} recorded by {}, {} participants, duration {}{}",
+            manifest.session_id,
+            manifest.emitter_player,
+            manifest.participants.len(),
+            manifest
+                .duration_ms
+                .map(|ms| format!("{}ms", ms))
+                .unwrap_or_else(|| "missing".to_string()),
+            if manifest.recovered { " (recovered)" } else { "" }
+        ),
+        (None, Some(error)) => println!("  manifest: unreadable ({})", error),
+        (None, None) => println!("  manifest: missing"),
+    }
+
+    for segment in &report.segments {
+        let status = match &segment.stop_reason {
+            Some(reason) => format!("{}, {} trailing bytes", reason, segment.trailing_bytes),
+            None if segment.undecodable > 0 => format!("{} undecodable headers", segment.undecodable),
+            None => "ok".to_string(),
+        };
+        println!("  {}: {} records, {}", segment.file_name, segment.records, status);
+    }
+
+    if !report.unlisted_players.is_empty() {
+        println!("  players missing from manifest: {}", report.unlisted_players.join(", "));
+    }
+    for file_name in &report.truncated {
+        println!("  truncated {}", file_name);
+    }
+    if report.manifest_rebuilt {
+        println!("  rebuilt session.json");
+    }
+
+    println!("  {}", if report.is_healthy() { "healthy" } else { "needs repair" });
+}
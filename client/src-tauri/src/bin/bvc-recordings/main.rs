@@ -0,0 +1,15 @@
+//! Headless tooling for recording sessions.
+//!
+//! Lists, inspects, renders, validates and repairs session directories without
+//! starting the Tauri runtime, so recordings can be processed on machines that
+//! never run the client (e.g. a server-side archival job).
+//!
+//! Built only with the `cli` feature:
+//! `cargo run --features cli --bin bvc-recordings -- <command>`
+
+mod commands;
+
+#[tokio::main]
+async fn main() {
+    commands::launch().await;
+}
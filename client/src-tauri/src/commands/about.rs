@@ -8,6 +8,7 @@ use tauri::async_runtime::Mutex;
 
 use crate::commands::env::get_variant;
 use crate::logging::{SentryLogger, Telemetry};
+use crate::network::diagnostics;
 use crate::structs::app_state::AppState;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -60,11 +61,28 @@ pub(crate) async fn export_logs(app_handle: tauri::AppHandle) -> Result<bool, St
         return Err("Log directory does not exist".to_string());
     }
 
+    // Bundled so reports of choppy audio come with the network conditions at the time
+    let report = serde_json::to_vec_pretty(&diagnostics::collect(&app_handle).await)
+        .map_err(|e| format!("Failed to serialize network diagnostics: {}", e))?;
+
     let tar_data = {
         let mut tar_builder = tar::Builder::new(Vec::new());
         tar_builder
             .append_dir_all("logs", &log_dir)
             .map_err(|e| format!("Failed to create tar archive: {}", e))?;
+
+        let mut header = tar::Header::new_gnu();
+        header.set_size(report.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        );
+        tar_builder
+            .append_data(&mut header, "diagnostics.json", report.as_slice())
+            .map_err(|e| format!("Failed to add network diagnostics: {}", e))?;
         tar_builder
             .into_inner()
             .map_err(|e| format!("Failed to finalize tar archive: {}", e))?
@@ -2,6 +2,7 @@ use crate::audio::types::{AudioDevice, AudioDeviceType};
 use crate::audio::{AudioActionsManager, RecordingManager};
 use crate::{structs::app_state::AppState, AudioStreamManager};
 use common::structs::audio::StreamEvent;
+use common::structs::recording::RecordingMarker;
 use log::info;
 use std::sync::Arc;
 use std::{collections::HashMap};
@@ -189,9 +190,10 @@ pub(crate) async fn start_recording(
 ) -> Result<String, String> {
     let current_player = extract_current_player(&app).await
         .ok_or_else(|| "No current player set for recording".to_string())?;
+    let spatial_config = actions.spatial_config().await;
 
     let mut manager = recording_manager.lock().await;
-    let result = match manager.start_recording(current_player).await {
+    let result = match manager.start_recording(current_player, spatial_config).await {
         Ok(_) => {
             if let Some(session_id) = manager.current_session_id() {
                 Ok(session_id)
@@ -226,6 +228,18 @@ pub(crate) async fn stop_recording(
     result
 }
 
+/// Place a marker, with an optional label, in the current recording session
+#[tauri::command]
+pub(crate) async fn mark_recording(
+    label: Option<String>,
+    actions: State<'_, AudioActionsManager>,
+) -> Result<RecordingMarker, String> {
+    actions
+        .mark_recording(label)
+        .await
+        .map_err(|e| format!("Failed to mark recording: {:?}", e))
+}
+
 /// Get current recording status
 #[tauri::command]
 pub(crate) async fn get_recording_status(
@@ -0,0 +1,69 @@
+use crate::network::discovery;
+use common::structs::network::{FavouriteServer, ServerSource, ServerStatus};
+use log::info;
+
+#[tauri::command]
+pub(crate) async fn list_favourite_servers(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<FavouriteServer>, String> {
+    Ok(discovery::favourites(&app_handle))
+}
+
+#[tauri::command]
+pub(crate) async fn add_favourite_server(
+    url: String,
+    name: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<FavouriteServer>, String> {
+    let url = discovery::normalize_url(&url).ok_or_else(|| "Invalid server URL".to_string())?;
+    let name = name.filter(|name| !name.trim().is_empty());
+
+    let mut favourites = discovery::favourites(&app_handle);
+    match favourites.iter_mut().find(|server| server.url == url) {
+        Some(existing) => existing.name = name.or(existing.name.take()),
+        None => favourites.push(FavouriteServer {
+            url,
+            name,
+            source: ServerSource::Manual,
+        }),
+    }
+
+    discovery::save_favourites(&app_handle, &favourites).map_err(|e| e.to_string())?;
+    Ok(favourites)
+}
+
+#[tauri::command]
+pub(crate) async fn remove_favourite_server(
+    url: String,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<FavouriteServer>, String> {
+    let mut favourites = discovery::favourites(&app_handle);
+    favourites.retain(|server| server.url != url);
+
+    discovery::save_favourites(&app_handle, &favourites).map_err(|e| e.to_string())?;
+    Ok(favourites)
+}
+
+/// Add the servers listed by a directory endpoint or JSON file to the favourites
+#[tauri::command]
+pub(crate) async fn import_favourite_servers(
+    source: String,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<FavouriteServer>, String> {
+    let servers = discovery::load_directory(&source)
+        .await
+        .map_err(|e| format!("Failed to import servers: {}", e))?;
+
+    let mut favourites = discovery::favourites(&app_handle);
+    let added = discovery::merge_favourites(&mut favourites, servers);
+    discovery::save_favourites(&app_handle, &favourites).map_err(|e| e.to_string())?;
+
+    info!("Imported {} servers from {}", added, source);
+    Ok(favourites)
+}
+
+/// Check reachability, latency, protocol compatibility and player count of each server
+#[tauri::command]
+pub(crate) async fn probe_servers(servers: Vec<String>) -> Result<Vec<ServerStatus>, ()> {
+    Ok(discovery::probe_all(&servers).await)
+}
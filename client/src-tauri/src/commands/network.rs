@@ -1,5 +1,7 @@
+use crate::network::{diagnostics, ServerAddress};
 use crate::{structs::app_state::AppState, NetworkStreamManager};
 use common::structs::config::LoginResponse;
+use common::structs::network::{NetworkDiagnostics, ServerSessionStatus};
 use log::{error, info};
 use std::net::SocketAddr;
 use tauri::async_runtime::Mutex;
@@ -30,8 +32,109 @@ pub(crate) async fn change_network_stream(
     let mut state = state.lock().await;
     state.current_server = Some(server.clone());
 
+    let address = resolve_server(&server, &data.quic_connect_string).await?;
+
+    let mut network_stream = network_stream.lock().await;
+    match network_stream.restart(address, &data).await {
+        Ok(()) => {
+            info!("Now streaming {}", server.clone());
+        }
+        Err(e) => {
+            error!(
+                "Failed to re-initialize network stream: {:?} {}",
+                e,
+                e.to_string()
+            );
+            return Err(());
+        }
+    };
+
+    Ok(())
+}
+
+/// Connect to another server while staying connected to the current one
+#[tauri::command]
+#[tracing::instrument(skip(state, network_stream, data), fields(server = %server))]
+pub(crate) async fn connect_server(
+    server: String,
+    data: LoginResponse,
+    state: State<'_, Mutex<AppState>>,
+    network_stream: State<'_, Mutex<NetworkStreamManager>>,
+) -> Result<(), ()> {
+    let address = resolve_server(&server, &data.quic_connect_string).await?;
+
+    let mut network_stream = network_stream.lock().await;
+    if let Err(e) = network_stream.add(address, &data).await {
+        error!("Failed to connect to {}: {}", server, e.to_string());
+        return Err(());
+    }
+    drop(network_stream);
+
+    // Let the API commands reach this server by name
+    let state = state.lock().await;
+    state
+        .add_pooled_api_client(
+            server.clone(),
+            data.certificate_ca.clone(),
+            data.certificate.clone() + &data.certificate_key,
+        )
+        .await;
+
+    info!("Now also streaming {}", server);
+    Ok(())
+}
+
+#[tauri::command]
+pub(crate) async fn disconnect_server(
+    server: String,
+    network_stream: State<'_, Mutex<NetworkStreamManager>>,
+) -> Result<(), ()> {
+    let mut network_stream = network_stream.lock().await;
+    match network_stream.remove(&server).await {
+        Ok(()) => {
+            info!("Stopped streaming {}", server);
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to disconnect from {}: {}", server, e.to_string());
+            Err(())
+        }
+    }
+}
+
+#[tauri::command]
+pub(crate) async fn set_server_transmit(
+    server: String,
+    transmit: bool,
+    network_stream: State<'_, Mutex<NetworkStreamManager>>,
+) -> Result<(), String> {
+    let network_stream = network_stream.lock().await;
+    network_stream
+        .set_transmit(&server, transmit)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn list_server_sessions(
+    network_stream: State<'_, Mutex<NetworkStreamManager>>,
+) -> Result<Vec<ServerSessionStatus>, ()> {
+    let network_stream = network_stream.lock().await;
+    Ok(network_stream.sessions())
+}
+
+/// Jitter, loss and buffer statistics of every heard player, and round trip and datagram
+/// statistics of every connection
+#[tauri::command]
+pub(crate) async fn get_network_diagnostics(
+    app_handle: tauri::AppHandle,
+) -> Result<NetworkDiagnostics, ()> {
+    Ok(diagnostics::collect(&app_handle).await)
+}
+
+/// Resolve `server` to the socket address of its QUIC endpoint on `quic_port`
+async fn resolve_server(server: &str, quic_port: &str) -> Result<ServerAddress, ()> {
     // Parse URL to extract just the hostname (without port) for DNS lookup and SNI
-    let server_fqdn = Url::parse(&server)
+    let server_fqdn = Url::parse(server)
         .ok()
         .and_then(|u| u.host_str().map(|s| s.to_string()))
         .unwrap_or_else(|| {
@@ -41,16 +144,20 @@ pub(crate) async fn change_network_stream(
                 .replace("http://", "")
                 .split(':')
                 .next()
-                .unwrap_or(&server)
+                .unwrap_or(server)
                 .to_string()
         });
 
+    let port: u16 = quic_port.parse().map_err(|e| {
+        error!("Invalid QUIC port {:?}: {:?}", quic_port, e);
+    })?;
+
     let resolver = TokioAsyncResolver::tokio(ResolverConfig::cloudflare(), ResolverOpts::default());
 
     // Try a DNS lookup for the network, then fallback to the /etc/hosts on the machine
-    let socket_addr = match resolver.lookup_ip(server_fqdn.clone()).await {
+    let socket = match resolver.lookup_ip(server_fqdn.clone()).await {
         Ok(response) => match response.iter().next() {
-            Some(ip) => SocketAddr::new(ip, data.quic_connect_string.parse().unwrap()),
+            Some(ip) => SocketAddr::new(ip, port),
             None => {
                 error!("TrustDNS Lookup was successful, but no IP's were returned. Networking issue, restart BVC.");
                 return Err(());
@@ -59,7 +166,7 @@ pub(crate) async fn change_network_stream(
         Err(_) => match Resolver::from_system_conf() {
             Ok(resolver) => match resolver.lookup_ip(server_fqdn.clone()) {
                 Ok(response) => match response.iter().next() {
-                    Some(ip) => SocketAddr::new(ip, data.quic_connect_string.parse().unwrap()),
+                    Some(ip) => SocketAddr::new(ip, port),
                     None => {
                         error!("TrustDNS Lookup was successful, but no IP's were returned. Networking issue, restart BVC.");
                         return Err(());
@@ -77,33 +184,11 @@ pub(crate) async fn change_network_stream(
         },
     };
 
-    let mut network_stream = network_stream.lock().await;
-    match network_stream
-        .restart(
-            server_fqdn.clone(),
-            server.clone(),
-            socket_addr,
-            data.gamertag,
-            data.certificate_ca,
-            data.certificate,
-            data.certificate_key,
-        )
-        .await
-    {
-        Ok(()) => {
-            info!("Now streaming {}", server.clone());
-        }
-        Err(e) => {
-            error!(
-                "Failed to re-initialize network stream: {:?} {}",
-                e,
-                e.to_string()
-            );
-            return Err(());
-        }
-    };
-
-    Ok(())
+    Ok(ServerAddress {
+        fqdn: server_fqdn,
+        url: server.to_string(),
+        socket,
+    })
 }
 
 #[tauri::command]
@@ -1,9 +1,15 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use tauri::Manager;
-use common::structs::recording::SessionManifest;
-use crate::audio::recording::renderer::AudioFormatRenderer;
+use std::path::Path;
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::{Manager, State};
+use tauri_plugin_store::StoreExt;
+use common::structs::recording::{RecordingRetentionPolicy, SessionManifest};
+use crate::audio::recording::renderer::{file_name::player_file_stems, mixdown::{MixdownOptions, Perspective}, AudioFormatRenderer};
+use crate::audio::recording::retention::{self, RetentionReport};
+use crate::audio::recording::session::{self, directory_size};
+use crate::audio::RecordingManager;
 use common::structs::AudioFormat;
 use tauri_plugin_opener::OpenerExt;
 use log::{info, error};
@@ -15,24 +21,6 @@ pub struct RecordingSession {
     pub recording_path: String,
 }
 
-fn get_directory_size(path: &PathBuf) -> Result<u64, std::io::Error> {
-    let mut total_size = 0u64;
-
-    if path.is_dir() {
-        for entry in fs::read_dir(path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
-                total_size += get_directory_size(&path)?;
-            } else {
-                total_size += entry.metadata()?.len();
-            }
-        }
-    }
-
-    Ok(total_size)
-}
-
 #[tauri::command]
 pub async fn get_recording_sessions(app_handle: tauri::AppHandle) -> Result<Vec<RecordingSession>, String> {
     let recordings_dir = app_handle
@@ -71,7 +59,7 @@ pub async fn get_recording_sessions(app_handle: tauri::AppHandle) -> Result<Vec<
             .map_err(|e| format!("Failed to parse session.json: {}", e))?;
 
         // Calculate directory size
-        let size_bytes = get_directory_size(&session_dir)
+        let size_bytes = directory_size(&session_dir)
             .map_err(|e| format!("Failed to calculate directory size: {}", e))?;
 
         let file_size_mb = size_bytes as f64 / (1024.0 * 1024.0);
@@ -109,6 +97,57 @@ pub async fn delete_recording_session(app_handle: tauri::AppHandle, session_id:
     Ok(true)
 }
 
+/// Pinned sessions are never removed by the retention policy
+#[tauri::command]
+pub async fn set_recording_pinned(app_handle: tauri::AppHandle, session_id: String, pinned: bool) -> Result<bool, String> {
+    let session_path = app_handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("recordings")
+        .join(&session_id);
+
+    let mut manifest = session::read_manifest(&session_path)
+        .map_err(|e| format!("Failed to read session.json: {}", e))?;
+    manifest.pinned = pinned;
+    session::write_manifest(&session_path, &manifest)
+        .map_err(|e| format!("Failed to write session.json: {}", e))?;
+
+    Ok(true)
+}
+
+#[tauri::command]
+pub async fn get_recording_retention(app_handle: tauri::AppHandle) -> Result<RecordingRetentionPolicy, String> {
+    Ok(RecordingManager::retention_policy(&app_handle))
+}
+
+#[tauri::command]
+pub async fn set_recording_retention(app_handle: tauri::AppHandle, policy: RecordingRetentionPolicy) -> Result<(), String> {
+    let store = app_handle
+        .store("store.json")
+        .map_err(|e| format!("Failed to open store: {}", e))?;
+
+    let value = serde_json::to_value(&policy)
+        .map_err(|e| format!("Failed to serialize retention policy: {}", e))?;
+    store.set(retention::RETENTION_STORE_KEY, value);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    Ok(())
+}
+
+/// Runs a retention pass immediately instead of waiting for the background task
+#[tauri::command]
+pub async fn apply_recording_retention(
+    app_handle: tauri::AppHandle,
+    recording_manager: State<'_, Arc<Mutex<RecordingManager>>>,
+) -> Result<RetentionReport, String> {
+    let retention_lock = recording_manager.lock().await.retention_lock();
+
+    RecordingManager::enforce_retention(&app_handle, &retention_lock)
+        .await
+        .map_err(|e| format!("Failed to apply retention policy: {}", e))
+}
+
 #[tauri::command]
 #[tracing::instrument(skip(app_handle, selected_players), fields(session_id = %session_id, format = ?format, player_count = selected_players.len()))]
 pub async fn export_recording(
@@ -133,19 +172,50 @@ pub async fn export_recording(
         .join("recordings")
         .join(&session_id);
 
-    let session_path = rec_path.clone();
+    let extracted = extract(&rec_path).await?;
     let render_path = rec_path.join("renders");
     let _ = fs::create_dir_all(render_path.clone().to_path_buf());
     let render_path_for_open = render_path.clone();
 
+    if format.is_multitrack() {
+        let output_path = match format {
+            AudioFormat::BwavStems => render_path.join("stems"),
+            _ => render_path.join(format!("stems.{}", format.extension())),
+        };
+
+        let task = tokio::spawn({
+            use tracing::Instrument;
+            async move {
+                format.render_stems(extracted.path(), &output_path, &selected_players).await
+            }.instrument(tracing::Span::current())
+        });
+
+        return match task.await {
+            Ok(Ok(())) => {
+                info!("Rendered stems for session {}", session_id);
+                let _ = app_handle.opener().open_path(render_path_for_open.to_string_lossy().to_string(), None::<&str>);
+                Ok(true)
+            },
+            Ok(Err(e)) => {
+                error!("Error rendering stems: {}", e);
+                Err(format!("Failed to render stems: {}", e))
+            },
+            Err(e) => {
+                error!("JoinHandler for Recording failed to join, {}", e);
+                Err(format!("Stem task failed: {}", e))
+            }
+        };
+    }
+
     let task = tokio::spawn({
         use tracing::Instrument;
         async move {
-            for (index, player) in selected_players.iter().enumerate() {
+            let file_stems = player_file_stems(&selected_players);
+            for (index, (player, stem)) in selected_players.iter().zip(&file_stems).enumerate() {
                 let span = tracing::info_span!("render_player", index = index);
-                let output_path = render_path.join(format!("{}.{}", player, format.extension()));
+                let output_path = render_path.join(format!("{}.{}", stem, format.extension()));
                 async {
-                    match format.render(&session_path, player, &output_path).await {
+                    match format.render(extracted.path(), player, &output_path).await {
                         Ok(()) => {
                             info!("Rendered player {}", index);
                         },
@@ -169,3 +239,80 @@ pub async fn export_recording(
 
     Ok(true)
 }
+
+#[tauri::command]
+#[tracing::instrument(skip(app_handle, selected_players), fields(session_id = %session_id, format = ?format, player_count = selected_players.len()))]
+pub async fn export_recording_mixdown(
+    session_id: String,
+    selected_players: Vec<String>,
+    spatial: bool,
+    perspective: Option<Perspective>,
+    format: AudioFormat,
+    app_handle: tauri::AppHandle
+) -> Result<bool, String> {
+    log::info!(
+        "Export mixdown called - Session ID: {}, Players: {}, Spatial: {}, Perspective: {:?}, Format: {:?}",
+        session_id,
+        selected_players.len(),
+        spatial,
+        perspective,
+        format
+    );
+
+    let session_path = app_handle
+        .path()
+        .app_local_data_dir()
+        .map_err(|e| format!("Failed to get app data dir: {}", e))?
+        .join("recordings")
+        .join(&session_id);
+
+    if !session_path.exists() {
+        return Err("Recording session not found".to_string());
+    }
+    let extracted = extract(&session_path).await?;
+
+    let render_path = session_path.join("renders");
+    fs::create_dir_all(&render_path)
+        .map_err(|e| format!("Failed to create renders directory: {}", e))?;
+    let output_path = render_path.join(format!("mixdown.{}", format.extension()));
+
+    let options = MixdownOptions {
+        players: selected_players,
+        spatial,
+        perspective: perspective.unwrap_or_default(),
+        ..Default::default()
+    };
+
+    let task = tokio::spawn({
+        use tracing::Instrument;
+        async move {
+            format.render_mixdown(extracted.path(), &output_path, options).await
+        }.instrument(tracing::Span::current())
+    });
+
+    match task.await {
+        Ok(Ok(())) => {
+            info!("Rendered mixdown for session {}", session_id);
+            let _ = app_handle.opener().open_path(render_path.to_string_lossy().to_string(), None::<&str>);
+            Ok(true)
+        },
+        Ok(Err(e)) => {
+            error!("Error rendering mixdown: {}", e);
+            Err(format!("Failed to render mixdown: {}", e))
+        },
+        Err(e) => {
+            error!("JoinHandler for Recording failed to join, {}", e);
+            Err(format!("Mixdown task failed: {}", e))
+        }
+    }
+}
+
+/// A readable copy of a session for rendering. Sessions compressed by the retention policy are
+/// extracted to a temporary directory, so they stay compressed.
+async fn extract(session_path: &Path) -> Result<retention::ExtractedSession, String> {
+    let session_path = session_path.to_path_buf();
+    tokio::task::spawn_blocking(move || retention::extract_session(&session_path))
+        .await
+        .map_err(|e| format!("Extraction task failed: {}", e))?
+        .map_err(|e| format!("Failed to read recording: {}", e))
+}
@@ -2,3 +2,4 @@ pub(crate) mod channel_event;
 pub(crate) mod notification;
 pub(crate) mod player_presence;
 pub(crate) mod server_error;
+pub(crate) mod speaking;
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+pub(crate) const SPEAKING: &str = "speaking";
+
+/// Sent when a player (including the local player) starts or stops speaking
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Speaking {
+    pub player: String,
+    pub speaking: bool,
+    /// RMS level of the most recent audio heard from the player
+    pub level: f32,
+}
+
+impl Speaking {
+    pub fn new(player: String, speaking: bool, level: f32) -> Self {
+        Self {
+            player,
+            speaking,
+            level,
+        }
+    }
+}
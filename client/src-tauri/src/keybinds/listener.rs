@@ -1,15 +1,24 @@
 use crate::audio::types::AudioDeviceType;
+use crate::api::ChannelActionsManager;
 use crate::audio::AudioActionsManager;
 use common::structs::keybinds::{KeybindAction, KeybindConfig, PttEvent, VoiceMode};
-use log::info;
+use log::{info, warn};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tauri::{async_runtime::Mutex, AppHandle, Emitter, Manager};
 
+/// Master output volume change per volume hotkey press
+const VOLUME_STEP: f32 = 0.1;
+
 pub struct KeybindListener {
     app_handle: AppHandle,
     ptt_held: AtomicBool,
+    /// Whether the current push-to-mute hold muted the microphone, so release only
+    /// unmutes what it muted
+    ptm_muted: AtomicBool,
+    whisper_held: AtomicBool,
     last_voice_mode: Mutex<Option<VoiceMode>>,
+    favourite_channels: Mutex<Vec<String>>,
 }
 
 impl KeybindListener {
@@ -17,12 +26,25 @@ impl KeybindListener {
         Self {
             app_handle,
             ptt_held: AtomicBool::new(false),
+            ptm_muted: AtomicBool::new(false),
+            whisper_held: AtomicBool::new(false),
             last_voice_mode: Mutex::new(None),
+            favourite_channels: Mutex::new(Vec::new()),
         }
     }
 
+    /// Channels cycled through by the cycle channel hotkey, in order
+    pub async fn set_favourite_channels(&self, config: &KeybindConfig) {
+        *self.favourite_channels.lock().await = config
+            .favourite_channels
+            .iter()
+            .map(|favourite| favourite.channel.clone())
+            .collect();
+    }
+
     pub async fn handle_voice_mode_transition(&self, config: &KeybindConfig) {
         self.ptt_held.store(false, Ordering::Relaxed);
+        self.ptm_muted.store(false, Ordering::Relaxed);
 
         // Only run the transition if the voice mode actually changed
         let mut last = self.last_voice_mode.lock().await;
@@ -37,12 +59,17 @@ impl KeybindListener {
         info!("Voice mode transition: {:?} -> {:?}", previous, config.voice_mode);
 
         let actions = self.app_handle.state::<AudioActionsManager>();
+        let voice_activation = config.voice_mode == VoiceMode::VoiceActivation;
+        if let Err(e) = actions.set_voice_activation(voice_activation).await {
+            warn!("Failed to switch voice activation: {}", e);
+        }
+
         let is_muted = actions.is_muted(AudioDeviceType::InputDevice).await;
         match config.voice_mode {
             VoiceMode::PushToTalk if !is_muted => {
                 actions.toggle_mute(AudioDeviceType::InputDevice).await;
             }
-            VoiceMode::OpenMic if is_muted => {
+            VoiceMode::OpenMic | VoiceMode::VoiceActivation if is_muted => {
                 actions.toggle_mute(AudioDeviceType::InputDevice).await;
             }
             _ => {}
@@ -54,13 +81,23 @@ impl KeybindListener {
             KeybindAction::ToggleMute => self.dispatch_toggle_mute().await,
             KeybindAction::ToggleDeafen => self.dispatch_toggle_deafen().await,
             KeybindAction::ToggleRecording => self.dispatch_toggle_recording().await,
+            KeybindAction::MarkRecording => self.dispatch_mark_recording().await,
             KeybindAction::PushToTalk => self.dispatch_ptt_press().await,
+            KeybindAction::PushToMute => self.dispatch_ptm_press().await,
+            KeybindAction::WhisperToChannel => self.dispatch_whisper_press().await,
+            KeybindAction::CycleChannel => self.dispatch_cycle_channel().await,
+            KeybindAction::JoinChannel(channel) => self.dispatch_join_channel(channel).await,
+            KeybindAction::VolumeUp => self.dispatch_adjust_volume(VOLUME_STEP).await,
+            KeybindAction::VolumeDown => self.dispatch_adjust_volume(-VOLUME_STEP).await,
         }
     }
 
     pub async fn on_action_release(&self, action: KeybindAction) {
-        if action == KeybindAction::PushToTalk {
-            self.dispatch_ptt_release().await;
+        match action {
+            KeybindAction::PushToTalk => self.dispatch_ptt_release().await,
+            KeybindAction::PushToMute => self.dispatch_ptm_release().await,
+            KeybindAction::WhisperToChannel => self.dispatch_whisper_release().await,
+            _ => {}
         }
     }
 
@@ -82,6 +119,13 @@ impl KeybindListener {
         actions.broadcast_state().await;
     }
 
+    async fn dispatch_mark_recording(&self) {
+        let actions = self.app_handle.state::<AudioActionsManager>();
+        if let Err(e) = actions.mark_recording(None).await {
+            warn!("Failed to place recording marker: {}", e);
+        }
+    }
+
     async fn dispatch_ptt_press(&self) {
         if self.ptt_held.swap(true, Ordering::Relaxed) {
             return;
@@ -106,4 +150,87 @@ impl KeybindListener {
             actions.broadcast_state().await;
         }
     }
+
+    async fn dispatch_ptm_press(&self) {
+        let actions = self.app_handle.state::<AudioActionsManager>();
+        if actions.is_muted(AudioDeviceType::InputDevice).await {
+            return;
+        }
+        if !self.ptm_muted.swap(true, Ordering::Relaxed) {
+            actions.toggle_mute(AudioDeviceType::InputDevice).await;
+            actions.broadcast_state().await;
+        }
+    }
+
+    async fn dispatch_ptm_release(&self) {
+        if !self.ptm_muted.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        let actions = self.app_handle.state::<AudioActionsManager>();
+        if actions.is_muted(AudioDeviceType::InputDevice).await {
+            actions.toggle_mute(AudioDeviceType::InputDevice).await;
+        }
+        actions.broadcast_state().await;
+    }
+
+    async fn dispatch_whisper_press(&self) {
+        if self.whisper_held.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let actions = self.app_handle.state::<AudioActionsManager>();
+        if let Err(e) = actions.set_whisper(true).await {
+            warn!("Failed to start whispering to channel: {}", e);
+        }
+        self.app_handle.emit(&PttEvent::Whisper.to_string(), true).ok();
+
+        // In push-to-talk the whisper key also opens the microphone
+        if self.is_push_to_talk().await {
+            self.dispatch_ptt_press().await;
+        }
+    }
+
+    async fn dispatch_whisper_release(&self) {
+        self.whisper_held.store(false, Ordering::Relaxed);
+        // Keep the trailing frames of the push-to-talk release channel-only too
+        if self.is_push_to_talk().await {
+            self.dispatch_ptt_release().await;
+        }
+        if self.whisper_held.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let actions = self.app_handle.state::<AudioActionsManager>();
+        if let Err(e) = actions.set_whisper(false).await {
+            warn!("Failed to stop whispering to channel: {}", e);
+        }
+        self.app_handle.emit(&PttEvent::Whisper.to_string(), false).ok();
+    }
+
+    async fn dispatch_cycle_channel(&self) {
+        let favourites = self.favourite_channels.lock().await.clone();
+        let channels = self.app_handle.state::<ChannelActionsManager>();
+        match channels.cycle(&favourites).await {
+            Ok(Some(channel)) => info!("Cycled to channel {}", channel),
+            Ok(None) => info!("No channels to cycle through"),
+            Err(e) => warn!("Failed to cycle channel: {}", e),
+        }
+    }
+
+    async fn dispatch_join_channel(&self, channel: String) {
+        let channels = self.app_handle.state::<ChannelActionsManager>();
+        if let Err(e) = channels.join(channel.clone()).await {
+            warn!("Failed to join channel {}: {}", channel, e);
+        }
+    }
+
+    async fn dispatch_adjust_volume(&self, delta: f32) {
+        let actions = self.app_handle.state::<AudioActionsManager>();
+        if let Err(e) = actions.adjust_output_volume(delta).await {
+            warn!("Failed to adjust output volume: {}", e);
+        }
+    }
+
+    async fn is_push_to_talk(&self) -> bool {
+        *self.last_voice_mode.lock().await == Some(VoiceMode::PushToTalk)
+    }
 }
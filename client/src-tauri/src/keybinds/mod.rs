@@ -31,6 +31,7 @@ impl KeybindManager {
     pub async fn start(&self, config: KeybindConfig) {
         // Voice mode transition
         self.listener.handle_voice_mode_transition(&config).await;
+        self.listener.set_favourite_channels(&config).await;
 
         // Unregister all existing shortcuts
         let gs = self.app_handle.global_shortcut();
@@ -41,7 +42,7 @@ impl KeybindManager {
         // Build new shortcuts from config
         let mut entries = Vec::new();
 
-        if config.voice_mode == VoiceMode::OpenMic {
+        if config.voice_mode != VoiceMode::PushToTalk {
             if let Some(s) = Self::parse_shortcut(&config.toggle_mute) {
                 entries.push((s, KeybindAction::ToggleMute));
             }
@@ -52,11 +53,36 @@ impl KeybindManager {
         if let Some(s) = Self::parse_shortcut(&config.toggle_recording) {
             entries.push((s, KeybindAction::ToggleRecording));
         }
+        if let Some(s) = Self::parse_shortcut(&config.mark_recording) {
+            entries.push((s, KeybindAction::MarkRecording));
+        }
         if config.voice_mode == VoiceMode::PushToTalk {
             if let Some(s) = Self::parse_shortcut(&config.push_to_talk) {
                 entries.push((s, KeybindAction::PushToTalk));
             }
         }
+        if config.voice_mode != VoiceMode::PushToTalk {
+            if let Some(s) = Self::parse_shortcut(&config.push_to_mute) {
+                entries.push((s, KeybindAction::PushToMute));
+            }
+        }
+        if let Some(s) = Self::parse_shortcut(&config.whisper_to_channel) {
+            entries.push((s, KeybindAction::WhisperToChannel));
+        }
+        if let Some(s) = Self::parse_shortcut(&config.cycle_channel) {
+            entries.push((s, KeybindAction::CycleChannel));
+        }
+        for favourite in &config.favourite_channels {
+            if let Some(s) = Self::parse_shortcut(&favourite.keybind) {
+                entries.push((s, KeybindAction::JoinChannel(favourite.channel.clone())));
+            }
+        }
+        if let Some(s) = Self::parse_shortcut(&config.volume_up) {
+            entries.push((s, KeybindAction::VolumeUp));
+        }
+        if let Some(s) = Self::parse_shortcut(&config.volume_down) {
+            entries.push((s, KeybindAction::VolumeDown));
+        }
 
         // Register shortcuts
         for (shortcut, action) in &entries {
@@ -72,7 +98,21 @@ impl KeybindManager {
         *self.action_map.write() = entries;
     }
 
+    /// Press or release push-to-talk as if the bound key had been used
+    pub async fn push_to_talk(&self, pressed: bool) {
+        if pressed {
+            self.listener.on_action_press(KeybindAction::PushToTalk).await;
+        } else {
+            self.listener.on_action_release(KeybindAction::PushToTalk).await;
+        }
+    }
+
     fn parse_shortcut(combo: &str) -> Option<Shortcut> {
+        // Unbound action
+        if combo.is_empty() {
+            return None;
+        }
+
         let parts: Vec<&str> = combo.split('+').collect();
         let mut mods = Modifiers::empty();
         let mut code: Option<Code> = None;
@@ -134,6 +134,7 @@ pub fn run() {
             crate::commands::audio::reset_asm,
             crate::commands::audio::start_recording,
             crate::commands::audio::stop_recording,
+            crate::commands::audio::mark_recording,
             crate::commands::audio::get_recording_status,
             crate::commands::audio::is_recording,
             crate::commands::audio::get_current_players,
@@ -141,11 +142,27 @@ pub fn run() {
             // Recordings Management
             crate::commands::recordings::get_recording_sessions,
             crate::commands::recordings::delete_recording_session,
+            crate::commands::recordings::set_recording_pinned,
+            crate::commands::recordings::get_recording_retention,
+            crate::commands::recordings::set_recording_retention,
+            crate::commands::recordings::apply_recording_retention,
             crate::commands::recordings::export_recording,
+            crate::commands::recordings::export_recording_mixdown,
             // Stream Information
             crate::commands::network::stop_network_stream,
             crate::commands::network::change_network_stream,
             crate::commands::network::reset_nsm,
+            crate::commands::network::connect_server,
+            crate::commands::network::disconnect_server,
+            crate::commands::network::set_server_transmit,
+            crate::commands::network::list_server_sessions,
+            crate::commands::network::get_network_diagnostics,
+            // Server browser
+            crate::commands::discovery::list_favourite_servers,
+            crate::commands::discovery::add_favourite_server,
+            crate::commands::discovery::remove_favourite_server,
+            crate::commands::discovery::import_favourite_servers,
+            crate::commands::discovery::probe_servers,
             // API implementation
             crate::api::commands::api_initialize_client,
             crate::api::commands::api_ping,
@@ -320,8 +337,17 @@ pub fn run() {
             // It is responsible for managing recording sessions and owns internal producer/consumer channels
             // for both the input and output stream
             let recording_manager = RecordingManager::new(handle.clone());
+            // Sessions without a final manifest were interrupted mid-recording; repair them before they're listed
+            recording_manager.recover_orphaned_sessions();
+            recording_manager.start_retention_task();
             app.manage(Arc::new(Mutex::new(recording_manager)));
 
+            // SpeakingMonitor turns input and output activity into speaking started/stopped events
+            app.manage(crate::audio::SpeakingMonitor::new(handle.clone()));
+
+            // DiagnosticsCollector keeps the latest statistics of every jitter buffer
+            app.manage(crate::network::diagnostics::DiagnosticsCollector::new());
+
             // Create AudioStreamManager with RecordingManager reference
             let audio_stream = AudioStreamManager::new(
                 handle.state::<Arc<Sender<NetworkPacket>>>().inner().clone(),
@@ -341,6 +367,9 @@ pub fn run() {
             let audio_actions = crate::audio::AudioActionsManager::new(handle.clone());
             app.manage(audio_actions);
 
+            // ChannelActionsManager joins and leaves channels for keybinds and API calls
+            app.manage(crate::api::ChannelActionsManager::new(handle.clone()));
+
             // KeybindManager listens for global key events and triggers actions in AudioActionsManager for desktop
             #[cfg(desktop)]
             {
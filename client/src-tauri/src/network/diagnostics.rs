@@ -0,0 +1,150 @@
+//! Network diagnostics
+//!
+//! Every jitter buffer reports its loss, jitter, concealment and buffer statistics here about
+//! once a second, and every voice server connection tracks its health check round trips and
+//! datagram counts. `collect` puts both together for the diagnostics panel and for the report
+//! bundled into exported logs.
+
+use common::structs::network::{EmitterDiagnostics, NetworkDiagnostics};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::Manager;
+
+use super::NetworkStreamManager;
+
+/// Jitter buffers that stopped reporting this long ago have been dropped
+const EMITTER_STALE_AFTER: Duration = Duration::from_secs(10);
+
+/// Latest report of each jitter buffer, keyed by its identifier
+type EmitterReports = Arc<Mutex<HashMap<String, (Instant, EmitterDiagnostics)>>>;
+
+/// Holds the latest statistics of every jitter buffer. Managed as Tauri state so buffers
+/// created by any output stream report to the same place.
+#[derive(Default)]
+pub(crate) struct DiagnosticsCollector {
+    emitters: EmitterReports,
+}
+
+impl DiagnosticsCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle for a jitter buffer to report through
+    pub fn reporter(&self) -> DiagnosticsReporter {
+        DiagnosticsReporter {
+            emitters: self.emitters.clone(),
+        }
+    }
+
+    /// Jitter buffers that are still reporting, ordered by player
+    pub fn emitters(&self) -> Vec<EmitterDiagnostics> {
+        let mut reports = self.emitters.lock();
+        reports.retain(|_, (reported_at, _)| reported_at.elapsed() < EMITTER_STALE_AFTER);
+
+        let mut emitters: Vec<EmitterDiagnostics> = reports
+            .values()
+            .map(|(_, diagnostics)| diagnostics.clone())
+            .collect();
+        emitters.sort_by(|a, b| (&a.player, a.spatial).cmp(&(&b.player, b.spatial)));
+        emitters
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct DiagnosticsReporter {
+    emitters: EmitterReports,
+}
+
+impl DiagnosticsReporter {
+    /// Called from the audio thread, so a report is skipped rather than waited on when the
+    /// collector is being read
+    pub fn report(&self, identifier: &str, diagnostics: EmitterDiagnostics) {
+        if let Some(mut reports) = self.emitters.try_lock() {
+            reports.insert(identifier.to_string(), (Instant::now(), diagnostics));
+        }
+    }
+}
+
+/// Statistics of every connection and jitter buffer right now
+pub(crate) async fn collect(app_handle: &tauri::AppHandle) -> NetworkDiagnostics {
+    let connections =
+        match app_handle.try_state::<tauri::async_runtime::Mutex<NetworkStreamManager>>() {
+            Some(network_stream) => network_stream.lock().await.diagnostics(),
+            None => Vec::new(),
+        };
+
+    let emitters = app_handle
+        .try_state::<DiagnosticsCollector>()
+        .map(|collector| collector.emitters())
+        .unwrap_or_default();
+
+    NetworkDiagnostics {
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        connections,
+        emitters,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::structs::network::LinkQuality;
+
+    fn emitter(player: &str, spatial: bool) -> EmitterDiagnostics {
+        EmitterDiagnostics {
+            player: player.to_string(),
+            spatial,
+            jitter_ms: 4.0,
+            loss_rate: 0.0,
+            packets_received: 50,
+            packets_lost: 0,
+            frames_decoded: 50,
+            frames_concealed: 0,
+            frames_dropped_overflow: 0,
+            frames_dropped_late: 0,
+            buffer_depth: 3,
+            buffer_capacity: 60,
+            avg_buffer_depth: 3.0,
+            quality: LinkQuality::Excellent,
+            quality_score: 1.0,
+            adaptations: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_latest_report_per_buffer() {
+        let collector = DiagnosticsCollector::new();
+        let reporter = collector.reporter();
+
+        reporter.report("spatial_c3RldmU=", emitter("Steve", true));
+        reporter.report("normal_YWxleA==", emitter("Alex", false));
+        let mut update = emitter("Steve", true);
+        update.packets_received = 100;
+        reporter.report("spatial_c3RldmU=", update);
+
+        let emitters = collector.emitters();
+        assert_eq!(emitters.len(), 2);
+        assert_eq!(emitters[0].player, "Alex");
+        assert_eq!(emitters[1].packets_received, 100);
+    }
+
+    #[test]
+    fn test_stale_reports_dropped() {
+        let collector = DiagnosticsCollector::new();
+        collector.emitters.lock().insert(
+            "normal_c3RldmU=".to_string(),
+            (
+                Instant::now() - EMITTER_STALE_AFTER,
+                emitter("Steve", false),
+            ),
+        );
+
+        assert!(collector.emitters().is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+//! Server browser
+//!
+//! Favourite servers are kept in the store and probed through their public `/api/config`
+//! endpoint, which needs no login. Entries can be imported from a directory endpoint, such as a
+//! Meridian instance listing, or from a local JSON file.
+
+use common::consts::version::{self, VersionCompatibility};
+use common::structs::config::ApiConfig;
+use common::structs::network::{FavouriteServer, ServerSource, ServerStatus};
+use futures_util::future::join_all;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tauri_plugin_store::StoreExt;
+use url::Url;
+
+const FAVOURITES_KEY: &str = "favourite_servers";
+
+/// How long a server has to answer a probe or directory request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Favourite servers in the order they were added
+pub(crate) fn favourites(app_handle: &tauri::AppHandle) -> Vec<FavouriteServer> {
+    app_handle
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get(FAVOURITES_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_favourites(
+    app_handle: &tauri::AppHandle,
+    favourites: &[FavouriteServer],
+) -> Result<(), anyhow::Error> {
+    let store = app_handle.store("store.json")?;
+    store.set(FAVOURITES_KEY, serde_json::to_value(favourites)?);
+    store.save()?;
+    Ok(())
+}
+
+/// Add servers not already in `favourites`, returning how many were added
+pub(crate) fn merge_favourites(
+    favourites: &mut Vec<FavouriteServer>,
+    incoming: Vec<FavouriteServer>,
+) -> usize {
+    let before = favourites.len();
+    for server in incoming {
+        if !favourites.iter().any(|existing| existing.url == server.url) {
+            favourites.push(server);
+        }
+    }
+    favourites.len() - before
+}
+
+/// Same form as the login page: https, no trailing slash. `None` when there is no host.
+pub(crate) fn normalize_url(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let host = trimmed
+        .strip_prefix("https://")
+        .or_else(|| trimmed.strip_prefix("http://"))
+        .unwrap_or(trimmed)
+        .trim_end_matches('/');
+    let url = format!("https://{}", host);
+
+    let parsed = Url::parse(&url).ok()?;
+    match parsed.host_str() {
+        Some(host) if host.len() >= 3 => Some(url),
+        _ => None,
+    }
+}
+
+/// Probe every server at once
+pub(crate) async fn probe_all(servers: &[String]) -> Vec<ServerStatus> {
+    join_all(servers.iter().map(|server| probe(server))).await
+}
+
+/// Fetch a server's public config, timing the request
+pub(crate) async fn probe(server_url: &str) -> ServerStatus {
+    let mut status = ServerStatus {
+        url: server_url.to_string(),
+        online: false,
+        latency_ms: None,
+        protocol_version: None,
+        compatible: false,
+        client_too_old: false,
+        players: None,
+    };
+
+    let client = match http_client() {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Failed to build HTTP client for probe: {}", e);
+            return status;
+        }
+    };
+
+    let base_url = if server_url.starts_with("http://") || server_url.starts_with("https://") {
+        server_url.to_string()
+    } else {
+        format!("https://{}", server_url)
+    };
+    let url = format!("{}/api/config", base_url);
+    log::debug!("Probing server at: {}", url);
+
+    let started = Instant::now();
+    let body = match client.get(&url).send().await {
+        Ok(resp) if resp.status().is_success() => match resp.text().await {
+            Ok(body) => body,
+            Err(e) => {
+                log::warn!("Failed to read response body: {}", e);
+                return status;
+            }
+        },
+        Ok(resp) => {
+            log::debug!("Probe response status: {}", resp.status());
+            return status;
+        }
+        Err(e) => {
+            log::debug!("Probe failed: {}", e);
+            return status;
+        }
+    };
+    let latency = started.elapsed();
+
+    let Some(config) = parse_config(&body) else {
+        log::warn!("Failed to parse ApiConfig response from {}", server_url);
+        return status;
+    };
+
+    status.online = true;
+    status.latency_ms = Some(latency.as_millis().min(u32::MAX as u128) as u32);
+    status.players = config.players;
+    if let Some(protocol_version) = config.protocol_version {
+        let compatibility = version::compatibility(&protocol_version);
+        status.compatible = compatibility == VersionCompatibility::Compatible;
+        status.client_too_old = compatibility == VersionCompatibility::ClientTooOld;
+        status.protocol_version = Some(protocol_version);
+    }
+
+    status
+}
+
+/// Read servers from a directory endpoint (http or https url) or a local JSON file
+pub(crate) async fn load_directory(source: &str) -> Result<Vec<FavouriteServer>, anyhow::Error> {
+    let source = source.trim();
+
+    if source.starts_with("https://") || source.starts_with("http://") {
+        let body = http_client()?
+            .get(source)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        parse_directory(&body, ServerSource::Directory)
+    } else {
+        let body = tokio::fs::read_to_string(source).await?;
+        parse_directory(&body, ServerSource::File)
+    }
+}
+
+fn http_client() -> Result<common::reqwest::Client, common::reqwest::Error> {
+    let mut builder = common::reqwest::Client::builder().timeout(REQUEST_TIMEOUT);
+
+    #[cfg(dev)]
+    {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    builder.build()
+}
+
+/// The parts of `/api/config` the browser shows
+struct ConfigSummary {
+    protocol_version: Option<String>,
+    players: Option<u32>,
+}
+
+fn parse_config(body: &str) -> Option<ConfigSummary> {
+    if let Ok(config) = serde_json::from_str::<ApiConfig>(body) {
+        return Some(ConfigSummary {
+            protocol_version: Some(config.protocol_version),
+            players: config.players,
+        });
+    }
+
+    // Servers from before protocol versions were reported are still BVC servers
+    #[derive(Deserialize)]
+    struct LegacyApiConfig {
+        status: String,
+    }
+
+    match serde_json::from_str::<LegacyApiConfig>(body) {
+        Ok(legacy) if legacy.status == "Ok" => Some(ConfigSummary {
+            protocol_version: None,
+            players: None,
+        }),
+        _ => None,
+    }
+}
+
+/// A directory is a list of entries, either bare or under `servers`, `instances` or `data`
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DirectoryDocument {
+    List(Vec<DirectoryEntry>),
+    Wrapped {
+        #[serde(alias = "instances", alias = "data")]
+        servers: Vec<DirectoryEntry>,
+    },
+}
+
+/// A server url, or an object naming one. Meridian instances give a `hostname` and the
+/// `tcp_addr` the server's API listens on.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DirectoryEntry {
+    Url(String),
+    Server {
+        #[serde(default, alias = "server")]
+        url: Option<String>,
+        #[serde(default)]
+        hostname: Option<String>,
+        #[serde(default)]
+        tcp_addr: Option<String>,
+        #[serde(default)]
+        name: Option<String>,
+    },
+}
+
+impl DirectoryEntry {
+    fn into_favourite(self, source: ServerSource) -> Option<FavouriteServer> {
+        let (url, name) = match self {
+            DirectoryEntry::Url(url) => (normalize_url(&url)?, None),
+            DirectoryEntry::Server {
+                url,
+                hostname,
+                tcp_addr,
+                name,
+            } => {
+                let url = match (url, hostname, tcp_addr) {
+                    (Some(url), _, _) => normalize_url(&url)?,
+                    (None, Some(hostname), tcp_addr) => {
+                        let port = tcp_addr
+                            .as_deref()
+                            .and_then(|addr| addr.rsplit_once(':'))
+                            .map(|(_, port)| port)
+                            .filter(|port| *port != "443");
+                        match port {
+                            Some(port) => normalize_url(&format!("{}:{}", hostname, port))?,
+                            None => normalize_url(&hostname)?,
+                        }
+                    }
+                    (None, None, Some(tcp_addr)) => normalize_url(&tcp_addr)?,
+                    (None, None, None) => return None,
+                };
+                (url, name.filter(|name| !name.trim().is_empty()))
+            }
+        };
+
+        Some(FavouriteServer { url, name, source })
+    }
+}
+
+fn parse_directory(
+    body: &str,
+    source: ServerSource,
+) -> Result<Vec<FavouriteServer>, anyhow::Error> {
+    let entries = match serde_json::from_str::<DirectoryDocument>(body)? {
+        DirectoryDocument::List(entries) => entries,
+        DirectoryDocument::Wrapped { servers } => servers,
+    };
+
+    let mut servers = Vec::new();
+    merge_favourites(
+        &mut servers,
+        entries
+            .into_iter()
+            .filter_map(|entry| entry.into_favourite(source))
+            .collect(),
+    );
+    Ok(servers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_url() {
+        assert_eq!(
+            normalize_url(" bvc.example.com/ "),
+            Some("https://bvc.example.com".to_string())
+        );
+        assert_eq!(
+            normalize_url("http://bvc.example.com:8443"),
+            Some("https://bvc.example.com:8443".to_string())
+        );
+        assert_eq!(normalize_url(""), None);
+        assert_eq!(normalize_url("https://"), None);
+    }
+
+    #[test]
+    fn test_parse_directory() {
+        let meridian = r#"{
+            "instances": [
+                {"name": "Survival", "hostname": "voice.example.com", "tcp_addr": "203.0.113.5:443", "udp_addr": "203.0.113.5:8443", "instance_id": 1},
+                {"hostname": "creative.example.com", "tcp_addr": "203.0.113.6:9443"},
+                {"udp_addr": "203.0.113.7:8443"}
+            ]
+        }"#;
+        let servers = parse_directory(meridian, ServerSource::Directory).unwrap();
+        assert_eq!(
+            servers,
+            vec![
+                FavouriteServer {
+                    url: "https://voice.example.com".to_string(),
+                    name: Some("Survival".to_string()),
+                    source: ServerSource::Directory,
+                },
+                FavouriteServer {
+                    url: "https://creative.example.com:9443".to_string(),
+                    name: None,
+                    source: ServerSource::Directory,
+                },
+            ]
+        );
+
+        let file =
+            r#"["bvc.example.com", {"url": "https://bvc.example.com/", "name": "Duplicate"}]"#;
+        let servers = parse_directory(file, ServerSource::File).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].url, "https://bvc.example.com");
+
+        assert!(parse_directory("{}", ServerSource::File).is_err());
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse_config(
+            r#"{"status":"Ok","client_id":"id","protocol_version":"1.5.0","quic_port":8443,"players":3}"#,
+        )
+        .unwrap();
+        assert_eq!(config.protocol_version.as_deref(), Some("1.5.0"));
+        assert_eq!(config.players, Some(3));
+
+        let legacy = parse_config(r#"{"status":"Ok","client_id":"id"}"#).unwrap();
+        assert_eq!(legacy.protocol_version, None);
+
+        assert!(parse_config("<html></html>").is_none());
+    }
+}
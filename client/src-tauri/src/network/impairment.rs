@@ -0,0 +1,415 @@
+//! Network impairment simulator
+//!
+//! Degrades received audio the way a bad network would, between the QUIC datagram receive
+//! path and the jitter buffers: random and burst loss, reordering, duplication and jitter.
+//! Set `BVC_NETWORK_IMPAIRMENT` before connecting to reproduce bad network playback locally,
+//! for example `BVC_NETWORK_IMPAIRMENT="loss=0.02,burst=0.01:0.3,jitter=40,seed=7"`.
+//!
+//! `Impairment` only decides what happens to each packet, so the jitter buffer replay tests can
+//! run it on simulated time. Delayed packets wait in `DelayedPackets`, which the receive loop
+//! owns, so nothing is delivered after the session ends.
+
+use crate::AudioPacket;
+use rand::rngs::StdRng;
+use rand::{RngExt, SeedableRng};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Environment variable holding the impairment applied to received packets
+const IMPAIRMENT_ENV: &str = "BVC_NETWORK_IMPAIRMENT";
+
+/// How long reordered packets are held back unless configured
+const DEFAULT_REORDER_DELAY_MS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ImpairmentConfig {
+    /// Chance each packet is lost
+    pub loss: f64,
+    /// Gilbert-Elliott burst loss: the chance a delivered packet starts a burst of losses, and
+    /// the chance a lost packet ends it
+    pub burst: Option<(f64, f64)>,
+    /// Chance a packet is held back so the packets after it overtake it
+    pub reorder: f64,
+    /// How long reordered packets are held back
+    pub reorder_delay_ms: u64,
+    /// Chance a packet is delivered twice
+    pub duplicate: f64,
+    /// Largest random delay added to each packet
+    pub jitter_ms: u64,
+    /// Seed for a repeatable run, random when unset
+    pub seed: Option<u64>,
+}
+
+impl Default for ImpairmentConfig {
+    fn default() -> Self {
+        Self {
+            loss: 0.0,
+            burst: None,
+            reorder: 0.0,
+            reorder_delay_ms: DEFAULT_REORDER_DELAY_MS,
+            duplicate: 0.0,
+            jitter_ms: 0,
+            seed: None,
+        }
+    }
+}
+
+impl ImpairmentConfig {
+    /// Impairment requested through `BVC_NETWORK_IMPAIRMENT`, if any
+    pub fn from_env() -> Option<Self> {
+        let value = std::env::var(IMPAIRMENT_ENV).ok()?;
+        match value.parse::<Self>() {
+            Ok(config) => {
+                log::warn!(
+                    "Simulating network impairment on received audio: {:?}",
+                    config
+                );
+                Some(config)
+            }
+            Err(e) => {
+                log::error!("Ignoring {}: {}", IMPAIRMENT_ENV, e);
+                None
+            }
+        }
+    }
+}
+
+/// Parses comma separated `key=value` pairs, e.g. `loss=0.05,reorder=0.1,jitter=30`
+impl FromStr for ImpairmentConfig {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut config = Self::default();
+
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (key, value) = pair
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("Expected key=value, got '{}'", pair))?;
+
+            match key.trim() {
+                "loss" => config.loss = parse_chance(value)?,
+                "burst" => {
+                    let (enter, exit) = value.split_once(':').ok_or_else(|| {
+                        anyhow::anyhow!("Expected burst=<enter>:<exit>, got '{}'", value)
+                    })?;
+                    config.burst = Some((parse_chance(enter)?, parse_chance(exit)?));
+                }
+                "reorder" => config.reorder = parse_chance(value)?,
+                "reorder_delay" => config.reorder_delay_ms = value.trim().parse()?,
+                "duplicate" => config.duplicate = parse_chance(value)?,
+                "jitter" => config.jitter_ms = value.trim().parse()?,
+                "seed" => config.seed = Some(value.trim().parse()?),
+                other => return Err(anyhow::anyhow!("Unknown impairment '{}'", other)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+fn parse_chance(value: &str) -> Result<f64, anyhow::Error> {
+    let chance: f64 = value.trim().parse()?;
+    if !(0.0..=1.0).contains(&chance) {
+        return Err(anyhow::anyhow!("{} is not between 0 and 1", chance));
+    }
+    Ok(chance)
+}
+
+/// Decides the fate of each received packet
+pub(crate) struct Impairment {
+    config: ImpairmentConfig,
+    rng: StdRng,
+    in_burst: bool,
+}
+
+impl Impairment {
+    pub fn new(config: ImpairmentConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::seed_from_u64(rand::random()),
+        };
+
+        Self {
+            config,
+            rng,
+            in_burst: false,
+        }
+    }
+
+    /// Delay before each copy of the next packet is delivered, empty when it is lost
+    pub fn apply(&mut self) -> Vec<Duration> {
+        if let Some((enter, exit)) = self.config.burst {
+            let leave = if self.in_burst { exit } else { enter };
+            if self.rng.random_bool(leave) {
+                self.in_burst = !self.in_burst;
+            }
+            if self.in_burst {
+                return Vec::new();
+            }
+        }
+
+        if self.rng.random_bool(self.config.loss) {
+            return Vec::new();
+        }
+
+        let copies = if self.rng.random_bool(self.config.duplicate) {
+            2
+        } else {
+            1
+        };
+        (0..copies)
+            .map(|_| {
+                let mut delay_ms = self.rng.random_range(0..=self.config.jitter_ms);
+                if self.rng.random_bool(self.config.reorder) {
+                    delay_ms += self.config.reorder_delay_ms;
+                }
+                Duration::from_millis(delay_ms)
+            })
+            .collect()
+    }
+}
+
+/// Packets held back by the impairment until their delay has passed
+#[derive(Default)]
+pub(crate) struct DelayedPackets {
+    /// Keyed by release time, then arrival order so copies due together keep their order
+    held: BTreeMap<(Instant, u64), AudioPacket>,
+    arrivals: u64,
+}
+
+impl DelayedPackets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold each delayed copy of `packet`, returning the copies to deliver right away.
+    /// Undelayed packets keep their order with the rest of the stream.
+    pub fn hold(
+        &mut self,
+        delays: Vec<Duration>,
+        packet: AudioPacket,
+        now: Instant,
+    ) -> Vec<AudioPacket> {
+        let mut immediate = Vec::new();
+        for delay in delays {
+            if delay.is_zero() {
+                immediate.push(packet.clone());
+                continue;
+            }
+
+            self.arrivals += 1;
+            self.held
+                .insert((now + delay, self.arrivals), packet.clone());
+        }
+        immediate
+    }
+
+    /// When the earliest held packet is due
+    pub fn next_due(&self) -> Option<Instant> {
+        self.held.keys().next().map(|(due, _)| *due)
+    }
+
+    /// Remove every packet due at or before `now`, in release order
+    pub fn take_due(&mut self, now: Instant) -> Vec<AudioPacket> {
+        let mut due = Vec::new();
+        while let Some(entry) = self.held.first_entry() {
+            if entry.key().0 > now {
+                break;
+            }
+            due.push(entry.remove());
+        }
+        due
+    }
+}
+
+/// Resolves at `due`, or never when nothing is held
+pub(crate) async fn until_due(due: Option<Instant>) {
+    match due {
+        Some(due) => tokio::time::sleep_until(due).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: ImpairmentConfig, packets: usize) -> Vec<Vec<Duration>> {
+        let mut impairment = Impairment::new(config);
+        (0..packets).map(|_| impairment.apply()).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let config: ImpairmentConfig = "loss=0.05, burst=0.01:0.4,reorder=0.1,jitter=30,seed=7"
+            .parse()
+            .unwrap();
+
+        assert_eq!(config.loss, 0.05);
+        assert_eq!(config.burst, Some((0.01, 0.4)));
+        assert_eq!(config.reorder, 0.1);
+        assert_eq!(config.reorder_delay_ms, DEFAULT_REORDER_DELAY_MS);
+        assert_eq!(config.jitter_ms, 30);
+        assert_eq!(config.seed, Some(7));
+
+        assert!("loss=2".parse::<ImpairmentConfig>().is_err());
+        assert!("latency=20".parse::<ImpairmentConfig>().is_err());
+        assert!("burst=0.1".parse::<ImpairmentConfig>().is_err());
+    }
+
+    #[test]
+    fn test_no_impairment_delivers_everything_at_once() {
+        let fates = run(ImpairmentConfig::default(), 500);
+        assert!(fates.iter().all(|delays| delays == &[Duration::ZERO]));
+    }
+
+    #[test]
+    fn test_seeded_runs_repeat() {
+        let config = ImpairmentConfig {
+            loss: 0.1,
+            duplicate: 0.1,
+            reorder: 0.1,
+            jitter_ms: 40,
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        assert_eq!(run(config.clone(), 500), run(config, 500));
+    }
+
+    #[test]
+    fn test_loss_rate() {
+        let fates = run(
+            ImpairmentConfig {
+                loss: 0.1,
+                seed: Some(1),
+                ..Default::default()
+            },
+            10_000,
+        );
+
+        let lost = fates.iter().filter(|delays| delays.is_empty()).count();
+        assert!((800..1200).contains(&lost), "lost {}", lost);
+    }
+
+    #[test]
+    fn test_burst_loss_is_clustered() {
+        // One burst every 50 packets on average, lasting 4 packets
+        let fates = run(
+            ImpairmentConfig {
+                burst: Some((0.02, 0.25)),
+                seed: Some(3),
+                ..Default::default()
+            },
+            10_000,
+        );
+
+        let lost: Vec<bool> = fates.iter().map(|delays| delays.is_empty()).collect();
+        let losses = lost.iter().filter(|lost| **lost).count();
+        let bursts = lost.windows(2).filter(|pair| !pair[0] && pair[1]).count();
+        assert!(bursts > 0);
+        assert!(losses as f64 / bursts as f64 > 2.5);
+    }
+
+    /// A packet told apart by the server it came from
+    fn packet(server: &str) -> AudioPacket {
+        use common::structs::packet::{
+            HealthCheckPacket, PacketType, QuicNetworkPacket, QuicNetworkPacketData,
+        };
+
+        AudioPacket {
+            data: QuicNetworkPacket {
+                packet_type: PacketType::HealthCheck,
+                owner: None,
+                data: QuicNetworkPacketData::HealthCheck(HealthCheckPacket),
+            },
+            server: server.to_string(),
+            primary: true,
+        }
+    }
+
+    fn servers(packets: &[AudioPacket]) -> Vec<&str> {
+        packets.iter().map(|p| p.server.as_str()).collect()
+    }
+
+    #[test]
+    fn test_delayed_packets_release_in_due_order() {
+        let start = Instant::now();
+        let mut delayed = DelayedPackets::new();
+
+        let now = delayed.hold(
+            vec![Duration::ZERO, Duration::from_millis(40)],
+            packet("a"),
+            start,
+        );
+        assert_eq!(servers(&now), vec!["a"]);
+        assert!(
+            delayed
+                .hold(vec![Duration::from_millis(20)], packet("b"), start)
+                .is_empty()
+        );
+        assert!(
+            delayed
+                .hold(vec![Duration::from_millis(20)], packet("c"), start)
+                .is_empty()
+        );
+
+        assert_eq!(delayed.next_due(), Some(start + Duration::from_millis(20)));
+        assert!(
+            delayed
+                .take_due(start + Duration::from_millis(10))
+                .is_empty()
+        );
+
+        let due = delayed.take_due(start + Duration::from_millis(20));
+        assert_eq!(servers(&due), vec!["b", "c"]);
+        assert_eq!(delayed.next_due(), Some(start + Duration::from_millis(40)));
+
+        let due = delayed.take_due(start + Duration::from_millis(60));
+        assert_eq!(servers(&due), vec!["a"]);
+        assert_eq!(delayed.next_due(), None);
+    }
+
+    #[tokio::test]
+    async fn test_held_packets_are_dropped_with_the_queue() {
+        let (tx, rx) = flume::unbounded::<AudioPacket>();
+        let task = tokio::spawn(async move {
+            let mut delayed = DelayedPackets::new();
+            delayed.hold(vec![Duration::from_millis(50)], packet("a"), Instant::now());
+            loop {
+                until_due(delayed.next_due()).await;
+                for packet in delayed.take_due(Instant::now()) {
+                    _ = tx.send_async(packet).await;
+                }
+            }
+        });
+
+        // Ending the session before the delay passes drops the held packet
+        task.abort();
+        _ = task.await;
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_duplicates_and_reorder_delay() {
+        let fates = run(
+            ImpairmentConfig {
+                duplicate: 1.0,
+                reorder: 1.0,
+                reorder_delay_ms: 100,
+                seed: Some(5),
+                ..Default::default()
+            },
+            10,
+        );
+
+        assert!(
+            fates
+                .iter()
+                .all(|delays| delays == &[Duration::from_millis(100); 2])
+        );
+    }
+}
@@ -1,10 +1,13 @@
 use common::structs::packet::QuicNetworkPacket;
 
+pub(crate) mod diagnostics;
+pub(crate) mod discovery;
+pub(crate) mod impairment;
 mod stream;
 
 use serde::{Deserialize, Serialize};
 
-pub(crate) use stream::NetworkStreamManager;
+pub(crate) use stream::{NetworkStreamManager, ServerAddress};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct NetworkPacket {
@@ -1,16 +1,17 @@
 use bytes::Bytes;
 use common::consts::version::PROTOCOL_VERSION;
 use common::s2n_quic::Connection;
-use common::structs::config::ApiConfig;
-use common::structs::network::ConnectionHealth;
+use common::structs::network::{ConnectionHealth, ServerSessionHealth};
 use common::structs::packet::{
     HealthCheckPacket, PacketOwner, PacketType, QuicNetworkPacket, QuicNetworkPacketData,
 };
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
-use tauri::Emitter;
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, Manager};
 use tokio::task::AbortHandle;
+use tokio::time::Instant;
 
 use super::stream_manager::HealthMonitorState;
 
@@ -39,6 +40,9 @@ pub struct HealthConfig {
     pub timeout: Duration,
     /// Number of consecutive failures before triggering reconnect
     pub max_failures: u32,
+    /// How long to keep pinging a failed connection before reconnecting, giving QUIC the
+    /// chance to migrate it to a new network path
+    pub migration_window: Duration,
 }
 
 impl Default for HealthConfig {
@@ -48,6 +52,7 @@ impl Default for HealthConfig {
             threshold: Duration::from_secs(5),
             timeout: Duration::from_secs(2),
             max_failures: 3,
+            migration_window: Duration::from_secs(8),
         }
     }
 }
@@ -85,6 +90,8 @@ pub struct ConnectionHealthManager {
     shutdown: Arc<AtomicBool>,
     task_handle: Option<AbortHandle>,
     app_handle: tauri::AppHandle,
+    /// Set for connections other than the current server, which report under their own events
+    secondary_server: Option<String>,
     health_config: HealthConfig,
     reconnect_config: ReconnectConfig,
 }
@@ -97,11 +104,19 @@ impl ConnectionHealthManager {
             shutdown: Arc::new(AtomicBool::new(false)),
             task_handle: None,
             app_handle,
+            secondary_server: None,
             health_config: HealthConfig::default(),
             reconnect_config: ReconnectConfig::default(),
         }
     }
 
+    /// Report health as `server_session_health` and ask for a reconnect with
+    /// `server_session_reconnect` instead of refreshing the dashboard
+    pub fn for_secondary_server(mut self, server: String) -> Self {
+        self.secondary_server = Some(server);
+        self
+    }
+
     /// Get the health state for sharing with input stream
     pub fn health_state(&self) -> Arc<HealthMonitorState> {
         self.health_state.clone()
@@ -112,6 +127,11 @@ impl ConnectionHealthManager {
         self.health_state.reset();
     }
 
+    /// Whether health checks have given up on the connection
+    pub fn is_lost(&self) -> bool {
+        self.health_state.failure_count() >= self.health_config.max_failures
+    }
+
     /// Start health monitoring for a connection
     pub fn start(
         &mut self,
@@ -122,13 +142,16 @@ impl ConnectionHealthManager {
         self.stop();
         self.shutdown.store(false, Ordering::Relaxed);
 
-        let _ = self
-            .app_handle
-            .emit("connection_health", ConnectionHealth::Connected);
+        Self::emit_health(
+            &self.app_handle,
+            &self.secondary_server,
+            ConnectionHealth::Connected,
+        );
 
         let health_state = self.health_state.clone();
         let shutdown = self.shutdown.clone();
         let app_handle = self.app_handle.clone();
+        let secondary_server = self.secondary_server.clone();
         let health_config = self.health_config.clone();
         let reconnect_config = self.reconnect_config.clone();
 
@@ -139,6 +162,7 @@ impl ConnectionHealthManager {
                 packet_owner,
                 shutdown,
                 app_handle,
+                secondary_server,
                 server_url,
                 health_config,
                 reconnect_config,
@@ -164,6 +188,7 @@ impl ConnectionHealthManager {
         packet_owner: Option<PacketOwner>,
         shutdown: Arc<AtomicBool>,
         app_handle: tauri::AppHandle,
+        secondary_server: Option<String>,
         server_url: String,
         health_config: HealthConfig,
         reconnect_config: ReconnectConfig,
@@ -187,10 +212,40 @@ impl ConnectionHealthManager {
                 let failures = health_state.on_timeout();
                 if failures >= health_config.max_failures {
                     log::warn!(
-                        "Health check failed {} times, triggering reconnect",
+                        "Health check failed {} times, waiting for the connection to migrate",
                         failures
                     );
-                    Self::probe_and_reconnect(&server_url, &app_handle, &reconnect_config).await;
+                    Self::emit_health(
+                        &app_handle,
+                        &secondary_server,
+                        ConnectionHealth::Reconnecting { attempt: 0 },
+                    );
+
+                    if Self::await_migration(
+                        &connection,
+                        &packet_owner,
+                        &health_state,
+                        &health_config,
+                    )
+                    .await
+                    {
+                        log::info!("Connection recovered on its existing session");
+                        Self::emit_health(
+                            &app_handle,
+                            &secondary_server,
+                            ConnectionHealth::Connected,
+                        );
+                        continue;
+                    }
+
+                    log::warn!("Connection did not recover, triggering reconnect");
+                    Self::probe_and_reconnect(
+                        &server_url,
+                        &app_handle,
+                        &secondary_server,
+                        &reconnect_config,
+                    )
+                    .await;
                     break;
                 } else if failures > 0 {
                     log::debug!("Health check timeout, failure count: {}", failures);
@@ -211,7 +266,7 @@ impl ConnectionHealthManager {
             data: QuicNetworkPacketData::HealthCheck(HealthCheckPacket),
         };
 
-        health_state.set_awaiting(true);
+        health_state.on_health_check_sent();
 
         if let Ok(bytes) = health_packet.to_datagram() {
             let send_result = connection.datagram_mut(
@@ -226,27 +281,53 @@ impl ConnectionHealthManager {
         }
     }
 
-    /// Probe server availability and trigger refresh when ready
+    /// Keep sending health checks over the failed connection for the migration window.
+    /// Returns true once the server answers again.
+    async fn await_migration(
+        connection: &Connection,
+        packet_owner: &Option<PacketOwner>,
+        health_state: &HealthMonitorState,
+        health_config: &HealthConfig,
+    ) -> bool {
+        let deadline = Instant::now() + health_config.migration_window;
+
+        while Instant::now() < deadline {
+            Self::send_health_check(connection, packet_owner, health_state).await;
+            tokio::time::sleep(health_config.check_interval).await;
+
+            // A health check response resets the failure count
+            if health_state.failure_count() == 0 {
+                return true;
+            }
+        }
+
+        health_state.set_awaiting(false);
+        false
+    }
+
+    /// Probe server availability and resume the session when ready
     async fn probe_and_reconnect(
         server_url: &str,
         app_handle: &tauri::AppHandle,
+        secondary_server: &Option<String>,
         config: &ReconnectConfig,
     ) {
         let mut attempt = 0u32;
         let mut delay = config.initial_delay;
 
-        let _ = app_handle.emit("connection_health", ConnectionHealth::Disconnected);
+        Self::emit_health(app_handle, secondary_server, ConnectionHealth::Disconnected);
 
         while attempt < config.max_attempts {
-            let _ = app_handle.emit(
-                "connection_health",
+            Self::emit_health(
+                app_handle,
+                secondary_server,
                 ConnectionHealth::Reconnecting { attempt },
             );
 
             match Self::probe_server(server_url).await {
                 ProbeResult::Available => {
-                    log::info!("Server is back online, triggering refresh...");
-                    let _ = app_handle.emit("trigger_refresh", ());
+                    log::info!("Server {} is back online, resuming session...", server_url);
+                    Self::request_resume(app_handle, secondary_server, server_url);
                     return;
                 }
                 ProbeResult::VersionMismatch {
@@ -260,8 +341,9 @@ impl ConnectionHealthManager {
                         server_version,
                         client_too_old
                     );
-                    let _ = app_handle.emit(
-                        "connection_health",
+                    Self::emit_health(
+                        app_handle,
+                        secondary_server,
                         ConnectionHealth::VersionMismatch {
                             client_version,
                             server_version,
@@ -289,126 +371,86 @@ impl ConnectionHealthManager {
         }
 
         log::error!("Failed to reconnect after {} attempts", config.max_attempts);
-        let _ = app_handle.emit("connection_health", ConnectionHealth::Failed);
+        Self::emit_health(app_handle, secondary_server, ConnectionHealth::Failed);
     }
 
-    /// Probe the server's HTTP endpoint to check availability and version compatibility
-    async fn probe_server(server_url: &str) -> ProbeResult {
-
-        let mut builder = common::reqwest::Client::builder()
-            .timeout(Duration::from_secs(5));
-
-        #[cfg(dev)]
-        {
-            builder = builder.danger_accept_invalid_certs(true);
-        }
+    /// Reconnect the session in place. This runs on its own task because resuming stops the
+    /// session's health monitor. If the session can't be resumed, the dashboard reconnects
+    /// from scratch instead.
+    fn request_resume(
+        app_handle: &tauri::AppHandle,
+        secondary_server: &Option<String>,
+        server_url: &str,
+    ) {
+        let app_handle = app_handle.clone();
+        let secondary_server = secondary_server.clone();
+        let server_url = server_url.to_string();
+
+        tauri::async_runtime::spawn(async move {
+            let resumed = match app_handle.try_state::<Mutex<crate::NetworkStreamManager>>() {
+                Some(network_stream) => network_stream.lock().await.resume(&server_url).await,
+                None => false,
+            };
+            if resumed {
+                return;
+            }
 
-        let client = match builder
-            .build()
-        {
-            Ok(c) => c,
-            Err(e) => {
-                log::warn!("Failed to build HTTP client for probe: {}", e);
-                return ProbeResult::Unavailable;
+            match secondary_server {
+                Some(server) => {
+                    log::info!("Requesting reconnect to {}...", server);
+                    let _ = app_handle.emit("server_session_reconnect", server);
+                }
+                None => {
+                    log::info!("Triggering refresh to reconnect...");
+                    let _ = app_handle.emit("trigger_refresh", ());
+                }
             }
-        };
+        });
+    }
 
-        let base_url = if server_url.starts_with("http://") || server_url.starts_with("https://") {
-            server_url.to_string()
-        } else {
-            format!("https://{}", server_url)
+    fn emit_health(
+        app_handle: &tauri::AppHandle,
+        secondary_server: &Option<String>,
+        health: ConnectionHealth,
+    ) {
+        let _ = match secondary_server {
+            Some(server) => app_handle.emit(
+                "server_session_health",
+                ServerSessionHealth {
+                    server: server.clone(),
+                    health,
+                },
+            ),
+            None => app_handle.emit("connection_health", health),
         };
+    }
 
-        let url = format!("{}/api/config", base_url);
-        log::debug!("Probing server at: {}", url);
+    /// Probe the server's HTTP endpoint to check availability and version compatibility
+    async fn probe_server(server_url: &str) -> ProbeResult {
+        let status = crate::network::discovery::probe(server_url).await;
 
-        match client.get(&url).send().await {
-            Ok(resp) => {
-                log::debug!("Probe response status: {}", resp.status());
-                if !resp.status().is_success() {
-                    return ProbeResult::Unavailable;
-                }
+        if !status.online {
+            return ProbeResult::Unavailable;
+        }
+        if status.compatible {
+            return ProbeResult::Available;
+        }
 
-                // Try to parse as the new ApiConfig format with protocol_version
-                let body = match resp.text().await {
-                    Ok(text) => text,
-                    Err(e) => {
-                        log::warn!("Failed to read response body: {}", e);
-                        return ProbeResult::Unavailable;
-                    }
-                };
-
-                // Try to parse with protocol_version first
-                match serde_json::from_str::<ApiConfig>(&body) {
-                    Ok(config) => {
-                        let server_version = &config.protocol_version;
-                        let client_version = PROTOCOL_VERSION;
-
-                        // Parse versions for comparison (major.minor.patch)
-                        let server_parts: Vec<u32> = server_version
-                            .split('.')
-                            .filter_map(|s| s.parse().ok())
-                            .collect();
-                        let client_parts: Vec<u32> = client_version
-                            .split('.')
-                            .filter_map(|s| s.parse().ok())
-                            .collect();
-
-                        // Compare major and minor versions - both must match (patch can differ)
-                        let server_major = server_parts.first().copied().unwrap_or(0);
-                        let server_minor = server_parts.get(1).copied().unwrap_or(0);
-                        let client_major = client_parts.first().copied().unwrap_or(0);
-                        let client_minor = client_parts.get(1).copied().unwrap_or(0);
-
-                        let compatible = server_major == client_major && server_minor == client_minor;
-                        if !compatible {
-                            let client_too_old = (client_major, client_minor) < (server_major, server_minor);
-                            log::warn!(
-                                "Protocol version mismatch: client={}, server={}, client_too_old={}",
-                                client_version,
-                                server_version,
-                                client_too_old
-                            );
-                            return ProbeResult::VersionMismatch {
-                                client_version: client_version.to_string(),
-                                server_version: server_version.clone(),
-                                client_too_old,
-                            };
-                        }
-
-                        ProbeResult::Available
-                    }
-                    Err(_) => {
-                        // Check if we can parse as legacy ApiConfig (without protocol_version)
-                        // If we can, server is available but outdated - treat as version mismatch
-                        #[derive(serde::Deserialize)]
-                        struct LegacyApiConfig {
-                            status: String,
-                            #[allow(dead_code)]
-                            client_id: String,
-                        }
-
-                        match serde_json::from_str::<LegacyApiConfig>(&body) {
-                            Ok(legacy) if legacy.status == "Ok" => {
-                                log::warn!("Server is running outdated version without protocol_version field");
-                                ProbeResult::VersionMismatch {
-                                    client_version: PROTOCOL_VERSION.to_string(),
-                                    server_version: "unknown (outdated)".to_string(),
-                                    client_too_old: false, // Server is too old
-                                }
-                            }
-                            _ => {
-                                log::warn!("Failed to parse ApiConfig response");
-                                ProbeResult::Unavailable
-                            }
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                log::debug!("Probe failed: {}", e);
-                ProbeResult::Unavailable
-            }
+        // Servers without a protocol version predate it, so they are the outdated side
+        let server_version = status
+            .protocol_version
+            .unwrap_or_else(|| "unknown (outdated)".to_string());
+        log::warn!(
+            "Protocol version mismatch: client={}, server={}, client_too_old={}",
+            PROTOCOL_VERSION,
+            server_version,
+            status.client_too_old
+        );
+
+        ProbeResult::VersionMismatch {
+            client_version: PROTOCOL_VERSION.to_string(),
+            server_version,
+            client_too_old: status.client_too_old,
         }
     }
 }
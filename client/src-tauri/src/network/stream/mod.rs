@@ -1,154 +1,227 @@
 mod health_manager;
+mod session;
 mod stream_manager;
 
 use crate::AudioPacket;
 use crate::NetworkPacket;
-use common::structs::packet::PacketOwner;
-use common::s2n_quic::client::Connect;
-use common::s2n_quic::Client;
+use common::structs::config::LoginResponse;
+use common::structs::network::{ConnectionDiagnostics, ServerSessionStatus};
+use std::collections::HashMap;
 use std::error::Error;
-use std::net::SocketAddr;
 use std::sync::Arc;
-use stream_manager::StreamTrait;
-use stream_manager::StreamTraitType;
+use tauri_plugin_store::StoreExt;
 
-use health_manager::ConnectionHealthManager;
+pub(crate) use session::ServerAddress;
+use session::{ServerSession, SessionRoute};
+
+/// Microphone packet routes shared with the distributor, keyed by server url
+type SessionRoutes = Arc<parking_lot::RwLock<HashMap<String, SessionRoute>>>;
 
 pub(crate) struct NetworkStreamManager {
     producer: Arc<flume::Sender<AudioPacket>>,
-    consumer: Arc<flume::Receiver<NetworkPacket>>,
-    input: StreamTraitType,
-    output: StreamTraitType,
     app_handle: tauri::AppHandle,
-    health_manager: ConnectionHealthManager,
+    /// Url of the current server, whose connection health drives the dashboard
+    primary: Option<String>,
+    sessions: HashMap<String, ServerSession>,
+    routes: SessionRoutes,
 }
 
 impl NetworkStreamManager {
     /// Initializes the NetworkStreamManager
-    /// By default, this doesn't do anything accept setup the StreamTraitTypes
-    /// The stream will not start until it is connected
+    /// By default, this only starts forwarding microphone packets to the connected servers
+    /// The stream will not start until a server is connected
     pub fn new(
         producer: Arc<flume::Sender<AudioPacket>>,
         consumer: Arc<flume::Receiver<NetworkPacket>>,
         app_handle: tauri::AppHandle,
     ) -> Self {
-        let health_manager = ConnectionHealthManager::new(app_handle.clone());
+        let routes: SessionRoutes = Arc::new(parking_lot::RwLock::new(HashMap::new()));
+        tauri::async_runtime::spawn(Self::distribute(consumer, routes.clone()));
 
         Self {
-            producer: producer.clone(),
-            consumer: consumer.clone(),
-            input: StreamTraitType::Input(stream_manager::InputStream::new(
-                producer.clone(),
-                None,
-                app_handle.clone(),
-                health_manager.health_state(),
-            )),
-            output: StreamTraitType::Output(stream_manager::OutputStream::new(
-                consumer.clone(),
-                None,
-                None,
-                app_handle.clone(),
-            )),
-            app_handle: app_handle.clone(),
-            health_manager,
+            producer,
+            app_handle,
+            primary: None,
+            sessions: HashMap::new(),
+            routes,
         }
     }
 
-    /// Initializes a new network connection to the server, and immediately begins
+    /// Initializes a new network connection to the current server, and immediately begins
+    /// Servers connected alongside the previous one stay connected
     pub async fn restart(
         &mut self,
-        server_fqdn: String,
-        server_url: String,
-        socket: SocketAddr,
-        name: String,
-        ca_cert: String,
-        cert: String,
-        key: String,
+        address: ServerAddress,
+        credentials: &LoginResponse,
     ) -> Result<(), Box<dyn Error>> {
-        self.stop().await?;
+        if let Some(primary) = self.primary.take() {
+            self.remove(&primary).await?;
+        }
+        // Promote an additional connection to the same server instead of connecting twice
+        self.remove(&address.url).await?;
 
-        let provider = common::rustls::MtlsProvider::new_from_vec(
-            ca_cert.as_bytes().to_vec(),
-            cert.as_bytes().to_vec(),
-            key.as_bytes().to_vec(),
-        )
-        .await?;
+        let server_url = address.url.clone();
+        self.connect(address, credentials, true).await?;
+        self.primary = Some(server_url);
+
+        Ok(())
+    }
+
+    /// Connects to another server alongside the current one. Its players are heard through the
+    /// same output, and the microphone is sent to it unless transmit was turned off for it.
+    pub async fn add(
+        &mut self,
+        address: ServerAddress,
+        credentials: &LoginResponse,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.primary.as_deref() == Some(address.url.as_str()) {
+            return Err("Server is already connected as the current server".into());
+        }
+        self.remove(&address.url).await?;
 
-        let dg_endpoint = common::s2n_quic::provider::datagram::default::Endpoint::builder()
-            .with_send_capacity(1024)
-            .expect("send cap > 0")
-            .with_recv_capacity(1024)
-            .expect("recv cap > 0")
-            .build()
-            .expect("build dg endpoint");
-
-        let client = Client::builder()
-            .with_tls(provider)?
-            .with_io("0.0.0.0:0")?
-            .with_datagram(dg_endpoint)?
-            .start()?;
-
-        let connect = Connect::new(socket).with_server_name(server_fqdn);
-
-        let mut connection = client.connect(connect).await?;
-        connection.keep_alive(true)?;
-        let conn_arc = Arc::new(connection);
-        self.health_manager.reset();
-
-        let packet_owner = PacketOwner {
-            name,
-            client_id: (0..32).map(|_| rand::random::<u8>()).collect(),
+        self.connect(address, credentials, false).await
+    }
+
+    /// Reconnects a session whose connection was lost, keeping its place on the server.
+    /// Returns false when the session is gone or could not reconnect.
+    pub async fn resume(&mut self, server_url: &str) -> bool {
+        let Some(session) = self.sessions.get_mut(server_url) else {
+            return false;
         };
+        // Reconnected some other way in the meantime
+        if !session.is_lost() {
+            return true;
+        }
 
-        self.input = StreamTraitType::Input(stream_manager::InputStream::new(
-            self.producer.clone(),
-            Some(conn_arc.clone()),
-            self.app_handle.clone(),
-            self.health_manager.health_state(),
-        ));
+        match session.resume().await {
+            Ok(()) => {
+                log::info!("Resumed session with {}", server_url);
+                true
+            }
+            Err(e) => {
+                log::warn!("Failed to resume session with {}: {}", server_url, e);
+                false
+            }
+        }
+    }
 
-        self.output = StreamTraitType::Output(stream_manager::OutputStream::new(
-            self.consumer.clone(),
-            Some(packet_owner.clone()),
-            Some(conn_arc.clone()),
-            self.app_handle.clone(),
-        ));
+    /// Disconnects from a single server
+    pub async fn remove(&mut self, server_url: &str) -> Result<(), anyhow::Error> {
+        self.routes.write().remove(server_url);
+        if self.primary.as_deref() == Some(server_url) {
+            self.primary = None;
+        }
+
+        match self.sessions.remove(server_url) {
+            Some(mut session) => session.stop().await,
+            None => Ok(()),
+        }
+    }
 
-        self.input.start().await?;
-        self.output.start().await?;
-        self.health_manager
-            .start(conn_arc, Some(packet_owner), server_url);
+    /// Choose whether the microphone is sent to `server_url`. The choice is kept in the store
+    /// and applied whenever the server is connected again.
+    pub fn set_transmit(&self, server_url: &str, transmit: bool) -> Result<(), anyhow::Error> {
+        let store = self.app_handle.store("store.json")?;
+        let mut transmit_store = Self::transmit_store(&self.app_handle);
+        transmit_store.insert(server_url.to_string(), transmit);
+        store.set("server_transmit", serde_json::to_value(&transmit_store)?);
+        store.save()?;
+
+        if let Some(session) = self.sessions.get(server_url) {
+            session.set_transmit(transmit);
+        }
 
         Ok(())
     }
 
+    /// Connected servers, the current one first
+    pub fn sessions(&self) -> Vec<ServerSessionStatus> {
+        let mut sessions: Vec<ServerSessionStatus> = self
+            .sessions
+            .iter()
+            .map(|(server, session)| ServerSessionStatus {
+                server: server.clone(),
+                primary: self.primary.as_deref() == Some(server.as_str()),
+                transmit: session.is_transmitting(),
+            })
+            .collect();
+        sessions.sort_by_key(|session| (!session.primary, session.server.clone()));
+        sessions
+    }
+
+    /// Statistics of every connection, the current server first
+    pub fn diagnostics(&self) -> Vec<ConnectionDiagnostics> {
+        let mut connections: Vec<ConnectionDiagnostics> = self
+            .sessions
+            .iter()
+            .map(|(server, session)| {
+                session.diagnostics(self.primary.as_deref() == Some(server.as_str()))
+            })
+            .collect();
+        connections.sort_by_key(|connection| (!connection.primary, connection.server.clone()));
+        connections
+    }
+
     pub async fn stop(&mut self) -> Result<(), anyhow::Error> {
-        self.health_manager.stop();
-        self.input.stop().await?;
-        self.output.stop().await?;
+        self.routes.write().clear();
+        self.primary = None;
+
+        for (_, mut session) in self.sessions.drain() {
+            session.stop().await?;
+        }
 
         Ok(())
     }
 
     pub async fn reset(&mut self) -> Result<(), anyhow::Error> {
-        self.health_manager.stop();
-        let (_, _) = tokio::join!(self.input.stop(), self.output.stop());
-        self.health_manager.reset();
+        self.stop().await
+    }
 
-        self.input = StreamTraitType::Input(stream_manager::InputStream::new(
+    async fn connect(
+        &mut self,
+        address: ServerAddress,
+        credentials: &LoginResponse,
+        primary: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let server_url = address.url.clone();
+        let transmit = Self::transmit_store(&self.app_handle)
+            .get(&server_url)
+            .copied()
+            .unwrap_or(true);
+
+        let session = ServerSession::connect(
             self.producer.clone(),
-            None,
             self.app_handle.clone(),
-            self.health_manager.health_state(),
-        ));
+            address,
+            credentials,
+            primary,
+            transmit,
+        )
+        .await?;
 
-        self.output = StreamTraitType::Output(stream_manager::OutputStream::new(
-            self.consumer.clone(),
-            None,
-            None,
-            self.app_handle.clone(),
-        ));
+        self.routes
+            .write()
+            .insert(server_url.clone(), session.route());
+        self.sessions.insert(server_url, session);
 
         Ok(())
     }
+
+    fn transmit_store(app_handle: &tauri::AppHandle) -> HashMap<String, bool> {
+        app_handle
+            .store("store.json")
+            .ok()
+            .and_then(|store| store.get("server_transmit"))
+            .and_then(|v| serde_json::from_value(v).ok())
+            .unwrap_or_default()
+    }
+
+    /// Forwards every microphone packet to each server that is transmitting
+    async fn distribute(consumer: Arc<flume::Receiver<NetworkPacket>>, routes: SessionRoutes) {
+        while let Ok(packet) = consumer.recv_async().await {
+            for route in routes.read().values() {
+                route.send(&packet);
+            }
+        }
+    }
 }
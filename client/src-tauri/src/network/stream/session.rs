@@ -0,0 +1,242 @@
+use crate::network::impairment::ImpairmentConfig;
+use crate::AudioPacket;
+use crate::NetworkPacket;
+use common::s2n_quic::client::Connect;
+use common::s2n_quic::Client;
+use common::structs::config::LoginResponse;
+use common::structs::network::ConnectionDiagnostics;
+use common::structs::packet::PacketOwner;
+use std::error::Error;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::health_manager::ConnectionHealthManager;
+use super::stream_manager::{self, StreamTrait, StreamTraitType};
+
+/// Microphone packets buffered per connection before new ones are dropped
+const SESSION_BUFFER: usize = 10000;
+
+/// A resolved voice server
+#[derive(Debug, Clone)]
+pub(crate) struct ServerAddress {
+    /// Hostname used for DNS and SNI
+    pub fqdn: String,
+    /// Server url as stored in the server list, identifying the session
+    pub url: String,
+    pub socket: SocketAddr,
+}
+
+/// Where the distributor sends microphone packets for one session
+#[derive(Clone)]
+pub(super) struct SessionRoute {
+    sender: flume::Sender<NetworkPacket>,
+    transmit: Arc<AtomicBool>,
+}
+
+impl SessionRoute {
+    pub fn send(&self, packet: &NetworkPacket) {
+        if self.transmit.load(Ordering::Relaxed) {
+            // Drop rather than hold up the other servers when this connection falls behind
+            let _ = self.sender.try_send(packet.clone());
+        }
+    }
+}
+
+/// A QUIC connection to one voice server. Received audio goes to the shared output producer,
+/// so every session plays through the same mixer.
+pub(super) struct ServerSession {
+    input: StreamTraitType,
+    output: StreamTraitType,
+    health_manager: ConnectionHealthManager,
+    route: SessionRoute,
+    receiver: Arc<flume::Receiver<NetworkPacket>>,
+    producer: Arc<flume::Sender<AudioPacket>>,
+    app_handle: tauri::AppHandle,
+    address: ServerAddress,
+    credentials: LoginResponse,
+    primary: bool,
+    /// Presented on every connection, so the server keeps the session across reconnects
+    resume_token: Vec<u8>,
+}
+
+impl ServerSession {
+    /// Connects to the server and starts streaming. Only the primary session reports health
+    /// under the dashboard's `connection_health` event.
+    pub async fn connect(
+        producer: Arc<flume::Sender<AudioPacket>>,
+        app_handle: tauri::AppHandle,
+        address: ServerAddress,
+        credentials: &LoginResponse,
+        primary: bool,
+        transmit: bool,
+    ) -> Result<Self, Box<dyn Error>> {
+        let (sender, receiver) = flume::bounded::<NetworkPacket>(SESSION_BUFFER);
+        let route = SessionRoute {
+            sender,
+            transmit: Arc::new(AtomicBool::new(transmit)),
+        };
+        let receiver = Arc::new(receiver);
+        let resume_token: Vec<u8> = (0..32).map(|_| rand::random::<u8>()).collect();
+
+        let (input, output, health_manager) = Self::open(
+            &producer,
+            &app_handle,
+            &address,
+            credentials,
+            primary,
+            &receiver,
+            &resume_token,
+        )
+        .await?;
+
+        Ok(Self {
+            input,
+            output,
+            health_manager,
+            route,
+            receiver,
+            producer,
+            app_handle,
+            address,
+            credentials: credentials.clone(),
+            primary,
+            resume_token,
+        })
+    }
+
+    /// Replaces a lost connection with a new one to the same server. The server recognizes the
+    /// resume token, so other players see no disconnect and channel membership is kept.
+    pub async fn resume(&mut self) -> Result<(), anyhow::Error> {
+        self.stop().await?;
+
+        let (input, output, health_manager) = Self::open(
+            &self.producer,
+            &self.app_handle,
+            &self.address,
+            &self.credentials,
+            self.primary,
+            &self.receiver,
+            &self.resume_token,
+        )
+        .await
+        .map_err(|e| anyhow::anyhow!("{}", e))?;
+
+        self.input = input;
+        self.output = output;
+        self.health_manager = health_manager;
+
+        Ok(())
+    }
+
+    /// Whether health checks gave up on the connection
+    pub fn is_lost(&self) -> bool {
+        self.health_manager.is_lost()
+    }
+
+    /// Round trip and datagram statistics of the current connection
+    pub fn diagnostics(&self, primary: bool) -> ConnectionDiagnostics {
+        self.health_manager
+            .health_state()
+            .diagnostics(self.address.url.clone(), primary)
+    }
+
+    pub fn route(&self) -> SessionRoute {
+        self.route.clone()
+    }
+
+    pub fn is_transmitting(&self) -> bool {
+        self.route.transmit.load(Ordering::Relaxed)
+    }
+
+    pub fn set_transmit(&self, transmit: bool) {
+        self.route.transmit.store(transmit, Ordering::Relaxed);
+    }
+
+    pub async fn stop(&mut self) -> Result<(), anyhow::Error> {
+        self.health_manager.stop();
+        self.input.stop().await?;
+        self.output.stop().await?;
+
+        Ok(())
+    }
+
+    /// Opens the QUIC connection and starts its streams. The endpoint is bound to the
+    /// unspecified address, so after a network change packets leave through whichever
+    /// interface now reaches the server and the server migrates the connection to that path.
+    async fn open(
+        producer: &Arc<flume::Sender<AudioPacket>>,
+        app_handle: &tauri::AppHandle,
+        address: &ServerAddress,
+        credentials: &LoginResponse,
+        primary: bool,
+        receiver: &Arc<flume::Receiver<NetworkPacket>>,
+        resume_token: &[u8],
+    ) -> Result<(StreamTraitType, StreamTraitType, ConnectionHealthManager), Box<dyn Error>> {
+        let provider = common::rustls::MtlsProvider::new_from_vec(
+            credentials.certificate_ca.as_bytes().to_vec(),
+            credentials.certificate.as_bytes().to_vec(),
+            credentials.certificate_key.as_bytes().to_vec(),
+        )
+        .await?;
+
+        let dg_endpoint = common::s2n_quic::provider::datagram::default::Endpoint::builder()
+            .with_send_capacity(1024)
+            .expect("send cap > 0")
+            .with_recv_capacity(1024)
+            .expect("recv cap > 0")
+            .build()
+            .expect("build dg endpoint");
+
+        let client = Client::builder()
+            .with_tls(provider)?
+            .with_io("0.0.0.0:0")?
+            .with_datagram(dg_endpoint)?
+            .start()?;
+
+        let connect = Connect::new(address.socket).with_server_name(address.fqdn.clone());
+
+        let mut connection = client.connect(connect).await?;
+        connection.keep_alive(true)?;
+        let conn_arc = Arc::new(connection);
+
+        let mut health_manager = ConnectionHealthManager::new(app_handle.clone());
+        if !primary {
+            health_manager = health_manager.for_secondary_server(address.url.clone());
+        }
+
+        let packet_owner = PacketOwner {
+            name: credentials.gamertag.clone(),
+            client_id: (0..32).map(|_| rand::random::<u8>()).collect(),
+        };
+
+        let mut input = StreamTraitType::Input(
+            stream_manager::InputStream::new(
+                producer.clone(),
+                Some(conn_arc.clone()),
+                app_handle.clone(),
+                health_manager.health_state(),
+                address.url.clone(),
+                primary,
+            )
+            .with_impairment(ImpairmentConfig::from_env()),
+        );
+
+        let mut output = StreamTraitType::Output(
+            stream_manager::OutputStream::new(
+                receiver.clone(),
+                Some(packet_owner.clone()),
+                Some(conn_arc.clone()),
+                app_handle.clone(),
+            )
+            .with_resume_token(resume_token.to_vec())
+            .with_health_state(health_manager.health_state()),
+        );
+
+        input.start().await?;
+        output.start().await?;
+        health_manager.start(conn_arc, Some(packet_owner), address.url.clone());
+
+        Ok((input, output, health_manager))
+    }
+}
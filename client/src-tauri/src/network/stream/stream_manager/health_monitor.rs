@@ -1,6 +1,10 @@
+use common::structs::network::ConnectionDiagnostics;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Stored in place of a round trip until the first health check is answered
+const NO_RTT: u32 = u32::MAX;
+
 /// Health monitor state for tracking connection health
 /// Used to detect disconnections and trigger reconnection
 pub struct HealthMonitorState {
@@ -10,36 +14,58 @@ pub struct HealthMonitorState {
     awaiting_response: AtomicBool,
     /// Number of consecutive health check failures
     failure_count: AtomicU32,
+    /// When the pending health check was sent (Unix timestamp in milliseconds)
+    health_check_sent_at: AtomicU64,
+    last_rtt_ms: AtomicU32,
+    smoothed_rtt_ms: AtomicU32,
+    datagrams_received: AtomicU64,
+    datagrams_sent: AtomicU64,
+    datagrams_dropped: AtomicU64,
 }
 
 impl HealthMonitorState {
     pub fn new() -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
-
         Self {
-            last_packet_received: AtomicU64::new(now),
+            last_packet_received: AtomicU64::new(now_ms()),
             awaiting_response: AtomicBool::new(false),
             failure_count: AtomicU32::new(0),
+            health_check_sent_at: AtomicU64::new(0),
+            last_rtt_ms: AtomicU32::new(NO_RTT),
+            smoothed_rtt_ms: AtomicU32::new(NO_RTT),
+            datagrams_received: AtomicU64::new(0),
+            datagrams_sent: AtomicU64::new(0),
+            datagrams_dropped: AtomicU64::new(0),
         }
     }
 
     /// Called when any packet is received from the server
     /// Updates the last_packet_received timestamp
     pub fn on_packet_received(&self) {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
-        self.last_packet_received.store(now, Ordering::Relaxed);
+        self.last_packet_received.store(now_ms(), Ordering::Relaxed);
+    }
+
+    /// Called when a health check is sent
+    /// Sets the awaiting flag and starts timing the round trip
+    pub fn on_health_check_sent(&self) {
+        self.health_check_sent_at.store(now_ms(), Ordering::Relaxed);
+        self.awaiting_response.store(true, Ordering::Relaxed);
     }
 
     /// Called when a health check response is received
-    /// Clears the awaiting flag and resets failure count
+    /// Clears the awaiting flag, records the round trip and resets failure count
     pub fn on_health_check_received(&self) {
-        self.awaiting_response.store(false, Ordering::Relaxed);
+        if self.awaiting_response.swap(false, Ordering::Relaxed) {
+            let sent_at = self.health_check_sent_at.load(Ordering::Relaxed);
+            let rtt = now_ms().saturating_sub(sent_at).min((NO_RTT - 1) as u64) as u32;
+            self.last_rtt_ms.store(rtt, Ordering::Relaxed);
+
+            // Smoothed the way TCP smooths its round trip estimate
+            let smoothed = match self.smoothed_rtt_ms.load(Ordering::Relaxed) {
+                NO_RTT => rtt,
+                smoothed => ((smoothed as u64 * 7 + rtt as u64) / 8) as u32,
+            };
+            self.smoothed_rtt_ms.store(smoothed, Ordering::Relaxed);
+        }
         self.failure_count.store(0, Ordering::Relaxed);
         self.on_packet_received();
     }
@@ -51,12 +77,8 @@ impl HealthMonitorState {
             return false;
         }
 
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_millis() as u64;
         let last = self.last_packet_received.load(Ordering::Relaxed);
-        let elapsed_ms = now.saturating_sub(last);
+        let elapsed_ms = now_ms().saturating_sub(last);
 
         elapsed_ms >= threshold.as_millis() as u64
     }
@@ -83,6 +105,48 @@ impl HealthMonitorState {
         self.failure_count.load(Ordering::Relaxed)
     }
 
+    /// Called for every datagram received from the server
+    pub fn on_datagram_received(&self) {
+        self.datagrams_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Called for every datagram handed to the connection, `sent` being false when it was refused
+    pub fn on_datagram_sent(&self, sent: bool) {
+        let counter = if sent {
+            &self.datagrams_sent
+        } else {
+            &self.datagrams_dropped
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Round trip and datagram statistics of the connection
+    pub fn diagnostics(&self, server: String, primary: bool) -> ConnectionDiagnostics {
+        let rtt = |value: &AtomicU32| match value.load(Ordering::Relaxed) {
+            NO_RTT => None,
+            rtt => Some(rtt),
+        };
+        let datagrams_sent = self.datagrams_sent.load(Ordering::Relaxed);
+        let datagrams_dropped = self.datagrams_dropped.load(Ordering::Relaxed);
+        let attempted = datagrams_sent + datagrams_dropped;
+
+        ConnectionDiagnostics {
+            server,
+            primary,
+            rtt_ms: rtt(&self.last_rtt_ms),
+            smoothed_rtt_ms: rtt(&self.smoothed_rtt_ms),
+            failed_health_checks: self.failure_count(),
+            datagrams_received: self.datagrams_received.load(Ordering::Relaxed),
+            datagrams_sent,
+            datagrams_dropped,
+            datagram_drop_rate: if attempted == 0 {
+                0.0
+            } else {
+                datagrams_dropped as f64 / attempted as f64
+            },
+        }
+    }
+
     /// Reset the health monitor state (e.g., on successful reconnect)
     pub fn reset(&self) {
         self.on_packet_received();
@@ -97,6 +161,13 @@ impl Default for HealthMonitorState {
     }
 }
 
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,6 +242,41 @@ mod tests {
         assert!(!state.should_send_health_check(Duration::from_millis(50)));
     }
 
+    #[test]
+    fn test_round_trip_measured_from_answered_checks() {
+        let state = HealthMonitorState::new();
+        assert_eq!(state.diagnostics("server".to_string(), true).rtt_ms, None);
+
+        state.on_health_check_sent();
+        sleep(Duration::from_millis(20));
+        state.on_health_check_received();
+
+        let diagnostics = state.diagnostics("server".to_string(), true);
+        assert!(diagnostics.rtt_ms.unwrap() >= 20);
+        assert_eq!(diagnostics.smoothed_rtt_ms, diagnostics.rtt_ms);
+
+        // A response after the check timed out is not a round trip
+        state.on_health_check_received();
+        assert_eq!(
+            state.diagnostics("server".to_string(), true).rtt_ms,
+            diagnostics.rtt_ms
+        );
+    }
+
+    #[test]
+    fn test_datagram_drop_rate() {
+        let state = HealthMonitorState::new();
+        for _ in 0..3 {
+            state.on_datagram_sent(true);
+        }
+        state.on_datagram_sent(false);
+
+        let diagnostics = state.diagnostics("server".to_string(), false);
+        assert_eq!(diagnostics.datagrams_sent, 3);
+        assert_eq!(diagnostics.datagrams_dropped, 1);
+        assert_eq!(diagnostics.datagram_drop_rate, 0.25);
+    }
+
     #[test]
     fn test_reset() {
         let state = HealthMonitorState::new();
@@ -1,3 +1,4 @@
+use crate::network::impairment::{self, DelayedPackets, Impairment, ImpairmentConfig};
 use crate::AudioPacket;
 use bytes::Bytes;
 use common::structs::packet::{PacketType, QuicNetworkPacket};
@@ -27,6 +28,10 @@ pub(crate) struct InputStream {
     pub metadata: Arc<moka::future::Cache<String, String>>,
     app_handle: tauri::AppHandle,
     pub health_state: Arc<HealthMonitorState>,
+    /// Url of the server this stream receives from
+    server: String,
+    primary: bool,
+    impairment: Option<ImpairmentConfig>,
 }
 
 impl common::traits::StreamTrait for InputStream {
@@ -60,9 +65,27 @@ impl common::traits::StreamTrait for InputStream {
         let shutdown = self.shutdown.clone();
         let app_handle = self.app_handle.clone();
         let health_state = self.health_state.clone();
+        let server = self.server.clone();
+        let primary = self.primary;
+        let mut impairment = self.impairment.clone().map(Impairment::new);
         jobs.push(tokio::spawn(async move {
             log::info!("Started network recv stream.");
-            while let Ok(bytes) = recv_one_datagram(&connection).await {
+            // Owned by this task, so packets still held back are dropped when the stream stops
+            let mut delayed = DelayedPackets::new();
+            loop {
+                let bytes = tokio::select! {
+                    result = recv_one_datagram(&connection) => match result {
+                        Ok(bytes) => bytes,
+                        Err(_) => break,
+                    },
+                    _ = impairment::until_due(delayed.next_due()) => {
+                        for packet in delayed.take_due(tokio::time::Instant::now()) {
+                            _ = tx.send_async(packet).await;
+                        }
+                        continue;
+                    }
+                };
+
                 if shutdown.load(Ordering::Relaxed) {
                     warn!("Network stream input handler stopped.");
                     break;
@@ -70,6 +93,7 @@ impl common::traits::StreamTrait for InputStream {
                 match QuicNetworkPacket::from_datagram(&bytes) {
                     Ok(packet) => {
                         health_state.on_packet_received();
+                        health_state.on_datagram_received();
 
                         if packet.packet_type == PacketType::HealthCheck {
                             health_state.on_health_check_received();
@@ -77,7 +101,22 @@ impl common::traits::StreamTrait for InputStream {
                             continue;
                         }
 
-                        _ = tx.send_async(AudioPacket { data: packet }).await;
+                        let packet = AudioPacket {
+                            data: packet,
+                            server: server.clone(),
+                            primary,
+                        };
+                        match impairment.as_mut() {
+                            Some(simulated) => {
+                                let now = tokio::time::Instant::now();
+                                for packet in delayed.hold(simulated.apply(), packet, now) {
+                                    _ = tx.send_async(packet).await;
+                                }
+                            }
+                            None => {
+                                _ = tx.send_async(packet).await;
+                            }
+                        }
                     }
                     Err(e) => {
                         error!("Couldn't decode datagram packet. {:?}", e);
@@ -110,6 +149,8 @@ impl InputStream {
         connection: Option<Arc<Connection>>,
         app_handle: tauri::AppHandle,
         health_state: Arc<HealthMonitorState>,
+        server: String,
+        primary: bool,
     ) -> Self {
         Self {
             bus: producer.clone(),
@@ -119,8 +160,17 @@ impl InputStream {
             metadata: Arc::new(moka::future::Cache::builder().build()),
             app_handle: app_handle.clone(),
             health_state,
+            server,
+            primary,
+            impairment: None,
         }
     }
+
+    /// Degrade received audio before it reaches the jitter buffers
+    pub fn with_impairment(mut self, impairment: Option<ImpairmentConfig>) -> Self {
+        self.impairment = impairment;
+        self
+    }
 }
 
 struct RecvDatagram<'c> {
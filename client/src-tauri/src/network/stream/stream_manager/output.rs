@@ -1,6 +1,6 @@
 use crate::NetworkPacket;
 use bytes::Bytes;
-use common::structs::packet::{DebugPacket, PacketOwner, QuicNetworkPacket};
+use common::structs::packet::{DebugPacket, PacketOwner, QuicNetworkPacket, SessionResumePacket};
 use log::{error, info, warn};
 use common::s2n_quic::Connection;
 use std::sync::{
@@ -10,8 +10,13 @@ use std::sync::{
 use tauri::Emitter;
 use tokio::{task::AbortHandle, time::Instant};
 
+use super::HealthMonitorState;
+
 use common::consts::version::PROTOCOL_VERSION as CLIENT_VERSION;
 
+/// Copies of the session resume token sent at the start of each connection
+const RESUME_TOKEN_COPIES: usize = 3;
+
 /// The OutputStream consumes PCM NetworkPackets from the AudioStreamManager::InputStream
 /// Then sends it to the server
 pub(crate) struct OutputStream {
@@ -22,6 +27,10 @@ pub(crate) struct OutputStream {
     shutdown: Arc<AtomicBool>,
     pub metadata: Arc<moka::future::Cache<String, String>>,
     app_handle: tauri::AppHandle,
+    /// Sent ahead of everything else so the server can resume the session after a reconnect
+    resume_token: Option<Vec<u8>>,
+    /// Counts sent and refused datagrams for diagnostics
+    health_state: Option<Arc<HealthMonitorState>>,
 }
 
 impl common::traits::StreamTrait for OutputStream {
@@ -57,10 +66,34 @@ impl common::traits::StreamTrait for OutputStream {
         let connection = self.connection.clone().unwrap();
         let packet_owner = self.packet_owner.clone();
         let app_handle = self.app_handle.clone();
+        let resume_token = self.resume_token.clone();
+        let health_state = self.health_state.clone();
 
         let shutdown = self.shutdown.clone();
         jobs.push(tokio::spawn(async move {
 
+            if let Some(token) = resume_token {
+                let resume_packet = QuicNetworkPacket {
+                    packet_type: common::structs::packet::PacketType::SessionResume,
+                    owner: packet_owner.clone(),
+                    data: common::structs::packet::QuicNetworkPacketData::SessionResume(
+                        SessionResumePacket { token }
+                    )
+                };
+
+                // Datagrams can be lost, so send a few copies. The server takes the first to arrive,
+                // even after the connection has already identified the player.
+                match resume_packet.to_datagram() {
+                    Ok(bytes) => {
+                        let payload = Bytes::from(bytes);
+                        for _ in 0..RESUME_TOKEN_COPIES {
+                            if let Err(e) = connection.datagram_mut(|dg: &mut common::s2n_quic::provider::datagram::default::Sender| dg.send_datagram(payload.clone())) { error!("Session resume datagram send error: {:?}", e); }
+                        }
+                    }
+                    Err(e) => { error!("Failed to serialize session resume packet: {:?}", e); }
+                }
+            }
+
             // Send a DEBUG Packet to initialize the stream on the server
             let debug_packet = QuicNetworkPacket {
                 packet_type: common::structs::packet::PacketType::Debug,
@@ -101,6 +134,9 @@ impl common::traits::StreamTrait for OutputStream {
                             Ok(bytes) => {
                                 let payload = Bytes::from(bytes);
                                 let send_res = connection.datagram_mut(|dg: &mut common::s2n_quic::provider::datagram::default::Sender| dg.send_datagram(payload.clone()));
+                                if let Some(health_state) = &health_state {
+                                    health_state.on_datagram_sent(matches!(send_res, Ok(Ok(()))));
+                                }
                                 if let Err(e) = send_res {
                                     error_count += 1;
                                     if error_count == 100 {
@@ -159,6 +195,18 @@ impl OutputStream {
             shutdown: Arc::new(AtomicBool::new(false)),
             metadata: Arc::new(moka::future::Cache::builder().build()),
             app_handle: app_handle.clone(),
+            resume_token: None,
+            health_state: None,
         }
     }
+
+    pub fn with_resume_token(mut self, token: Vec<u8>) -> Self {
+        self.resume_token = Some(token);
+        self
+    }
+
+    pub fn with_health_state(mut self, health_state: Arc<HealthMonitorState>) -> Self {
+        self.health_state = Some(health_state);
+        self
+    }
 }
@@ -52,6 +52,14 @@ impl AppState {
         pool.insert(endpoint, api);
     }
 
+    /// Add an API client for a server connected alongside the current one, leaving the
+    /// default client untouched
+    pub async fn add_pooled_api_client(&self, endpoint: String, ca_cert: String, pem: String) {
+        let api = Api::new(endpoint.clone(), ca_cert, pem);
+        let mut pool = self.server_pool.write().await;
+        pool.insert(endpoint, api);
+    }
+
     /// Get the API client, returning an error if not initialized
     pub fn get_api_client(&self) -> Result<&Api, String> {
         self.api_client.as_ref().ok_or_else(|| "API client not initialized. Please log in first.".to_string())
@@ -1,13 +1,16 @@
 use common::traits::StreamTrait;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
 use tokio::sync::{broadcast, watch};
 use tokio::task::AbortHandle;
-use tauri::{AppHandle, Manager};
+use tauri::{AppHandle, Listener, Manager};
 use tauri_plugin_store::StoreExt;
 use serde::{Deserialize, Serialize};
 
+mod overlay;
 pub mod structs;
-pub use structs::{Command, CommandMessage, DeviceType, SuccessResponse, ErrorResponse, ResponseData, PongData, MuteData, RecordData, StateData};
+pub use structs::{Command, CommandMessage, DeviceType, SuccessResponse, ErrorResponse, ResponseData, PongData, MuteData, RecordData, MarkData, StateData};
+pub use structs::{PlayerData, ChannelInfo, ChannelsData, ChannelCreatedData, ChannelMembershipData, PttData, SubscribeData};
+pub use structs::{EventType, PushEvent, ActivityData, SpeakingData, PresenceData, ChannelEventData, ConnectionData};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(default)]
@@ -16,6 +19,8 @@ pub struct WebSocketConfig {
     pub localhost_only: bool,
     pub port: u16,
     pub key: String,
+    /// Serve the browser-source overlay to plain HTTP requests on the same port
+    pub overlay: bool,
 }
 
 impl Default for WebSocketConfig {
@@ -25,6 +30,7 @@ impl Default for WebSocketConfig {
             localhost_only: true,
             port: 9595,
             key: String::new(),
+            overlay: true,
         }
     }
 }
@@ -36,7 +42,7 @@ pub struct WebSocketBroadcaster(pub broadcast::Sender<String>);
 impl WebSocketBroadcaster {
     /// Serialize a StateData DTO and broadcast to all connected WS clients.
     pub fn broadcast_state(&self, state: StateData) {
-        let response = SuccessResponse::state(state.muted, state.deafened, state.recording);
+        let response = SuccessResponse::state(state);
         if let Ok(json) = serde_json::to_string(&response) {
             let _ = self.0.send(json);
         }
@@ -49,6 +55,7 @@ pub struct WebSocketManager {
     config: Option<WebSocketConfig>,
     app_handle: AppHandle,
     broadcast_tx: broadcast::Sender<String>,
+    event_tx: broadcast::Sender<PushEvent>,
 }
 
 impl WebSocketManager {
@@ -59,6 +66,8 @@ impl WebSocketManager {
             .and_then(|v| serde_json::from_value(v.clone()).ok());
 
         let (broadcast_tx, _) = broadcast::channel(16);
+        let (event_tx, _) = broadcast::channel(64);
+        Self::forward_app_events(&app_handle, &event_tx);
 
         Self {
             abort_handle: None,
@@ -66,9 +75,48 @@ impl WebSocketManager {
             config,
             app_handle,
             broadcast_tx,
+            event_tx,
         }
     }
 
+    /// Relay the app events that WS clients can subscribe to onto `event_tx`
+    fn forward_app_events(app_handle: &AppHandle, event_tx: &broadcast::Sender<PushEvent>) {
+        let tx = event_tx.clone();
+        app_handle.listen("audio-activity", move |event| {
+            if let Ok(levels) = serde_json::from_str::<HashMap<String, f32>>(event.payload()) {
+                let _ = tx.send(PushEvent::Activity(ActivityData { levels }));
+            }
+        });
+
+        let tx = event_tx.clone();
+        app_handle.listen(crate::events::event::speaking::SPEAKING, move |event| {
+            if let Ok(data) = serde_json::from_str::<SpeakingData>(event.payload()) {
+                let _ = tx.send(PushEvent::Speaking(data));
+            }
+        });
+
+        let tx = event_tx.clone();
+        app_handle.listen(crate::events::event::player_presence::PLAYER_PRESENCE, move |event| {
+            if let Ok(data) = serde_json::from_str::<PresenceData>(event.payload()) {
+                let _ = tx.send(PushEvent::Presence(data));
+            }
+        });
+
+        let tx = event_tx.clone();
+        app_handle.listen(crate::events::event::channel_event::CHANNEL_EVENT, move |event| {
+            if let Ok(data) = serde_json::from_str::<ChannelEventData>(event.payload()) {
+                let _ = tx.send(PushEvent::Channel(data));
+            }
+        });
+
+        let tx = event_tx.clone();
+        app_handle.listen("connection_health", move |event| {
+            if let Ok(data) = serde_json::from_str::<ConnectionData>(event.payload()) {
+                let _ = tx.send(PushEvent::Connection(data));
+            }
+        });
+    }
+
     /// Extract a broadcaster handle for registration as Tauri managed state
     pub fn broadcaster(&self) -> WebSocketBroadcaster {
         WebSocketBroadcaster(self.broadcast_tx.clone())
@@ -137,16 +185,25 @@ impl WebSocketManager {
         let config = config.clone();
         let app_handle = self.app_handle.clone();
         let broadcast_tx = self.broadcast_tx.clone();
+        let event_tx = self.event_tx.clone();
 
         let handle = tokio::spawn(async move {
             while let Ok((stream, _)) = listener.accept().await {
                 let app_handle = app_handle.clone();
                 let key = config.key.clone();
                 let broadcast_tx = broadcast_tx.clone();
+                let event_rx = event_tx.subscribe();
                 let shutdown_rx = shutdown_rx.clone();
+                let overlay = config.overlay;
 
                 tokio::spawn(async move {
-                    if let Err(e) = Self::handle_connection(stream, app_handle, key, broadcast_tx, shutdown_rx).await {
+                    let result = match overlay::is_websocket_upgrade(&stream).await {
+                        Ok(false) if overlay => overlay::serve(stream, app_handle).await,
+                        Ok(_) => Self::handle_connection(stream, app_handle, key, broadcast_tx, event_rx, shutdown_rx).await,
+                        Err(e) => Err(e),
+                    };
+
+                    if let Err(e) = result {
                         // Connection resets / broken pipes are normal client disconnects
                         let is_disconnect = e.root_cause()
                             .downcast_ref::<std::io::Error>()
@@ -172,6 +229,7 @@ impl WebSocketManager {
         app_handle: AppHandle,
         auth_key: String,
         broadcast_tx: broadcast::Sender<String>,
+        mut event_rx: broadcast::Receiver<PushEvent>,
         mut shutdown_rx: watch::Receiver<bool>,
     ) -> Result<(), anyhow::Error> {
         use tokio_tungstenite::accept_async;
@@ -180,6 +238,8 @@ impl WebSocketManager {
         let ws_stream = accept_async(stream).await?;
         let (mut write, mut read) = ws_stream.split();
         let mut broadcast_rx = broadcast_tx.subscribe();
+        // Push events this connection asked for with `subscribe`
+        let mut subscriptions: HashSet<EventType> = HashSet::new();
 
         loop {
             tokio::select! {
@@ -217,10 +277,13 @@ impl WebSocketManager {
                     }
 
                     // Check if this is a state-changing command
-                    let is_state_changing = matches!(parsed.command, Command::Mute { .. } | Command::Record);
+                    let is_state_changing = matches!(
+                        parsed.command,
+                        Command::Mute { .. } | Command::Deafen { .. } | Command::Ptt { .. } | Command::Record
+                    );
 
                     // Execute command
-                    let response_json = match Self::execute_command_from(parsed.command, &app_handle).await {
+                    let response_json = match Self::execute_command_from(parsed.command, &app_handle, &mut subscriptions).await {
                         Ok(data) => {
                             let success_response = SuccessResponse {
                                 success: true,
@@ -260,7 +323,24 @@ impl WebSocketManager {
                     }
                 }
 
-                // Branch 3: Server shutdown signal
+                // Branch 3: Push events this connection subscribed to
+                result = event_rx.recv() => {
+                    match result {
+                        Ok(event) if subscriptions.contains(&event.event_type()) => {
+                            let json = serde_json::to_string(&event)?;
+                            write.send(tokio_tungstenite::tungstenite::Message::Text(json.into())).await?;
+                        }
+                        Ok(_) => {}
+                        Err(broadcast::error::RecvError::Lagged(n)) => {
+                            log::warn!("WebSocket event receiver lagged by {} messages", n);
+                        }
+                        Err(broadcast::error::RecvError::Closed) => {
+                            return Ok(());
+                        }
+                    }
+                }
+
+                // Branch 4: Server shutdown signal
                 _ = shutdown_rx.changed() => {
                     return Ok(());
                 }
@@ -271,27 +351,20 @@ impl WebSocketManager {
     async fn execute_command_from(
         cmd: Command,
         app_handle: &AppHandle,
+        subscriptions: &mut HashSet<EventType>,
     ) -> Result<ResponseData, anyhow::Error> {
         match cmd {
             Command::Ping => Ok(ResponseData::Pong(PongData { pong: true })),
 
             Command::Mute { device } => {
                 // In PTT mode, input mute via WebSocket is a no-op
-                if matches!(device, DeviceType::Input) {
-                    if let Ok(store) = app_handle.store("store.json") {
-                        if let Some(config) = store.get("keybinds") {
-                            if let Some(mode) = config.get("voiceMode").and_then(|v| v.as_str()) {
-                                if mode == "pushToTalk" {
-                                    let actions = app_handle.state::<crate::audio::AudioActionsManager>();
-                                    let status = actions.is_muted(crate::audio::types::AudioDeviceType::InputDevice).await;
-                                    return Ok(ResponseData::Mute(MuteData {
-                                        device: "input".to_string(),
-                                        muted: status,
-                                    }));
-                                }
-                            }
-                        }
-                    }
+                if matches!(device, DeviceType::Input) && Self::is_push_to_talk(app_handle) {
+                    let actions = app_handle.state::<crate::audio::AudioActionsManager>();
+                    let status = actions.is_muted(crate::audio::types::AudioDeviceType::InputDevice).await;
+                    return Ok(ResponseData::Mute(MuteData {
+                        device: "input".to_string(),
+                        muted: status,
+                    }));
                 }
 
                 let audio_device = match device {
@@ -319,33 +392,134 @@ impl WebSocketManager {
                 Ok(ResponseData::Record(RecordData { recording }))
             }
 
+            Command::Mark { label } => {
+                let actions = app_handle.state::<crate::audio::AudioActionsManager>();
+                let marker = actions.mark_recording(label).await?;
+                Ok(ResponseData::Mark(MarkData {
+                    marker_ms: marker.relative_timestamp_ms,
+                    label: marker.label,
+                }))
+            }
+
             Command::State => {
                 let state_data = Self::query_state(app_handle).await;
                 Ok(ResponseData::State(state_data))
             }
+
+            Command::Deafen { deafened } => {
+                let actions = app_handle.state::<crate::audio::AudioActionsManager>();
+                let muted = actions.set_deafened(deafened).await;
+                Ok(ResponseData::Mute(MuteData {
+                    device: "output".to_string(),
+                    muted,
+                }))
+            }
+
+            Command::Ptt { pressed } => {
+                if !Self::is_push_to_talk(app_handle) {
+                    return Err(anyhow::anyhow!("Push-to-talk is not enabled"));
+                }
+
+                #[cfg(desktop)]
+                {
+                    let keybinds = app_handle
+                        .try_state::<crate::keybinds::KeybindManager>()
+                        .ok_or_else(|| anyhow::anyhow!("Keybinds are not available"))?;
+                    keybinds.push_to_talk(pressed).await;
+                    Ok(ResponseData::Ptt(PttData { active: pressed }))
+                }
+
+                #[cfg(not(desktop))]
+                {
+                    let _ = pressed;
+                    Err(anyhow::anyhow!("Push-to-talk is only available on desktop"))
+                }
+            }
+
+            Command::Player { player, gain, muted } => {
+                let actions = app_handle.state::<crate::audio::AudioActionsManager>();
+                let settings = actions.set_player_gain(player.clone(), gain, muted).await?;
+                Ok(ResponseData::Player(PlayerData {
+                    player,
+                    gain: settings.gain,
+                    muted: settings.muted,
+                }))
+            }
+
+            Command::Channels => {
+                let app_state = app_handle.state::<tauri::async_runtime::Mutex<crate::structs::app_state::AppState>>();
+                let channels = crate::api::commands::api_list_channels(app_state, None)
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))?;
+
+                Ok(ResponseData::Channels(ChannelsData {
+                    channels: channels
+                        .into_iter()
+                        .map(|channel| ChannelInfo {
+                            id: channel.id(),
+                            name: channel.name,
+                            creator: channel.creator,
+                            players: channel.players.into_iter().map(|p| p.name).collect(),
+                        })
+                        .collect(),
+                }))
+            }
+
+            Command::Create { name } => {
+                let app_state = app_handle.state::<tauri::async_runtime::Mutex<crate::structs::app_state::AppState>>();
+                let channel = crate::api::commands::api_create_channel(app_state, name.clone(), None)
+                    .await
+                    .map_err(|e| anyhow::anyhow!(e))?;
+                Ok(ResponseData::ChannelCreated(ChannelCreatedData { channel, name }))
+            }
+
+            Command::Join { channel } => {
+                let channels = app_handle.state::<crate::api::ChannelActionsManager>();
+                channels.join(channel.clone()).await?;
+
+                Ok(ResponseData::ChannelMembership(ChannelMembershipData {
+                    channel,
+                    joined: true,
+                }))
+            }
+
+            Command::Leave => {
+                let channels = app_handle.state::<crate::api::ChannelActionsManager>();
+                let channel = channels.leave().await?;
+
+                Ok(ResponseData::ChannelMembership(ChannelMembershipData {
+                    channel,
+                    joined: false,
+                }))
+            }
+
+            Command::Subscribe { events } => {
+                *subscriptions = events.iter().copied().collect();
+                Ok(ResponseData::Subscribe(SubscribeData { subscribed: events }))
+            }
         }
     }
 
+    /// Whether the saved keybind config uses push-to-talk
+    fn is_push_to_talk(app_handle: &AppHandle) -> bool {
+        app_handle
+            .store("store.json")
+            .ok()
+            .and_then(|store| store.get("keybinds"))
+            .and_then(|config| config.get("voiceMode").and_then(|v| v.as_str()).map(|mode| mode == "pushToTalk"))
+            .unwrap_or(false)
+    }
+
     /// Query current muted/deafened/recording state from the app
     async fn query_state(app_handle: &AppHandle) -> StateData {
-        let asm = app_handle.state::<tauri::async_runtime::Mutex<crate::AudioStreamManager>>();
-        let mut asm = asm.lock().await;
-
-        let muted = asm.mute_status(&crate::audio::types::AudioDeviceType::InputDevice).await.unwrap_or(false);
-        let deafened = asm.mute_status(&crate::audio::types::AudioDeviceType::OutputDevice).await.unwrap_or(false);
-        drop(asm);
-
-        let recording_manager = app_handle.state::<Arc<tauri::async_runtime::Mutex<crate::audio::RecordingManager>>>();
-        let manager = recording_manager.lock().await;
-        let recording = manager.is_recording();
-
-        StateData { muted, deafened, recording }
+        let actions = app_handle.state::<crate::audio::AudioActionsManager>();
+        actions.query_state().await
     }
 
     /// Build a full state JSON string for broadcasting
     async fn build_state_json(app_handle: &AppHandle) -> Result<String, serde_json::Error> {
         let state_data = Self::query_state(app_handle).await;
-        let response = SuccessResponse::state(state_data.muted, state_data.deafened, state_data.recording);
+        let response = SuccessResponse::state(state_data);
         serde_json::to_string(&response)
     }
 }
@@ -0,0 +1,222 @@
+//! Browser-source overlay
+//!
+//! Plain HTTP requests to the WebSocket port are answered here instead of being upgraded. The
+//! page connects back to the same port, subscribes to speaking, presence, channel and connection
+//! events, and follows the state broadcast every client receives. Theme options are read from
+//! the page's query string, so nothing needs configuring in the client.
+
+use common::Game;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const OVERLAY_HTML: &str = include_str!("overlay.html");
+
+/// Largest request head read before giving up on a client
+const MAX_REQUEST_HEAD: usize = 8192;
+
+/// How long a client has to send its request head
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, PartialEq)]
+enum OverlayRoute {
+    Page,
+    Gamerpic { game: Game, player: String },
+    NotFound,
+}
+
+impl OverlayRoute {
+    fn from_path(path: &str) -> Self {
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+        match segments.as_slice() {
+            [] | ["overlay"] => OverlayRoute::Page,
+            ["overlay", "gamerpic", game, player] => {
+                let game = match *game {
+                    "minecraft" => Game::Minecraft,
+                    "hytale" => Game::Hytale,
+                    _ => return OverlayRoute::NotFound,
+                };
+                match url::form_urlencoded::parse(format!("p={}", player).as_bytes()).next() {
+                    Some((_, player)) if !player.is_empty() => OverlayRoute::Gamerpic {
+                        game,
+                        player: player.to_string(),
+                    },
+                    _ => OverlayRoute::NotFound,
+                }
+            }
+            _ => OverlayRoute::NotFound,
+        }
+    }
+}
+
+/// Whether the request waiting on `stream` asks for a WebSocket upgrade. The request is only
+/// peeked, so the WebSocket handshake can still read it afterwards.
+pub(crate) async fn is_websocket_upgrade(stream: &TcpStream) -> Result<bool, anyhow::Error> {
+    let mut buf = vec![0u8; MAX_REQUEST_HEAD];
+
+    let head = tokio::time::timeout(REQUEST_TIMEOUT, async {
+        loop {
+            let n = stream.peek(&mut buf).await?;
+            let head = &buf[..n];
+            if n == 0 || n == buf.len() || head.windows(4).any(|w| w == b"\r\n\r\n") {
+                return Ok::<_, std::io::Error>(String::from_utf8_lossy(head).to_string());
+            }
+            // Wait for the rest of the head to arrive
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await??;
+
+    Ok(is_upgrade_request(&head))
+}
+
+fn is_upgrade_request(head: &str) -> bool {
+    head.lines().skip(1).any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("upgrade")
+                && value.trim().eq_ignore_ascii_case("websocket")
+        })
+    })
+}
+
+/// Answer a plain HTTP request with the overlay page or a gamerpic redirect
+pub(crate) async fn serve(
+    mut stream: TcpStream,
+    app_handle: AppHandle,
+) -> Result<(), anyhow::Error> {
+    let head = read_request_head(&mut stream).await?;
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let path = request_line.next().unwrap_or("/");
+
+    let response = if method != "GET" {
+        http_response(
+            "405 Method Not Allowed",
+            "text/plain",
+            &[],
+            b"Method Not Allowed",
+        )
+    } else {
+        match OverlayRoute::from_path(path) {
+            OverlayRoute::Page => http_response(
+                "200 OK",
+                "text/html; charset=utf-8",
+                &[("Cache-Control", "no-cache")],
+                OVERLAY_HTML.as_bytes(),
+            ),
+            OverlayRoute::Gamerpic { game, player } => {
+                match gamerpic_url(&app_handle, game, player).await {
+                    Some(url) => http_response(
+                        "302 Found",
+                        "text/plain",
+                        &[
+                            ("Location", url.as_str()),
+                            ("Cache-Control", "max-age=3600"),
+                        ],
+                        b"",
+                    ),
+                    None => http_response("404 Not Found", "text/plain", &[], b"Not Found"),
+                }
+            }
+            OverlayRoute::NotFound => {
+                http_response("404 Not Found", "text/plain", &[], b"Not Found")
+            }
+        }
+    };
+
+    stream.write_all(&response).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> Result<String, anyhow::Error> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+
+    tokio::time::timeout(REQUEST_TIMEOUT, async {
+        while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            head.extend_from_slice(&buf[..n]);
+        }
+        Ok::<_, std::io::Error>(())
+    })
+    .await??;
+
+    Ok(String::from_utf8_lossy(&head).to_string())
+}
+
+fn http_response(
+    status: &str,
+    content_type: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Vec<u8> {
+    let mut head = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let mut response = head.into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+async fn gamerpic_url(app_handle: &AppHandle, game: Game, player: String) -> Option<String> {
+    let app_state =
+        app_handle.state::<tauri::async_runtime::Mutex<crate::structs::app_state::AppState>>();
+    crate::api::commands::api_get_player_gamerpic(app_state, game, player, None)
+        .await
+        .ok()
+        .and_then(|response| response.gamerpic)
+        // Never redirect to anything but a web image
+        .filter(|url| url.starts_with("https://") || url.starts_with("http://"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        assert_eq!(OverlayRoute::from_path("/"), OverlayRoute::Page);
+        assert_eq!(
+            OverlayRoute::from_path("/overlay?key=abc&theme=dark"),
+            OverlayRoute::Page
+        );
+        assert_eq!(
+            OverlayRoute::from_path("/overlay/gamerpic/minecraft/Steve%20Two"),
+            OverlayRoute::Gamerpic {
+                game: Game::Minecraft,
+                player: "Steve Two".to_string()
+            }
+        );
+        assert_eq!(
+            OverlayRoute::from_path("/overlay/gamerpic/other/Steve"),
+            OverlayRoute::NotFound
+        );
+        assert_eq!(
+            OverlayRoute::from_path("/favicon.ico"),
+            OverlayRoute::NotFound
+        );
+    }
+
+    #[test]
+    fn test_upgrade_detection() {
+        let upgrade = "GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\r\n";
+        let browser = "GET /overlay HTTP/1.1\r\nHost: localhost\r\nAccept: text/html\r\n\r\n";
+        assert!(is_upgrade_request(upgrade));
+        assert!(!is_upgrade_request(browser));
+    }
+}
@@ -20,11 +20,14 @@ import ImageCacheOptions from './components/imageCacheOptions';
 import { PlayerManager } from './managers/PlayerManager';
 import ChannelManager from './managers/ChannelManager';
 import { AudioActivityManager } from './managers/AudioActivityManager';
+import ServerSessionManager from './managers/ServerSessionManager';
 
 import Notification from "../../components/events/Notification.svelte";
 import type { KeybindConfig } from '../bindings/KeybindConfig.ts';
 import type { NoiseGateSettings } from '../bindings/NoiseGateSettings.ts';
+import type { VoiceActivationSettings } from '../bindings/VoiceActivationSettings.ts';
 import type { PlayerGainStore } from '../bindings/PlayerGainStore.ts';
+import type { PlayerGainSettings } from '../bindings/PlayerGainSettings.ts';
 import type { ApiConfig } from '../bindings/ApiConfig.ts';
 
 import {
@@ -56,6 +59,7 @@ export default class Dashboard extends BVCApp {
     public playerManager: PlayerManager | undefined;
     public channelManager: ChannelManager | undefined;
     public audioActivityManager: AudioActivityManager | undefined;
+    public serverSessionManager: ServerSessionManager | undefined;
     public platformDetector: PlatformDetector | undefined;
 
     async initialize() {
@@ -100,6 +104,12 @@ export default class Dashboard extends BVCApp {
         // Initialize managers with dependency injection
         await this.initializeManagers();
 
+        // Player volume and mute changed outside the dashboard, e.g. over the WebSocket API
+        const playerGainUnlisten = await appWebview.listen('player_gain', (event: { payload: { player: string, settings: PlayerGainSettings } }) => {
+            this.playerManager?.update(event.payload.player, event.payload.settings);
+        });
+        this.eventUnlisteners.push(playerGainUnlisten);
+
         // Start the websocket server if on desktop and is enabled
         if (!await this.platformDetector.checkMobile()) {
             await invoke<boolean>('is_websocket_running').then(async (isRunning) => {
@@ -117,7 +127,14 @@ export default class Dashboard extends BVCApp {
                 toggleMute: "ControlLeft+BracketLeft",
                 toggleDeafen: "ControlLeft+BracketRight",
                 toggleRecording: "ControlLeft+Backslash",
+                markRecording: "ControlLeft+Quote",
                 pushToTalk: "Backquote",
+                pushToMute: "",
+                whisperToChannel: "",
+                cycleChannel: "",
+                volumeUp: "",
+                volumeDown: "",
+                favouriteChannels: [],
                 voiceMode: "openMic",
             };
             await invoke('start_keybind_listener', { config: keybindConfig }).catch((e) => {
@@ -215,6 +232,11 @@ export default class Dashboard extends BVCApp {
             // Initialize AudioActivityManager (independent)
             this.audioActivityManager = new AudioActivityManager(this.store);
             await this.audioActivityManager.initialize();
+
+            // Connections to servers other than the current one
+            this.serverSessionManager = new ServerSessionManager(this.store);
+            await this.serverSessionManager.initialize();
+            await this.serverSessionManager.watchReconnects();
         } catch (err) {
             error(`Dashboard: Failed to initialize managers: ${err}`);
             throw err;
@@ -228,7 +250,8 @@ export default class Dashboard extends BVCApp {
         return {
             playerManager: this.playerManager,
             channelManager: this.channelManager,
-            audioActivityManager: this.audioActivityManager
+            audioActivityManager: this.audioActivityManager,
+            serverSessionManager: this.serverSessionManager
         };
     }
 
@@ -426,6 +449,16 @@ export default class Dashboard extends BVCApp {
                     device: "InputDevice"
                 });
 
+                // Voice activation tuning; the voice mode itself is applied by the keybind listener
+                const voiceActivationSettings = await store.get("voice_activation_settings") as VoiceActivationSettings | null;
+                if (voiceActivationSettings != null) {
+                    await invoke("update_stream_metadata", {
+                        key: "voice_activation_settings",
+                        value: JSON.stringify(voiceActivationSettings),
+                        device: "InputDevice"
+                    });
+                }
+
                 // Update the player gain metadata
                 let playerGainStore = await store.get("player_gain_store") as PlayerGainStore | null;
                 if (!playerGainStore || typeof playerGainStore !== "object" || Array.isArray(playerGainStore)) {
@@ -440,8 +473,17 @@ export default class Dashboard extends BVCApp {
                     device: "OutputDevice"
                 });
 
+                // Master output volume, adjusted from settings or volume hotkeys
+                const outputVolume = await store.get<number>("output_volume");
+                await invoke("update_stream_metadata", {
+                    key: "output_volume",
+                    value: (outputVolume ?? 1.0).toString(),
+                    device: "OutputDevice"
+                });
+
                 // Fetch server config to get fresh QUIC port and spatial audio settings
                 try {
+                    // Spatial audio follows the backend game server the player is on, resolved by the voice server
                     const configResponse = await invoke<{ config: ApiConfig }>("api_get_config", { server: currentServer });
 
                     // Update QUIC port from server config
@@ -510,6 +552,7 @@ export default class Dashboard extends BVCApp {
         try {
             await invoke("change_network_stream", { server: currentServer, data: credentials });
             info(`Changed network stream to ${currentServer}`);
+            await this.serverSessionManager?.connectSaved(currentServer);
         } catch (e) {
             error(`Error changing network stream: ${e}`);
             window.location.href = "/error?code=CONN01";
@@ -525,6 +568,9 @@ export default class Dashboard extends BVCApp {
             if (this.audioActivityManager) {
                 this.audioActivityManager.destroy();
             }
+            if (this.serverSessionManager) {
+                this.serverSessionManager.cleanup();
+            }
             // PlayerManager doesn't need explicit cleanup currently
         } catch (err) {
             error(`Error cleaning up managers: ${err}`);
@@ -0,0 +1,157 @@
+import { writable, type Writable, type Readable } from 'svelte/store';
+import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import { info, error, warn } from '@tauri-apps/plugin-log';
+import type { Store } from '@tauri-apps/plugin-store';
+import Keyring from '../keyring';
+import Server from '../server';
+import type { ConnectionHealth } from '../../bindings/ConnectionHealth';
+import type { ServerSessionHealth } from '../../bindings/ServerSessionHealth';
+import type { ServerSessionStatus } from '../../bindings/ServerSessionStatus';
+
+/**
+ * ServerSessionManager keeps voice connections to servers other than the current one.
+ * The servers are remembered under "additional_servers" and reconnected with the dashboard.
+ */
+export default class ServerSessionManager {
+    private store: Store;
+    private sessionsStore: Writable<ServerSessionStatus[]>;
+    private healthStore: Writable<Record<string, ConnectionHealth>>;
+    private eventUnlisteners: Array<() => void> = [];
+
+    // Readonly exports for components
+    public readonly sessions: Readable<ServerSessionStatus[]>;
+    public readonly health: Readable<Record<string, ConnectionHealth>>;
+
+    constructor(store: Store) {
+        this.store = store;
+        this.sessionsStore = writable<ServerSessionStatus[]>([]);
+        this.healthStore = writable<Record<string, ConnectionHealth>>({});
+
+        this.sessions = { subscribe: this.sessionsStore.subscribe };
+        this.health = { subscribe: this.healthStore.subscribe };
+    }
+
+    /**
+     * Follow the health of additional connections
+     */
+    async initialize(): Promise<void> {
+        const healthUnlisten = await listen<ServerSessionHealth>('server_session_health', (event) => {
+            this.healthStore.update(health => ({ ...health, [event.payload.server]: event.payload.health }));
+        });
+        this.eventUnlisteners.push(healthUnlisten);
+
+        await this.refresh();
+    }
+
+    /**
+     * Reconnect remembered servers once they are back after a lost connection.
+     * Only the dashboard should do this, so each server is reconnected once.
+     */
+    async watchReconnects(): Promise<void> {
+        const reconnectUnlisten = await listen<string>('server_session_reconnect', async (event) => {
+            const server = event.payload;
+            if ((await this.savedServers()).includes(server)) {
+                info(`Reconnecting to ${server}`);
+                await this.connectServer(server);
+            }
+        });
+        this.eventUnlisteners.push(reconnectUnlisten);
+    }
+
+    /**
+     * Connect every remembered server that isn't already connected
+     */
+    async connectSaved(currentServer: string): Promise<void> {
+        const connected = (await this.refresh()).map(session => session.server);
+
+        for (const server of await this.savedServers()) {
+            if (server !== currentServer && !connected.includes(server)) {
+                await this.connectServer(server);
+            }
+        }
+    }
+
+    /**
+     * Connect to a server alongside the current one and remember it
+     */
+    async connect(server: string): Promise<boolean> {
+        const connected = await this.connectServer(server);
+        if (connected) {
+            const saved = await this.savedServers();
+            if (!saved.includes(server)) {
+                await this.store.set("additional_servers", [...saved, server]);
+                await this.store.save();
+            }
+        }
+        return connected;
+    }
+
+    /**
+     * Disconnect from an additional server and forget it
+     */
+    async disconnect(server: string): Promise<void> {
+        const saved = await this.savedServers();
+        await this.store.set("additional_servers", saved.filter(s => s !== server));
+        await this.store.save();
+
+        await invoke("disconnect_server", { server }).catch((e) => {
+            error(`Error disconnecting from ${server}: ${e}`);
+        });
+        this.healthStore.update(health => {
+            const { [server]: _, ...rest } = health;
+            return rest;
+        });
+        await this.refresh();
+    }
+
+    /**
+     * Choose whether the microphone is sent to a connected server
+     */
+    async setTransmit(server: string, transmit: boolean): Promise<void> {
+        await invoke("set_server_transmit", { server, transmit }).catch((e) => {
+            error(`Error changing transmit for ${server}: ${e}`);
+        });
+        await this.refresh();
+    }
+
+    async refresh(): Promise<ServerSessionStatus[]> {
+        const sessions = await invoke<ServerSessionStatus[]>("list_server_sessions").catch((e) => {
+            error(`Error listing server sessions: ${e}`);
+            return [] as ServerSessionStatus[];
+        });
+        this.sessionsStore.set(sessions);
+        return sessions;
+    }
+
+    cleanup(): void {
+        this.eventUnlisteners.forEach(unlisten => unlisten());
+        this.eventUnlisteners = [];
+    }
+
+    private async savedServers(): Promise<string[]> {
+        return await this.store.get<string[]>("additional_servers") ?? [];
+    }
+
+    private async connectServer(server: string): Promise<boolean> {
+        try {
+            const session = new Server();
+            await session.setKeyring(await Keyring.new("servers"), server);
+            const credentials = await session.getCredentials();
+
+            if (!credentials?.certificate) {
+                warn(`No credentials found for ${server}, sign in to it first`);
+                return false;
+            }
+
+            await invoke("connect_server", { server, data: credentials });
+            info(`Also streaming ${server}`);
+            return true;
+        } catch (e) {
+            error(`Error connecting to ${server}: ${e}`);
+            return false;
+        } finally {
+            await this.refresh();
+        }
+    }
+}
@@ -1,5 +1,6 @@
 import { info, error, warn } from '@tauri-apps/plugin-log';
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from '@tauri-apps/api/event';
 import { mount } from "svelte";
 
 import RecordingsTable from '../../../components/recordings/RecordingsTable.svelte';
@@ -9,6 +10,13 @@ import EmptyState from '../../../components/recordings/EmptyState.svelte';
 import type { RecordingSession } from '../../bindings/RecordingSession';
 import type { AudioFormat } from '../../bindings/AudioFormat';
 
+interface RetentionReport {
+    deleted: string[];
+    exported: string[];
+    compressed: string[];
+    freed_bytes: number;
+}
+
 declare global {
   interface Window {
     App: any;
@@ -23,6 +31,17 @@ export default class RecordingSettings {
     constructor() {}
 
     async initialize(): Promise<boolean> {
+        // Sessions interrupted by a crash are repaired in the background on startup
+        await listen<string[]>('recording:recovered', async (event) => {
+            info(`Recovered ${event.payload.length} interrupted recording sessions`);
+            await this.loadRecordings();
+        });
+
+        await listen<RetentionReport>('recording:retention', async (event) => {
+            info(`Retention policy removed ${event.payload.deleted.length} recording sessions`);
+            await this.loadRecordings();
+        });
+
         await this.loadRecordings();
         return true;
     }
@@ -67,8 +86,10 @@ export default class RecordingSettings {
                 target: container,
                 props: {
                     recordings: this.recordings,
-                    onExport: (sessionId: string, selectedPlayers: string[], withSpatial: boolean) => this.handleExport(sessionId, selectedPlayers, withSpatial),
-                    onDelete: (sessionId: string) => this.handleDelete(sessionId)
+                    onExport: (sessionId: string, selectedPlayers: string[], withSpatial: boolean, format?: AudioFormat) => this.handleExport(sessionId, selectedPlayers, withSpatial, format),
+                    onMixdown: (sessionId: string, selectedPlayers: string[], withSpatial: boolean, format: AudioFormat) => this.handleMixdown(sessionId, selectedPlayers, withSpatial, format),
+                    onDelete: (sessionId: string) => this.handleDelete(sessionId),
+                    onPin: (sessionId: string, pinned: boolean) => this.handlePin(sessionId, pinned)
                 }
             });
         }
@@ -84,20 +105,34 @@ export default class RecordingSettings {
         }
     }
 
-    private async handleExport(sessionId: string, selectedPlayers: string[], withSpatial: boolean): Promise<void> {
+    private async handleExport(sessionId: string, selectedPlayers: string[], withSpatial: boolean, format: AudioFormat = "Mp4Opus"): Promise<void> {
         try {
-            info(`Exporting session ${sessionId} with ${selectedPlayers.length} participants (spatial: ${withSpatial})`);
+            info(`Exporting session ${sessionId} with ${selectedPlayers.length} participants (spatial: ${withSpatial}, format: ${format})`);
             await invoke("export_recording", {
                 sessionId,
                 selectedPlayers,
                 spatial: withSpatial,
-                format: "Mp4Opus"
+                format
             });
         } catch (e) {
             error(`Failed to export recording: ${e}`);
         }
     }
 
+    private async handleMixdown(sessionId: string, selectedPlayers: string[], withSpatial: boolean, format: AudioFormat = "Mp4Opus"): Promise<void> {
+        try {
+            info(`Exporting mixdown of session ${sessionId} with ${selectedPlayers.length} participants (spatial: ${withSpatial}, format: ${format})`);
+            await invoke("export_recording_mixdown", {
+                sessionId,
+                selectedPlayers,
+                spatial: withSpatial,
+                format
+            });
+        } catch (e) {
+            error(`Failed to export mixdown: ${e}`);
+        }
+    }
+
     private async handleDelete(sessionId: string): Promise<void> {
         try {
             info(`Deleting session ${sessionId}`);
@@ -111,6 +146,21 @@ export default class RecordingSettings {
         }
     }
 
+    private async handlePin(sessionId: string, pinned: boolean): Promise<void> {
+        try {
+            info(`${pinned ? 'Pinning' : 'Unpinning'} session ${sessionId}`);
+            await invoke("set_recording_pinned", { sessionId, pinned });
+
+            const recording = this.recordings.find(r => r.session_data.session_id === sessionId);
+            if (recording) {
+                recording.session_data.pinned = pinned;
+            }
+            this.renderContent();
+        } catch (e) {
+            error(`Failed to pin recording: ${e}`);
+        }
+    }
+
     public async refresh(): Promise<void> {
         await this.loadRecordings();
         this.renderContent();
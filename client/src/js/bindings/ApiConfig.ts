@@ -1,4 +1,12 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { SpatialAudioConfig } from "./SpatialAudioConfig";
 
-export type ApiConfig = { status: string, client_id: string, protocol_version: string, quic_port: number, spatial_audio: SpatialAudioConfig, };
+export type ApiConfig = { status: string, client_id: string, protocol_version: string, quic_port: number, spatial_audio: SpatialAudioConfig, 
+/**
+ * Players connected to voice. Absent on servers that don't report it.
+ */
+players: number | null, 
+/**
+ * Backend game server `spatial_audio` was resolved for, on voice servers shared by several
+ */
+server_id: string | null, };
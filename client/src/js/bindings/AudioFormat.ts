@@ -3,4 +3,4 @@
 /**
  * Audio output format selection for rendering recordings
  */
-export type AudioFormat = "Bwav" | "Mp4Opus";
+export type AudioFormat = "Bwav" | "Mp4Opus" | "Mp4OpusStems" | "BwavPolyphonic" | "BwavStems" | "OggOpus" | "Flac";
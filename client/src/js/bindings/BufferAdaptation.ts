@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LinkQuality } from "./LinkQuality";
+
+/**
+ * A jitter buffer resize
+ */
+export type BufferAdaptation = { 
+/**
+ * Sender timestamp of the last packet accepted before the resize
+ */
+timestamp: bigint, capacity: number, quality: LinkQuality, };
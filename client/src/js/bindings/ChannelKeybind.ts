@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A favourite channel and the key that joins it. An empty keybind keeps the channel in the
+ * cycle without a dedicated key.
+ */
+export type ChannelKeybind = { channel: string, keybind: string, };
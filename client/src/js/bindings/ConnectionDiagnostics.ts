@@ -0,0 +1,18 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Transport statistics of one voice server connection
+ */
+export type ConnectionDiagnostics = { server: string, primary: boolean, 
+/**
+ * Round trip of the most recent health check
+ */
+rtt_ms: number | null, smoothed_rtt_ms: number | null, 
+/**
+ * Consecutive health checks without a response
+ */
+failed_health_checks: number, datagrams_received: bigint, datagrams_sent: bigint, 
+/**
+ * Datagrams the connection refused to send
+ */
+datagrams_dropped: bigint, datagram_drop_rate: number, };
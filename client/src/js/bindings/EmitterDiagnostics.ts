@@ -0,0 +1,32 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BufferAdaptation } from "./BufferAdaptation";
+import type { LinkQuality } from "./LinkQuality";
+
+/**
+ * Playback statistics of one heard player's jitter buffer
+ */
+export type EmitterDiagnostics = { player: string, spatial: boolean, 
+/**
+ * Smoothed interarrival jitter
+ */
+jitter_ms: number, 
+/**
+ * Fraction of frames missing from the sender's stream while they were speaking
+ */
+loss_rate: number, packets_received: bigint, packets_lost: bigint, frames_decoded: bigint, 
+/**
+ * Frames the decoder concealed because their packet had not arrived
+ */
+frames_concealed: bigint, frames_dropped_overflow: bigint, 
+/**
+ * Packets that arrived too late or out of order to be played
+ */
+frames_dropped_late: bigint, buffer_depth: number, buffer_capacity: number, avg_buffer_depth: number, quality: LinkQuality, 
+/**
+ * 0.0 to 1.0, from the share of frames played as sent
+ */
+quality_score: number, 
+/**
+ * Most recent resizes, oldest first
+ */
+adaptations: Array<BufferAdaptation>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ServerSource } from "./ServerSource";
+
+/**
+ * A server saved in the server browser
+ */
+export type FavouriteServer = { url: string, name: string | null, source: ServerSource, };
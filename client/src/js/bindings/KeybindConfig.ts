@@ -1,4 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChannelKeybind } from "./ChannelKeybind";
 import type { VoiceMode } from "./VoiceMode";
 
-export type KeybindConfig = { toggleMute: string, toggleDeafen: string, toggleRecording: string, pushToTalk: string, voiceMode: VoiceMode, };
+/**
+ * Key combinations for each action. An empty string leaves the action unbound.
+ */
+export type KeybindConfig = { toggleMute: string, toggleDeafen: string, toggleRecording: string, markRecording: string, pushToTalk: string, pushToMute: string, whisperToChannel: string, cycleChannel: string, volumeUp: string, volumeDown: string, favouriteChannels: Array<ChannelKeybind>, voiceMode: VoiceMode, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Network quality as judged by a jitter buffer from its loss and jitter
+ */
+export type LinkQuality = "excellent" | "good" | "moderate" | "poor";
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConnectionDiagnostics } from "./ConnectionDiagnostics";
+import type { EmitterDiagnostics } from "./EmitterDiagnostics";
+
+/**
+ * Everything needed to tell a bad network from a bad buffer
+ */
+export type NetworkDiagnostics = { 
+/**
+ * Unix timestamp in milliseconds
+ */
+generated_at: bigint, connections: Array<ConnectionDiagnostics>, emitters: Array<EmitterDiagnostics>, };
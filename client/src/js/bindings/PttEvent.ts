@@ -4,4 +4,4 @@
  * PTT state event emitted to the frontend via Tauri events.
  * The Display/to_string() of the variant is the event name.
  */
-export type PttEvent = "ptt:active";
+export type PttEvent = "ptt:active" | "whisper:active";
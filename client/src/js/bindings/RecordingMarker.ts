@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A user-placed marker within a recording session
+ */
+export type RecordingMarker = { 
+/**
+ * Milliseconds since the session started, on the same clock as WAL entry timestamps
+ */
+relative_timestamp_ms: bigint, label: string | null, };
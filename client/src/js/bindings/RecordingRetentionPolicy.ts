@@ -0,0 +1,31 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AudioFormat } from "./AudioFormat";
+
+/**
+ * Automatic cleanup of old recording sessions. Every limit is optional and pinned sessions are always kept.
+ */
+export type RecordingRetentionPolicy = { enabled: boolean, 
+/**
+ * Delete sessions that started more than this many days ago
+ */
+max_age_days: number | null, 
+/**
+ * Delete the oldest sessions until all recordings fit in this many megabytes
+ */
+max_total_size_mb: number | null, 
+/**
+ * Only keep this many of the most recent unpinned sessions
+ */
+keep_latest: number | null, 
+/**
+ * Compress the WAL of sessions older than this many days
+ */
+compress_after_days: number | null, 
+/**
+ * Render every participant in this format before a session is deleted
+ */
+export_before_delete: AudioFormat | null, 
+/**
+ * Directory that receives exports; defaults to `exports` beside the recordings directory
+ */
+export_directory: string | null, };
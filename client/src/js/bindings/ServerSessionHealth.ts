@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ConnectionHealth } from "./ConnectionHealth";
+
+/**
+ * Health of a voice server connected alongside the current one
+ */
+export type ServerSessionHealth = { server: string, health: ConnectionHealth, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A connected voice server and whether the microphone is sent to it
+ */
+export type ServerSessionStatus = { server: string, 
+/**
+ * The current server, whose channels and players the dashboard shows
+ */
+primary: boolean, transmit: boolean, };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of probing a server's public config endpoint
+ */
+export type ServerStatus = { url: string, online: boolean, 
+/**
+ * Round trip of the config request
+ */
+latency_ms: number | null, 
+/**
+ * `None` for servers too old to report a protocol version
+ */
+protocol_version: string | null, compatible: boolean, client_too_old: boolean, players: number | null, };
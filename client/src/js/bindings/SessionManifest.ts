@@ -1,3 +1,21 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RecordingMarker } from "./RecordingMarker";
+import type { SpatialAudioConfig } from "./SpatialAudioConfig";
 
-export type SessionManifest = { session_id: string, start_timestamp: bigint, end_timestamp: bigint | null, duration_ms: bigint | null, emitter_player: string, participants: Array<string>, created_at: string, };
+export type SessionManifest = { session_id: string, start_timestamp: bigint, end_timestamp: bigint | null, duration_ms: bigint | null, emitter_player: string, participants: Array<string>, created_at: string, 
+/**
+ * Set when the session was reconstructed after the client stopped mid-recording
+ */
+recovered: boolean, 
+/**
+ * Pinned sessions are never pruned or compressed by the retention policy
+ */
+pinned: boolean, 
+/**
+ * Moments flagged by the user while recording, in the order they were placed
+ */
+markers: Array<RecordingMarker>, 
+/**
+ * Server spatial audio settings in effect when the session started
+ */
+spatial_config: SpatialAudioConfig | null, };
@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Tuning for the voice activation detector. Thresholds are in dB above the tracked
+ * background noise floor rather than absolute levels, so they hold across microphones.
+ */
+export type VoiceActivationSettings = { 
+/**
+ * Speech band level above the noise floor that opens the microphone
+ */
+open_threshold: number, 
+/**
+ * Level above the noise floor that keeps an open microphone open
+ */
+close_threshold: number, 
+/**
+ * How long speech must stay below the close threshold before the microphone closes
+ */
+hang_time: number, 
+/**
+ * Audio from before speech was detected that is sent when the microphone opens
+ */
+pre_roll: number, };
@@ -8,3 +8,58 @@ macro_rules! define_protocol_version {
 }
 
 define_protocol_version!("1.5.0");
+
+/// How a server's protocol version relates to [`PROTOCOL_VERSION`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionCompatibility {
+    Compatible,
+    ClientTooOld,
+    ServerTooOld,
+}
+
+/// Major and minor versions must match; the patch version can differ
+pub fn compatibility(server_version: &str) -> VersionCompatibility {
+    fn major_minor(version: &str) -> (u32, u32) {
+        let mut parts = version.split('.').filter_map(|s| s.parse().ok());
+        (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+    }
+
+    let server = major_minor(server_version);
+    let client = major_minor(PROTOCOL_VERSION);
+
+    if server == client {
+        VersionCompatibility::Compatible
+    } else if client < server {
+        VersionCompatibility::ClientTooOld
+    } else {
+        VersionCompatibility::ServerTooOld
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compatibility() {
+        let (major, minor) = {
+            let mut parts = PROTOCOL_VERSION.split('.').map(|s| s.parse::<u32>().unwrap());
+            (parts.next().unwrap(), parts.next().unwrap())
+        };
+
+        assert_eq!(
+            compatibility(&format!("{}.{}.99", major, minor)),
+            VersionCompatibility::Compatible
+        );
+        assert_eq!(
+            compatibility(&format!("{}.{}.0", major, minor + 1)),
+            VersionCompatibility::ClientTooOld
+        );
+        assert_eq!(
+            compatibility(&format!("{}.0.0", major + 1)),
+            VersionCompatibility::ClientTooOld
+        );
+        assert_eq!(compatibility("0.0.1"), VersionCompatibility::ServerTooOld);
+        assert_eq!(compatibility(""), VersionCompatibility::ServerTooOld);
+    }
+}
@@ -12,6 +12,9 @@ pub use minecraft::Dimension;
 #[derive(Clone, Debug, Serialize)]
 pub struct GameDataCollection {
     pub game: Option<Game>,
+    /// Backend game server the batch came from, when several servers share one voice server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<String>,
     pub players: Vec<PlayerEnum>,
 }
 
@@ -26,6 +29,8 @@ impl<'de> Deserialize<'de> for GameDataCollection {
         #[derive(Deserialize)]
         struct LegacyFormat {
             game: Option<Game>,
+            #[serde(default)]
+            server_id: Option<String>,
             players: Vec<serde_json::Value>,
         }
 
@@ -54,6 +59,7 @@ impl<'de> Deserialize<'de> for GameDataCollection {
 
         Ok(GameDataCollection {
             game: legacy.game,
+            server_id: legacy.server_id,
             players: players?,
         })
     }
@@ -63,6 +69,7 @@ impl GameDataCollection {
     pub fn new(game: Option<Game>) -> Self {
         Self {
             game,
+            server_id: None,
             players: Vec::new(),
         }
     }
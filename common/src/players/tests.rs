@@ -110,4 +110,21 @@ mod tests {
             _ => panic!("Expected Minecraft player"),
         }
     }
+
+    #[test]
+    fn test_game_data_collection_server_id() {
+        let json = r#"{
+  "game": "minecraft",
+  "server_id": "lobby",
+  "players": []
+}"#;
+
+        let collection: GameDataCollection = serde_json::from_str(json)
+            .expect("Failed to deserialize GameDataCollection from JSON");
+        assert_eq!(collection.server_id.as_deref(), Some("lobby"));
+
+        let legacy: GameDataCollection = serde_json::from_str(r#"{"game":"minecraft","players":[]}"#)
+            .expect("Failed to deserialize GameDataCollection from JSON");
+        assert_eq!(legacy.server_id, None);
+    }
 }
@@ -29,6 +29,33 @@ impl Default for NoiseGateSettings {
     }
 }
 
+/// Tuning for the voice activation detector. Thresholds are in dB above the tracked
+/// background noise floor rather than absolute levels, so they hold across microphones.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
+#[serde(default)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct VoiceActivationSettings {
+    /// Speech band level above the noise floor that opens the microphone
+    pub open_threshold: f32,
+    /// Level above the noise floor that keeps an open microphone open
+    pub close_threshold: f32,
+    /// How long speech must stay below the close threshold before the microphone closes
+    pub hang_time: f32,
+    /// Audio from before speech was detected that is sent when the microphone opens
+    pub pre_roll: f32,
+}
+
+impl Default for VoiceActivationSettings {
+    fn default() -> Self {
+        Self {
+            open_threshold: 12.0,
+            close_threshold: 6.0,
+            hang_time: 400.0,
+            pre_roll: 200.0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 #[ts(export, export_to = "./../../client/src/js/bindings/")]
 pub struct PlayerGainSettings {
@@ -36,6 +63,14 @@ pub struct PlayerGainSettings {
     pub muted: bool,
 }
 
+impl PlayerGainSettings {
+    /// Convert the linear slider position (0.0-1.5) to a perceptually-correct amplitude factor.
+    /// Uses a power curve (x^2.5) so equal slider increments produce roughly equal loudness changes.
+    pub fn amplitude(&self) -> f32 {
+        self.gain.powf(2.5)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 #[ts(export, export_to = "./../../client/src/js/bindings/")]
 pub struct PlayerGainStore(pub HashMap<String, PlayerGainSettings>);
@@ -61,16 +96,36 @@ pub enum AudioFormat {
     Bwav,
     /// MP4/M4A with Opus audio (compressed, lossless passthrough)
     Mp4Opus,
+    /// Single MP4/M4A with one time-aligned Opus track per participant
+    Mp4OpusStems,
+    /// Single polyphonic Broadcast WAV with one channel per participant
+    BwavPolyphonic,
+    /// Folder of time-aligned mono Broadcast WAVs, one per participant
+    BwavStems,
+    /// Ogg Opus (compressed, lossless passthrough)
+    OggOpus,
+    /// FLAC (lossless compressed PCM)
+    Flac,
 }
 
 impl AudioFormat {
     /// Returns the file extension for this format (without dot)
     pub fn extension(&self) -> &'static str {
         match self {
-            AudioFormat::Bwav => "wav",
-            AudioFormat::Mp4Opus => "m4a",
+            AudioFormat::Bwav | AudioFormat::BwavPolyphonic | AudioFormat::BwavStems => "wav",
+            AudioFormat::Mp4Opus | AudioFormat::Mp4OpusStems => "m4a",
+            AudioFormat::OggOpus => "opus",
+            AudioFormat::Flac => "flac",
         }
     }
+
+    /// Whether this format renders all selected participants together as stems
+    pub fn is_multitrack(&self) -> bool {
+        matches!(
+            self,
+            AudioFormat::Mp4OpusStems | AudioFormat::BwavPolyphonic | AudioFormat::BwavStems
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -13,6 +13,12 @@ pub struct ApiConfig {
     pub quic_port: u32,
     #[serde(default)]
     pub spatial_audio: SpatialAudioConfig,
+    /// Players connected to voice. Absent on servers that don't report it.
+    #[serde(default)]
+    pub players: Option<u32>,
+    /// Backend game server `spatial_audio` was resolved for, on voice servers shared by several
+    #[serde(default)]
+    pub server_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
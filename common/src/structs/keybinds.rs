@@ -8,8 +8,20 @@ pub enum VoiceMode {
     #[default]
     OpenMic,
     PushToTalk,
+    VoiceActivation,
 }
 
+/// A favourite channel and the key that joins it. An empty keybind keeps the channel in the
+/// cycle without a dedicated key.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, TS)]
+#[serde(default, rename_all = "camelCase")]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct ChannelKeybind {
+    pub channel: String,
+    pub keybind: String,
+}
+
+/// Key combinations for each action. An empty string leaves the action unbound.
 #[derive(Serialize, Deserialize, Clone, Debug, TS)]
 #[serde(default, rename_all = "camelCase")]
 #[ts(export, export_to = "./../../client/src/js/bindings/")]
@@ -17,7 +29,14 @@ pub struct KeybindConfig {
     pub toggle_mute: String,
     pub toggle_deafen: String,
     pub toggle_recording: String,
+    pub mark_recording: String,
     pub push_to_talk: String,
+    pub push_to_mute: String,
+    pub whisper_to_channel: String,
+    pub cycle_channel: String,
+    pub volume_up: String,
+    pub volume_down: String,
+    pub favourite_channels: Vec<ChannelKeybind>,
     pub voice_mode: VoiceMode,
 }
 
@@ -27,7 +46,14 @@ impl Default for KeybindConfig {
             toggle_mute: "ControlLeft+BracketLeft".to_string(),
             toggle_deafen: "ControlLeft+BracketRight".to_string(),
             toggle_recording: "ControlLeft+Backslash".to_string(),
+            mark_recording: "ControlLeft+Quote".to_string(),
             push_to_talk: "Backquote".to_string(),
+            push_to_mute: String::new(),
+            whisper_to_channel: String::new(),
+            cycle_channel: String::new(),
+            volume_up: String::new(),
+            volume_down: String::new(),
+            favourite_channels: Vec::new(),
             voice_mode: VoiceMode::default(),
         }
     }
@@ -38,7 +64,14 @@ pub enum KeybindAction {
     ToggleMute,
     ToggleDeafen,
     ToggleRecording,
+    MarkRecording,
     PushToTalk,
+    PushToMute,
+    WhisperToChannel,
+    CycleChannel,
+    JoinChannel(String),
+    VolumeUp,
+    VolumeDown,
 }
 
 /// PTT state event emitted to the frontend via Tauri events.
@@ -48,12 +81,15 @@ pub enum KeybindAction {
 pub enum PttEvent {
     #[serde(rename = "ptt:active")]
     Active,
+    #[serde(rename = "whisper:active")]
+    Whisper,
 }
 
 impl std::fmt::Display for PttEvent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             PttEvent::Active => write!(f, "ptt:active"),
+            PttEvent::Whisper => write!(f, "whisper:active"),
         }
     }
 }
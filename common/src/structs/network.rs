@@ -15,3 +15,132 @@ pub enum ConnectionHealth {
         client_too_old: bool,
     },
 }
+
+/// Health of a voice server connected alongside the current one
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct ServerSessionHealth {
+    pub server: String,
+    pub health: ConnectionHealth,
+}
+
+/// A connected voice server and whether the microphone is sent to it
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct ServerSessionStatus {
+    pub server: String,
+    /// The current server, whose channels and players the dashboard shows
+    pub primary: bool,
+    pub transmit: bool,
+}
+
+/// Where a favourite server entry came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub enum ServerSource {
+    Manual,
+    Directory,
+    File,
+}
+
+/// A server saved in the server browser
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct FavouriteServer {
+    pub url: String,
+    pub name: Option<String>,
+    pub source: ServerSource,
+}
+
+/// Result of probing a server's public config endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct ServerStatus {
+    pub url: String,
+    pub online: bool,
+    /// Round trip of the config request
+    pub latency_ms: Option<u32>,
+    /// `None` for servers too old to report a protocol version
+    pub protocol_version: Option<String>,
+    pub compatible: bool,
+    pub client_too_old: bool,
+    pub players: Option<u32>,
+}
+
+/// Network quality as judged by a jitter buffer from its loss and jitter
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+#[serde(rename_all = "camelCase")]
+pub enum LinkQuality {
+    Excellent,
+    Good,
+    Moderate,
+    Poor,
+}
+
+/// A jitter buffer resize
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct BufferAdaptation {
+    /// Sender timestamp of the last packet accepted before the resize
+    pub timestamp: u64,
+    pub capacity: u32,
+    pub quality: LinkQuality,
+}
+
+/// Playback statistics of one heard player's jitter buffer
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct EmitterDiagnostics {
+    pub player: String,
+    pub spatial: bool,
+    /// Smoothed interarrival jitter
+    pub jitter_ms: f64,
+    /// Fraction of frames missing from the sender's stream while they were speaking
+    pub loss_rate: f64,
+    pub packets_received: u64,
+    pub packets_lost: u64,
+    pub frames_decoded: u64,
+    /// Frames the decoder concealed because their packet had not arrived
+    pub frames_concealed: u64,
+    pub frames_dropped_overflow: u64,
+    /// Packets that arrived too late or out of order to be played
+    pub frames_dropped_late: u64,
+    pub buffer_depth: u32,
+    pub buffer_capacity: u32,
+    pub avg_buffer_depth: f64,
+    pub quality: LinkQuality,
+    /// 0.0 to 1.0, from the share of frames played as sent
+    pub quality_score: f64,
+    /// Most recent resizes, oldest first
+    pub adaptations: Vec<BufferAdaptation>,
+}
+
+/// Transport statistics of one voice server connection
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct ConnectionDiagnostics {
+    pub server: String,
+    pub primary: bool,
+    /// Round trip of the most recent health check
+    pub rtt_ms: Option<u32>,
+    pub smoothed_rtt_ms: Option<u32>,
+    /// Consecutive health checks without a response
+    pub failed_health_checks: u32,
+    pub datagrams_received: u64,
+    pub datagrams_sent: u64,
+    /// Datagrams the connection refused to send
+    pub datagrams_dropped: u64,
+    pub datagram_drop_rate: f64,
+}
+
+/// Everything needed to tell a bad network from a bad buffer
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct NetworkDiagnostics {
+    /// Unix timestamp in milliseconds
+    pub generated_at: u64,
+    pub connections: Vec<ConnectionDiagnostics>,
+    pub emitters: Vec<EmitterDiagnostics>,
+}
@@ -17,6 +17,7 @@ pub enum PacketType {
     PlayerPresence,
     ServerError,
     HealthCheck,
+    SessionResume,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
@@ -35,6 +36,7 @@ pub enum QuicNetworkPacketData {
     PlayerPresence(PlayerPresenceEvent),
     ServerError(ServerErrorPacket),
     HealthCheck(HealthCheckPacket),
+    SessionResume(SessionResumePacket),
 }
 
 /// A Quic Network Datagram
@@ -74,6 +76,14 @@ impl QuicNetworkPacket {
             .map_err(|e| anyhow!("Postcard deserialization error: {}", e))
     }
 
+    /// Reads only the packet type from a QUIC DATAGRAM payload without decoding the body.
+    /// `packet_type` is the first serialized field, so this is a single varint read.
+    pub fn peek_packet_type(data: &[u8]) -> Option<PacketType> {
+        postcard::take_from_bytes::<PacketType>(data)
+            .ok()
+            .map(|(packet_type, _)| packet_type)
+    }
+
     /// Returns the packet type
     pub fn get_packet_type(&self) -> PacketType {
         self.packet_type.clone()
@@ -90,6 +100,7 @@ impl QuicNetworkPacket {
             PacketType::PlayerPresence => true,
             PacketType::ServerError => false,
             PacketType::HealthCheck => false,
+            PacketType::SessionResume => false,
         }
     }
 
@@ -448,3 +459,21 @@ impl TryFrom<QuicNetworkPacketData> for HealthCheckPacket {
         }
     }
 }
+
+/// Sent first on every connection. A client reconnecting with the token of a session the server
+/// still holds picks that session back up, keeping its channel membership.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SessionResumePacket {
+    pub token: Vec<u8>,
+}
+
+impl TryFrom<QuicNetworkPacketData> for SessionResumePacket {
+    type Error = ();
+
+    fn try_from(value: QuicNetworkPacketData) -> Result<Self, Self::Error> {
+        match value {
+            QuicNetworkPacketData::SessionResume(s) => Ok(s),
+            _ => Err(()),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
-use crate::structs::audio::PlayerGainSettings;
+use crate::structs::audio::{AudioFormat, PlayerGainSettings};
+use crate::structs::SpatialAudioConfig;
 use crate::structs::packet::{PacketOwner, AudioFramePacket};
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -130,6 +131,47 @@ pub struct SessionManifest {
     pub emitter_player: String,
     pub participants: Vec<String>,
     pub created_at: String,
+    /// Set when the session was reconstructed after the client stopped mid-recording
+    #[serde(default)]
+    pub recovered: bool,
+    /// Pinned sessions are never pruned or compressed by the retention policy
+    #[serde(default)]
+    pub pinned: bool,
+    /// Moments flagged by the user while recording, in the order they were placed
+    #[serde(default)]
+    pub markers: Vec<RecordingMarker>,
+    /// Server spatial audio settings in effect when the session started
+    #[serde(default)]
+    pub spatial_config: Option<SpatialAudioConfig>,
+}
+
+/// A user-placed marker within a recording session
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+pub struct RecordingMarker {
+    /// Milliseconds since the session started, on the same clock as WAL entry timestamps
+    pub relative_timestamp_ms: u64,
+    pub label: Option<String>,
+}
+
+/// Automatic cleanup of old recording sessions. Every limit is optional and pinned sessions are always kept.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export, export_to = "./../../client/src/js/bindings/")]
+#[serde(default)]
+pub struct RecordingRetentionPolicy {
+    pub enabled: bool,
+    /// Delete sessions that started more than this many days ago
+    pub max_age_days: Option<u32>,
+    /// Delete the oldest sessions until all recordings fit in this many megabytes
+    pub max_total_size_mb: Option<u32>,
+    /// Only keep this many of the most recent unpinned sessions
+    pub keep_latest: Option<u32>,
+    /// Compress the WAL of sessions older than this many days
+    pub compress_after_days: Option<u32>,
+    /// Render every participant in this format before a session is deleted
+    pub export_before_delete: Option<AudioFormat>,
+    /// Directory that receives exports; defaults to `exports` beside the recordings directory
+    pub export_directory: Option<String>,
 }
 
 /// Concrete header type for input recording WAL entries
@@ -8,17 +8,34 @@ fn main() {
     let command_schema_value = serde_json::to_value(&schema_for!(Command)).unwrap();
     let success_schema_value = serde_json::to_value(&schema_for!(SuccessResponse)).unwrap();
     let error_schema_value = serde_json::to_value(&schema_for!(ErrorResponse)).unwrap();
+    let push_event_schema_value = serde_json::to_value(&schema_for!(PushEvent)).unwrap();
 
     let device_type_schema = extract_def(&command_schema_value, "DeviceType");
+    let event_type_schema = extract_def(&command_schema_value, "EventType");
     let pong_data_schema = extract_def(&success_schema_value, "PongData");
     let mute_data_schema = extract_def(&success_schema_value, "MuteData");
     let record_data_schema = extract_def(&success_schema_value, "RecordData");
+    let mark_data_schema = extract_def(&success_schema_value, "MarkData");
     let state_data_schema = extract_def(&success_schema_value, "StateData");
+    let player_data_schema = extract_def(&success_schema_value, "PlayerData");
+    let channel_info_schema = extract_def(&success_schema_value, "ChannelInfo");
+    let channels_data_schema = extract_def(&success_schema_value, "ChannelsData");
+    let channel_created_data_schema = extract_def(&success_schema_value, "ChannelCreatedData");
+    let channel_membership_data_schema =
+        extract_def(&success_schema_value, "ChannelMembershipData");
+    let ptt_data_schema = extract_def(&success_schema_value, "PttData");
+    let subscribe_data_schema = extract_def(&success_schema_value, "SubscribeData");
+    let activity_data_schema = extract_def(&push_event_schema_value, "ActivityData");
+    let speaking_data_schema = extract_def(&push_event_schema_value, "SpeakingData");
+    let presence_data_schema = extract_def(&push_event_schema_value, "PresenceData");
+    let channel_event_data_schema = extract_def(&push_event_schema_value, "ChannelEventData");
+    let connection_data_schema = extract_def(&push_event_schema_value, "ConnectionData");
     let response_data_schema = extract_def(&success_schema_value, "ResponseData");
 
     let command_payload = remove_defs(command_schema_value.clone());
     let success_payload = remove_defs(success_schema_value.clone());
     let error_payload = remove_defs(error_schema_value.clone());
+    let push_event_payload = remove_defs(push_event_schema_value.clone());
 
     let spec = json!({
         "asyncapi": "3.0.0",
@@ -46,6 +63,9 @@ fn main() {
                     },
                     "error": {
                         "$ref": "#/components/messages/ErrorResponse"
+                    },
+                    "event": {
+                        "$ref": "#/components/messages/PushEvent"
                     }
                 }
             }
@@ -56,7 +76,7 @@ fn main() {
                     "name": "Command",
                     "title": "WebSocket Command",
                     "summary": "Commands that can be sent to the WebSocket server",
-                    "description": "Tagged union of all available commands (ping, mute, record)",
+                    "description": "Tagged union of all available commands (ping, mute, record, mark, state, deafen, ptt, player, channels, create, join, leave, subscribe)",
                     "contentType": "application/json",
                     "payload": command_payload
                 },
@@ -75,15 +95,37 @@ fn main() {
                     "description": "Contains success flag (false) and error message",
                     "contentType": "application/json",
                     "payload": error_payload
+                },
+                "PushEvent": {
+                    "name": "PushEvent",
+                    "title": "Push Event",
+                    "summary": "Event pushed to connections that subscribed to its type",
+                    "description": "Sent after a subscribe command for activity, speaking, presence, channel and connection events",
+                    "contentType": "application/json",
+                    "payload": push_event_payload
                 }
             },
             "schemas": {
                 "DeviceType": device_type_schema,
+                "EventType": event_type_schema,
                 "PongData": pong_data_schema,
                 "MuteData": mute_data_schema,
                 "RecordData": record_data_schema,
+                "MarkData": mark_data_schema,
                 "StateData": state_data_schema,
-                "ResponseData": response_data_schema
+                "PlayerData": player_data_schema,
+                "ChannelInfo": channel_info_schema,
+                "ChannelsData": channels_data_schema,
+                "ChannelCreatedData": channel_created_data_schema,
+                "ChannelMembershipData": channel_membership_data_schema,
+                "PttData": ptt_data_schema,
+                "SubscribeData": subscribe_data_schema,
+                "ResponseData": response_data_schema,
+                "ActivityData": activity_data_schema,
+                "SpeakingData": speaking_data_schema,
+                "PresenceData": presence_data_schema,
+                "ChannelEventData": channel_event_data_schema,
+                "ConnectionData": connection_data_schema
             }
         }
     });
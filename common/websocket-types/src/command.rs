@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use super::DeviceType;
+use super::{DeviceType, EventType};
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "action", rename_all = "lowercase")]
@@ -9,7 +9,37 @@ pub enum Command {
     Ping,
     Mute { device: DeviceType },
     Record,
+    /// Place a marker in the active recording
+    Mark {
+        #[serde(default)]
+        label: Option<String>,
+    },
     State,
+    /// Toggle output mute, or set it when `deafened` is given
+    Deafen {
+        #[serde(default)]
+        deafened: Option<bool>,
+    },
+    /// Hold (`pressed: true`) or release push-to-talk
+    Ptt { pressed: bool },
+    /// Set a player's volume and/or mute them locally. Omitted fields are left unchanged.
+    Player {
+        player: String,
+        #[serde(default)]
+        gain: Option<f32>,
+        #[serde(default)]
+        muted: Option<bool>,
+    },
+    /// List the channels on the current server
+    Channels,
+    /// Create a channel on the current server
+    Create { name: String },
+    /// Join a channel, leaving the current one first
+    Join { channel: String },
+    /// Leave the current channel
+    Leave,
+    /// Replace this connection's push event subscriptions. An empty list unsubscribes.
+    Subscribe { events: Vec<EventType> },
 }
 
 impl Command {
@@ -65,6 +95,79 @@ mod tests {
         assert!(matches!(cmd, Command::Record));
     }
 
+    #[test]
+    fn test_parse_mark() {
+        let cmd = Command::from_json(r#"{"action":"mark","label":"good clip here"}"#).unwrap();
+        match cmd {
+            Command::Mark { label } => assert_eq!(label.as_deref(), Some("good clip here")),
+            _ => panic!("Expected Mark command"),
+        }
+
+        let cmd = Command::from_json(r#"{"action":"mark"}"#).unwrap();
+        assert!(matches!(cmd, Command::Mark { label: None }));
+    }
+
+    #[test]
+    fn test_parse_player() {
+        let cmd = Command::from_json(r#"{"action":"player","player":"Steve","gain":0.5}"#).unwrap();
+        match cmd {
+            Command::Player { player, gain, muted } => {
+                assert_eq!(player, "Steve");
+                assert_eq!(gain, Some(0.5));
+                assert_eq!(muted, None);
+            }
+            _ => panic!("Expected Player command"),
+        }
+    }
+
+    #[test]
+    fn test_parse_channel_commands() {
+        assert!(matches!(
+            Command::from_json(r#"{"action":"channels"}"#).unwrap(),
+            Command::Channels
+        ));
+        assert!(matches!(
+            Command::from_json(r#"{"action":"join","channel":"abc"}"#).unwrap(),
+            Command::Join { channel } if channel == "abc"
+        ));
+        assert!(matches!(
+            Command::from_json(r#"{"action":"leave"}"#).unwrap(),
+            Command::Leave
+        ));
+        assert!(matches!(
+            Command::from_json(r#"{"action":"create","name":"Raid"}"#).unwrap(),
+            Command::Create { name } if name == "Raid"
+        ));
+    }
+
+    #[test]
+    fn test_parse_ptt_and_deafen() {
+        assert!(matches!(
+            Command::from_json(r#"{"action":"ptt","pressed":true}"#).unwrap(),
+            Command::Ptt { pressed: true }
+        ));
+        assert!(matches!(
+            Command::from_json(r#"{"action":"deafen"}"#).unwrap(),
+            Command::Deafen { deafened: None }
+        ));
+        assert!(matches!(
+            Command::from_json(r#"{"action":"deafen","deafened":false}"#).unwrap(),
+            Command::Deafen { deafened: Some(false) }
+        ));
+    }
+
+    #[test]
+    fn test_parse_subscribe() {
+        let cmd = Command::from_json(r#"{"action":"subscribe","events":["presence","connection"]}"#).unwrap();
+        match cmd {
+            Command::Subscribe { events } => {
+                assert_eq!(events, vec![EventType::Presence, EventType::Connection]);
+            }
+            _ => panic!("Expected Subscribe command"),
+        }
+        assert!(Command::from_json(r#"{"action":"subscribe","events":["nope"]}"#).is_err());
+    }
+
     #[test]
     fn test_parse_command_message_with_key() {
         let msg = CommandMessage::from_json(r#"{"action":"ping","key":"secret123"}"#).unwrap();
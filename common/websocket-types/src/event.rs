@@ -0,0 +1,131 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Push event categories a connection can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EventType {
+    /// Audio levels of players currently heard
+    Activity,
+    /// Players, including the local player, starting or stopping to speak
+    Speaking,
+    /// Players joining or leaving the voice server
+    Presence,
+    /// Channel create, delete, join, leave and rename
+    Channel,
+    /// Changes to the connection to the voice server
+    Connection,
+}
+
+/// An unsolicited message sent to connections subscribed to its event type
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum PushEvent {
+    Activity(ActivityData),
+    Speaking(SpeakingData),
+    Presence(PresenceData),
+    Channel(ChannelEventData),
+    Connection(ConnectionData),
+}
+
+impl PushEvent {
+    pub fn event_type(&self) -> EventType {
+        match self {
+            PushEvent::Activity(_) => EventType::Activity,
+            PushEvent::Speaking(_) => EventType::Speaking,
+            PushEvent::Presence(_) => EventType::Presence,
+            PushEvent::Channel(_) => EventType::Channel,
+            PushEvent::Connection(_) => EventType::Connection,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActivityData {
+    /// RMS level per player over the last 100ms
+    pub levels: HashMap<String, f32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SpeakingData {
+    pub player: String,
+    /// `true` when the player started speaking, `false` once they have been silent for a moment
+    pub speaking: bool,
+    /// RMS level of the most recent audio heard from the player
+    pub level: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PresenceData {
+    pub player: String,
+    /// `joined` or `disconnected`
+    pub status: String,
+    pub game: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelEventData {
+    /// `create`, `delete`, `join`, `leave` or `rename`
+    pub event_type: String,
+    pub channel_id: String,
+    pub channel_name: Option<String>,
+    pub creator: Option<String>,
+    pub player_name: String,
+    pub timestamp: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "status")]
+pub enum ConnectionData {
+    Connected,
+    Reconnecting { attempt: u32 },
+    Disconnected,
+    Failed,
+    VersionMismatch {
+        client_version: String,
+        server_version: String,
+        client_too_old: bool,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_event_shape() {
+        let event = PushEvent::Presence(PresenceData {
+            player: "Steve".to_string(),
+            status: "joined".to_string(),
+            game: None,
+        });
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "presence");
+        assert_eq!(json["data"]["player"], "Steve");
+        assert_eq!(event.event_type(), EventType::Presence);
+    }
+
+    #[test]
+    fn test_speaking_event_shape() {
+        let data: SpeakingData =
+            serde_json::from_str(r#"{"player":"Steve","speaking":true,"level":0.2}"#).unwrap();
+        let json = serde_json::to_value(PushEvent::Speaking(data)).unwrap();
+        assert_eq!(json["event"], "speaking");
+        assert_eq!(json["data"]["speaking"], true);
+
+        let events: Vec<EventType> = serde_json::from_str(r#"["speaking"]"#).unwrap();
+        assert_eq!(events, vec![EventType::Speaking]);
+    }
+
+    #[test]
+    fn test_connection_data_matches_health_payload() {
+        let data: ConnectionData =
+            serde_json::from_str(r#"{"status":"Reconnecting","attempt":2}"#).unwrap();
+        assert!(matches!(data, ConnectionData::Reconnecting { attempt: 2 }));
+
+        let json = serde_json::to_value(PushEvent::Connection(data)).unwrap();
+        assert_eq!(json["event"], "connection");
+        assert_eq!(json["data"]["status"], "Reconnecting");
+    }
+}
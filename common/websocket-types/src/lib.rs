@@ -1,9 +1,18 @@
 pub mod command;
 pub mod device_type;
 pub mod error_response;
+pub mod event;
 pub mod success_response;
 
 pub use command::{Command, CommandMessage};
 pub use device_type::DeviceType;
 pub use error_response::ErrorResponse;
-pub use success_response::{MuteData, PongData, RecordData, ResponseData, StateData, SuccessResponse};
+pub use event::{
+    ActivityData, ChannelEventData, ConnectionData, EventType, PresenceData, PushEvent,
+    SpeakingData,
+};
+pub use success_response::{
+    ChannelCreatedData, ChannelInfo, ChannelMembershipData, ChannelsData, MarkData, MuteData,
+    PlayerData, PongData, PttData, RecordData, ResponseData, StateData, SubscribeData,
+    SuccessResponse,
+};
@@ -1,6 +1,8 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use super::EventType;
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SuccessResponse {
     pub success: bool,
@@ -13,7 +15,14 @@ pub enum ResponseData {
     Pong(PongData),
     Mute(MuteData),
     Record(RecordData),
+    Mark(MarkData),
     State(StateData),
+    Player(PlayerData),
+    Channels(ChannelsData),
+    ChannelCreated(ChannelCreatedData),
+    ChannelMembership(ChannelMembershipData),
+    Ptt(PttData),
+    Subscribe(SubscribeData),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -32,11 +41,64 @@ pub struct RecordData {
     pub recording: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MarkData {
+    /// Milliseconds since the recording session started
+    pub marker_ms: u64,
+    pub label: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct StateData {
     pub muted: bool,
     pub deafened: bool,
     pub recording: bool,
+    /// Name of the local player, once signed in to a server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PlayerData {
+    pub player: String,
+    pub gain: f32,
+    pub muted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelInfo {
+    pub id: String,
+    pub name: String,
+    pub creator: String,
+    pub players: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelsData {
+    pub channels: Vec<ChannelInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelCreatedData {
+    pub channel: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChannelMembershipData {
+    /// The channel joined or left
+    pub channel: String,
+    pub joined: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PttData {
+    pub active: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubscribeData {
+    pub subscribed: Vec<EventType>,
 }
 
 impl SuccessResponse {
@@ -61,14 +123,17 @@ impl SuccessResponse {
         }
     }
 
-    pub fn state(muted: bool, deafened: bool, recording: bool) -> Self {
+    pub fn mark(marker_ms: u64, label: Option<String>) -> Self {
+        Self {
+            success: true,
+            data: ResponseData::Mark(MarkData { marker_ms, label }),
+        }
+    }
+
+    pub fn state(state: StateData) -> Self {
         Self {
             success: true,
-            data: ResponseData::State(StateData {
-                muted,
-                deafened,
-                recording,
-            }),
+            data: ResponseData::State(state),
         }
     }
 }
@@ -0,0 +1,100 @@
+use super::server::RateLimitPolicy;
+use serde::{Deserialize, Serialize};
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_bandwidth() -> RateLimitPolicy {
+    // Bytes. Opus voice at 50 frames/s is well under 20 KiB/s.
+    RateLimitPolicy {
+        capacity: 256 * 1024,
+        refill_per_second: 64.0 * 1024.0,
+    }
+}
+
+fn default_audio_frame() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 150,
+        refill_per_second: 100.0,
+    }
+}
+
+fn default_player_data() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 5,
+        refill_per_second: 1.0,
+    }
+}
+
+fn default_channel_event() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 5,
+        refill_per_second: 1.0,
+    }
+}
+
+fn default_health_check() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 10,
+        refill_per_second: 2.0,
+    }
+}
+
+fn default_other() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 10,
+        refill_per_second: 2.0,
+    }
+}
+
+fn default_max_violations() -> u32 {
+    500
+}
+
+fn default_violation_window_secs() -> u64 {
+    10
+}
+
+/// Per-connection limits on inbound QUIC datagrams.
+/// Datagrams exceeding a budget are dropped before being decoded; a connection that
+/// exceeds `max_violations` drops within `violation_window_secs` is disconnected.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FloodProtection {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Total inbound bytes per connection
+    #[serde(default = "default_bandwidth")]
+    pub bandwidth: RateLimitPolicy,
+    #[serde(default = "default_audio_frame")]
+    pub audio_frame: RateLimitPolicy,
+    #[serde(default = "default_player_data")]
+    pub player_data: RateLimitPolicy,
+    #[serde(default = "default_channel_event")]
+    pub channel_event: RateLimitPolicy,
+    #[serde(default = "default_health_check")]
+    pub health_check: RateLimitPolicy,
+    // Debug, Collection, PlayerPresence and ServerError
+    #[serde(default = "default_other")]
+    pub other: RateLimitPolicy,
+    #[serde(default = "default_max_violations")]
+    pub max_violations: u32,
+    #[serde(default = "default_violation_window_secs")]
+    pub violation_window_secs: u64,
+}
+
+impl Default for FloodProtection {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            bandwidth: default_bandwidth(),
+            audio_frame: default_audio_frame(),
+            player_data: default_player_data(),
+            channel_event: default_channel_event(),
+            health_check: default_health_check(),
+            other: default_other(),
+            max_violations: default_max_violations(),
+            violation_window_secs: default_violation_window_secs(),
+        }
+    }
+}
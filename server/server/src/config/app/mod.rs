@@ -1,16 +1,26 @@
 mod database;
+mod flood_protection;
 mod logger;
+mod recording;
 pub mod server;
 mod voice;
+mod webhooks;
 
 pub use database::Database;
+pub use flood_protection::FloodProtection;
 pub use logger::Logger;
+pub use recording::{Recording, RecordingRegion};
+pub use server::Backend;
 pub use server::Features;
+pub use server::Federation;
 pub use server::Meridian;
 pub use server::Minecraft;
+pub use server::RateLimit;
+pub use server::RateLimitPolicy;
 pub use server::Server;
 pub use server::Tls;
 pub use voice::Voice;
+pub use webhooks::{WebhookEndpoint, WebhookEventType, Webhooks};
 
 use common::ncryptflib::randombytes_buf;
 use rocket::{
@@ -33,6 +43,10 @@ pub struct ApplicationConfig {
     pub log: Logger,
     #[serde(default)]
     pub voice: Voice,
+    #[serde(default)]
+    pub webhooks: Webhooks,
+    #[serde(default)]
+    pub recording: Recording,
 }
 
 impl Default for ApplicationConfig {
@@ -42,6 +56,8 @@ impl Default for ApplicationConfig {
             server: Server::default(),
             voice: Voice::default(),
             log: Logger::default(),
+            webhooks: Webhooks::default(),
+            recording: Recording::default(),
         }
     }
 }
@@ -0,0 +1,60 @@
+use common::Coordinate;
+use serde::{Deserialize, Serialize};
+
+fn default_recording_path() -> String {
+    "./recordings".to_string()
+}
+
+/// A named box of world space that can be recorded
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordingRegion {
+    pub name: String,
+    pub min: Coordinate,
+    pub max: Coordinate,
+    // Restrict the region to one federated backend; positions from other servers are not comparable
+    #[serde(default)]
+    pub server_id: Option<String>,
+}
+
+impl RecordingRegion {
+    pub fn contains(&self, position: &Coordinate, server_id: Option<&str>) -> bool {
+        if let Some(region_server) = &self.server_id
+            && server_id != Some(region_server.as_str())
+        {
+            return false;
+        }
+
+        let within = |value: f32, a: f32, b: f32| value >= a.min(b) && value <= a.max(b);
+        within(position.x, self.min.x, self.max.x)
+            && within(position.y, self.min.y, self.max.y)
+            && within(position.z, self.min.z, self.max.z)
+    }
+}
+
+/// Server-side recording of channels and world regions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Recording {
+    #[serde(default)]
+    pub enabled: bool,
+    // Directory that receives one session directory per recording
+    #[serde(default = "default_recording_path")]
+    pub path: String,
+    #[serde(default)]
+    pub regions: Vec<RecordingRegion>,
+}
+
+impl Recording {
+    pub fn region(&self, name: &str) -> Option<&RecordingRegion> {
+        self.regions.iter().find(|region| region.name == name)
+    }
+}
+
+impl Default for Recording {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: default_recording_path(),
+            regions: Vec::new(),
+        }
+    }
+}
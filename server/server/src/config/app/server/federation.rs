@@ -0,0 +1,38 @@
+use common::structs::SpatialAudioConfig;
+use serde::{Deserialize, Serialize};
+
+/// A backend game server posting positions to this voice server
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Backend {
+    pub id: String,
+    /// Access token this backend uses instead of `minecraft.access_token`
+    pub access_token: String,
+    /// Overrides `voice.spatial_audio` for players on this backend
+    #[serde(default)]
+    pub spatial_audio: Option<SpatialAudioConfig>,
+}
+
+/// Several game servers (e.g. behind a proxy) sharing one voice server
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Federation {
+    #[serde(default)]
+    pub backends: Vec<Backend>,
+    /// Allow channel members on different backends to hear each other
+    #[serde(default)]
+    pub cross_server_channels: bool,
+}
+
+impl Federation {
+    /// Find the backend an access token belongs to
+    pub fn backend_for_token(&self, access_token: &str) -> Option<&Backend> {
+        self.backends.iter().find(|b| b.access_token == access_token)
+    }
+
+    /// Spatial audio settings for a backend, if it overrides the defaults
+    pub fn spatial_audio_for(&self, server_id: &str) -> Option<&SpatialAudioConfig> {
+        self.backends
+            .iter()
+            .find(|b| b.id == server_id)
+            .and_then(|b| b.spatial_audio.as_ref())
+    }
+}
@@ -1,11 +1,15 @@
 pub mod features;
+pub mod federation;
 pub mod meridian;
 pub mod minecraft;
+pub mod rate_limit;
 pub mod tls;
 
 pub use features::Features;
+pub use federation::{Backend, Federation};
 pub use meridian::Meridian;
 pub use minecraft::Minecraft;
+pub use rate_limit::{RateLimit, RateLimitPolicy};
 pub use tls::Tls;
 
 use serde::{Deserialize, Serialize};
@@ -49,6 +53,10 @@ pub struct Server {
     pub features: Features,
     #[serde(default)]
     pub meridian: Option<Meridian>,
+    #[serde(default)]
+    pub rate_limit: RateLimit,
+    #[serde(default)]
+    pub federation: Federation,
 }
 
 impl Default for Server {
@@ -63,6 +71,8 @@ impl Default for Server {
             minecraft: Minecraft::default(),
             features: Features::default(),
             meridian: None,
+            rate_limit: RateLimit::default(),
+            federation: Federation::default(),
         }
     }
 }
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_auth_policy() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 20,
+        refill_per_second: 0.5,
+    }
+}
+
+fn default_auth_poll_policy() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 60,
+        refill_per_second: 1.0,
+    }
+}
+
+fn default_code_login_failure_policy() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 10,
+        refill_per_second: 1.0 / 60.0,
+    }
+}
+
+fn default_channel_policy() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 60,
+        refill_per_second: 2.0,
+    }
+}
+
+fn default_channel_create_policy() -> RateLimitPolicy {
+    RateLimitPolicy {
+        capacity: 5,
+        refill_per_second: 0.05,
+    }
+}
+
+/// Token bucket parameters for a single group of routes.
+/// A client may burst up to `capacity` requests, after which requests are
+/// admitted at `refill_per_second`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RateLimitPolicy {
+    pub capacity: u32,
+    pub refill_per_second: f64,
+}
+
+/// HTTP rate limiting, applied independently per client IP and per player identity
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RateLimit {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    // Login routes: /auth/minecraft, /auth/code, /auth/hytale/start-device-flow, /auth/link-java
+    #[serde(default = "default_auth_policy")]
+    pub auth: RateLimitPolicy,
+    // Hytale device flow polling, which clients call repeatedly until the user signs in
+    #[serde(default = "default_auth_poll_policy")]
+    pub auth_poll: RateLimitPolicy,
+    // Failed /auth/code attempts per gamertag, so codes can't be guessed from many addresses.
+    // Only failures are counted; once exhausted the gamertag is locked out until it refills.
+    #[serde(default = "default_code_login_failure_policy")]
+    pub code_login_failure: RateLimitPolicy,
+    // All /api/channel routes
+    #[serde(default = "default_channel_policy")]
+    pub channel: RateLimitPolicy,
+    // Additional, stricter limit for channel creation so the channel cache can't be flooded
+    #[serde(default = "default_channel_create_policy")]
+    pub channel_create: RateLimitPolicy,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            auth: default_auth_policy(),
+            auth_poll: default_auth_poll_policy(),
+            code_login_failure: default_code_login_failure_policy(),
+            channel: default_channel_policy(),
+            channel_create: default_channel_create_policy(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use super::FloodProtection;
 use common::structs::SpatialAudioConfig;
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +10,10 @@ fn default_datagram_recv_capacity() -> usize {
     1024
 }
 
+fn default_session_resume_grace_secs() -> u64 {
+    30
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Voice {
     // Maximum number of outbound datagrams buffered per connection before backpressure / drops
@@ -17,8 +22,13 @@ pub struct Voice {
     // Maximum number of inbound datagrams buffered per connection
     #[serde(default = "default_datagram_recv_capacity")]
     pub datagram_recv_capacity: usize,
+    // Seconds a dropped connection keeps its channels while the client reconnects (0 disables)
+    #[serde(default = "default_session_resume_grace_secs")]
+    pub session_resume_grace_secs: u64,
     #[serde(default)]
     pub spatial_audio: SpatialAudioConfig,
+    #[serde(default)]
+    pub flood_protection: FloodProtection,
 }
 
 impl Default for Voice {
@@ -26,7 +36,9 @@ impl Default for Voice {
         Self {
             datagram_send_capacity: default_datagram_send_capacity(),
             datagram_recv_capacity: default_datagram_recv_capacity(),
+            session_resume_grace_secs: default_session_resume_grace_secs(),
             spatial_audio: SpatialAudioConfig::default(),
+            flood_protection: FloodProtection::default(),
         }
     }
 }
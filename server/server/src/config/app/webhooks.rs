@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+
+fn default_queue_path() -> String {
+    "./webhooks".to_string()
+}
+
+fn default_max_attempts() -> u32 {
+    10
+}
+
+fn default_initial_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_max_backoff_secs() -> u64 {
+    300
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// Server events that can be delivered to outbound webhooks
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    PlayerPresence,
+    ChannelEvent,
+}
+
+/// A single outbound webhook destination
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    // Shared secret used to HMAC-SHA256 sign each payload
+    pub secret: String,
+    // Event types delivered to this endpoint. Empty delivers everything.
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+}
+
+impl WebhookEndpoint {
+    pub fn accepts(&self, event_type: WebhookEventType) -> bool {
+        self.events.is_empty() || self.events.contains(&event_type)
+    }
+}
+
+/// Outbound webhook delivery configuration
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Webhooks {
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+    // Directory where undelivered payloads are persisted across restarts
+    #[serde(default = "default_queue_path")]
+    pub queue_path: String,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+impl Default for Webhooks {
+    fn default() -> Self {
+        Self {
+            endpoints: Vec::new(),
+            queue_path: default_queue_path(),
+            max_attempts: default_max_attempts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_secs: default_max_backoff_secs(),
+            timeout_secs: default_timeout_secs(),
+        }
+    }
+}
@@ -1,11 +1,21 @@
 mod app;
 
 pub use app::ApplicationConfig;
+pub use app::Backend;
 pub use app::Database;
 pub use app::Features;
+pub use app::Federation;
+pub use app::FloodProtection;
 pub use app::Logger;
 pub use app::Meridian;
 pub use app::Minecraft;
+pub use app::RateLimit;
+pub use app::RateLimitPolicy;
+pub use app::Recording;
+pub use app::RecordingRegion;
 pub use app::Server;
 pub use app::Tls;
 pub use app::Voice;
+pub use app::WebhookEndpoint;
+pub use app::WebhookEventType;
+pub use app::Webhooks;
@@ -19,7 +19,7 @@
 use crate::config::ApplicationConfig;
 use crate::runtime::{position_updater, ServerRuntime};
 use crate::services::{PlayerIdentityService, PlayerRegistrarService};
-use crate::stream::quic::WebhookReceiver;
+use crate::stream::quic::{CacheManager, WebhookReceiver};
 
 use common::traits::player_data::PlayerData;
 use common::Game;
@@ -36,6 +36,8 @@ pub struct RuntimeHandle {
     shutdown_flag: Arc<AtomicBool>,
     /// Webhook receiver for position updates - accessible without locking runtime mutex
     webhook_receiver: Arc<RwLock<Option<WebhookReceiver>>>,
+    /// Cache manager for backend server assignment - accessible without locking runtime mutex
+    cache_manager: Arc<RwLock<Option<CacheManager>>>,
     /// Player registrar for player registration - accessible without locking runtime mutex
     player_registrar: Arc<RwLock<Option<PlayerRegistrarService>>>,
     /// Player identity service for cross-platform name resolution - accessible without locking runtime mutex
@@ -114,6 +116,7 @@ pub unsafe extern "C" fn bvc_server_create(config_json: *const c_char) -> *mut R
     // This allows stop() and update_positions() to work without locking the runtime
     let shutdown_flag = runtime.shutdown_flag();
     let webhook_receiver = runtime.get_webhook_receiver();
+    let cache_manager = runtime.get_cache_manager();
     let player_registrar = runtime.get_player_registrar();
     let identity_service = runtime.get_identity_service();
 
@@ -134,6 +137,7 @@ pub unsafe extern "C" fn bvc_server_create(config_json: *const c_char) -> *mut R
         tokio_runtime: Some(tokio_runtime),
         shutdown_flag,
         webhook_receiver,
+        cache_manager,
         player_registrar,
         identity_service,
     });
@@ -328,6 +332,7 @@ pub extern "C" fn bvc_protocol_version() -> *const c_char {
 ///   ```json
 ///   {
 ///     "game": "minecraft",
+///     "server_id": "lobby",
 ///     "players": [
 ///       {"name": "Player1", "x": 100.0, "y": 64.0, "z": 200.0, ...},
 ///       ...
@@ -434,8 +439,17 @@ pub unsafe extern "C" fn bvc_update_positions(
     let identity_service = is_guard.as_ref().cloned();
     drop(is_guard);
 
+    let cache_manager = match handle_ref.cache_manager.read() {
+        Ok(g) => g.as_ref().cloned(),
+        Err(e) => {
+            set_last_error(&format!("Failed to read cache_manager: {}", e));
+            return -1;
+        }
+    };
+
     // Get game type, defaulting to Minecraft for backwards compatibility
     let game_type = game_data.game.clone().unwrap_or(Game::Minecraft);
+    let server_id = game_data.server_id;
     let mut players = game_data.players;
 
     tokio_rt.block_on(async {
@@ -478,6 +492,10 @@ pub unsafe extern "C" fn bvc_update_positions(
             tracing::warn!("FFI: PlayerRegistrarService not available - player registration skipped");
         }
 
+        if let (Some(cache_manager), Some(server_id)) = (&cache_manager, &server_id) {
+            cache_manager.assign_server(server_id, &players);
+        }
+
         // Broadcast positions to QUIC clients (this happens immediately)
         position_updater::broadcast_positions(players, &webhook_receiver_clone).await;
     });
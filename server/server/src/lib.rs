@@ -23,7 +23,9 @@ pub mod runtime;
 
 // Re-exports for public API
 pub use config::{
-    ApplicationConfig, Database, Features, Logger, Meridian, Minecraft, Server, Tls, Voice,
+    ApplicationConfig, Backend, Database, Features, Federation, FloodProtection, Logger, Meridian,
+    Minecraft, RateLimit, RateLimitPolicy, Server, Tls, Voice, WebhookEndpoint, WebhookEventType,
+    Webhooks,
 };
 pub use runtime::{RuntimeState, ServerRuntime};
 
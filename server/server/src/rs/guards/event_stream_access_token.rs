@@ -0,0 +1,33 @@
+use rocket::{
+    async_trait,
+    http::Status,
+    request::{FromRequest, Outcome, Request},
+};
+
+use super::mc_access_token::{MCAccessToken, MCAccessTokenError};
+
+/// Access Token for the event stream.
+/// Browser `EventSource` connections cannot set headers, so the token may also be passed as
+/// the `access_token` query parameter. Only use this guard on that route, since tokens in
+/// URLs end up in proxy logs and browser history.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EventStreamAccessToken(pub MCAccessToken);
+
+#[async_trait]
+impl<'r> FromRequest<'r> for EventStreamAccessToken {
+    type Error = MCAccessTokenError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let key = req
+            .headers()
+            .get_one("X-MC-Access-Token")
+            .or_else(|| req.query_value::<&str>("access_token").and_then(|v| v.ok()));
+
+        match key {
+            Some(key) => MCAccessToken::verify(req, key)
+                .await
+                .map(EventStreamAccessToken),
+            None => Outcome::Error((Status::BadRequest, MCAccessTokenError::Invalid)),
+        }
+    }
+}
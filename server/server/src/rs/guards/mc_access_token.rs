@@ -7,9 +7,14 @@ use rocket::{
 
 use crate::config::Server;
 
-/// Extracts the Access Token from the ncryptf request
+/// Extracts the Access Token from the ncryptf request.
+///
+/// Tokens belonging to a federated backend carry that backend's `server_id`.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct MCAccessToken(pub String);
+pub struct MCAccessToken {
+    pub token: String,
+    pub server_id: Option<String>,
+}
 
 #[derive(Debug)]
 pub enum MCAccessTokenError {
@@ -21,22 +26,35 @@ impl<'r> FromRequest<'r> for MCAccessToken {
     type Error = MCAccessTokenError;
 
     async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
-        return match req.headers().get_one("X-MC-Access-Token") {
-            Some(key) => {
-                let at = req
-                    .guard::<&State<Server>>()
-                    .await
-                    .map(|config| MCAccessToken(config.minecraft.access_token.clone()));
-                let current = Outcome::Success(MCAccessToken(key.to_string()));
-
-                // Ensure that the access tokens match
-                if at.eq(&current) {
-                    Outcome::Success(MCAccessToken(key.to_string()))
-                } else {
-                    Outcome::Error((Status::Forbidden, MCAccessTokenError::Invalid))
-                }
-            }
+        match req.headers().get_one("X-MC-Access-Token") {
+            Some(key) => MCAccessToken::verify(req, key).await,
             None => Outcome::Error((Status::BadRequest, MCAccessTokenError::Invalid)),
+        }
+    }
+}
+
+impl MCAccessToken {
+    /// Checks the key against the server and federated backend access tokens
+    pub(crate) async fn verify(req: &Request<'_>, key: &str) -> Outcome<Self, MCAccessTokenError> {
+        let config = match req.guard::<&State<Server>>().await {
+            Outcome::Success(config) => config,
+            _ => return Outcome::Error((Status::Forbidden, MCAccessTokenError::Invalid)),
         };
+
+        // Ensure that the access tokens match
+        if config.minecraft.access_token.eq(key) {
+            return Outcome::Success(MCAccessToken {
+                token: key.to_string(),
+                server_id: None,
+            });
+        }
+
+        match config.federation.backend_for_token(key) {
+            Some(backend) => Outcome::Success(MCAccessToken {
+                token: key.to_string(),
+                server_id: Some(backend.id.clone()),
+            }),
+            None => Outcome::Error((Status::Forbidden, MCAccessTokenError::Invalid)),
+        }
     }
 }
@@ -1,8 +1,16 @@
 mod access_token;
 //pub(crate) use access_token::AccessToken;
 
+mod event_stream_access_token;
+pub(crate) use event_stream_access_token::EventStreamAccessToken;
+
 mod hytale_session_id;
 pub(crate) use hytale_session_id::HytaleSessionId;
 
 mod mc_access_token;
 pub(crate) use mc_access_token::MCAccessToken;
+
+mod rate_limit;
+pub(crate) use rate_limit::{
+    AuthPollRateLimit, AuthRateLimit, ChannelCreateRateLimit, ChannelRateLimit,
+};
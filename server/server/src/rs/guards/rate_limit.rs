@@ -0,0 +1,96 @@
+use rocket::{
+    async_trait,
+    http::Status,
+    mtls::Certificate,
+    request::{FromRequest, Outcome, Request},
+    State,
+};
+
+use crate::services::{RateLimitScope, RateLimitService};
+
+#[derive(Debug)]
+pub enum RateLimitError {
+    TooManyRequests,
+}
+
+/// Applies the scope's token bucket to the client IP, and to the player identity
+/// from the mTLS client certificate when one is presented.
+async fn enforce<'r>(req: &'r Request<'_>, scope: RateLimitScope) -> Outcome<(), RateLimitError> {
+    let limiter = match req.guard::<&State<RateLimitService>>().await {
+        Outcome::Success(limiter) => limiter,
+        _ => return Outcome::Success(()),
+    };
+
+    if !limiter.is_enabled() {
+        return Outcome::Success(());
+    }
+
+    if let Some(ip) = req.client_ip() {
+        if !limiter.check_ip(scope, ip) {
+            return Outcome::Error((Status::TooManyRequests, RateLimitError::TooManyRequests));
+        }
+    }
+
+    if let Outcome::Success(identity) = req.guard::<Certificate<'r>>().await {
+        if let Some(player) = identity.subject().common_name() {
+            if !limiter.check_player(scope, player) {
+                return Outcome::Error((Status::TooManyRequests, RateLimitError::TooManyRequests));
+            }
+        }
+    }
+
+    Outcome::Success(())
+}
+
+/// Rate limit for the login routes
+pub struct AuthRateLimit;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for AuthRateLimit {
+    type Error = RateLimitError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        enforce(req, RateLimitScope::Auth).await.map(|_| AuthRateLimit)
+    }
+}
+
+/// Looser rate limit for device flow polling, so a client waiting on sign-in
+/// doesn't use up the budget for the login routes
+pub struct AuthPollRateLimit;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for AuthPollRateLimit {
+    type Error = RateLimitError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        enforce(req, RateLimitScope::AuthPoll)
+            .await
+            .map(|_| AuthPollRateLimit)
+    }
+}
+
+/// Rate limit for the channel routes
+pub struct ChannelRateLimit;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for ChannelRateLimit {
+    type Error = RateLimitError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        enforce(req, RateLimitScope::Channel).await.map(|_| ChannelRateLimit)
+    }
+}
+
+/// Stricter rate limit for channel creation, applied in addition to `ChannelRateLimit`
+pub struct ChannelCreateRateLimit;
+
+#[async_trait]
+impl<'r> FromRequest<'r> for ChannelCreateRateLimit {
+    type Error = RateLimitError;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        enforce(req, RateLimitScope::ChannelCreate)
+            .await
+            .map(|_| ChannelCreateRateLimit)
+    }
+}
@@ -2,8 +2,11 @@ use crate::{
     config::ApplicationConfig,
     rs::pool::AppDb,
     rs::routes,
-    services::{PlayerIdentityService, PlayerRegistrarService},
-    stream::quic::{CacheManager, WebhookReceiver},
+    services::{PlayerIdentityService, PlayerRegistrarService, RateLimitService},
+    stream::quic::{
+        event_feed::EventFeed, flood_guard::FloodMetrics, server_recorder::ServerRecorder,
+        CacheManager, WebhookReceiver,
+    },
 };
 use anyhow::Error;
 use common::ncryptflib as ncryptf;
@@ -25,6 +28,10 @@ pub struct RocketManager {
     player_registrar: PlayerRegistrarService,
     identity_service: PlayerIdentityService,
     hytale_session_cache: routes::api::HytaleSessionCache,
+    rate_limiter: RateLimitService,
+    event_feed: EventFeed,
+    server_recorder: ServerRecorder,
+    flood_metrics: Arc<FloodMetrics>,
 }
 
 impl RocketManager {
@@ -34,7 +41,12 @@ impl RocketManager {
         cache_manager: CacheManager,
         player_registrar: PlayerRegistrarService,
         identity_service: PlayerIdentityService,
+        event_feed: EventFeed,
+        server_recorder: ServerRecorder,
+        flood_metrics: Arc<FloodMetrics>,
     ) -> Self {
+        let rate_limiter = RateLimitService::new(config.server.rate_limit.clone());
+
         Self {
             config,
             webhook_receiver,
@@ -42,6 +54,10 @@ impl RocketManager {
             player_registrar,
             identity_service,
             hytale_session_cache: routes::api::HytaleSessionCache::new(),
+            rate_limiter,
+            event_feed,
+            server_recorder,
+            flood_metrics,
         }
     }
 
@@ -87,6 +103,10 @@ impl RocketManager {
                     .manage(self.player_registrar.clone())
                     .manage(self.identity_service.clone())
                     .manage(self.hytale_session_cache.clone())
+                    .manage(self.rate_limiter.clone())
+                    .manage(self.event_feed.clone())
+                    .manage(self.server_recorder.clone())
+                    .manage(self.flood_metrics.clone())
                     .attach(AppDb::init())
                     .attach(cors.to_cors().unwrap())
                     .attach(rocket::fairing::AdHoc::try_on_ignite("Migrations", migrate))
@@ -106,6 +126,8 @@ impl RocketManager {
                             routes::api::get_config,
                             routes::api::update_position,
                             routes::api::position,
+                            routes::api::event_stream,
+                            routes::api::server_stats,
                             routes::api::pong
                         ],
                     )
@@ -127,6 +149,14 @@ impl RocketManager {
                             routes::api::channel_rename
                         ],
                     )
+                    .mount(
+                        "/api/recording",
+                        routes![
+                            routes::api::recording_start,
+                            routes::api::recording_status,
+                            routes::api::recording_stop
+                        ],
+                    )
                     .mount(
                         "/api/gamerpic",
                         routes![
@@ -9,15 +9,20 @@ use sea_orm_rocket::Connection as SeaOrmConnection;
 
 use crate::config::{Features, Server};
 use crate::rs::dtos::ncryptf::JsonMessage;
+use crate::rs::guards::AuthRateLimit;
 use crate::rs::pool::AppDb;
-use crate::services::{AuthCodeError, AuthCodeService, AuthError, AuthService};
+use crate::services::{
+    AuthCodeError, AuthCodeService, AuthError, AuthService, RateLimitScope, RateLimitService,
+};
 
 #[post("/auth/code", data = "<payload>")]
 pub async fn code_authenticate(
+    _rate_limit: AuthRateLimit,
     db: SeaOrmConnection<'_, AppDb>,
     payload: Json<CodeLoginRequest>,
     config: &State<Server>,
     features: &State<Features>,
+    rate_limiter: &State<RateLimitService>,
 ) -> ncryptf::rocket::JsonResponse<JsonMessage<LoginResponse>> {
     let conn = db.into_inner();
 
@@ -28,12 +33,26 @@ pub async fn code_authenticate(
     let code = &payload.0.code;
     let gamertag = &payload.0.gamertag;
 
+    // Limit failed attempts per gamertag too, so codes can't be guessed from many addresses
+    if !rate_limiter.peek_player(RateLimitScope::CodeLoginFailure, gamertag) {
+        return JsonMessage::create(Status::TooManyRequests, None, None, None);
+    }
+
     // Validate the code and get the associated player
     let player_record = match AuthCodeService::validate_and_consume_code(conn, code, gamertag).await
     {
         Ok(player) => player,
         Err(e) => {
             tracing::error!("Code login failed: {}", e);
+            if matches!(
+                e,
+                AuthCodeError::CodeNotFound
+                    | AuthCodeError::CodeExpired
+                    | AuthCodeError::CodeAlreadyUsed
+                    | AuthCodeError::GamertagMismatch
+            ) {
+                rate_limiter.check_player(RateLimitScope::CodeLoginFailure, gamertag);
+            }
             return match e {
                 AuthCodeError::CodeNotFound => {
                     JsonMessage::create(Status::NotFound, None, None, None)
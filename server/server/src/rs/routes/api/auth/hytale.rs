@@ -16,13 +16,14 @@ use crate::config::Server;
 use crate::rs::pool::AppDb;
 use crate::rs::dtos::ncryptf::JsonMessage;
 use crate::rs::dtos::{HytaleSession, HytaleSessionCache};
-use crate::rs::guards::HytaleSessionId;
+use crate::rs::guards::{AuthPollRateLimit, AuthRateLimit, HytaleSessionId};
 use crate::services::{AuthError, AuthService};
 
 /// Start a new Hytale device code flow
 /// Returns session_id and user code for the client to display
 #[post("/auth/hytale/start-device-flow")]
 pub async fn start_device_flow(
+    _rate_limit: AuthRateLimit,
     session_cache: &State<HytaleSessionCache>,
 ) -> ncryptf::rocket::JsonResponse<JsonMessage<HytaleDeviceFlowStartResponse>> {
     // Create the Hytale auth provider and start the device flow
@@ -64,6 +65,7 @@ pub async fn start_device_flow(
 /// Poll the status of a Hytale device code flow
 #[get("/auth/hytale/status")]
 pub async fn poll_status(
+    _rate_limit: AuthPollRateLimit,
     db: SeaOrmConnection<'_, AppDb>,
     config: &State<Server>,
     session_cache: &State<HytaleSessionCache>,
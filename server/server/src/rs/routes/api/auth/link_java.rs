@@ -6,6 +6,7 @@ use common::{
 };
 use rocket::{http::Status, mtls::Certificate, serde::json::Json, State};
 
+use crate::rs::guards::AuthRateLimit;
 use crate::services::PlayerIdentityService;
 
 /// Links a Java Minecraft identity to an existing player.
@@ -15,6 +16,7 @@ use crate::services::PlayerIdentityService;
 #[post("/auth/link-java", data = "<payload>")]
 pub async fn link_java_identity<'r>(
     _identity: Certificate<'r>,
+    _rate_limit: AuthRateLimit,
     payload: Json<LinkJavaIdentityRequest>,
     identity_service: &State<PlayerIdentityService>,
 ) -> Result<Json<LinkJavaIdentityResponse>, Status> {
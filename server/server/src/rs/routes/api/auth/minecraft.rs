@@ -9,6 +9,7 @@ use rocket::{http::Status, serde::json::Json, State};
 use sea_orm_rocket::Connection as SeaOrmConnection;
 
 use crate::config::Server;
+use crate::rs::guards::AuthRateLimit;
 use crate::rs::pool::AppDb;
 use crate::rs::dtos::ncryptf::JsonMessage;
 use crate::services::{AuthError, AuthService, PlayerIdentityService};
@@ -16,6 +17,7 @@ use crate::services::{AuthError, AuthService, PlayerIdentityService};
 /// Authenticates the Player via Xbox Live to grab their gamertag and other identifying information
 #[post("/auth/minecraft", data = "<payload>")]
 pub async fn authenticate(
+    _rate_limit: AuthRateLimit,
     db: SeaOrmConnection<'_, AppDb>,
     payload: Json<LoginRequest>,
     config: &State<Server>,
@@ -1,3 +1,4 @@
+use crate::rs::guards::{ChannelCreateRateLimit, ChannelRateLimit};
 use crate::stream::quic::{CacheManager, WebhookReceiver};
 use common::structs::{
     channel::{Channel, ChannelEvents::Create},
@@ -11,6 +12,8 @@ use rocket::{http::Status, mtls::Certificate, response::status, serde::json::Jso
 #[post("/", data = "<name>")]
 pub async fn channel_create<'r>(
     identity: Certificate<'r>,
+    _rate_limit: ChannelRateLimit,
+    _create_rate_limit: ChannelCreateRateLimit,
     cache_manager: &State<CacheManager>,
     webhook_receiver: &State<WebhookReceiver>,
     name: Json<String>,
@@ -1,3 +1,4 @@
+use crate::rs::guards::ChannelRateLimit;
 use crate::stream::quic::{CacheManager, WebhookReceiver};
 use common::structs::{
     channel::ChannelEvents::Delete,
@@ -11,6 +12,7 @@ use rocket::{http::Status, mtls::Certificate, response::status, serde::json::Jso
 #[delete("/<id>")]
 pub async fn channel_delete<'r>(
     identity: Certificate<'r>,
+    _rate_limit: ChannelRateLimit,
     cache_manager: &State<CacheManager>,
     webhook_receiver: &State<WebhookReceiver>,
     id: &str,
@@ -1,4 +1,5 @@
 use crate::rs::pool::AppDb;
+use crate::rs::guards::ChannelRateLimit;
 use crate::stream::quic::{CacheManager, WebhookReceiver};
 use common::structs::{
     channel::{
@@ -20,6 +21,7 @@ use sea_orm_rocket::Connection as SeaOrmConnection;
 #[put("/<id>", data = "<event>")]
 pub async fn channel_event<'r>(
     identity: Certificate<'r>,
+    _rate_limit: ChannelRateLimit,
     db: SeaOrmConnection<'_, AppDb>,
     cache_manager: &State<CacheManager>,
     id: &str,
@@ -3,6 +3,7 @@ pub(crate) mod delete;
 pub(crate) mod event;
 pub(crate) mod rename;
 
+use crate::rs::guards::ChannelRateLimit;
 use crate::rs::pool::AppDb;
 use crate::stream::quic::CacheManager;
 use entity::player;
@@ -17,6 +18,7 @@ use common::structs::channel::Channel;
 #[get("/?<id>")]
 pub async fn channel_list<'r>(
     _identity: Certificate<'r>,
+    _rate_limit: ChannelRateLimit,
     db: SeaOrmConnection<'_, AppDb>,
     cache_manager: &State<CacheManager>,
     id: Option<String>,
@@ -1,3 +1,4 @@
+use crate::rs::guards::ChannelRateLimit;
 use crate::stream::quic::{CacheManager, WebhookReceiver};
 use common::structs::{
     channel::ChannelEvents::Rename,
@@ -10,6 +11,7 @@ use rocket::{http::Status, mtls::Certificate, response::status, serde::json::Jso
 #[patch("/<id>", data = "<name>")]
 pub async fn channel_rename<'r>(
     identity: Certificate<'r>,
+    _rate_limit: ChannelRateLimit,
     cache_manager: &State<CacheManager>,
     webhook_receiver: &State<WebhookReceiver>,
     id: &str,
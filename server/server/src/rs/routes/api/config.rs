@@ -1,19 +1,40 @@
 use common::consts::version::PROTOCOL_VERSION;
 use common::structs::config::ApiConfig;
-use rocket::{serde::json::Json, State};
+use rocket::{mtls::Certificate, serde::json::Json, State};
 
 use crate::config::{Server, Voice};
+use crate::stream::quic::CacheManager;
 
-#[get("/config")]
+/// Spatial audio overrides follow the federated backend the requesting player is on, resolved
+/// from their client certificate. `server` only selects a backend for callers without one.
+#[get("/config?<server>")]
 pub async fn get_config(
+    identity: Option<Certificate<'_>>,
     config: &State<Server>,
     voice: &State<Voice>,
+    cache_manager: &State<CacheManager>,
+    server: Option<String>,
 ) -> Json<ApiConfig> {
+    let server_id = identity
+        .as_ref()
+        .and_then(|identity| identity.subject().common_name())
+        .and_then(|player| cache_manager.server_of(player))
+        .or(server);
+    let spatial_audio = server_id
+        .as_deref()
+        .and_then(|server_id| config.federation.spatial_audio_for(server_id))
+        .unwrap_or(&voice.spatial_audio)
+        .clone();
+
     Json(ApiConfig {
         status: String::from("Ok"),
         client_id: config.minecraft.client_id.clone(),
         protocol_version: PROTOCOL_VERSION.to_string(),
         quic_port: config.quic_port,
-        spatial_audio: voice.spatial_audio.clone(),
+        spatial_audio,
+        players: cache_manager
+            .connected_players()
+            .and_then(|count| u32::try_from(count).ok()),
+        server_id,
     })
 }
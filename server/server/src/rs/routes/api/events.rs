@@ -0,0 +1,52 @@
+use rocket::response::stream::{Event, EventStream};
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::{Shutdown, State};
+
+use crate::{rs::guards::EventStreamAccessToken, stream::quic::event_feed::EventFeed};
+
+/// Server-Sent Events feed of presence, channel, speaking and position changes
+/// for dashboards and stream overlays.
+///
+/// Accepts the access token as the `access_token` query parameter for `EventSource` clients.
+/// `types` optionally restricts the feed to a comma separated list of event names
+/// (`player_presence`, `channel_event`, `speaking`, `position`).
+#[get("/events?<types>")]
+pub async fn event_stream(
+    _access_token: EventStreamAccessToken,
+    event_feed: &State<EventFeed>,
+    mut shutdown: Shutdown,
+    types: Option<String>,
+) -> EventStream![] {
+    let mut rx = event_feed.subscribe();
+    let filter: Option<Vec<String>> = types.map(|types| {
+        types
+            .split(',')
+            .map(|t| t.trim().to_lowercase())
+            .filter(|t| !t.is_empty())
+            .collect()
+    });
+
+    EventStream! {
+        loop {
+            let event = rocket::tokio::select! {
+                event = rx.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Closed) => break,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Event stream subscriber lagged, skipped {} events", skipped);
+                        continue;
+                    }
+                },
+                _ = &mut shutdown => break,
+            };
+
+            if let Some(filter) = &filter {
+                if !filter.iter().any(|t| t == event.name()) {
+                    continue;
+                }
+            }
+
+            yield Event::json(&event).event(event.name());
+        }
+    }
+}
@@ -1,9 +1,12 @@
 mod auth;
 mod channel;
 mod config;
+mod events;
 mod gamerpic;
 mod positions;
 mod ping;
+mod recording;
+mod stats;
 
 pub use auth::{
     code_authenticate,
@@ -14,10 +17,13 @@ pub use auth::{
     HytaleSessionCache,
 };
 pub use config::get_config;
+pub use events::event_stream;
 pub use gamerpic::get_gamerpic;
 pub use positions::position;
 pub use positions::update_position;
 pub use ping::pong;
+pub use recording::{recording_start, recording_status, recording_stop};
+pub use stats::server_stats;
 
 pub use channel::channel_list;
 pub use channel::create::channel_create;
@@ -13,9 +13,10 @@ use crate::runtime::position_updater;
 /// Stores player position data
 #[post("/position", data = "<positions>")]
 pub async fn update_position(
-    _access_token: MCAccessToken,
+    access_token: MCAccessToken,
     positions: Json<common::GameDataCollection>,
     webhook_receiver: &State<WebhookReceiver>,
+    cache_manager: &State<CacheManager>,
     player_registrar: &State<PlayerRegistrarService>,
     identity_service: &State<PlayerIdentityService>,
 ) -> Status {
@@ -48,6 +49,11 @@ pub async fn update_position(
         .process_players(&all_players, game_type)
         .await;
 
+    // A backend's own access token decides which server it posts for
+    if let Some(server_id) = access_token.server_id.or(positions.0.server_id.clone()) {
+        cache_manager.assign_server(&server_id, &all_players);
+    }
+
     position_updater::broadcast_positions(all_players, webhook_receiver).await;
 
     Status::Ok
@@ -0,0 +1,77 @@
+use rocket::{State, http::Status, response::status, serde::json::Json};
+
+use crate::{
+    rs::guards::MCAccessToken,
+    stream::quic::server_recorder::{
+        RecordingError, RecordingStatus, RecordingTarget, ServerRecorder,
+    },
+};
+
+fn error_status(error: &RecordingError) -> Status {
+    match error {
+        RecordingError::Disabled => Status::ServiceUnavailable,
+        RecordingError::AlreadyRecording | RecordingError::NotRecording => Status::Conflict,
+        RecordingError::UnknownRegion(_) => Status::NotFound,
+        RecordingError::Io(_) => Status::InternalServerError,
+    }
+}
+
+/// Server recording is an administrative action: federated backend tokens are refused
+fn is_admin(access_token: &MCAccessToken) -> bool {
+    access_token.server_id.is_none()
+}
+
+/// Starts recording a channel or a configured region
+#[post("/start", data = "<target>")]
+pub async fn recording_start(
+    access_token: MCAccessToken,
+    recorder: &State<ServerRecorder>,
+    target: Json<RecordingTarget>,
+) -> status::Custom<Option<Json<RecordingStatus>>> {
+    if !is_admin(&access_token) {
+        return status::Custom(Status::Forbidden, None);
+    }
+
+    match recorder.start(target.0) {
+        Ok(status) => status::Custom(Status::Ok, Some(Json(status))),
+        Err(e) => {
+            tracing::warn!("Failed to start server recording: {}", e);
+            status::Custom(error_status(&e), None)
+        }
+    }
+}
+
+/// The recording in progress, if any
+#[get("/")]
+pub async fn recording_status(
+    access_token: MCAccessToken,
+    recorder: &State<ServerRecorder>,
+) -> status::Custom<Option<Json<RecordingStatus>>> {
+    if !is_admin(&access_token) {
+        return status::Custom(Status::Forbidden, None);
+    }
+
+    match recorder.status() {
+        Some(status) => status::Custom(Status::Ok, Some(Json(status))),
+        None => status::Custom(Status::NotFound, None),
+    }
+}
+
+/// Stops the recording in progress once its session has been written out
+#[post("/stop")]
+pub async fn recording_stop(
+    access_token: MCAccessToken,
+    recorder: &State<ServerRecorder>,
+) -> status::Custom<Option<Json<RecordingStatus>>> {
+    if !is_admin(&access_token) {
+        return status::Custom(Status::Forbidden, None);
+    }
+
+    match recorder.stop().await {
+        Ok(status) => status::Custom(Status::Ok, Some(Json(status))),
+        Err(e) => {
+            tracing::warn!("Failed to stop server recording: {}", e);
+            status::Custom(error_status(&e), None)
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use rocket::{State, http::Status, response::status, serde::json::Json};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::{
+    rs::guards::MCAccessToken,
+    stream::quic::{
+        CacheManager,
+        flood_guard::{FloodMetrics, FloodMetricsSnapshot},
+    },
+};
+
+#[derive(Serialize, Debug)]
+pub struct ServerStats {
+    /// Players connected to voice, once the QUIC server is running
+    pub players: Option<usize>,
+    pub flood_protection: FloodMetricsSnapshot,
+}
+
+/// Connection and flood protection counters. Federated backend tokens are refused.
+#[get("/stats")]
+pub async fn server_stats(
+    access_token: MCAccessToken,
+    cache_manager: &State<CacheManager>,
+    flood_metrics: &State<Arc<FloodMetrics>>,
+) -> status::Custom<Option<Json<ServerStats>>> {
+    if access_token.server_id.is_some() {
+        return status::Custom(Status::Forbidden, None);
+    }
+
+    status::Custom(
+        Status::Ok,
+        Some(Json(ServerStats {
+            players: cache_manager.connected_players(),
+            flood_protection: flood_metrics.snapshot(),
+        })),
+    )
+}
@@ -2,8 +2,11 @@ pub mod position_updater;
 
 use crate::config::ApplicationConfig;
 use crate::rs::manager::RocketManager;
-use crate::services::{CertificateService, MeridianService, PlayerIdentityService, PlayerRegistrarService};
-use crate::stream::quic::{QuicServerManager, WebhookReceiver};
+use crate::services::{
+    CertificateService, MeridianService, PlayerIdentityService, PlayerRegistrarService,
+    WebhookDeliveryService,
+};
+use crate::stream::quic::{CacheManager, QuicServerManager, WebhookReceiver};
 
 use anyhow::anyhow;
 use faccess::PathExt;
@@ -41,6 +44,8 @@ pub struct ServerRuntime {
     shutdown_flag: Arc<AtomicBool>,
     /// Webhook receiver for sending position updates directly (populated after start)
     webhook_receiver: Arc<RwLock<Option<WebhookReceiver>>>,
+    /// Cache manager for recording which backend server players are on (populated after start)
+    cache_manager: Arc<RwLock<Option<CacheManager>>>,
     /// Player registrar for handling player registration (populated after start)
     player_registrar: Arc<RwLock<Option<PlayerRegistrarService>>>,
     /// Player identity service for cross-platform name resolution (populated after start)
@@ -56,6 +61,7 @@ impl ServerRuntime {
             state: RuntimeState::Stopped,
             shutdown_flag: Arc::new(AtomicBool::new(false)),
             webhook_receiver: Arc::new(RwLock::new(None)),
+            cache_manager: Arc::new(RwLock::new(None)),
             player_registrar: Arc::new(RwLock::new(None)),
             identity_service: Arc::new(RwLock::new(None)),
             _logger_guard: None,
@@ -137,6 +143,18 @@ impl ServerRuntime {
         let mut quic_manager = QuicServerManager::new(self.config.clone());
         let webhook_receiver = quic_manager.get_webhook_receiver().clone();
         let cache_manager = quic_manager.get_cache_manager();
+        let event_feed = quic_manager.get_event_feed();
+        let server_recorder = quic_manager.get_server_recorder();
+        let flood_metrics = quic_manager.get_flood_metrics();
+
+        // Outbound webhooks for presence and channel events
+        let webhook_delivery_task = if self.config.webhooks.endpoints.is_empty() {
+            None
+        } else {
+            let webhook_delivery = WebhookDeliveryService::new(self.config.webhooks.clone())?;
+            quic_manager.set_webhook_delivery(webhook_delivery.clone());
+            Some(tokio::spawn(async move { webhook_delivery.run().await }))
+        };
 
         // Store webhook_receiver for FFI position updates
         {
@@ -145,6 +163,13 @@ impl ServerRuntime {
             *wr = Some(webhook_receiver.clone());
         }
 
+        // Store cache_manager for FFI server assignment
+        {
+            let mut cm = self.cache_manager.write()
+                .map_err(|_| anyhow!("cache_manager lock poisoned"))?;
+            *cm = Some(cache_manager.clone());
+        }
+
         // Create Rocket manager
         let rocket_manager = RocketManager::new(
             self.config.clone(),
@@ -152,6 +177,9 @@ impl ServerRuntime {
             cache_manager,
             player_registrar,
             identity_service,
+            event_feed,
+            server_recorder,
+            flood_metrics,
         );
 
         self.state = RuntimeState::Running;
@@ -210,6 +238,10 @@ impl ServerRuntime {
             tracing::error!("Error stopping QUIC server: {}", e);
         }
 
+        if let Some(task) = webhook_delivery_task {
+            task.abort();
+        }
+
         self.state = RuntimeState::Stopped;
         Ok(())
     }
@@ -224,6 +256,11 @@ impl ServerRuntime {
         self.webhook_receiver.clone()
     }
 
+    /// Get a clone of the cache manager Arc for external use (FFI)
+    pub fn get_cache_manager(&self) -> Arc<RwLock<Option<CacheManager>>> {
+        self.cache_manager.clone()
+    }
+
     /// Get a clone of the player registrar Arc for external use (FFI)
     pub fn get_player_registrar(&self) -> Arc<RwLock<Option<PlayerRegistrarService>>> {
         self.player_registrar.clone()
@@ -7,6 +7,8 @@ pub mod gamerpic_decoder;
 pub mod meridian_service;
 pub mod player_identity_service;
 pub mod player_registrar_service;
+pub mod rate_limit_service;
+pub mod webhook_delivery_service;
 
 pub use auth_code_service::{AuthCodeError, AuthCodeService};
 pub use auth_service::{AuthError, AuthService};
@@ -15,3 +17,5 @@ pub use gamerpic_decoder::GamerpicDecoder;
 pub use meridian_service::MeridianService;
 pub use player_identity_service::PlayerIdentityService;
 pub use player_registrar_service::{PlayerRegistrarService, RegisteredPlayersCache};
+pub use rate_limit_service::{RateLimitScope, RateLimitService, TokenBucket};
+pub use webhook_delivery_service::WebhookDeliveryService;
@@ -0,0 +1,250 @@
+//! Token bucket rate limiting for the HTTP API
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use moka::sync::Cache;
+
+use crate::config::{RateLimit, RateLimitPolicy};
+
+/// The group of routes a request is counted against.
+/// Each scope has its own buckets, so exhausting one does not block the others.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum RateLimitScope {
+    Auth,
+    AuthPoll,
+    CodeLoginFailure,
+    Channel,
+    ChannelCreate,
+}
+
+/// A single token bucket. Tokens refill continuously at `refill_per_second`
+/// up to `capacity`, and each admitted request consumes one token.
+#[derive(Debug)]
+pub struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(policy: &RateLimitPolicy, now: Instant) -> Self {
+        let capacity = policy.capacity as f64;
+        Self {
+            tokens: capacity,
+            capacity,
+            refill_per_second: policy.refill_per_second.max(0.0),
+            last_refill: now,
+        }
+    }
+
+    /// Attempt to consume a token at `now`. Returns false if the bucket is empty.
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        self.try_acquire_n(1.0, now)
+    }
+
+    /// Attempt to consume `amount` tokens at `now`, e.g. a byte count for bandwidth budgets.
+    pub fn try_acquire_n(&mut self, amount: f64, now: Instant) -> bool {
+        if self.has_tokens(amount, now) {
+            self.tokens -= amount;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Whether `amount` tokens are available at `now`, without consuming them
+    pub fn has_tokens(&mut self, amount: f64, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = now;
+
+        self.tokens >= amount
+    }
+}
+
+/// Tracks token buckets per (scope, key), where key is a client IP or player identity.
+/// Idle buckets are evicted once they would have fully refilled anyway.
+#[derive(Clone)]
+pub struct RateLimitService {
+    config: RateLimit,
+    buckets: Cache<(RateLimitScope, String), Arc<Mutex<TokenBucket>>>,
+}
+
+impl RateLimitService {
+    pub fn new(config: RateLimit) -> Self {
+        let buckets = Cache::builder()
+            .time_to_idle(Duration::from_secs(15 * 60))
+            .max_capacity(65536)
+            .build();
+
+        Self { config, buckets }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    fn policy(&self, scope: RateLimitScope) -> &RateLimitPolicy {
+        match scope {
+            RateLimitScope::Auth => &self.config.auth,
+            RateLimitScope::AuthPoll => &self.config.auth_poll,
+            RateLimitScope::CodeLoginFailure => &self.config.code_login_failure,
+            RateLimitScope::Channel => &self.config.channel,
+            RateLimitScope::ChannelCreate => &self.config.channel_create,
+        }
+    }
+
+    fn bucket(&self, scope: RateLimitScope, key: &str, now: Instant) -> Arc<Mutex<TokenBucket>> {
+        let policy = self.policy(scope);
+        self.buckets.get_with((scope, key.to_string()), || {
+            Arc::new(Mutex::new(TokenBucket::new(policy, now)))
+        })
+    }
+
+    /// Consume a token for the given key. Returns false if the request should be rejected.
+    pub fn check(&self, scope: RateLimitScope, key: &str) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        let now = Instant::now();
+        let allowed = match self.bucket(scope, key, now).lock() {
+            Ok(mut bucket) => bucket.try_acquire(now),
+            Err(_) => true,
+        };
+
+        if !allowed {
+            tracing::warn!("Rate limit exceeded: scope={:?} key={}", scope, key);
+        }
+
+        allowed
+    }
+
+    /// Whether the key has a token left in the scope, without consuming it.
+    /// Used with `check` for budgets that only charge some outcomes, like failed logins.
+    pub fn peek(&self, scope: RateLimitScope, key: &str) -> bool {
+        if !self.config.enabled {
+            return true;
+        }
+
+        let now = Instant::now();
+        match self.bucket(scope, key, now).lock() {
+            Ok(mut bucket) => bucket.has_tokens(1.0, now),
+            Err(_) => true,
+        }
+    }
+
+    /// Check a client IP against the scope
+    pub fn check_ip(&self, scope: RateLimitScope, ip: std::net::IpAddr) -> bool {
+        self.check(scope, &format!("ip:{}", ip))
+    }
+
+    /// Check a player identity (gamertag, certificate CN, etc.) against the scope
+    pub fn check_player(&self, scope: RateLimitScope, player: &str) -> bool {
+        self.check(scope, &player_key(player))
+    }
+
+    /// Whether a player identity has a token left in the scope, without consuming it
+    pub fn peek_player(&self, scope: RateLimitScope, player: &str) -> bool {
+        self.peek(scope, &player_key(player))
+    }
+}
+
+fn player_key(player: &str) -> String {
+    format!("player:{}", player.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(capacity: u32, refill_per_second: f64) -> RateLimitPolicy {
+        RateLimitPolicy {
+            capacity,
+            refill_per_second,
+        }
+    }
+
+    #[test]
+    fn bucket_allows_burst_up_to_capacity() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(&policy(3, 1.0), now);
+
+        assert!(bucket.try_acquire(now));
+        assert!(bucket.try_acquire(now));
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.try_acquire(now));
+    }
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(&policy(1, 2.0), now);
+
+        assert!(bucket.try_acquire(now));
+        assert!(!bucket.try_acquire(now + Duration::from_millis(250)));
+        assert!(bucket.try_acquire(now + Duration::from_millis(600)));
+    }
+
+    #[test]
+    fn bucket_never_exceeds_capacity() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::new(&policy(2, 10.0), now);
+
+        let later = now + Duration::from_secs(60);
+        assert!(bucket.try_acquire(later));
+        assert!(bucket.try_acquire(later));
+        assert!(!bucket.try_acquire(later));
+    }
+
+    #[test]
+    fn service_scopes_and_keys_are_independent() {
+        let service = RateLimitService::new(RateLimit {
+            enabled: true,
+            auth: policy(1, 0.0),
+            auth_poll: policy(1, 0.0),
+            code_login_failure: policy(1, 0.0),
+            channel: policy(1, 0.0),
+            channel_create: policy(1, 0.0),
+        });
+
+        assert!(service.check_player(RateLimitScope::Auth, "Steve"));
+        assert!(!service.check_player(RateLimitScope::Auth, "steve"));
+        assert!(service.check_player(RateLimitScope::Channel, "Steve"));
+        assert!(service.check_player(RateLimitScope::AuthPoll, "Steve"));
+        assert!(service.check_player(RateLimitScope::Auth, "Alex"));
+    }
+
+    #[test]
+    fn disabled_service_always_allows() {
+        let service = RateLimitService::new(RateLimit {
+            enabled: false,
+            auth: policy(0, 0.0),
+            ..RateLimit::default()
+        });
+
+        for _ in 0..10 {
+            assert!(service.check_player(RateLimitScope::Auth, "Steve"));
+        }
+    }
+
+    #[test]
+    fn peek_does_not_consume() {
+        let service = RateLimitService::new(RateLimit {
+            enabled: true,
+            code_login_failure: policy(2, 0.0),
+            ..RateLimit::default()
+        });
+
+        for _ in 0..10 {
+            assert!(service.peek_player(RateLimitScope::CodeLoginFailure, "Steve"));
+        }
+
+        assert!(service.check_player(RateLimitScope::CodeLoginFailure, "Steve"));
+        assert!(service.check_player(RateLimitScope::CodeLoginFailure, "STEVE"));
+        assert!(!service.peek_player(RateLimitScope::CodeLoginFailure, "steve"));
+        assert!(service.peek_player(RateLimitScope::CodeLoginFailure, "Alex"));
+    }
+}
@@ -0,0 +1,56 @@
+//! JSON payloads delivered to outbound webhooks
+
+use common::structs::packet::{
+    ChannelEventPacket, PlayerPresenceEvent, QuicNetworkPacket, QuicNetworkPacketData,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::config::WebhookEventType;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum WebhookEventData {
+    PlayerPresence(PlayerPresenceEvent),
+    ChannelEvent(ChannelEventPacket),
+}
+
+/// A server event as delivered to a webhook endpoint
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookEvent {
+    pub id: String,
+    /// Unix timestamp in milliseconds when the event was observed
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub data: WebhookEventData,
+}
+
+impl WebhookEvent {
+    /// Build an event from a packet flowing through the server, if it is a deliverable type
+    pub fn from_packet(packet: &QuicNetworkPacket) -> Option<Self> {
+        let data = match &packet.data {
+            QuicNetworkPacketData::PlayerPresence(presence) => {
+                WebhookEventData::PlayerPresence(presence.clone())
+            }
+            QuicNetworkPacketData::ChannelEvent(channel_event) => {
+                WebhookEventData::ChannelEvent(channel_event.clone())
+            }
+            _ => return None,
+        };
+
+        Some(Self {
+            id: nanoid::nanoid!(21),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as i64,
+            data,
+        })
+    }
+
+    pub fn event_type(&self) -> WebhookEventType {
+        match self.data {
+            WebhookEventData::PlayerPresence(_) => WebhookEventType::PlayerPresence,
+            WebhookEventData::ChannelEvent(_) => WebhookEventType::ChannelEvent,
+        }
+    }
+}
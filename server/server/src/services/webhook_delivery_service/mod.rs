@@ -0,0 +1,446 @@
+//! Outbound webhook delivery of server events
+//!
+//! Presence and channel events observed by the QUIC server are serialized to JSON,
+//! signed with each endpoint's shared secret, and delivered with exponential backoff.
+//! Pending deliveries are persisted to disk by the delivery task so they survive a restart.
+//!
+//! Each request carries:
+//! - `X-BVC-Event`: the event type (`player_presence`, `channel_event`)
+//! - `X-BVC-Delivery`: a unique delivery ID, stable across retries
+//! - `X-BVC-Timestamp`: Unix timestamp (ms) of the attempt
+//! - `X-BVC-Signature`: `sha256=<hex>` HMAC-SHA256 of `"{timestamp}.{body}"`
+
+mod event;
+mod queue;
+
+pub use event::{WebhookEvent, WebhookEventData};
+pub use queue::{WebhookDelivery, WebhookQueue};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use common::structs::packet::QuicNetworkPacket;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::sync::Notify;
+
+use crate::config::{WebhookEndpoint, Webhooks};
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[derive(Clone)]
+pub struct WebhookDeliveryService {
+    config: Arc<Webhooks>,
+    client: reqwest::Client,
+    queue: Arc<WebhookQueue>,
+    notify: Arc<Notify>,
+}
+
+impl WebhookDeliveryService {
+    /// Create the service and load any deliveries left over from a previous run
+    pub fn new(config: Webhooks) -> Result<Self, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()?;
+        let queue = WebhookQueue::open(&config.queue_path)?;
+
+        Ok(Self {
+            config: Arc::new(config),
+            client,
+            queue: Arc::new(queue),
+            notify: Arc::new(Notify::new()),
+        })
+    }
+
+    /// Compute the signature header value for a payload
+    pub fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(timestamp.to_string().as_bytes());
+        mac.update(b".");
+        mac.update(body.as_bytes());
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    /// Queue a packet for delivery if it is an event type any endpoint subscribes to
+    pub fn observe(&self, packet: &QuicNetworkPacket) {
+        if let Some(event) = WebhookEvent::from_packet(packet) {
+            self.enqueue(&event);
+        }
+    }
+
+    /// Queue an event for every endpoint whose filter accepts it
+    pub fn enqueue(&self, event: &WebhookEvent) {
+        let event_type = event.event_type();
+        let endpoints: Vec<(usize, &WebhookEndpoint)> = self
+            .config
+            .endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, endpoint)| endpoint.accepts(event_type))
+            .collect();
+
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let body = match serde_json::to_string(event) {
+            Ok(body) => body,
+            Err(e) => {
+                tracing::error!("Failed to serialize webhook event: {}", e);
+                return;
+            }
+        };
+
+        for (index, endpoint) in endpoints {
+            self.queue.push(WebhookDelivery {
+                id: nanoid::nanoid!(21),
+                endpoint: index,
+                url: endpoint.url.clone(),
+                event_type,
+                body: body.clone(),
+                attempts: 0,
+                next_attempt_at: now_ms(),
+            });
+        }
+
+        self.notify.notify_one();
+    }
+
+    /// Delivery loop. Runs until the task is aborted.
+    pub async fn run(&self) {
+        tracing::info!(
+            "Webhook delivery started for {} endpoint(s)",
+            self.config.endpoints.len()
+        );
+
+        loop {
+            self.sync().await;
+
+            let mut attempts = tokio::task::JoinSet::new();
+            for delivery in self.queue.take_due(now_ms()) {
+                let service = self.clone();
+                attempts.spawn(async move { service.attempt(delivery).await });
+            }
+            while attempts.join_next().await.is_some() {}
+
+            self.sync().await;
+
+            let wait_ms = match self.queue.next_due_at() {
+                Some(at) => (at - now_ms()).max(0) as u64,
+                None => 60_000,
+            };
+
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = tokio::time::sleep(Duration::from_millis(wait_ms)) => {}
+            }
+        }
+    }
+
+    /// Mirror queue changes to disk off the async runtime
+    async fn sync(&self) {
+        let queue = self.queue.clone();
+        if let Err(e) = tokio::task::spawn_blocking(move || queue.sync()).await {
+            tracing::error!("Webhook queue sync task failed: {}", e);
+        }
+    }
+
+    fn backoff(&self, attempts: u32) -> Duration {
+        let exponent = attempts.saturating_sub(1).min(20);
+        let delay_ms = self.config.initial_backoff_ms.saturating_mul(1u64 << exponent);
+        Duration::from_millis(delay_ms).min(Duration::from_secs(self.config.max_backoff_secs))
+    }
+
+    async fn attempt(&self, mut delivery: WebhookDelivery) {
+        let endpoint = match self
+            .config
+            .endpoints
+            .get(delivery.endpoint)
+            .filter(|e| e.url == delivery.url)
+        {
+            Some(endpoint) => endpoint,
+            None => {
+                tracing::warn!(
+                    "Dropping webhook delivery {}: endpoint {} is no longer configured",
+                    delivery.id,
+                    delivery.url
+                );
+                self.queue.complete(&delivery);
+                return;
+            }
+        };
+
+        delivery.attempts += 1;
+
+        match self.send(endpoint, &delivery).await {
+            Ok(()) => {
+                tracing::debug!("Delivered webhook {} to {}", delivery.id, delivery.url);
+                self.queue.complete(&delivery);
+            }
+            Err(e) if delivery.attempts >= self.config.max_attempts => {
+                tracing::error!(
+                    "Giving up on webhook {} to {} after {} attempts: {}",
+                    delivery.id,
+                    delivery.url,
+                    delivery.attempts,
+                    e
+                );
+                self.queue.complete(&delivery);
+            }
+            Err(e) => {
+                let backoff = self.backoff(delivery.attempts);
+                tracing::warn!(
+                    "Webhook {} to {} failed (attempt {}), retrying in {:?}: {}",
+                    delivery.id,
+                    delivery.url,
+                    delivery.attempts,
+                    backoff,
+                    e
+                );
+                delivery.next_attempt_at = now_ms() + backoff.as_millis() as i64;
+                self.queue.reschedule(delivery);
+            }
+        }
+    }
+
+    async fn send(
+        &self,
+        endpoint: &WebhookEndpoint,
+        delivery: &WebhookDelivery,
+    ) -> Result<(), anyhow::Error> {
+        let timestamp = now_ms();
+        let signature = Self::sign(&endpoint.secret, timestamp, &delivery.body);
+        let event_type = serde_json::to_value(delivery.event_type)?;
+
+        let response = self
+            .client
+            .post(&endpoint.url)
+            .header("Content-Type", "application/json")
+            .header("X-BVC-Event", event_type.as_str().unwrap_or_default())
+            .header("X-BVC-Delivery", &delivery.id)
+            .header("X-BVC-Timestamp", timestamp.to_string())
+            .header("X-BVC-Signature", signature)
+            .body(delivery.body.clone())
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(anyhow!("Endpoint responded with {}", response.status()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WebhookEventType;
+    use common::structs::packet::{
+        ConnectionEventType, PacketType, PlayerPresenceEvent, QuicNetworkPacketData,
+    };
+    use std::collections::HashMap;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    struct CapturedRequest {
+        headers: HashMap<String, String>,
+        body: String,
+    }
+
+    /// Minimal HTTP/1.1 endpoint standing in for a webhook consumer.
+    /// Responds with each status in turn, repeating the last one.
+    async fn spawn_stand_in(statuses: Vec<u16>) -> (String, mpsc::UnboundedReceiver<CapturedRequest>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            let mut index = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buffer = Vec::new();
+                let mut chunk = [0u8; 4096];
+                let (head, body) = loop {
+                    let n = socket.read(&mut chunk).await.unwrap();
+                    buffer.extend_from_slice(&chunk[..n]);
+                    let text = String::from_utf8_lossy(&buffer).to_string();
+                    if let Some(split) = text.find("\r\n\r\n") {
+                        let head = text[..split].to_string();
+                        let length = head
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap())
+                            })
+                            .unwrap_or(0);
+                        if buffer.len() >= split + 4 + length {
+                            break (head, text[split + 4..split + 4 + length].to_string());
+                        }
+                    }
+                };
+
+                let headers = head
+                    .lines()
+                    .skip(1)
+                    .filter_map(|l| l.split_once(':'))
+                    .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+                    .collect();
+                let _ = tx.send(CapturedRequest { headers, body });
+
+                let status = statuses[index.min(statuses.len() - 1)];
+                index += 1;
+                let response = format!(
+                    "HTTP/1.1 {} Stand-In\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, rx)
+    }
+
+    fn config(url: &str, events: Vec<WebhookEventType>) -> Webhooks {
+        Webhooks {
+            endpoints: vec![WebhookEndpoint {
+                url: url.to_string(),
+                secret: "s3cret".to_string(),
+                events,
+            }],
+            queue_path: std::env::temp_dir()
+                .join(format!("bvc-webhooks-{}", nanoid::nanoid!(8)))
+                .to_string_lossy()
+                .to_string(),
+            max_attempts: 3,
+            initial_backoff_ms: 10,
+            max_backoff_secs: 1,
+            timeout_secs: 5,
+        }
+    }
+
+    fn presence_packet() -> QuicNetworkPacket {
+        QuicNetworkPacket {
+            packet_type: PacketType::PlayerPresence,
+            owner: None,
+            data: QuicNetworkPacketData::PlayerPresence(PlayerPresenceEvent {
+                player_name: "Steve".to_string(),
+                timestamp: 1,
+                event_type: ConnectionEventType::Connected,
+            }),
+        }
+    }
+
+    async fn next(rx: &mut mpsc::UnboundedReceiver<CapturedRequest>) -> CapturedRequest {
+        tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("timed out waiting for webhook")
+            .expect("stand-in server stopped")
+    }
+
+    #[tokio::test]
+    async fn delivers_signed_payload() {
+        let (url, mut rx) = spawn_stand_in(vec![200]).await;
+        let service = WebhookDeliveryService::new(config(&url, vec![])).unwrap();
+        let runner = service.clone();
+        let task = tokio::spawn(async move { runner.run().await });
+
+        service.observe(&presence_packet());
+        let request = next(&mut rx).await;
+        task.abort();
+
+        let timestamp: i64 = request.headers["x-bvc-timestamp"].parse().unwrap();
+        assert_eq!(
+            request.headers["x-bvc-signature"],
+            WebhookDeliveryService::sign("s3cret", timestamp, &request.body)
+        );
+        assert_eq!(request.headers["x-bvc-event"], "player_presence");
+
+        let json: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(json["type"], "player_presence");
+        assert_eq!(json["data"]["player_name"], "Steve");
+    }
+
+    #[tokio::test]
+    async fn retries_failed_delivery() {
+        let (url, mut rx) = spawn_stand_in(vec![500, 200]).await;
+        let service = WebhookDeliveryService::new(config(&url, vec![])).unwrap();
+        let runner = service.clone();
+        let task = tokio::spawn(async move { runner.run().await });
+
+        service.observe(&presence_packet());
+        let first = next(&mut rx).await;
+        let second = next(&mut rx).await;
+        task.abort();
+
+        assert_eq!(first.headers["x-bvc-delivery"], second.headers["x-bvc-delivery"]);
+        assert_eq!(first.body, second.body);
+    }
+
+    #[tokio::test]
+    async fn signs_with_each_endpoint_secret() {
+        let (url, mut rx) = spawn_stand_in(vec![200]).await;
+        let mut config = config(&url, vec![]);
+        config.endpoints.push(WebhookEndpoint {
+            url: url.clone(),
+            secret: "other".to_string(),
+            events: vec![],
+        });
+        let service = WebhookDeliveryService::new(config).unwrap();
+        let runner = service.clone();
+        let task = tokio::spawn(async move { runner.run().await });
+
+        service.observe(&presence_packet());
+        let mut secrets = Vec::new();
+        for _ in 0..2 {
+            let request = next(&mut rx).await;
+            let timestamp: i64 = request.headers["x-bvc-timestamp"].parse().unwrap();
+            let signature = &request.headers["x-bvc-signature"];
+            for secret in ["s3cret", "other"] {
+                if *signature == WebhookDeliveryService::sign(secret, timestamp, &request.body) {
+                    secrets.push(secret);
+                }
+            }
+        }
+        task.abort();
+
+        secrets.sort();
+        assert_eq!(secrets, vec!["other", "s3cret"]);
+    }
+
+    #[tokio::test]
+    async fn filters_by_event_type() {
+        let service = WebhookDeliveryService::new(config(
+            "http://127.0.0.1:9/hook",
+            vec![WebhookEventType::ChannelEvent],
+        ))
+        .unwrap();
+
+        service.observe(&presence_packet());
+        assert_eq!(service.queue.len(), 0);
+    }
+
+    #[tokio::test]
+    async fn persisted_deliveries_survive_restart() {
+        let config = config("http://127.0.0.1:9/hook", vec![]);
+        let service = WebhookDeliveryService::new(config.clone()).unwrap();
+        service.observe(&presence_packet());
+        assert_eq!(service.queue.len(), 1);
+        service.sync().await;
+
+        let reloaded = WebhookQueue::open(&config.queue_path).unwrap();
+        assert_eq!(reloaded.len(), 1);
+
+        let _ = std::fs::remove_dir_all(&config.queue_path);
+    }
+}
@@ -0,0 +1,171 @@
+//! File-backed queue of pending webhook deliveries
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+use crate::config::WebhookEventType;
+
+/// A payload waiting to be delivered to a single endpoint.
+/// Secrets are never persisted; they are looked up from config by endpoint index at send time.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookDelivery {
+    pub id: String,
+    /// Index of the endpoint in the configured endpoint list
+    #[serde(default)]
+    pub endpoint: usize,
+    /// URL of the endpoint when the delivery was queued, used to detect config changes across restarts
+    pub url: String,
+    pub event_type: WebhookEventType,
+    pub body: String,
+    pub attempts: u32,
+    /// Unix timestamp in milliseconds of the next attempt
+    pub next_attempt_at: i64,
+}
+
+/// Changes to the in-memory queue that have not been written to disk yet
+#[derive(Default)]
+struct Unsynced {
+    written: Vec<WebhookDelivery>,
+    removed: Vec<String>,
+}
+
+/// Pending deliveries, mirrored one JSON file per delivery under `path`
+/// so that undelivered events survive a restart.
+///
+/// Queue operations only touch memory; call [`WebhookQueue::sync`] from a blocking
+/// context to mirror them to disk.
+pub struct WebhookQueue {
+    path: PathBuf,
+    pending: Mutex<Vec<WebhookDelivery>>,
+    unsynced: Mutex<Unsynced>,
+}
+
+impl WebhookQueue {
+    /// Open the queue directory, creating it if needed, and load any persisted deliveries
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, anyhow::Error> {
+        let path = path.as_ref().to_path_buf();
+        std::fs::create_dir_all(&path)
+            .map_err(|e| anyhow!("Could not create webhook queue {}: {}", path.display(), e))?;
+
+        let mut pending = Vec::new();
+        for entry in std::fs::read_dir(&path)? {
+            let file = entry?.path();
+            if file.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+
+            match std::fs::read_to_string(&file)
+                .map_err(anyhow::Error::from)
+                .and_then(|s| serde_json::from_str::<WebhookDelivery>(&s).map_err(Into::into))
+            {
+                Ok(delivery) => pending.push(delivery),
+                Err(e) => {
+                    tracing::warn!("Discarding unreadable webhook delivery {}: {}", file.display(), e);
+                    let _ = std::fs::remove_file(&file);
+                }
+            }
+        }
+
+        if !pending.is_empty() {
+            tracing::info!("Loaded {} pending webhook deliveries", pending.len());
+        }
+
+        Ok(Self {
+            path,
+            pending: Mutex::new(pending),
+            unsynced: Mutex::new(Unsynced::default()),
+        })
+    }
+
+    fn file_for(&self, id: &str) -> PathBuf {
+        self.path.join(format!("{}.json", id))
+    }
+
+    fn persist(&self, delivery: &WebhookDelivery) {
+        match serde_json::to_string(delivery) {
+            Ok(json) => {
+                if let Err(e) = std::fs::write(self.file_for(&delivery.id), json) {
+                    tracing::error!("Failed to persist webhook delivery {}: {}", delivery.id, e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize webhook delivery {}: {}", delivery.id, e),
+        }
+    }
+
+    pub fn push(&self, delivery: WebhookDelivery) {
+        if let Ok(mut unsynced) = self.unsynced.lock() {
+            unsynced.written.retain(|d| d.id != delivery.id);
+            unsynced.written.push(delivery.clone());
+        }
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.push(delivery);
+        }
+    }
+
+    /// Remove and return every delivery due at or before `now`
+    pub fn take_due(&self, now: i64) -> Vec<WebhookDelivery> {
+        match self.pending.lock() {
+            Ok(mut pending) => {
+                let (due, waiting): (Vec<_>, Vec<_>) =
+                    pending.drain(..).partition(|d| d.next_attempt_at <= now);
+                *pending = waiting;
+                due
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /// Put a delivery back for a later attempt
+    pub fn reschedule(&self, delivery: WebhookDelivery) {
+        self.push(delivery);
+    }
+
+    /// Drop a delivery permanently, after success or when it has exhausted its attempts
+    pub fn complete(&self, delivery: &WebhookDelivery) {
+        if let Ok(mut unsynced) = self.unsynced.lock() {
+            unsynced.written.retain(|d| d.id != delivery.id);
+            unsynced.removed.push(delivery.id.clone());
+        }
+    }
+
+    /// Write queued and rescheduled deliveries to disk and remove completed ones.
+    /// Performs blocking file I/O.
+    pub fn sync(&self) {
+        let Unsynced { written, removed } = match self.unsynced.lock() {
+            Ok(mut unsynced) => std::mem::take(&mut *unsynced),
+            Err(_) => return,
+        };
+
+        for delivery in &written {
+            self.persist(delivery);
+        }
+
+        for id in removed {
+            let file = self.file_for(&id);
+            if file.exists() {
+                if let Err(e) = std::fs::remove_file(&file) {
+                    tracing::error!("Failed to remove webhook delivery {}: {}", id, e);
+                }
+            }
+        }
+    }
+
+    /// Timestamp of the earliest pending attempt
+    pub fn next_due_at(&self) -> Option<i64> {
+        self.pending
+            .lock()
+            .ok()
+            .and_then(|pending| pending.iter().map(|d| d.next_attempt_at).min())
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.lock().map(|p| p.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
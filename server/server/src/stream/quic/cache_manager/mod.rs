@@ -46,6 +46,30 @@ impl CacheManager {
         self.player_cache.clone()
     }
 
+    /// Players currently connected to voice, once the QUIC server is running
+    pub fn connected_players(&self) -> Option<usize> {
+        self.connection_registry
+            .as_ref()
+            .map(|registry| registry.player_count())
+    }
+
+    /// Record which backend game server a batch of players is on
+    pub fn assign_server(&self, server_id: &str, players: &[PlayerEnum]) {
+        use common::traits::player_data::PlayerData;
+        if let Some(registry) = &self.connection_registry {
+            for player in players {
+                registry.update_player_server(player.get_name().to_string(), server_id.to_string());
+            }
+        }
+    }
+
+    /// Backend game server a player was last seen on
+    pub fn server_of(&self, player_name: &str) -> Option<String> {
+        self.connection_registry
+            .as_ref()
+            .and_then(|registry| registry.player_server(player_name))
+    }
+
     /// Get a specific channel by ID
     pub async fn get_channel(&self, channel_id: &str) -> Option<Channel> {
         self.channel_cache.get(channel_id).await
@@ -224,7 +248,7 @@ impl CacheManager {
         self.player_cache.remove(player_name).await;
 
         if let Some(registry) = &self.connection_registry {
-            registry.remove_player_channel(player_name);
+            registry.forget_player(player_name);
         }
 
         let removed_channels = self.remove_player_from_all_channels(player_name).await;
@@ -1,3 +1,5 @@
+use super::server_recorder::ServerRecorder;
+use crate::config::Federation;
 use bytes::Bytes;
 use common::structs::packet::{QuicNetworkPacket, QuicNetworkPacketData};
 use common::traits::player_data::PlayerData;
@@ -20,6 +22,11 @@ pub(crate) struct ConnectionRegistry {
     connections: DashMap<Vec<u8>, ConnectionEntry>,
     // player_name -> channel_id (one channel per player)
     player_channel: DashMap<String, String>,
+    // player_name -> backend server_id, for voice servers shared by several game servers
+    player_server: DashMap<String, String>,
+    federation: Federation,
+    // Receives every routed audio frame while a server recording is running
+    recorder: Option<ServerRecorder>,
 }
 
 impl Default for ConnectionRegistry {
@@ -30,12 +37,24 @@ impl Default for ConnectionRegistry {
 
 impl ConnectionRegistry {
     pub fn new() -> Self {
+        Self::with_federation(Federation::default())
+    }
+
+    pub fn with_federation(federation: Federation) -> Self {
         Self {
             connections: DashMap::new(),
             player_channel: DashMap::new(),
+            player_server: DashMap::new(),
+            federation,
+            recorder: None,
         }
     }
 
+    pub fn with_recorder(mut self, recorder: ServerRecorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     pub fn register(
         &self,
         client_id: Vec<u8>,
@@ -53,9 +72,10 @@ impl ConnectionRegistry {
         );
     }
 
+    /// Drop a connection. The player's channel and backend are kept until `forget_player`,
+    /// so a resumed session routes the same as before.
     pub fn unregister(&self, client_id: &[u8]) {
         if let Some((_, entry)) = self.connections.remove(client_id) {
-            self.player_channel.remove(&entry.player_name);
             tracing::info!(
                 "Unregistered connection for player: {} (connections: {})",
                 entry.player_name,
@@ -64,6 +84,18 @@ impl ConnectionRegistry {
         }
     }
 
+    /// Number of distinct players with a voice connection
+    pub fn player_count(&self) -> usize {
+        let mut players: Vec<String> = self
+            .connections
+            .iter()
+            .map(|entry| entry.value().player_name.clone())
+            .collect();
+        players.sort_unstable();
+        players.dedup();
+        players.len()
+    }
+
     pub fn broadcast_to_all(&self, packet: QuicNetworkPacket) {
         let bytes = match packet.to_datagram() {
             Ok(bytes) => Bytes::from(bytes),
@@ -103,10 +135,34 @@ impl ConnectionRegistry {
         self.player_channel.remove(player_name);
     }
 
+    /// Forget a player's channel and backend once their session has ended
+    pub fn forget_player(&self, player_name: &str) {
+        self.player_channel.remove(player_name);
+        self.player_server.remove(player_name);
+    }
+
     pub fn remove_channel(&self, channel_id: &str) {
         self.player_channel.retain(|_, v| v != channel_id);
     }
 
+    pub fn update_player_server(&self, player_name: String, server_id: String) {
+        self.player_server.insert(player_name, server_id);
+    }
+
+    /// Backend game server a player was last seen on
+    pub fn player_server(&self, player_name: &str) -> Option<String> {
+        self.player_server.get(player_name).map(|r| r.clone())
+    }
+
+    /// Players with no known backend are treated as sharing the sender's server,
+    /// which keeps single-server deployments unchanged.
+    fn on_same_server(sender_server: Option<&String>, recipient_server: Option<&String>) -> bool {
+        match (sender_server, recipient_server) {
+            (Some(s), Some(r)) => s == r,
+            _ => true,
+        }
+    }
+
     pub async fn route_audio_frame(
         &self,
         packet: &QuicNetworkPacket,
@@ -126,6 +182,17 @@ impl ConnectionRegistry {
 
         let sender_channel: Option<String> =
             self.player_channel.get(sender_name).map(|r| r.clone());
+        let sender_server: Option<String> =
+            self.player_server.get(sender_name).map(|r| r.clone());
+
+        // Per-backend spatial overrides apply to the sender's server
+        let (broadcast_range, deafen_distance) = match sender_server
+            .as_deref()
+            .and_then(|server_id| self.federation.spatial_audio_for(server_id))
+        {
+            Some(spatial) => (spatial.broadcast_range, spatial.deafen_distance),
+            None => (broadcast_range, deafen_distance),
+        };
 
         let original_spatial = audio_frame.spatial;
         let has_sender = audio_frame.sender.is_some();
@@ -177,6 +244,24 @@ impl ConnectionRegistry {
         let mut sender_player: Option<PlayerEnum> = None;
         let mut sender_player_resolved = false;
 
+        if let Some(recorder) = &self.recorder
+            && recorder.is_recording()
+        {
+            sender_player = match &audio_frame.sender {
+                Some(player) => Some(player.clone()),
+                None => player_cache.get(sender_name).await,
+            };
+            sender_player_resolved = true;
+
+            recorder.observe(
+                sender_name,
+                audio_frame,
+                sender_channel.as_deref(),
+                sender_server.as_deref(),
+                sender_player.as_ref(),
+            );
+        }
+
         for (client_id, recipient_name, tx) in &snapshot {
             if recipient_name == sender_name {
                 continue;
@@ -190,7 +275,23 @@ impl ConnectionRegistry {
                 _ => false,
             };
 
+            let recipient_server: Option<String> =
+                self.player_server.get(recipient_name).map(|r| r.clone());
+            let same_server =
+                Self::on_same_server(sender_server.as_ref(), recipient_server.as_ref());
+
             let bytes_to_send = if in_same_channel {
+                if !same_server && !self.federation.cross_server_channels {
+                    tracing::debug!(
+                        "route_audio_frame: {} -> {} rejected: channel spans servers {:?} and {:?}",
+                        sender_name,
+                        recipient_name,
+                        sender_server,
+                        recipient_server,
+                    );
+                    continue;
+                }
+
                 tracing::debug!(
                     "route_audio_frame: {} -> {} IN_CHANNEL spatial={:?}",
                     sender_name,
@@ -209,6 +310,11 @@ impl ConnectionRegistry {
                     },
                 }
             } else {
+                // Positions from different game servers are not comparable
+                if !same_server {
+                    continue;
+                }
+
                 if !sender_player_resolved {
                     sender_player = match &audio_frame.sender {
                         Some(player) => Some(player.clone()),
@@ -276,3 +382,138 @@ impl ConnectionRegistry {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::quic::server_recorder::RecordingTarget;
+    use common::structs::packet::{AudioFramePacket, PacketOwner, PacketType};
+    use common::{Coordinate, Dimension, MinecraftPlayer, Orientation};
+
+    fn player(name: &str) -> PlayerEnum {
+        PlayerEnum::Minecraft(MinecraftPlayer {
+            name: name.to_string(),
+            coordinates: Coordinate { x: 0.0, y: 64.0, z: 0.0 },
+            orientation: Orientation { x: 0.0, y: 0.0 },
+            dimension: Dimension::Overworld,
+            deafen: false,
+            spectator: false,
+            world_uuid: None,
+            alternative_identity: None,
+            player_uuid: None,
+        })
+    }
+
+    fn audio_from(name: &str, client_id: u8) -> QuicNetworkPacket {
+        QuicNetworkPacket {
+            packet_type: PacketType::AudioFrame,
+            owner: Some(PacketOwner {
+                name: name.to_string(),
+                client_id: vec![client_id],
+            }),
+            data: QuicNetworkPacketData::AudioFrame(AudioFramePacket::new(
+                vec![0u8; 16],
+                48000,
+                None,
+                None,
+            )),
+        }
+    }
+
+    async fn setup(
+        federation: Federation,
+    ) -> (ConnectionRegistry, Arc<Cache<String, PlayerEnum>>, mpsc::Receiver<RoutedPacket>) {
+        let registry = ConnectionRegistry::with_federation(federation);
+        let (alice_tx, _) = mpsc::channel(8);
+        let (bob_tx, bob_rx) = mpsc::channel(8);
+        registry.register(vec![1], "Alice".to_string(), alice_tx);
+        registry.register(vec![2], "Bob".to_string(), bob_tx);
+
+        let cache: Arc<Cache<String, PlayerEnum>> = Arc::new(Cache::new(16));
+        cache.insert("Alice".to_string(), player("Alice")).await;
+        cache.insert("Bob".to_string(), player("Bob")).await;
+
+        (registry, cache, bob_rx)
+    }
+
+    #[tokio::test]
+    async fn spatial_audio_stays_on_its_server() {
+        let (registry, cache, mut bob_rx) = setup(Federation::default()).await;
+        registry.update_player_server("Alice".to_string(), "lobby".to_string());
+        registry.update_player_server("Bob".to_string(), "survival".to_string());
+
+        registry.route_audio_frame(&audio_from("Alice", 1), &cache, 48.0, 3.0).await;
+        assert!(bob_rx.try_recv().is_err());
+
+        registry.update_player_server("Bob".to_string(), "lobby".to_string());
+        registry.route_audio_frame(&audio_from("Alice", 1), &cache, 48.0, 3.0).await;
+        assert!(bob_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn player_server_follows_positions() {
+        let (registry, _, _) = setup(Federation::default()).await;
+        assert_eq!(registry.player_server("Alice"), None);
+
+        registry.update_player_server("Alice".to_string(), "lobby".to_string());
+        assert_eq!(registry.player_server("Alice").as_deref(), Some("lobby"));
+
+        registry.unregister(&[1]);
+        assert_eq!(registry.player_server("Alice"), None);
+    }
+
+    #[tokio::test]
+    async fn cross_server_channels_are_opt_in() {
+        let (registry, cache, mut bob_rx) = setup(Federation::default()).await;
+        registry.update_player_server("Alice".to_string(), "lobby".to_string());
+        registry.update_player_server("Bob".to_string(), "survival".to_string());
+        registry.update_player_channel("Alice".to_string(), "party".to_string());
+        registry.update_player_channel("Bob".to_string(), "party".to_string());
+
+        registry.route_audio_frame(&audio_from("Alice", 1), &cache, 48.0, 3.0).await;
+        assert!(bob_rx.try_recv().is_err());
+
+        let (registry, cache, mut bob_rx) = setup(Federation {
+            backends: Vec::new(),
+            cross_server_channels: true,
+        })
+        .await;
+        registry.update_player_server("Alice".to_string(), "lobby".to_string());
+        registry.update_player_server("Bob".to_string(), "survival".to_string());
+        registry.update_player_channel("Alice".to_string(), "party".to_string());
+        registry.update_player_channel("Bob".to_string(), "party".to_string());
+
+        registry.route_audio_frame(&audio_from("Alice", 1), &cache, 48.0, 3.0).await;
+        assert!(bob_rx.try_recv().is_ok());
+    }
+
+    #[tokio::test]
+    async fn routed_frames_feed_the_server_recorder() {
+        let dir = std::env::temp_dir().join(format!("bvc-registry-recording-{}", nanoid::nanoid!(8)));
+        let recorder = ServerRecorder::new(
+            crate::config::Recording {
+                enabled: true,
+                path: dir.to_string_lossy().to_string(),
+                regions: Vec::new(),
+            },
+            common::structs::SpatialAudioConfig::default(),
+        );
+        let (registry, cache, _bob_rx) = setup(Federation::default()).await;
+        let registry = registry.with_recorder(recorder.clone());
+        registry.update_player_channel("Alice".to_string(), "party".to_string());
+
+        registry.route_audio_frame(&audio_from("Alice", 1), &cache, 48.0, 3.0).await;
+
+        recorder
+            .start(RecordingTarget::Channel { id: "party".to_string() })
+            .unwrap();
+        registry.route_audio_frame(&audio_from("Alice", 1), &cache, 48.0, 3.0).await;
+        registry.route_audio_frame(&audio_from("Bob", 2), &cache, 48.0, 3.0).await;
+
+        let status = recorder.stop().await.unwrap();
+        assert_eq!(status.frames, 1);
+        assert_eq!(status.participants, vec!["Alice".to_string()]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,264 @@
+//! Live event feed for dashboards and overlays
+//!
+//! Fed from the same packet path as `WebhookReceiver` (presence, channel and position
+//! packets) plus routed audio frames, and fanned out to subscribers over a broadcast channel.
+
+use common::structs::packet::{
+    ChannelEventPacket, ConnectionEventType, PlayerPresenceEvent, QuicNetworkPacket,
+    QuicNetworkPacketData,
+};
+use common::traits::player_data::PlayerData;
+use common::{Coordinate, Orientation, PlayerEnum};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// A player starting or stopping transmission, derived from routed audio frames
+#[derive(Serialize, Debug, Clone)]
+pub struct SpeakingActivity {
+    pub player_name: String,
+    pub speaking: bool,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum FeedEvent {
+    PlayerPresence(PlayerPresenceEvent),
+    ChannelEvent(ChannelEventPacket),
+    Speaking(SpeakingActivity),
+    /// Players whose position, orientation or deafen state changed since the last update
+    Position(Vec<PlayerEnum>),
+}
+
+impl FeedEvent {
+    /// Event name used for SSE `event:` fields and subscription filters
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeedEvent::PlayerPresence(_) => "player_presence",
+            FeedEvent::ChannelEvent(_) => "channel_event",
+            FeedEvent::Speaking(_) => "speaking",
+            FeedEvent::Position(_) => "position",
+        }
+    }
+}
+
+fn now_ms() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
+#[derive(Clone)]
+pub struct EventFeed {
+    tx: broadcast::Sender<FeedEvent>,
+    // player_name -> last routed audio frame
+    speaking: Arc<DashMap<String, Instant>>,
+    // player_name -> last published (position, orientation, deafened)
+    positions: Arc<DashMap<String, (Coordinate, Orientation, bool)>>,
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventFeed {
+    const CAPACITY: usize = 1024;
+    // Silence after which a speaker is considered to have stopped
+    const SPEAKING_TIMEOUT: Duration = Duration::from_millis(400);
+
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(Self::CAPACITY);
+        Self {
+            tx,
+            speaking: Arc::new(DashMap::new()),
+            positions: Arc::new(DashMap::new()),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<FeedEvent> {
+        self.tx.subscribe()
+    }
+
+    fn publish(&self, event: FeedEvent) {
+        // No subscribers is not an error
+        let _ = self.tx.send(event);
+    }
+
+    /// Observe a packet from the webhook path. Audio frames are reported separately
+    /// through `observe_audio_frame` once they have been routed.
+    pub fn observe(&self, packet: &QuicNetworkPacket) {
+        match &packet.data {
+            QuicNetworkPacketData::PlayerPresence(presence) => {
+                if presence.event_type == ConnectionEventType::Disconnected {
+                    self.remove_player(&presence.player_name);
+                }
+                self.publish(FeedEvent::PlayerPresence(presence.clone()));
+            }
+            QuicNetworkPacketData::ChannelEvent(channel_event) => {
+                self.publish(FeedEvent::ChannelEvent(channel_event.clone()));
+            }
+            QuicNetworkPacketData::PlayerData(player_data) => {
+                let changed = self.position_delta(&player_data.players);
+                if !changed.is_empty() {
+                    self.publish(FeedEvent::Position(changed));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn position_delta(&self, players: &[PlayerEnum]) -> Vec<PlayerEnum> {
+        let mut changed = Vec::new();
+        for player in players {
+            let state = (
+                player.get_position().clone(),
+                player.get_orientation().clone(),
+                player.is_deafened(),
+            );
+            let is_changed = match self.positions.get(player.get_name()) {
+                Some(previous) => *previous != state,
+                None => true,
+            };
+            if is_changed {
+                self.positions.insert(player.get_name().to_string(), state);
+                changed.push(player.clone());
+            }
+        }
+        changed
+    }
+
+    /// Record that an audio frame from `sender` was routed
+    pub fn observe_audio_frame(&self, sender: &str) {
+        let was_speaking = self
+            .speaking
+            .insert(sender.to_string(), Instant::now())
+            .is_some();
+
+        if !was_speaking {
+            self.publish(FeedEvent::Speaking(SpeakingActivity {
+                player_name: sender.to_string(),
+                speaking: true,
+                timestamp: now_ms(),
+            }));
+        }
+    }
+
+    /// Emit stop events for speakers that have gone quiet, relative to `now`
+    fn sweep_speaking(&self, now: Instant) {
+        let stopped: Vec<String> = self
+            .speaking
+            .iter()
+            .filter(|entry| now.saturating_duration_since(*entry.value()) >= Self::SPEAKING_TIMEOUT)
+            .map(|entry| entry.key().clone())
+            .collect();
+
+        for player_name in stopped {
+            self.speaking.remove(&player_name);
+            self.publish(FeedEvent::Speaking(SpeakingActivity {
+                player_name,
+                speaking: false,
+                timestamp: now_ms(),
+            }));
+        }
+    }
+
+    /// Periodically close out speaking activity. Runs until the task is dropped.
+    pub async fn run_speaking_sweep(&self) {
+        let mut interval = tokio::time::interval(Duration::from_millis(100));
+        loop {
+            interval.tick().await;
+            self.sweep_speaking(Instant::now());
+        }
+    }
+
+    /// Forget a disconnected player's speaking and position state
+    fn remove_player(&self, player_name: &str) {
+        if self.speaking.remove(player_name).is_some() {
+            self.publish(FeedEvent::Speaking(SpeakingActivity {
+                player_name: player_name.to_string(),
+                speaking: false,
+                timestamp: now_ms(),
+            }));
+        }
+        self.positions.remove(player_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::structs::packet::{PacketType, PlayerDataPacket};
+    use common::{Dimension, MinecraftPlayer};
+
+    fn player_data(name: &str, x: f32) -> QuicNetworkPacket {
+        let player = MinecraftPlayer {
+            name: name.to_string(),
+            coordinates: Coordinate { x, y: 64.0, z: 0.0 },
+            orientation: Orientation { x: 0.0, y: 0.0 },
+            dimension: Dimension::Overworld,
+            deafen: false,
+            spectator: false,
+            world_uuid: None,
+            alternative_identity: None,
+            player_uuid: None,
+        };
+        QuicNetworkPacket {
+            packet_type: PacketType::PlayerData,
+            owner: None,
+            data: QuicNetworkPacketData::PlayerData(PlayerDataPacket {
+                players: vec![PlayerEnum::Minecraft(player)],
+            }),
+        }
+    }
+
+    #[test]
+    fn speaking_starts_once_and_stops_after_timeout() {
+        let feed = EventFeed::new();
+        let mut rx = feed.subscribe();
+
+        feed.observe_audio_frame("Steve");
+        feed.observe_audio_frame("Steve");
+
+        match rx.try_recv().unwrap() {
+            FeedEvent::Speaking(activity) => assert!(activity.speaking),
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(rx.try_recv().is_err());
+
+        feed.sweep_speaking(Instant::now() + EventFeed::SPEAKING_TIMEOUT);
+        match rx.try_recv().unwrap() {
+            FeedEvent::Speaking(activity) => {
+                assert_eq!(activity.player_name, "Steve");
+                assert!(!activity.speaking);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn position_updates_only_publish_changes() {
+        let feed = EventFeed::new();
+        let mut rx = feed.subscribe();
+
+        feed.observe(&player_data("Steve", 1.0));
+        feed.observe(&player_data("Steve", 1.0));
+        feed.observe(&player_data("Steve", 2.0));
+
+        assert_eq!(rx.try_recv().unwrap().name(), "position");
+        match rx.try_recv().unwrap() {
+            FeedEvent::Position(players) => {
+                assert_eq!(players.len(), 1);
+                assert_eq!(players[0].get_position().x, 2.0);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(rx.try_recv().is_err());
+    }
+}
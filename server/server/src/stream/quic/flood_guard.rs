@@ -0,0 +1,275 @@
+//! Per-connection inbound datagram flood protection
+//!
+//! Each connection gets its own `FloodGuard`, consulted by the `InputStream` before a
+//! datagram is decoded. Counters are shared across connections through `FloodMetrics` and
+//! reported by the admin stats route.
+
+use crate::config::FloodProtection;
+use crate::services::TokenBucket;
+use common::structs::packet::{PacketType, QuicNetworkPacket};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Server-wide flood protection counters
+#[derive(Debug, Default)]
+pub struct FloodMetrics {
+    datagrams_dropped: AtomicU64,
+    bytes_dropped: AtomicU64,
+    connections_disconnected: AtomicU64,
+}
+
+/// Point-in-time copy of `FloodMetrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct FloodMetricsSnapshot {
+    pub datagrams_dropped: u64,
+    pub bytes_dropped: u64,
+    pub connections_disconnected: u64,
+}
+
+impl FloodMetrics {
+    pub fn snapshot(&self) -> FloodMetricsSnapshot {
+        FloodMetricsSnapshot {
+            datagrams_dropped: self.datagrams_dropped.load(Ordering::Relaxed),
+            bytes_dropped: self.bytes_dropped.load(Ordering::Relaxed),
+            connections_disconnected: self.connections_disconnected.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_drop(&self, bytes: usize) {
+        self.datagrams_dropped.fetch_add(1, Ordering::Relaxed);
+        self.bytes_dropped.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    fn record_disconnect(&self) {
+        self.connections_disconnected.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// What the input stream should do with a datagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FloodVerdict {
+    Accept,
+    Drop,
+    Disconnect,
+}
+
+pub(crate) struct FloodGuard {
+    config: FloodProtection,
+    bandwidth: TokenBucket,
+    audio_frame: TokenBucket,
+    player_data: TokenBucket,
+    channel_event: TokenBucket,
+    health_check: TokenBucket,
+    other: TokenBucket,
+    violations: u32,
+    window_start: Instant,
+    metrics: Arc<FloodMetrics>,
+}
+
+impl FloodGuard {
+    pub fn new(config: FloodProtection, metrics: Arc<FloodMetrics>) -> Self {
+        Self::new_at(config, metrics, Instant::now())
+    }
+
+    fn new_at(config: FloodProtection, metrics: Arc<FloodMetrics>, now: Instant) -> Self {
+        Self {
+            bandwidth: TokenBucket::new(&config.bandwidth, now),
+            audio_frame: TokenBucket::new(&config.audio_frame, now),
+            player_data: TokenBucket::new(&config.player_data, now),
+            channel_event: TokenBucket::new(&config.channel_event, now),
+            health_check: TokenBucket::new(&config.health_check, now),
+            other: TokenBucket::new(&config.other, now),
+            violations: 0,
+            window_start: now,
+            metrics,
+            config,
+        }
+    }
+
+    pub fn metrics(&self) -> FloodMetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Check a raw datagram against the bandwidth and per-packet-type budgets
+    pub fn check(&mut self, datagram: &[u8]) -> FloodVerdict {
+        self.check_at(datagram, Instant::now())
+    }
+
+    fn check_at(&mut self, datagram: &[u8], now: Instant) -> FloodVerdict {
+        if !self.config.enabled {
+            return FloodVerdict::Accept;
+        }
+
+        // Undecodable datagrams are counted against `other` and rejected later by the decoder
+        let bucket = match QuicNetworkPacket::peek_packet_type(datagram) {
+            Some(PacketType::AudioFrame) => &mut self.audio_frame,
+            Some(PacketType::PlayerData) => &mut self.player_data,
+            Some(PacketType::ChannelEvent) => &mut self.channel_event,
+            Some(PacketType::HealthCheck) => &mut self.health_check,
+            _ => &mut self.other,
+        };
+
+        // Both budgets are checked before either is charged, so a datagram one of them
+        // rejects doesn't use up the other
+        let bytes = datagram.len() as f64;
+        if self.bandwidth.has_tokens(bytes, now) && bucket.has_tokens(1.0, now) {
+            self.bandwidth.try_acquire_n(bytes, now);
+            bucket.try_acquire(now);
+            return FloodVerdict::Accept;
+        }
+
+        self.metrics.record_drop(datagram.len());
+
+        let window = Duration::from_secs(self.config.violation_window_secs);
+        if now.saturating_duration_since(self.window_start) > window {
+            self.window_start = now;
+            self.violations = 0;
+        }
+        self.violations += 1;
+
+        if self.violations > self.config.max_violations {
+            self.metrics.record_disconnect();
+            return FloodVerdict::Disconnect;
+        }
+
+        FloodVerdict::Drop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RateLimitPolicy;
+    use common::structs::packet::{HealthCheckPacket, QuicNetworkPacketData};
+
+    fn policy(capacity: u32, refill_per_second: f64) -> RateLimitPolicy {
+        RateLimitPolicy {
+            capacity,
+            refill_per_second,
+        }
+    }
+
+    fn health_check_datagram() -> Vec<u8> {
+        QuicNetworkPacket {
+            packet_type: PacketType::HealthCheck,
+            owner: None,
+            data: QuicNetworkPacketData::HealthCheck(HealthCheckPacket),
+        }
+        .to_datagram()
+        .unwrap()
+    }
+
+    #[test]
+    fn peek_reads_packet_type() {
+        let datagram = health_check_datagram();
+        assert_eq!(
+            QuicNetworkPacket::peek_packet_type(&datagram),
+            Some(PacketType::HealthCheck)
+        );
+        assert_eq!(QuicNetworkPacket::peek_packet_type(&[]), None);
+    }
+
+    #[test]
+    fn drops_over_per_type_budget() {
+        let now = Instant::now();
+        let config = FloodProtection {
+            health_check: policy(2, 0.0),
+            ..FloodProtection::default()
+        };
+        let metrics = Arc::new(FloodMetrics::default());
+        let mut guard = FloodGuard::new_at(config, metrics.clone(), now);
+        let datagram = health_check_datagram();
+
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Accept);
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Accept);
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Drop);
+        assert_eq!(metrics.snapshot().datagrams_dropped, 1);
+        assert_eq!(metrics.snapshot().bytes_dropped, datagram.len() as u64);
+    }
+
+    #[test]
+    fn drops_over_bandwidth_budget() {
+        let now = Instant::now();
+        let datagram = health_check_datagram();
+        let config = FloodProtection {
+            bandwidth: policy(datagram.len() as u32, 0.0),
+            ..FloodProtection::default()
+        };
+        let mut guard = FloodGuard::new_at(config, Arc::new(FloodMetrics::default()), now);
+
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Accept);
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Drop);
+    }
+
+    #[test]
+    fn rejected_datagrams_do_not_use_bandwidth() {
+        let now = Instant::now();
+        let datagram = health_check_datagram();
+        let config = FloodProtection {
+            bandwidth: policy(2 * datagram.len() as u32, 0.0),
+            health_check: policy(1, 0.0),
+            ..FloodProtection::default()
+        };
+        let mut guard = FloodGuard::new_at(config, Arc::new(FloodMetrics::default()), now);
+
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Accept);
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Drop);
+
+        // Undecodable, so charged to `other`, and the bandwidth for it is still there
+        let other = vec![0xFF; datagram.len()];
+        assert_eq!(guard.check_at(&other, now), FloodVerdict::Accept);
+        assert_eq!(guard.check_at(&other, now), FloodVerdict::Drop);
+    }
+
+    #[test]
+    fn disconnects_repeat_offenders() {
+        let now = Instant::now();
+        let config = FloodProtection {
+            health_check: policy(0, 0.0),
+            max_violations: 3,
+            violation_window_secs: 10,
+            ..FloodProtection::default()
+        };
+        let metrics = Arc::new(FloodMetrics::default());
+        let mut guard = FloodGuard::new_at(config, metrics.clone(), now);
+        let datagram = health_check_datagram();
+
+        for _ in 0..3 {
+            assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Drop);
+        }
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Disconnect);
+        assert_eq!(metrics.snapshot().connections_disconnected, 1);
+    }
+
+    #[test]
+    fn violation_window_resets() {
+        let now = Instant::now();
+        let config = FloodProtection {
+            health_check: policy(0, 0.0),
+            max_violations: 1,
+            violation_window_secs: 1,
+            ..FloodProtection::default()
+        };
+        let mut guard = FloodGuard::new_at(config, Arc::new(FloodMetrics::default()), now);
+        let datagram = health_check_datagram();
+
+        assert_eq!(guard.check_at(&datagram, now), FloodVerdict::Drop);
+        let later = now + Duration::from_secs(2);
+        assert_eq!(guard.check_at(&datagram, later), FloodVerdict::Drop);
+    }
+
+    #[test]
+    fn disabled_guard_accepts_everything() {
+        let now = Instant::now();
+        let config = FloodProtection {
+            enabled: false,
+            health_check: policy(0, 0.0),
+            ..FloodProtection::default()
+        };
+        let mut guard = FloodGuard::new_at(config, Arc::new(FloodMetrics::default()), now);
+
+        assert_eq!(guard.check_at(&health_check_datagram(), now), FloodVerdict::Accept);
+    }
+}
@@ -8,24 +8,36 @@
 //! - AudioFrame packets are routed to specific recipients based on spatial/channel logic
 //! - Non-audio packets (PlayerData, ChannelEvent, PlayerPresence) are broadcast to all
 //! - CacheManager processes packets and updates coordinates for AudioFrame packets
+//! - Dropped connections with a resume token keep their session for a grace period, and QUIC
+//!   connection migration is left enabled so clients changing networks keep their connection
 //! - Graceful shutdown via oneshot channels
 
 mod cache_manager;
 mod client_id_hasher;
 mod connection_id_format;
+pub(crate) mod event_feed;
 pub(crate) mod connection_registry;
+pub(crate) mod flood_guard;
+pub(crate) mod server_recorder;
 mod server_input_packet;
+mod session_resumption;
 mod stream_manager;
 mod webhook_receiver;
 
 use crate::config::ApplicationConfig;
+use crate::services::WebhookDeliveryService;
 use anyhow;
 use client_id_hasher::ClientIdHasher;
 use common::structs::packet::{PacketType, QuicNetworkPacket};
 use common::traits::StreamTrait;
 use common::s2n_quic::Server;
 use connection_registry::ConnectionRegistry;
+use event_feed::EventFeed;
+use flood_guard::{FloodGuard, FloodMetrics};
+use server_recorder::ServerRecorder;
+use session_resumption::{Release, SessionResumption};
 use std::sync::Arc;
+use std::time::Duration;
 use stream_manager::{InputStream, OutputStream};
 use tokio::sync::{mpsc, oneshot};
 
@@ -40,13 +52,25 @@ pub struct QuicServerManager {
     webhook_rx: Option<mpsc::UnboundedReceiver<QuicNetworkPacket>>,
     cache_manager: CacheManager,
     webhook_receiver: WebhookReceiver,
+    flood_metrics: Arc<FloodMetrics>,
+    session_resumption: SessionResumption,
+    webhook_delivery: Option<WebhookDeliveryService>,
+    event_feed: EventFeed,
+    server_recorder: ServerRecorder,
     shutdown_tx: Option<oneshot::Sender<()>>,
     shutdown_rx: Option<oneshot::Receiver<()>>,
 }
 
 impl QuicServerManager {
     pub fn new(config: ApplicationConfig) -> Self {
-        let connection_registry = Arc::new(ConnectionRegistry::new());
+        let server_recorder = ServerRecorder::new(
+            config.recording.clone(),
+            config.voice.spatial_audio.clone(),
+        );
+        let connection_registry = Arc::new(
+            ConnectionRegistry::with_federation(config.server.federation.clone())
+                .with_recorder(server_recorder.clone()),
+        );
         let (webhook_tx, webhook_rx) = mpsc::unbounded_channel();
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
 
@@ -61,6 +85,13 @@ impl QuicServerManager {
             webhook_rx: Some(webhook_rx),
             cache_manager,
             webhook_receiver,
+            flood_metrics: Arc::new(FloodMetrics::default()),
+            session_resumption: SessionResumption::new(Duration::from_secs(
+                config.voice.session_resume_grace_secs,
+            )),
+            webhook_delivery: None,
+            event_feed: EventFeed::new(),
+            server_recorder,
             shutdown_tx: Some(shutdown_tx),
             shutdown_rx: Some(shutdown_rx),
         }
@@ -124,6 +155,9 @@ impl QuicServerManager {
         let player_cache = cache_manager.get_player_cache();
         let broadcast_range = self.config.voice.spatial_audio.broadcast_range;
         let deafen_distance = self.config.voice.spatial_audio.deafen_distance;
+        let webhook_delivery = self.webhook_delivery.clone();
+        let event_feed = self.event_feed.clone();
+        let speaking_feed = self.event_feed.clone();
         let mut shutdown_rx = self.shutdown_rx.take()
             .ok_or_else(|| anyhow::anyhow!("QUIC server already started"))?;
 
@@ -132,6 +166,11 @@ impl QuicServerManager {
         tokio::select! {
             _ = async {
                 while let Some(packet) = webhook_rx.recv().await {
+                    if let Some(webhook_delivery) = &webhook_delivery {
+                        webhook_delivery.observe(&packet);
+                    }
+                    event_feed.observe(&packet);
+
                     if let Err(e) = cache_manager.process_packet(packet.clone()).await {
                         tracing::error!("Failed to process packet in cache manager: {}", e);
                     }
@@ -141,6 +180,7 @@ impl QuicServerManager {
                             connection_registry
                                 .route_audio_frame(&packet, &player_cache, broadcast_range, deafen_distance)
                                 .await;
+                            event_feed.observe_audio_frame(&packet.get_author());
                         }
                         _ => {
                             connection_registry.broadcast_to_all(packet);
@@ -151,6 +191,8 @@ impl QuicServerManager {
                 tracing::info!("Webhook processing completed");
             }
 
+            _ = speaking_feed.run_speaking_sweep() => {}
+
             _ = self.accept_connections(server) => {
                 tracing::info!("QUIC connection handler completed");
             }
@@ -174,6 +216,26 @@ impl QuicServerManager {
         Ok(())
     }
 
+    /// Deliver presence and channel events to outbound webhooks
+    pub fn set_webhook_delivery(&mut self, webhook_delivery: WebhookDeliveryService) {
+        self.webhook_delivery = Some(webhook_delivery);
+    }
+
+    /// Live event feed consumed by the dashboard event stream
+    pub fn get_event_feed(&self) -> EventFeed {
+        self.event_feed.clone()
+    }
+
+    /// Flood protection counters reported by the admin stats route
+    pub fn get_flood_metrics(&self) -> Arc<FloodMetrics> {
+        self.flood_metrics.clone()
+    }
+
+    /// Server-side recorder controlled by the admin recording routes
+    pub fn get_server_recorder(&self) -> ServerRecorder {
+        self.server_recorder.clone()
+    }
+
     pub fn get_cache_manager(&self) -> CacheManager {
         self.cache_manager.clone()
     }
@@ -208,6 +270,12 @@ impl QuicServerManager {
             let broadcast_range = self.config.voice.spatial_audio.broadcast_range;
             let deafen_distance = self.config.voice.spatial_audio.deafen_distance;
             let webhook_receiver = self.webhook_receiver.clone();
+            let event_feed = self.event_feed.clone();
+            let flood_guard = FloodGuard::new(
+                self.config.voice.flood_protection.clone(),
+                self.flood_metrics.clone(),
+            );
+            let session_resumption = self.session_resumption.clone();
 
             tokio::spawn(async move {
                 if let Err(e) = connection.keep_alive(true) {
@@ -240,15 +308,17 @@ impl QuicServerManager {
                     }
                 };
 
-                // Disconnect callback: unregister from registry + cache cleanup
+                // Disconnect callback: unregister from registry, then end or hold the session
                 let cache_manager_for_callback = cache_manager.clone();
                 let webhook_receiver_for_callback = webhook_receiver.clone();
                 let registry_for_callback = connection_registry.clone();
+                let sessions_for_callback = session_resumption.clone();
                 input_stream.set_disconnect_callback(Box::new(
                     move |player_id: String, client_id: Vec<u8>| {
                         let cache_manager = cache_manager_for_callback.clone();
                         let webhook_receiver = webhook_receiver_for_callback.clone();
                         let registry = registry_for_callback.clone();
+                        let sessions = sessions_for_callback.clone();
                         tokio::spawn(async move {
                             let client_hash = ClientIdHasher::hash(&client_id);
                             tracing::info!(
@@ -259,49 +329,37 @@ impl QuicServerManager {
 
                             registry.unregister(&client_id);
 
-                            match cache_manager.remove_player(&player_id).await {
-                                Ok(removed_channels) => {
-                                    for channel_id in removed_channels {
-                                        let leave_packet = common::structs::packet::QuicNetworkPacket {
-                                            owner: Some(common::structs::packet::PacketOwner {
-                                                name: player_id.clone(),
-                                                client_id: client_id.clone(),
-                                            }),
-                                            packet_type: common::structs::packet::PacketType::ChannelEvent,
-                                            data: common::structs::packet::QuicNetworkPacketData::ChannelEvent(
-                                                common::structs::packet::ChannelEventPacket::new(
-                                                    common::structs::channel::ChannelEvents::Leave,
-                                                    player_id.clone(),
-                                                    channel_id.clone(),
-                                                ),
-                                            ),
-                                        };
-
-                                        if let Err(e) = webhook_receiver.send_packet(leave_packet).await {
-                                            tracing::error!(
-                                                "Failed to broadcast channel leave event for player {} channel {}: {}",
-                                                player_id,
-                                                channel_id,
-                                                e
-                                            );
-                                        } else {
-                                            tracing::info!(
-                                                "Broadcast channel leave event: player {} left channel {}",
-                                                player_id,
-                                                channel_id
-                                            );
-                                        }
-                                    }
+                            match sessions.release(&player_id, &client_id) {
+                                Release::Ended => {}
+                                Release::Superseded => {
+                                    tracing::info!(
+                                        "Player {} already reconnected, keeping their session",
+                                        player_id
+                                    );
+                                    return;
                                 }
-                                Err(e) => {
-                                    tracing::error!("Failed to remove player {}: {}", player_id, e);
+                                Release::Parked(grace_period) => {
+                                    tracing::info!(
+                                        "Holding session for player {} for {}s",
+                                        player_id,
+                                        grace_period.as_secs()
+                                    );
+                                    tokio::time::sleep(grace_period).await;
+                                    if !sessions.expire(&player_id, &client_id) {
+                                        return;
+                                    }
                                 }
                             }
+
+                            Self::end_session(&cache_manager, &webhook_receiver, player_id, client_id)
+                                .await;
                         });
                     },
                 ));
 
                 input_stream.set_webhook_receiver(webhook_receiver.clone());
+                input_stream.set_flood_guard(flood_guard);
+                input_stream.set_session_resumption(session_resumption);
 
                 let (input_shutdown_tx, input_shutdown_rx) = oneshot::channel();
                 let (output_shutdown_tx, output_shutdown_rx) = oneshot::channel();
@@ -315,6 +373,7 @@ impl QuicServerManager {
                         input_cache_manager,
                         broadcast_range,
                         deafen_distance,
+                        event_feed,
                         input_shutdown_rx,
                         Box::new(output_stream_identity_setter),
                     )
@@ -342,12 +401,83 @@ impl QuicServerManager {
         Ok(())
     }
 
+    /// Remove a player whose session is over from their channels and announce that they left
+    async fn end_session(
+        cache_manager: &CacheManager,
+        webhook_receiver: &WebhookReceiver,
+        player_id: String,
+        client_id: Vec<u8>,
+    ) {
+        match cache_manager.remove_player(&player_id).await {
+            Ok(removed_channels) => {
+                for channel_id in removed_channels {
+                    let leave_packet = common::structs::packet::QuicNetworkPacket {
+                        owner: Some(common::structs::packet::PacketOwner {
+                            name: player_id.clone(),
+                            client_id: client_id.clone(),
+                        }),
+                        packet_type: common::structs::packet::PacketType::ChannelEvent,
+                        data: common::structs::packet::QuicNetworkPacketData::ChannelEvent(
+                            common::structs::packet::ChannelEventPacket::new(
+                                common::structs::channel::ChannelEvents::Leave,
+                                player_id.clone(),
+                                channel_id.clone(),
+                            ),
+                        ),
+                    };
+
+                    if let Err(e) = webhook_receiver.send_packet(leave_packet).await {
+                        tracing::error!(
+                            "Failed to broadcast channel leave event for player {} channel {}: {}",
+                            player_id,
+                            channel_id,
+                            e
+                        );
+                    } else {
+                        tracing::info!(
+                            "Broadcast channel leave event: player {} left channel {}",
+                            player_id,
+                            channel_id
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("Failed to remove player {}: {}", player_id, e);
+            }
+        }
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64;
+        let presence_packet = QuicNetworkPacket {
+            owner: Some(common::structs::packet::PacketOwner {
+                name: player_id.clone(),
+                client_id,
+            }),
+            packet_type: PacketType::PlayerPresence,
+            data: common::structs::packet::QuicNetworkPacketData::PlayerPresence(
+                common::structs::packet::PlayerPresenceEvent {
+                    player_name: player_id.clone(),
+                    timestamp,
+                    event_type: common::structs::packet::ConnectionEventType::Disconnected,
+                },
+            ),
+        };
+        if let Err(e) = webhook_receiver.send_packet(presence_packet).await {
+            tracing::error!("Failed to send player disconnected event: {}", e);
+        }
+        tracing::debug!("Broadcast player disconnected event {}", player_id);
+    }
+
     async fn run_input_stream_with_player_callback(
         mut input_stream: InputStream,
         connection_registry: Arc<ConnectionRegistry>,
         cache_manager: CacheManager,
         broadcast_range: f32,
         deafen_distance: f32,
+        event_feed: EventFeed,
         mut shutdown_rx: oneshot::Receiver<()>,
         player_callback: Box<dyn Fn(String, Vec<u8>) + Send + Sync>,
     ) -> Result<(), anyhow::Error> {
@@ -392,6 +522,7 @@ impl QuicServerManager {
                             connection_registry
                                 .route_audio_frame(&updated_packet, &player_cache, broadcast_range, deafen_distance)
                                 .await;
+                            event_feed.observe_audio_frame(&updated_packet.get_author());
                         }
                         _ => {
                             connection_registry.broadcast_to_all(updated_packet);
@@ -0,0 +1,632 @@
+//! Server-side recording
+//!
+//! Records every routed audio frame for a channel or a configured world region, independent of
+//! any one player's connection or hearing range. Sessions use the client's layout (`session.json`
+//! plus one nano-wal segment per speaker, keyed by player name, with postcard `RecordingHeader`s)
+//! so the client's renderers and `bvc-recordings` read them unchanged.
+//!
+//! Frames are stored as output entries carrying the speaker's position and no listener; render
+//! from a camera perspective to re-apply spatial audio to the frames that were sent spatial.
+
+use crate::config::{Recording, RecordingRegion};
+use common::PlayerEnum;
+use common::structs::SpatialAudioConfig;
+use common::structs::packet::AudioFramePacket;
+use common::structs::recording::{
+    OutputRecordingHeader, PlayerMetadata, RecordingHeader, SessionManifest,
+};
+use common::traits::player_data::PlayerData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Stands in for the recording player in server session manifests
+pub const SERVER_EMITTER: &str = "Server";
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// What a server recording captures
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordingTarget {
+    /// Every member of a channel
+    Channel { id: String },
+    /// Every speaker inside a region from the `recording` config
+    Region { name: String },
+}
+
+/// State of a server recording, as reported by the admin routes
+#[derive(Serialize, Debug, Clone)]
+pub struct RecordingStatus {
+    pub session_id: String,
+    pub target: RecordingTarget,
+    pub path: String,
+    /// Unix timestamp in milliseconds
+    pub start_timestamp: u64,
+    pub frames: u64,
+    pub participants: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum RecordingError {
+    Disabled,
+    AlreadyRecording,
+    NotRecording,
+    UnknownRegion(String),
+    Io(String),
+}
+
+impl std::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordingError::Disabled => write!(f, "Server recording is disabled"),
+            RecordingError::AlreadyRecording => {
+                write!(f, "A server recording is already in progress")
+            }
+            RecordingError::NotRecording => write!(f, "No server recording in progress"),
+            RecordingError::UnknownRegion(name) => write!(f, "Unknown recording region {}", name),
+            RecordingError::Io(msg) => write!(f, "Recording I/O error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+/// A frame on its way to the writer task
+struct RecordedFrame {
+    speaker: String,
+    header: RecordingHeader,
+    opus_data: Vec<u8>,
+}
+
+/// Frames are matched against a resolved target so the routing path never touches config
+enum ActiveTarget {
+    Channel(String),
+    Region(RecordingRegion),
+}
+
+struct ActiveRecording {
+    target: RecordingTarget,
+    matcher: ActiveTarget,
+    session_id: String,
+    path: PathBuf,
+    start_timestamp: u64,
+    frames: AtomicU64,
+    participants: RwLock<HashSet<String>>,
+    tx: mpsc::UnboundedSender<RecordedFrame>,
+    writer: JoinHandle<()>,
+}
+
+impl ActiveRecording {
+    fn status(&self) -> RecordingStatus {
+        let mut participants: Vec<String> = self
+            .participants
+            .read()
+            .map(|p| p.iter().cloned().collect())
+            .unwrap_or_default();
+        participants.sort();
+
+        RecordingStatus {
+            session_id: self.session_id.clone(),
+            target: self.target.clone(),
+            path: self.path.to_string_lossy().to_string(),
+            start_timestamp: self.start_timestamp,
+            frames: self.frames.load(Ordering::Relaxed),
+            participants,
+        }
+    }
+
+    fn matches(
+        &self,
+        sender_channel: Option<&str>,
+        sender_server: Option<&str>,
+        sender: Option<&PlayerEnum>,
+    ) -> bool {
+        match &self.matcher {
+            ActiveTarget::Channel(id) => sender_channel == Some(id.as_str()),
+            ActiveTarget::Region(region) => {
+                sender.is_some_and(|player| region.contains(player.get_position(), sender_server))
+            }
+        }
+    }
+}
+
+/// Handle to the single server recording, shared by the connection registry and the admin routes
+#[derive(Clone)]
+pub struct ServerRecorder {
+    config: Arc<Recording>,
+    spatial_audio: SpatialAudioConfig,
+    active: Arc<RwLock<Option<ActiveRecording>>>,
+}
+
+impl ServerRecorder {
+    const BATCH_SIZE: usize = 50;
+    const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn new(config: Recording, spatial_audio: SpatialAudioConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            spatial_audio,
+            active: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.active.read().map(|a| a.is_some()).unwrap_or(false)
+    }
+
+    pub fn status(&self) -> Option<RecordingStatus> {
+        self.active
+            .read()
+            .ok()?
+            .as_ref()
+            .map(ActiveRecording::status)
+    }
+
+    /// Start recording `target` into a new session directory
+    pub fn start(&self, target: RecordingTarget) -> Result<RecordingStatus, RecordingError> {
+        if !self.config.enabled {
+            return Err(RecordingError::Disabled);
+        }
+
+        let matcher = match &target {
+            RecordingTarget::Channel { id } => ActiveTarget::Channel(id.clone()),
+            RecordingTarget::Region { name } => ActiveTarget::Region(
+                self.config
+                    .region(name)
+                    .cloned()
+                    .ok_or_else(|| RecordingError::UnknownRegion(name.clone()))?,
+            ),
+        };
+
+        let mut active = self
+            .active
+            .write()
+            .map_err(|_| RecordingError::Io("recorder lock poisoned".to_string()))?;
+        if active.is_some() {
+            return Err(RecordingError::AlreadyRecording);
+        }
+
+        let start_timestamp = now_ms();
+        let session_id = uuid::Uuid::now_v7().to_string();
+        let path = Path::new(&self.config.path).join(&session_id);
+        std::fs::create_dir_all(path.join("wal"))
+            .map_err(|e| RecordingError::Io(format!("{}: {}", path.display(), e)))?;
+
+        let manifest = SessionManifest {
+            session_id: session_id.clone(),
+            start_timestamp,
+            end_timestamp: None,
+            duration_ms: None,
+            emitter_player: SERVER_EMITTER.to_string(),
+            participants: Vec::new(),
+            created_at: format!("{}", start_timestamp / 1000),
+            recovered: false,
+            pinned: false,
+            markers: Vec::new(),
+            spatial_config: Some(self.spatial_audio.clone()),
+        };
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let writer = tokio::spawn(Self::write_session(path.clone(), manifest, rx));
+
+        let recording = ActiveRecording {
+            target,
+            matcher,
+            session_id,
+            path,
+            start_timestamp,
+            frames: AtomicU64::new(0),
+            participants: RwLock::new(HashSet::new()),
+            tx,
+            writer,
+        };
+        let status = recording.status();
+        tracing::info!(
+            "Server recording {} started for {:?}",
+            status.session_id,
+            status.target
+        );
+
+        *active = Some(recording);
+        Ok(status)
+    }
+
+    /// Stop the current recording and wait for its session to be finalized
+    pub async fn stop(&self) -> Result<RecordingStatus, RecordingError> {
+        let recording = self
+            .active
+            .write()
+            .map_err(|_| RecordingError::Io("recorder lock poisoned".to_string()))?
+            .take()
+            .ok_or(RecordingError::NotRecording)?;
+
+        let status = recording.status();
+
+        // Closing the channel lets the writer drain what is queued and write the final manifest
+        let ActiveRecording { tx, writer, .. } = recording;
+        drop(tx);
+        if let Err(e) = writer.await {
+            tracing::error!("Server recording writer failed: {}", e);
+        }
+
+        tracing::info!(
+            "Server recording {} stopped after {} frames",
+            status.session_id,
+            status.frames
+        );
+        Ok(status)
+    }
+
+    /// Record a routed audio frame if its sender is covered by the active recording
+    pub fn observe(
+        &self,
+        sender_name: &str,
+        audio_frame: &AudioFramePacket,
+        sender_channel: Option<&str>,
+        sender_server: Option<&str>,
+        sender: Option<&PlayerEnum>,
+    ) {
+        let Ok(active) = self.active.read() else {
+            return;
+        };
+        let Some(recording) = active.as_ref() else {
+            return;
+        };
+
+        if !recording.matches(sender_channel, sender_server, sender) {
+            return;
+        }
+
+        // Server receive time keeps every speaker on one clock
+        let header = RecordingHeader::Output(OutputRecordingHeader {
+            sample_rate: audio_frame.sample_rate,
+            channels: 1,
+            relative_timestamp_ms: now_ms().saturating_sub(recording.start_timestamp),
+            emitter_metadata: PlayerMetadata {
+                player_data: sender.cloned(),
+                spatial: audio_frame.spatial,
+                gain_settings: None,
+            },
+            listener_metadata: PlayerMetadata {
+                player_data: None,
+                spatial: None,
+                gain_settings: None,
+            },
+            // Frames without a flag are spatial, as they are when routed
+            is_spatial: audio_frame.spatial.unwrap_or(true),
+        });
+
+        let frame = RecordedFrame {
+            speaker: sender_name.to_string(),
+            header,
+            opus_data: audio_frame.data.clone(),
+        };
+
+        if recording.tx.send(frame).is_ok() {
+            recording.frames.fetch_add(1, Ordering::Relaxed);
+            let is_new = recording
+                .participants
+                .read()
+                .map(|p| !p.contains(sender_name))
+                .unwrap_or(false);
+            if is_new && let Ok(mut participants) = recording.participants.write() {
+                participants.insert(sender_name.to_string());
+            }
+        }
+    }
+
+    /// Writer task: batches frames into the WAL and keeps the manifest's participants current
+    async fn write_session(
+        path: PathBuf,
+        mut manifest: SessionManifest,
+        mut rx: mpsc::UnboundedReceiver<RecordedFrame>,
+    ) {
+        let mut wal = match nano_wal::Wal::new(
+            path.join("wal").to_string_lossy().as_ref(),
+            nano_wal::WalOptions::default(),
+        ) {
+            Ok(wal) => wal,
+            Err(e) => {
+                tracing::error!("Failed to initialize server recording WAL: {:?}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = Self::write_manifest(&path, &manifest).await {
+            tracing::error!("Failed to write initial server recording manifest: {}", e);
+        }
+
+        let mut batch: Vec<RecordedFrame> = Vec::new();
+        let mut participants: HashSet<String> = HashSet::new();
+        let mut manifest_dirty = false;
+
+        // Created once so continuous speech, with frames every 20ms, still flushes on schedule
+        let mut flush_interval = tokio::time::interval(Self::FLUSH_INTERVAL);
+        flush_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            tokio::select! {
+                frame = rx.recv() => match frame {
+                    Some(frame) => {
+                        if participants.insert(frame.speaker.clone()) {
+                            manifest_dirty = true;
+                        }
+                        batch.push(frame);
+                    }
+                    None => break,
+                },
+                _ = flush_interval.tick() => {
+                    if let Err(e) = Self::flush(&mut wal, &mut batch) {
+                        tracing::error!("Failed to flush server recording: {}", e);
+                    }
+
+                    if manifest_dirty {
+                        manifest.participants = participants.iter().cloned().collect();
+                        match Self::write_manifest(&path, &manifest).await {
+                            Ok(()) => manifest_dirty = false,
+                            Err(e) => tracing::error!("Failed to update server recording manifest: {}", e),
+                        }
+                    }
+                }
+            }
+
+            if batch.len() >= Self::BATCH_SIZE
+                && let Err(e) = Self::flush(&mut wal, &mut batch)
+            {
+                tracing::error!("Failed to flush server recording: {}", e);
+            }
+        }
+
+        if let Err(e) = Self::flush(&mut wal, &mut batch) {
+            tracing::error!("Failed final flush of server recording: {}", e);
+        }
+
+        let now = now_ms();
+        manifest.end_timestamp = Some(now);
+        manifest.duration_ms = Some(now.saturating_sub(manifest.start_timestamp));
+        manifest.participants = participants.into_iter().collect();
+
+        if let Err(e) = Self::write_manifest(&path, &manifest).await {
+            tracing::error!("Failed to write final server recording manifest: {}", e);
+        }
+    }
+
+    fn flush(wal: &mut nano_wal::Wal, batch: &mut Vec<RecordedFrame>) -> Result<(), anyhow::Error> {
+        if batch.is_empty() {
+            return Ok(());
+        }
+
+        for frame in batch.drain(..) {
+            let header = frame.header.to_bytes()?;
+            wal.append_entry(
+                &frame.speaker,
+                Some(header.into()),
+                frame.opus_data.into(),
+                false,
+            )?;
+        }
+        wal.sync()?;
+        Ok(())
+    }
+
+    /// Write the session manifest, replacing the previous one atomically
+    async fn write_manifest(path: &Path, manifest: &SessionManifest) -> Result<(), anyhow::Error> {
+        let json = serde_json::to_string_pretty(manifest)?;
+        let temp_path = path.join("session.json.tmp");
+        tokio::fs::write(&temp_path, json).await?;
+        tokio::fs::rename(&temp_path, path.join("session.json")).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::{Coordinate, Dimension, MinecraftPlayer, Orientation};
+
+    fn player_at(name: &str, x: f32) -> PlayerEnum {
+        PlayerEnum::Minecraft(MinecraftPlayer {
+            name: name.to_string(),
+            coordinates: Coordinate { x, y: 64.0, z: 0.0 },
+            orientation: Orientation { x: 0.0, y: 0.0 },
+            dimension: Dimension::Overworld,
+            deafen: false,
+            spectator: false,
+            world_uuid: None,
+            alternative_identity: None,
+            player_uuid: None,
+        })
+    }
+
+    fn recorder(path: &Path) -> ServerRecorder {
+        ServerRecorder::new(
+            Recording {
+                enabled: true,
+                path: path.to_string_lossy().to_string(),
+                regions: vec![RecordingRegion {
+                    name: "arena".to_string(),
+                    min: Coordinate {
+                        x: -10.0,
+                        y: 0.0,
+                        z: -10.0,
+                    },
+                    max: Coordinate {
+                        x: 10.0,
+                        y: 128.0,
+                        z: 10.0,
+                    },
+                    server_id: None,
+                }],
+            },
+            SpatialAudioConfig::default(),
+        )
+    }
+
+    fn frame() -> AudioFramePacket {
+        AudioFramePacket::new(vec![1u8; 16], 48000, None, None)
+    }
+
+    #[tokio::test]
+    async fn records_region_speakers_into_session() {
+        let dir = std::env::temp_dir().join(format!("bvc-server-recording-{}", nanoid::nanoid!(8)));
+        let recorder = recorder(&dir);
+
+        let status = recorder
+            .start(RecordingTarget::Region {
+                name: "arena".to_string(),
+            })
+            .unwrap();
+        assert!(matches!(
+            recorder.start(RecordingTarget::Channel {
+                id: "party".to_string()
+            }),
+            Err(RecordingError::AlreadyRecording)
+        ));
+
+        let inside = player_at("Alice", 5.0);
+        let outside = player_at("Bob", 50.0);
+        recorder.observe("Alice", &frame(), None, None, Some(&inside));
+        recorder.observe("Bob", &frame(), None, None, Some(&outside));
+        recorder.observe("Carol", &frame(), None, None, None);
+
+        let stopped = recorder.stop().await.unwrap();
+        assert_eq!(stopped.frames, 1);
+        assert_eq!(stopped.participants, vec!["Alice".to_string()]);
+        assert!(!recorder.is_recording());
+
+        let manifest: SessionManifest = serde_json::from_str(
+            &std::fs::read_to_string(Path::new(&status.path).join("session.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.emitter_player, SERVER_EMITTER);
+        assert_eq!(manifest.participants, vec!["Alice".to_string()]);
+        assert!(manifest.end_timestamp.is_some());
+
+        let segments: Vec<String> = std::fs::read_dir(Path::new(&status.path).join("wal"))
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(segments.iter().any(|name| name.starts_with("Alice")));
+        assert!(!segments.iter().any(|name| name.starts_with("Bob")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn manifest_is_updated_during_continuous_speech() {
+        let dir = std::env::temp_dir().join(format!("bvc-server-recording-{}", nanoid::nanoid!(8)));
+        let recorder = recorder(&dir);
+        let status = recorder
+            .start(RecordingTarget::Region {
+                name: "arena".to_string(),
+            })
+            .unwrap();
+
+        let alice = player_at("Alice", 5.0);
+        let manifest_path = Path::new(&status.path).join("session.json");
+        let mut participants = Vec::new();
+        for _ in 0..100 {
+            recorder.observe("Alice", &frame(), None, None, Some(&alice));
+            tokio::time::sleep(Duration::from_millis(20)).await;
+
+            // The writer creates the manifest asynchronously after start
+            let Ok(json) = std::fs::read_to_string(&manifest_path) else {
+                continue;
+            };
+            let manifest: SessionManifest = serde_json::from_str(&json).unwrap();
+            if !manifest.participants.is_empty() {
+                participants = manifest.participants;
+                break;
+            }
+        }
+        assert_eq!(participants, vec!["Alice".to_string()]);
+
+        recorder.stop().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn channel_target_and_errors() {
+        let dir = std::env::temp_dir().join(format!("bvc-server-recording-{}", nanoid::nanoid!(8)));
+        let recorder = recorder(&dir);
+
+        assert!(matches!(
+            recorder.stop().await,
+            Err(RecordingError::NotRecording)
+        ));
+        assert!(matches!(
+            recorder.start(RecordingTarget::Region {
+                name: "lobby".to_string()
+            }),
+            Err(RecordingError::UnknownRegion(_))
+        ));
+
+        recorder
+            .start(RecordingTarget::Channel {
+                id: "party".to_string(),
+            })
+            .unwrap();
+        recorder.observe("Alice", &frame(), Some("party"), None, None);
+        recorder.observe("Bob", &frame(), Some("other"), None, None);
+        recorder.observe("Carol", &frame(), None, None, None);
+        assert_eq!(recorder.status().unwrap().frames, 1);
+
+        recorder.stop().await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let disabled = ServerRecorder::new(Recording::default(), SpatialAudioConfig::default());
+        assert!(matches!(
+            disabled.start(RecordingTarget::Channel {
+                id: "party".to_string()
+            }),
+            Err(RecordingError::Disabled)
+        ));
+    }
+
+    #[test]
+    fn region_bounds_and_server() {
+        let region = RecordingRegion {
+            name: "arena".to_string(),
+            min: Coordinate {
+                x: 10.0,
+                y: 0.0,
+                z: 10.0,
+            },
+            max: Coordinate {
+                x: -10.0,
+                y: 128.0,
+                z: -10.0,
+            },
+            server_id: Some("lobby".to_string()),
+        };
+        let position = Coordinate {
+            x: 0.0,
+            y: 64.0,
+            z: 0.0,
+        };
+
+        assert!(region.contains(&position, Some("lobby")));
+        assert!(!region.contains(&position, Some("survival")));
+        assert!(!region.contains(&position, None));
+        assert!(!region.contains(
+            &Coordinate {
+                x: 11.0,
+                y: 64.0,
+                z: 0.0
+            },
+            Some("lobby")
+        ));
+    }
+}
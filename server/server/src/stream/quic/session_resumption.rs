@@ -0,0 +1,225 @@
+//! Session resumption across reconnects
+//!
+//! Clients send a `SessionResume` token as the first packet of every connection. When a
+//! connection drops, the player's session is held for a grace period instead of being torn
+//! down, and a new connection presenting the same token takes it over without presence or
+//! channel leave events. Connections without a token end their session immediately.
+//!
+//! The token is a datagram, so it can arrive after the connection has already identified the
+//! player. A connection that takes over a resumable session without a token waits briefly for
+//! it before being treated as a new session.
+
+use dashmap::DashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+struct PlayerSession {
+    /// Connection currently holding the session
+    client_id: Vec<u8>,
+    token: Option<Vec<u8>>,
+    /// Token of the session this connection took over without presenting one yet
+    superseded_token: Option<Vec<u8>>,
+}
+
+/// What to do with a player's session once one of their connections has closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Release {
+    /// No way to resume, clean up now
+    Ended,
+    /// Clean up after the grace period unless the session was resumed
+    Parked(Duration),
+    /// A newer connection already holds the session, nothing to clean up
+    Superseded,
+}
+
+/// Player sessions keyed by player name, shared by every connection
+#[derive(Clone)]
+pub(crate) struct SessionResumption {
+    sessions: Arc<DashMap<String, PlayerSession>>,
+    grace_period: Duration,
+}
+
+impl SessionResumption {
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            sessions: Arc::new(DashMap::new()),
+            grace_period,
+        }
+    }
+
+    /// Bind a connection to the player's session. Returns true when the token matches the
+    /// session already held, so the player never left.
+    pub fn attach(&self, player_name: &str, client_id: &[u8], token: Option<&[u8]>) -> bool {
+        if let Some(mut session) = self.sessions.get_mut(player_name) {
+            if token.is_some() && session.token.as_deref() == token {
+                session.client_id = client_id.to_vec();
+                return true;
+            }
+        }
+
+        let superseded_token = match token {
+            Some(_) => None,
+            None => self
+                .sessions
+                .get(player_name)
+                .and_then(|session| session.token.clone()),
+        };
+        self.sessions.insert(
+            player_name.to_string(),
+            PlayerSession {
+                client_id: client_id.to_vec(),
+                token: token.map(|token| token.to_vec()),
+                superseded_token,
+            },
+        );
+        false
+    }
+
+    /// True when the connection took over a resumable session and its token may still arrive
+    pub fn awaiting_token(&self, player_name: &str, client_id: &[u8]) -> bool {
+        self.sessions.get(player_name).is_some_and(|session| {
+            session.client_id == client_id && session.superseded_token.is_some()
+        })
+    }
+
+    /// A token that arrived after the connection was attached. Returns true when it resumes
+    /// the session the connection took over.
+    pub fn present_token(&self, player_name: &str, client_id: &[u8], token: &[u8]) -> bool {
+        let Some(mut session) = self.sessions.get_mut(player_name) else {
+            return false;
+        };
+        if session.client_id != client_id || session.token.is_some() {
+            return false;
+        }
+
+        session.token = Some(token.to_vec());
+        if session.superseded_token.as_deref() == Some(token) {
+            session.superseded_token = None;
+            return true;
+        }
+        false
+    }
+
+    /// Stop waiting for a late token. Returns true when none resumed the session, so the
+    /// connection is a new session.
+    pub fn settle(&self, player_name: &str, client_id: &[u8]) -> bool {
+        match self.sessions.get_mut(player_name) {
+            Some(mut session) if session.client_id == client_id => {
+                session.superseded_token.take().is_some()
+            }
+            _ => false,
+        }
+    }
+
+    /// Called when a connection closes
+    pub fn release(&self, player_name: &str, client_id: &[u8]) -> Release {
+        let resumable = match self.sessions.get(player_name) {
+            Some(session) if session.client_id != client_id => return Release::Superseded,
+            Some(session) => session.token.is_some() && !self.grace_period.is_zero(),
+            None => false,
+        };
+
+        if resumable {
+            Release::Parked(self.grace_period)
+        } else {
+            self.sessions.remove(player_name);
+            Release::Ended
+        }
+    }
+
+    /// Called once the grace period is over. Returns true when the session was not resumed
+    /// and should be cleaned up.
+    pub fn expire(&self, player_name: &str, client_id: &[u8]) -> bool {
+        self.sessions
+            .remove_if(player_name, |_, session| session.client_id == client_id)
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRACE: Duration = Duration::from_secs(30);
+
+    #[test]
+    fn test_resume_within_grace() {
+        let sessions = SessionResumption::new(GRACE);
+        assert!(!sessions.attach("Steve", b"first", Some(b"token")));
+
+        assert_eq!(sessions.release("Steve", b"first"), Release::Parked(GRACE));
+        assert!(sessions.attach("Steve", b"second", Some(b"token")));
+
+        // The parked connection's cleanup finds the session taken over
+        assert!(!sessions.expire("Steve", b"first"));
+        assert_eq!(sessions.release("Steve", b"second"), Release::Parked(GRACE));
+        assert!(sessions.expire("Steve", b"second"));
+    }
+
+    #[test]
+    fn test_resume_before_old_connection_closes() {
+        let sessions = SessionResumption::new(GRACE);
+        sessions.attach("Steve", b"first", Some(b"token"));
+        assert!(sessions.attach("Steve", b"second", Some(b"token")));
+
+        assert_eq!(sessions.release("Steve", b"first"), Release::Superseded);
+    }
+
+    #[test]
+    fn test_wrong_or_missing_token_starts_new_session() {
+        let sessions = SessionResumption::new(GRACE);
+        sessions.attach("Steve", b"first", Some(b"token"));
+        sessions.release("Steve", b"first");
+
+        assert!(!sessions.attach("Steve", b"second", Some(b"other")));
+        assert!(!sessions.attach("Steve", b"third", None));
+        assert_eq!(sessions.release("Steve", b"third"), Release::Ended);
+    }
+
+    #[test]
+    fn test_late_token_resumes() {
+        let sessions = SessionResumption::new(GRACE);
+        sessions.attach("Steve", b"first", Some(b"token"));
+        sessions.release("Steve", b"first");
+
+        // The second connection identified the player before its token arrived
+        assert!(!sessions.attach("Steve", b"second", None));
+        assert!(sessions.awaiting_token("Steve", b"second"));
+        assert!(sessions.present_token("Steve", b"second", b"token"));
+        assert!(!sessions.settle("Steve", b"second"));
+
+        // Resumable again on the next reconnect
+        assert_eq!(sessions.release("Steve", b"second"), Release::Parked(GRACE));
+        assert!(!sessions.expire("Steve", b"first"));
+    }
+
+    #[test]
+    fn test_late_wrong_token_is_new_session() {
+        let sessions = SessionResumption::new(GRACE);
+        sessions.attach("Steve", b"first", Some(b"token"));
+        sessions.release("Steve", b"first");
+
+        sessions.attach("Steve", b"second", None);
+        assert!(!sessions.present_token("Steve", b"second", b"other"));
+        assert!(sessions.settle("Steve", b"second"));
+        assert!(!sessions.settle("Steve", b"second"));
+    }
+
+    #[test]
+    fn test_late_token_for_new_session() {
+        let sessions = SessionResumption::new(GRACE);
+        assert!(!sessions.attach("Steve", b"first", None));
+        assert!(!sessions.awaiting_token("Steve", b"first"));
+
+        assert!(!sessions.present_token("Steve", b"first", b"token"));
+        assert_eq!(sessions.release("Steve", b"first"), Release::Parked(GRACE));
+    }
+
+    #[test]
+    fn test_no_grace_period() {
+        let sessions = SessionResumption::new(Duration::ZERO);
+        sessions.attach("Steve", b"first", Some(b"token"));
+        assert_eq!(sessions.release("Steve", b"first"), Release::Ended);
+        assert!(!sessions.attach("Steve", b"second", Some(b"token")));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::stream::quic::client_id_hasher::ClientIdHasher;
+use crate::stream::quic::flood_guard::{FloodGuard, FloodVerdict};
+use crate::stream::quic::session_resumption::SessionResumption;
 use crate::stream::quic::{ServerInputPacket, WebhookReceiver};
 use anyhow::Error;
 use bytes::Bytes;
@@ -65,10 +67,19 @@ pub(crate) struct InputStream {
     disconnect_callback: Option<Box<dyn Fn(String, Vec<u8>) + Send + Sync>>,
     // Webhook receiver for sending presence events
     webhook_receiver: Option<WebhookReceiver>,
+    // Per-connection datagram budgets, checked before decoding
+    flood_guard: Option<FloodGuard>,
+    // Player sessions that survive a reconnect, and the token this connection presented
+    session_resumption: Option<SessionResumption>,
+    resume_token: Option<Vec<u8>>,
 }
 
 impl InputStream {
     const LARGE_JUMP_FORWARD_MS: i64 = 3_000;
+    // QUIC application error code sent when closing a connection for flooding
+    const FLOOD_CLOSE_CODE: u32 = 0x429;
+    // How long a connection taking over a resumable session waits for its resume token
+    const LATE_RESUME_TOKEN_WAIT: Duration = Duration::from_secs(2);
 
     pub fn new(
         connection: Option<Arc<Connection>>,
@@ -88,6 +99,9 @@ impl InputStream {
             last_seen_ts,
             disconnect_callback: None,
             webhook_receiver: None,
+            flood_guard: None,
+            session_resumption: None,
+            resume_token: None,
         }
     }
 
@@ -106,6 +120,14 @@ impl InputStream {
         self.webhook_receiver = Some(webhook_receiver);
     }
 
+    pub fn set_flood_guard(&mut self, flood_guard: FloodGuard) {
+        self.flood_guard = Some(flood_guard);
+    }
+
+    pub fn set_session_resumption(&mut self, session_resumption: SessionResumption) {
+        self.session_resumption = Some(session_resumption);
+    }
+
     pub async fn send_event(&self, packet: QuicNetworkPacket) {
         if let Some(webhook_receiver) = &self.webhook_receiver {
             let webhook_receiver_clone = webhook_receiver.clone();
@@ -117,6 +139,24 @@ impl InputStream {
         }
     }
 
+    fn player_connected_event(player_name: &str) -> QuicNetworkPacket {
+        QuicNetworkPacket {
+            owner: Some(PacketOwner {
+                name: String::from("api"),
+                client_id: vec![],
+            }),
+            packet_type: PacketType::PlayerPresence,
+            data: QuicNetworkPacketData::PlayerPresence(PlayerPresenceEvent {
+                player_name: player_name.to_string(),
+                timestamp: std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as i64,
+                event_type: ConnectionEventType::Connected,
+            }),
+        }
+    }
+
     fn sender_key_for_packet(&self, packet: &QuicNetworkPacket, conn: &Connection) -> Vec<u8> {
         if let Some(owner) = &packet.owner {
             if !owner.client_id.is_empty() {
@@ -177,6 +217,34 @@ impl StreamTrait for InputStream {
                 let datagram = recv_one_datagram(&connection).await;
                 match datagram {
                     Ok(bytes) => {
+                        if let Some(flood_guard) = self.flood_guard.as_mut() {
+                            match flood_guard.check(&bytes) {
+                                FloodVerdict::Accept => {}
+                                FloodVerdict::Drop => {
+                                    tracing::trace!("Dropping datagram over flood budget ({} bytes)", bytes.len());
+                                    continue;
+                                }
+                                FloodVerdict::Disconnect => {
+                                    let metrics = flood_guard.metrics();
+                                    let player = self.player_id.clone().unwrap_or_else(|| "unknown".into());
+                                    let client_hash = self
+                                        .client_id
+                                        .as_ref()
+                                        .map(|cid| ClientIdHasher::hash(cid))
+                                        .unwrap_or_else(|| "????".into());
+                                    tracing::warn!(
+                                        "datagram_flood_disconnect player={} client={} total_dropped={} total_disconnected={}",
+                                        player,
+                                        client_hash,
+                                        metrics.datagrams_dropped,
+                                        metrics.connections_disconnected
+                                    );
+                                    connection.close(Self::FLOOD_CLOSE_CODE.into());
+                                    break;
+                                }
+                            }
+                        }
+
                         match QuicNetworkPacket::from_datagram(&bytes) {
                             Ok(packet) => {
                                 match packet.packet_type {
@@ -277,6 +345,25 @@ impl StreamTrait for InputStream {
                                         }
                                         continue;
                                     }
+                                    PacketType::SessionResume => {
+                                        // Kept for when the identity is known, never forwarded
+                                        if let QuicNetworkPacketData::SessionResume(resume) = &packet.data {
+                                            // Arrived after the identity, so the session was attached without it
+                                            if let (Some(sessions), Some(player_id), Some(client_id)) =
+                                                (&self.session_resumption, &self.player_id, &self.client_id)
+                                            {
+                                                if sessions.present_token(player_id, client_id, &resume.token) {
+                                                    tracing::info!(
+                                                        "Resumed session for player {} (client: {}) from a late token",
+                                                        player_id,
+                                                        ClientIdHasher::hash(client_id)
+                                                    );
+                                                }
+                                            }
+                                            self.resume_token = Some(resume.token.clone());
+                                        }
+                                        continue;
+                                    }
                                     _ => {}
                                 };
 
@@ -291,25 +378,38 @@ impl StreamTrait for InputStream {
                                         client_hash
                                     );
 
-                                    self.send_event(QuicNetworkPacket {
-                                        owner: Some(PacketOwner {
-                                            name: String::from("api"),
-                                            client_id: vec![],
-                                        }),
-                                        packet_type: PacketType::PlayerPresence,
-                                        data: QuicNetworkPacketData::PlayerPresence(
-                                            PlayerPresenceEvent {
-                                                player_name: owner.name.clone(),
-                                                timestamp: std::time::SystemTime::now()
-                                                    .duration_since(std::time::UNIX_EPOCH)
-                                                    .unwrap()
-                                                    .as_millis()
-                                                    as i64,
-                                                event_type: ConnectionEventType::Connected,
-                                            },
-                                        ),
-                                    })
-                                    .await;
+                                    let resumed = self.session_resumption.as_ref().is_some_and(|sessions| {
+                                        sessions.attach(&owner.name, &owner.client_id, self.resume_token.as_deref())
+                                    });
+                                    if resumed {
+                                        tracing::info!("Resumed session for player {} (client: {})", owner.name, client_hash);
+                                    } else if let Some(sessions) = self
+                                        .session_resumption
+                                        .as_ref()
+                                        .filter(|sessions| sessions.awaiting_token(&owner.name, &owner.client_id))
+                                    {
+                                        // The token may still be on its way, only announce the player if it isn't
+                                        let sessions = sessions.clone();
+                                        let webhook_receiver = self.webhook_receiver.clone();
+                                        let player_name = owner.name.clone();
+                                        let client_id = owner.client_id.clone();
+                                        tokio::spawn(async move {
+                                            tokio::time::sleep(Self::LATE_RESUME_TOKEN_WAIT).await;
+                                            if !sessions.settle(&player_name, &client_id) {
+                                                return;
+                                            }
+                                            if let Some(webhook_receiver) = webhook_receiver {
+                                                if let Err(e) = webhook_receiver
+                                                    .send_packet(Self::player_connected_event(&player_name))
+                                                    .await
+                                                {
+                                                    tracing::error!("Failed to send player connected event: {}", e);
+                                                }
+                                            }
+                                        });
+                                    } else {
+                                        self.send_event(Self::player_connected_event(&owner.name)).await;
+                                    }
                                 }
 
                                 let server_packet = ServerInputPacket { data: packet };
@@ -368,33 +468,6 @@ impl StreamTrait for InputStream {
                 (&self.disconnect_callback, &self.player_id, &self.client_id)
             {
                 callback(player_id.clone(), client_id.clone());
-                if let Some(webhook_receiver) = &self.webhook_receiver {
-                    let webhook_receiver_clone = webhook_receiver.clone();
-                    let client_id = client_id.clone();
-                    let player_name = player_id.clone();
-                    tokio::spawn(async move {
-                        let timestamp = std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap()
-                            .as_millis() as i64;
-                        let presence_packet = QuicNetworkPacket {
-                            owner: Some(PacketOwner {
-                                name: player_name.clone(),
-                                client_id: client_id.clone(),
-                            }),
-                            packet_type: PacketType::PlayerPresence,
-                            data: QuicNetworkPacketData::PlayerPresence(PlayerPresenceEvent {
-                                player_name: player_name.clone(),
-                                timestamp,
-                                event_type: ConnectionEventType::Disconnected,
-                            }),
-                        };
-                        if let Err(e) = webhook_receiver_clone.send_packet(presence_packet).await {
-                            tracing::error!("Failed to send player disconnected event: {}", e);
-                        }
-                        tracing::debug!("Broadcast player disconnected event {}", player_name);
-                    });
-                }
             }
         }
 